@@ -29,13 +29,103 @@ use isomdl::definitions::namespaces::org_iso_18013_5_1::OrgIso1801351;
 use isomdl::definitions::namespaces::org_iso_18013_5_1_aamva::OrgIso1801351Aamva;
 use isomdl::definitions::traits::{FromJson, ToNamespaceMap};
 use isomdl::definitions::x509::X5Chain;
-use isomdl::definitions::{CoseKey, DeviceKeyInfo, DigestAlgorithm, EC2Curve, ValidityInfo, EC2Y};
+use isomdl::definitions::{CoseKey, DeviceKeyInfo, DigestAlgorithm, EC2Curve, OKPCurve, ValidityInfo, EC2Y};
 use isomdl::issuance::mdoc::{Builder, Mdoc};
 use isomdl::cbor;
 use p256::ecdsa::{Signature, SigningKey};
 use p256::pkcs8::DecodePrivateKey;
 use p256::SecretKey;
+use pkcs8::{ObjectIdentifier, PrivateKeyInfo};
 use time::OffsetDateTime;
+use zeroize::Zeroize;
+
+// Named-curve OIDs needed to tell device keys apart from their PKCS8
+// SubjectPublicKeyInfo algorithm identifier: Edwards curves carry the curve
+// itself as the top-level algorithm OID, NIST curves carry `id-ecPublicKey`
+// with the curve as an OID parameter.
+const OID_EC_PUBLIC_KEY: &str = "1.2.840.10045.2.1";
+const OID_SECP256R1: &str = "1.2.840.10045.3.1.7";
+const OID_SECP384R1: &str = "1.3.132.0.34";
+const OID_ED25519: &str = "1.3.101.112";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum DeviceKeyCurve {
+    P256,
+    P384,
+    Ed25519,
+}
+
+impl DeviceKeyCurve {
+    fn from_override(name: &str) -> Self {
+        match name.to_ascii_lowercase().as_str() {
+            "p256" | "p-256" | "secp256r1" => DeviceKeyCurve::P256,
+            "p384" | "p-384" | "secp384r1" => DeviceKeyCurve::P384,
+            "ed25519" => DeviceKeyCurve::Ed25519,
+            other => panic!("Unsupported --device-key-curve override: {other}"),
+        }
+    }
+}
+
+// Sniffs the device key's curve from its PKCS8 algorithm identifier, so a
+// device holding an Ed25519 or P-384 key can be provisioned without the
+// caller having to know which it is ahead of time. `--device-key-curve` is
+// there for the rare PEM whose algorithm identifier doesn't disambiguate it.
+fn detect_device_key_curve(device_priv_key: &str) -> DeviceKeyCurve {
+    let der = pem::parse(device_priv_key).expect("Failed to parse device private key PEM");
+    let key_info = PrivateKeyInfo::try_from(der.contents.as_slice())
+        .expect("Failed to parse device private key PKCS8 structure");
+
+    let algorithm_oid = key_info.algorithm.oid.to_string();
+    if algorithm_oid == OID_ED25519 {
+        return DeviceKeyCurve::Ed25519;
+    }
+    if algorithm_oid == OID_EC_PUBLIC_KEY {
+        let curve_oid = key_info
+            .algorithm
+            .parameters
+            .expect("EC device private key is missing its named-curve parameter")
+            .decode_as::<ObjectIdentifier>()
+            .expect("Failed to decode EC named-curve parameter")
+            .to_string();
+        return match curve_oid.as_str() {
+            OID_SECP256R1 => DeviceKeyCurve::P256,
+            OID_SECP384R1 => DeviceKeyCurve::P384,
+            other => panic!("Unsupported EC named curve: {other}"),
+        };
+    }
+    panic!("Unsupported device key algorithm OID: {algorithm_oid}");
+}
+
+// Builds the device's `CoseKey`: an `EC2` key with the curve's x/y
+// coordinates for NIST curves, or an `OKP` key carrying just the encoded
+// point for Edwards curves.
+fn device_cose_key(device_priv_key: &str, curve: DeviceKeyCurve) -> CoseKey {
+    match curve {
+        DeviceKeyCurve::P256 => {
+            let priv_key = SecretKey::from_pkcs8_pem(device_priv_key).unwrap();
+            let pub_key = priv_key.public_key();
+            let ec = pub_key.to_encoded_point(false);
+            let x = ec.x().unwrap().to_vec();
+            let y = EC2Y::Value(ec.y().unwrap().to_vec());
+            CoseKey::EC2 { crv: EC2Curve::P256, x, y }
+        }
+        DeviceKeyCurve::P384 => {
+            use p384::pkcs8::DecodePrivateKey as _;
+            let priv_key = p384::SecretKey::from_pkcs8_pem(device_priv_key).unwrap();
+            let pub_key = priv_key.public_key();
+            let ec = pub_key.to_encoded_point(false);
+            let x = ec.x().unwrap().to_vec();
+            let y = EC2Y::Value(ec.y().unwrap().to_vec());
+            CoseKey::EC2 { crv: EC2Curve::P384, x, y }
+        }
+        DeviceKeyCurve::Ed25519 => {
+            use ed25519_dalek::pkcs8::DecodePrivateKey as _;
+            let signing_key = ed25519_dalek::SigningKey::from_pkcs8_pem(device_priv_key).unwrap();
+            let x = signing_key.verifying_key().to_bytes().to_vec();
+            CoseKey::OKP { crv: OKPCurve::Ed25519, x }
+        }
+    }
+}
 
 static MDL_DOCTYPE: &str = "org.iso.18013.5.1.mDL";
 static ISO_MDL_NAMESPACE: &str = "org.iso.18013.5.1";
@@ -63,9 +153,33 @@ struct Args {
     /// Output file for the mDL (CBOR format)
     #[arg(short = 'o', long = "output")]
     mdl: String,
+
+    /// Override curve detection for the device key (p256, p384, ed25519);
+    /// only needed when the PEM's algorithm identifier doesn't disambiguate it
+    #[arg(long = "device-key-curve")]
+    device_key_curve: Option<String>,
+}
+
+/// A PEM-encoded private key held only long enough to parse it. A plain
+/// `String` doesn't wipe its heap buffer on drop, so without this the key
+/// material would linger in freed memory after the program moves on.
+struct SecretPem(String);
+
+impl std::ops::Deref for SecretPem {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Drop for SecretPem {
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
 }
 
-fn mdoc_builder(claims: String, device_priv_key: String) -> Builder {
+fn mdoc_builder(claims: String, device_priv_key: &str, device_key_curve: Option<String>) -> Builder {
     // Parse the claims.json content into a serde_json::Value
     let parsed: serde_json::Value = serde_json::from_str(&claims).unwrap();
 
@@ -103,16 +217,10 @@ fn mdoc_builder(claims: String, device_priv_key: String) -> Builder {
 
     let digest_algorithm = DigestAlgorithm::SHA256;
 
-    let priv_key = SecretKey::from_pkcs8_pem(device_priv_key.as_str()).unwrap();
-    let pub_key = priv_key.public_key();
-    let ec = pub_key.to_encoded_point(false);
-    let x = ec.x().unwrap().to_vec();
-    let y = EC2Y::Value(ec.y().unwrap().to_vec());
-    let device_key = CoseKey::EC2 {
-        crv: EC2Curve::P256,
-        x,
-        y,
-    };
+    let curve = device_key_curve
+        .map(|name| DeviceKeyCurve::from_override(&name))
+        .unwrap_or_else(|| detect_device_key_curve(device_priv_key));
+    let device_key = device_cose_key(device_priv_key, curve);
 
     let device_key_info = DeviceKeyInfo {
         device_key,
@@ -129,8 +237,8 @@ fn mdoc_builder(claims: String, device_priv_key: String) -> Builder {
         .enable_decoy_digests(false)
 }
 
-fn generate_mdl(claims: String, device_pub_key: String, private_key_pem: String, x5chain_pem: String) -> Vec<u8> {
-    let mdoc_builder = mdoc_builder(claims, device_pub_key);
+fn generate_mdl(claims: String, device_priv_key: &str, private_key_pem: &str, x5chain_pem: String, device_key_curve: Option<String>) -> Vec<u8> {
+    let mdoc_builder = mdoc_builder(claims, device_priv_key, device_key_curve);
 
     let pem_blocks = pem::parse_many(x5chain_pem.as_bytes()).unwrap();
     let mut builder = X5Chain::builder();
@@ -142,7 +250,7 @@ fn generate_mdl(claims: String, device_pub_key: String, private_key_pem: String,
     let x5chain = builder
         .build()
         .unwrap();
-    let signer: SigningKey = SecretKey::from_pkcs8_pem(private_key_pem.as_str())
+    let signer: SigningKey = SecretKey::from_pkcs8_pem(private_key_pem)
         .expect("failed to parse pem")
         .into();
 
@@ -162,19 +270,27 @@ fn main() {
     let claims_data = std::fs::read_to_string(&args.claims)
         .expect("Failed to read claims file");
 
-    let device_priv_key_data = std::fs::read_to_string(&args.device_priv_key)
-        .expect("Failed to read device public key file");
+    let device_priv_key_data = SecretPem(
+        std::fs::read_to_string(&args.device_priv_key).expect("Failed to read device public key file"),
+    );
 
     // Read the issuer's private key file
-    let issuer_private_key_data = std::fs::read_to_string(&args.issuer_private_key)
-        .expect("Failed to read issuer private key file");
+    let issuer_private_key_data = SecretPem(
+        std::fs::read_to_string(&args.issuer_private_key).expect("Failed to read issuer private key file"),
+    );
 
     // Read the issuer's certificate file
     let issuer_x5chain_data = std::fs::read_to_string(&args.issuer_x5chain)
         .expect("Failed to read issuer certificate chain file");
 
     // Generate the mDL
-    let mdl_data = generate_mdl(claims_data, device_priv_key_data, issuer_private_key_data, issuer_x5chain_data);
+    let mdl_data = generate_mdl(
+        claims_data,
+        &device_priv_key_data,
+        &issuer_private_key_data,
+        issuer_x5chain_data,
+        args.device_key_curve,
+    );
 
     // Write the mDL to the output file
     std::fs::write(&args.mdl, mdl_data)
@@ -3,7 +3,7 @@
 
 use std::time::Duration;
 use criterion::{criterion_group, criterion_main, Criterion};
-use crescent::{dlog::DLogPoK, rangeproof::{RangeProof, RangeProofPK}, CrescentPairing, CrescentFr};
+use crescent::{dlog::DLogPoK, rangeproof::{RangeProof, RangeProofPK}, ipa_rangeproof::IpaRangeProofPK, CrescentPairing, CrescentFr};
 use ark_ff::PrimeField;
 use ark_ec::AffineRepr;
 use rayon::ThreadPoolBuilder;
@@ -49,11 +49,33 @@ pub fn range_proof_benchmark(c: &mut Criterion) {
     let bases_proj = [com_exp.bases[0].into_group(), com_exp.bases[1].into_group()];
     
     c.bench_function(&format!("RangeProof verifier time, {}-bit secret", N_BITS), |b| {
-        b.iter(|| {    
+        b.iter(|| {
             range_proof.verify_n_bits(&ped_com_exp, &bases_proj, N_BITS, &range_vk);
         })
     });
- 
+
+    // `IpaRangeProofPK` is the transparent-setup, logarithmic-size
+    // alternative to the KZG `range_pk.powers` scheme benchmarked above --
+    // no trusted setup, and a proof of O(log n) group elements (the KZG
+    // proof is constant-size), verified with O(n) group operations.
+    let (ipa_pk, ipa_vk) = IpaRangeProofPK::<G1>::setup(N_BITS);
+    let mut ipa_range_proof = IpaRangeProofPK::prove_n_bits(&com_exp, N_BITS, &ipa_pk);
+
+    c.bench_function(&format!("IpaRangeProof prover time, {}-bit secret", N_BITS), |b| {
+        b.iter(|| {
+            pool.install(|| {
+                ipa_range_proof = IpaRangeProofPK::prove_n_bits(&com_exp, N_BITS, &ipa_pk);
+            });
+        })
+    });
+
+    let bases_affine = [com_exp.bases[0], com_exp.bases[1]];
+    c.bench_function(&format!("IpaRangeProof verifier time, {}-bit secret", N_BITS), |b| {
+        b.iter(|| {
+            ipa_range_proof.verify_n_bits(&ped_com_exp, &bases_affine, N_BITS, &ipa_vk);
+        })
+    });
+
 }
 
 criterion_group!{
@@ -0,0 +1,339 @@
+#![allow(non_snake_case)]
+// A BBS+-based alternative to the ECDSA `DeviceProof` in `device.rs`. Instead
+// of binding the commitment `com0` to a SNARK-verified ECDSA signature, this
+// proves knowledge of a BBS+ signature issued over the committed scalar,
+// re-randomizing the signature `(A, e, s)` so that repeated presentations of
+// the same signature are unlinkable from one another. The `com1`/`pi0`
+// linking structure is identical to `DeviceProof`; the remaining two pieces
+// -- `bbs_pok` (replacing `pi2`'s Groth16 SNARK with a pairing-based sigma
+// protocol) and the `m_commit_t`/`s_m`/`s_r` fields (replacing `pi1`) -- both
+// treat the device-bound scalar `m` as a hidden witness rather than a public
+// value, since revealing it (even once, let alone identically on every
+// presentation) would both leak the hidden device-key commitments' sum and
+// make every show from the same device trivially linkable.
+
+use ark_ec::pairing::Pairing;
+use ark_ec::{CurveGroup, Group, VariableBaseMSM};
+use ark_ff::PrimeField;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::{rand::thread_rng, UniformRand, Zero};
+use merlin::Transcript;
+use num_bigint::BigUint;
+
+use crate::dlog::{DLogPoK, PedersenOpening};
+use crate::transcript::ProofTranscript;
+use crate::utils::add_to_transcript;
+
+const CONTEXT_PI0: &[u8] = "creating sigma proof pi0 for BBS+ linking proof".as_bytes();
+const CONTEXT_BBS: &[u8] = "creating BBS+ signature proof of knowledge".as_bytes();
+
+/// Reduces a scalar from one prime field into another via a big-integer
+/// round trip. `BbsDeviceProof` needs this anywhere a value has to cross
+/// from the commitment curve `G` into the BBS+ pairing curve `E` (or back):
+/// both fields here are cryptographically large relative to the values
+/// actually carried (message scalars, proof nonces, Fiat-Shamir challenges),
+/// so the reduction doesn't wrap and is value-preserving in practice.
+fn reduce_scalar<From, To>(x: From) -> To
+where
+    From: PrimeField,
+    BigUint: From<From>,
+    To: PrimeField,
+{
+    let bytes: BigUint = BigUint::from(x);
+    To::from_le_bytes_mod_order(&bytes.to_bytes_le())
+}
+
+/// Public parameters for a single-hidden-attribute BBS+ scheme: `g1` is the
+/// constant term, `h0` is the base the blinding factor `s` is signed under,
+/// and `h1` is the base the device-bound scalar `m` is signed under.
+#[derive(Clone, Debug, CanonicalSerialize, CanonicalDeserialize)]
+pub struct BbsParams<E: Pairing> {
+    pub g1: E::G1Affine,
+    pub h0: E::G1Affine,
+    pub h1: E::G1Affine,
+    pub g2: E::G2Affine,
+}
+
+impl<E: Pairing> BbsParams<E> {
+    pub fn setup() -> Self {
+        let mut rng = thread_rng();
+        BbsParams {
+            g1: E::G1::rand(&mut rng).into_affine(),
+            h0: E::G1::rand(&mut rng).into_affine(),
+            h1: E::G1::rand(&mut rng).into_affine(),
+            g2: E::G2::rand(&mut rng).into_affine(),
+        }
+    }
+}
+
+/// A BBS+ issuer keypair. Only a test/demo issuer is modeled here, mirroring
+/// `TestDevice` in `device.rs`.
+pub struct BbsIssuer<E: Pairing> {
+    sk: E::ScalarField,
+    pub pk: E::G2Affine,
+}
+
+#[derive(Clone, Debug, CanonicalSerialize, CanonicalDeserialize)]
+pub struct BbsSignature<E: Pairing> {
+    pub a: E::G1Affine,
+    pub e: E::ScalarField,
+    pub s: E::ScalarField,
+}
+
+impl<E: Pairing> BbsIssuer<E> {
+    pub fn new_with_keygen(params: &BbsParams<E>) -> Self {
+        let mut rng = thread_rng();
+        let sk = E::ScalarField::rand(&mut rng);
+        let pk = (params.g2 * sk).into_affine();
+        Self { sk, pk }
+    }
+
+    /// Signs the single hidden message `m` (the device key's bound scalar),
+    /// picking fresh `(e, s)` as in standard BBS+ issuance:
+    /// `A = (g1 * h0^s * h1^m)^{1/(e+x)}`.
+    pub fn sign(&self, params: &BbsParams<E>, m: E::ScalarField) -> BbsSignature<E> {
+        let mut rng = thread_rng();
+        let e = E::ScalarField::rand(&mut rng);
+        let s = E::ScalarField::rand(&mut rng);
+        let b = params.g1 + params.h0 * s + params.h1 * m;
+        let exp = (e + self.sk).inverse().unwrap();
+        let a = (b * exp).into_affine();
+        BbsSignature { a, e, s }
+    }
+}
+
+/// A proof of knowledge of a BBS+ signature on a *hidden* message `m`:
+/// unlike a typical selective-disclosure BBS+ proof, `m` is never disclosed
+/// here, not even in masked form, since `BbsDeviceProof` uses it purely as
+/// an internal linking value rather than a credential attribute the
+/// verifier is meant to learn. Binds the re-randomized signature components
+/// `(A', Abar, d)` to responses for `(e, r2, r3, s', m)` via a Fiat-Shamir
+/// sigma protocol over two linear equations in `G1`, plus a pairing check
+/// `e(Abar, g2) == e(A', pk)`. `link` is opaque bytes the caller folds into
+/// this proof's transcript before the challenge is drawn, binding it to an
+/// external commitment to the same `m` (see `BbsDeviceProof`) without ever
+/// hashing `m` itself.
+#[derive(Clone, Debug, CanonicalSerialize, CanonicalDeserialize)]
+pub struct BbsSigPoK<E: Pairing> {
+    pub a_prime: E::G1Affine,
+    pub a_bar: E::G1Affine,
+    pub d: E::G1Affine,
+    pub c: E::ScalarField,
+    pub s_e: E::ScalarField,
+    pub s_r2: E::ScalarField,
+    pub s_r3: E::ScalarField,
+    pub s_s: E::ScalarField,
+    pub s_m: E::ScalarField,
+}
+
+impl<E: Pairing> BbsSigPoK<E> {
+    /// `m` and `m_t` are secret; only `link` is folded into the transcript,
+    /// so the caller must pass the identical `m_t` (reduced into `E`'s
+    /// scalar field) it used to build the external commitment `link`
+    /// commits to, or the two halves won't bind together.
+    pub fn prove(
+        params: &BbsParams<E>,
+        sig: &BbsSignature<E>,
+        m: E::ScalarField,
+        m_t: E::ScalarField,
+        link: &[u8],
+    ) -> Self {
+        let mut rng = thread_rng();
+        let r1 = E::ScalarField::rand(&mut rng);
+        let r2 = E::ScalarField::rand(&mut rng);
+        let r3 = r1.inverse().unwrap();
+
+        let b = params.g1 + params.h0 * sig.s + params.h1 * m;
+        let a_prime = (sig.a * r1).into_affine();
+        let a_bar = (a_prime * (-sig.e) + b * r1).into_affine();
+        let d = (b * r1 + params.h0 * (-r2)).into_affine();
+        let s_prime = sig.s - r2 * r3;
+
+        // Blinding terms for the two linear equations:
+        //   Abar/d = A'^{-e} * h0^{r2}
+        //   g1     = d^{r3} * h0^{-s'} * h1^{-m}     (m hidden, unlike before)
+        let e_t = E::ScalarField::rand(&mut rng);
+        let r2_t = E::ScalarField::rand(&mut rng);
+        let r3_t = E::ScalarField::rand(&mut rng);
+        let s_t = E::ScalarField::rand(&mut rng);
+
+        let k1 = a_prime * (-e_t) + params.h0 * r2_t;
+        let k2 = d * r3_t + params.h0 * (-s_t) + params.h1 * (-m_t);
+
+        let mut ts = Transcript::new(CONTEXT_BBS);
+        add_to_transcript(&mut ts, b"a_prime", &a_prime);
+        add_to_transcript(&mut ts, b"a_bar", &a_bar);
+        add_to_transcript(&mut ts, b"d", &d);
+        add_to_transcript(&mut ts, b"k1", &k1.into_affine());
+        add_to_transcript(&mut ts, b"k2", &k2.into_affine());
+        ts.append_message(b"link", link);
+        let c = ts.challenge_scalar::<E::ScalarField>(b"c");
+
+        let s_e = e_t - c * sig.e;
+        let s_r2 = r2_t - c * r2;
+        let s_r3 = r3_t - c * r3;
+        let s_s = s_t - c * s_prime;
+        let s_m = m_t - c * m;
+
+        BbsSigPoK { a_prime, a_bar, d, c, s_e, s_r2, s_r3, s_s, s_m }
+    }
+
+    pub fn verify(&self, params: &BbsParams<E>, pk: &E::G2Affine, link: &[u8]) -> bool {
+        if self.a_prime.into_group().is_zero() {
+            println!("Failed to verify BBS+ proof, A' is the identity");
+            return false;
+        }
+
+        let y1 = self.a_bar.into_group() - self.d.into_group();
+        let k1 = y1 * self.c + self.a_prime * (-self.s_e) + params.h0 * self.s_r2;
+
+        // `m` no longer appears on the public side of this equation -- only
+        // folded into k2/s_m above/below -- so y2 is just the constant g1.
+        let y2 = params.g1;
+        let k2 =
+            y2 * self.c + self.d * self.s_r3 + params.h0 * (-self.s_s) + params.h1 * (-self.s_m);
+
+        let mut ts = Transcript::new(CONTEXT_BBS);
+        add_to_transcript(&mut ts, b"a_prime", &self.a_prime);
+        add_to_transcript(&mut ts, b"a_bar", &self.a_bar);
+        add_to_transcript(&mut ts, b"d", &self.d);
+        add_to_transcript(&mut ts, b"k1", &k1.into_affine());
+        add_to_transcript(&mut ts, b"k2", &k2.into_affine());
+        ts.append_message(b"link", link);
+        let c = ts.challenge_scalar::<E::ScalarField>(b"c");
+
+        if c != self.c {
+            println!("Failed to verify BBS+ proof, challenge mismatch");
+            return false;
+        }
+
+        if E::pairing(self.a_bar, params.g2) != E::pairing(self.a_prime, *pk) {
+            println!("Failed to verify BBS+ proof, pairing check failed");
+            return false;
+        }
+
+        true
+    }
+}
+
+/// Links a Pedersen commitment `com0` to a BBS+ signature on the committed
+/// scalar, reusing the same `com1`/`pi0` linking machinery as `DeviceProof`
+/// so that showings built on this proof plug into the rest of the `creds`
+/// crate unchanged. Unlike `DeviceProof` (where the SNARK `pi2` takes a
+/// *masked* version of the linking value as a public input and unmasks it
+/// internally), the BBS+ signature here is issued directly over the raw
+/// linking scalar, so there is no mask to substitute in its place -- instead
+/// `bbs_pok` and `m_commit_t`/`s_m`/`s_r` both treat that scalar as a hidden
+/// witness, sharing `bbs_pok.c` (reduced into `G::ScalarField`) as a single
+/// challenge so the two halves can't be mixed-and-matched across different
+/// hidden values.
+#[derive(Clone, Debug, CanonicalSerialize, CanonicalDeserialize)]
+pub struct BbsDeviceProof<G: Group, E: Pairing> {
+    com1: G,
+    pi0: DLogPoK<G>,
+    bbs_pok: BbsSigPoK<E>,
+    m_commit_t: G,
+    s_m: G::ScalarField,
+    s_r: G::ScalarField,
+}
+
+impl<G: Group, E: Pairing> BbsDeviceProof<G, E> {
+    pub fn prove(
+        com0: &PedersenOpening<G>,
+        com1: &PedersenOpening<G>,
+        bbs_params: &BbsParams<E>,
+        sig: &BbsSignature<E>,
+    ) -> Self
+    where
+        G: CurveGroup + VariableBaseMSM,
+        G::ScalarField: PrimeField,
+        BigUint: From<G::ScalarField>,
+        BigUint: From<E::ScalarField>,
+    {
+        // com1 and com0 have different bases, so re-create com1 on com0's
+        // bases and prove it commits to the same message (exactly as in
+        // `DeviceProof::prove`).
+        let com1_orig = com1;
+        let com1 = DLogPoK::<G>::pedersen_commit(&com1_orig.m, &com0.bases);
+        let bases1 = vec![com1_orig.bases[0].into(), com1_orig.bases[1].into()];
+        let bases2 = vec![com0.bases[0].into(), com0.bases[1].into()];
+        let scalars1 = vec![com1_orig.m, com1_orig.r];
+        let scalars2 = vec![com1.m, com1.r];
+        let pi0 = DLogPoK::prove(Some(CONTEXT_PI0), &[com1_orig.c, com1.c], &[bases1, bases2], &[scalars1, scalars2], Some(vec![vec![(0, 0), (1, 0)]]));
+
+        // The device-bound scalar stays hidden from here on: it's only ever
+        // proved consistent between `com0 + com1` and `bbs_pok`, never
+        // disclosed, so repeated presentations from the same device aren't
+        // linkable by comparing a revealed value.
+        let m = com0.m + com1.m;
+        let r = com0.r + com1.r;
+
+        let mut rng = thread_rng();
+        let m_t = G::ScalarField::rand(&mut rng);
+        let r_t = G::ScalarField::rand(&mut rng);
+        let g = com0.bases[0].into();
+        let h = com0.bases[1].into();
+        let m_commit_t = g * m_t + h * r_t;
+
+        let mut link = Vec::new();
+        m_commit_t.into_affine().serialize_compressed(&mut link).unwrap();
+
+        let m_bbs = reduce_scalar::<G::ScalarField, E::ScalarField>(m);
+        let m_t_bbs = reduce_scalar::<G::ScalarField, E::ScalarField>(m_t);
+        let bbs_pok = BbsSigPoK::prove(bbs_params, sig, m_bbs, m_t_bbs, &link);
+
+        let c = reduce_scalar::<E::ScalarField, G::ScalarField>(bbs_pok.c);
+        let s_m = m_t - c * m;
+        let s_r = r_t - c * r;
+
+        BbsDeviceProof { com1: com1.c, pi0, bbs_pok, m_commit_t, s_m, s_r }
+    }
+
+    pub fn verify(
+        proof: &BbsDeviceProof<G, E>,
+        com0: &G::Affine,
+        com1: &G::Affine,
+        bases: &[G::Affine],
+        bases_com1: &[G::Affine],
+        bbs_params: &BbsParams<E>,
+        bbs_pk: &E::G2Affine,
+    ) -> bool
+    where
+        G: CurveGroup + VariableBaseMSM,
+        G::ScalarField: PrimeField,
+        BigUint: From<G::ScalarField>,
+        BigUint: From<E::ScalarField>,
+    {
+        let bases1 = vec![bases_com1[0].into(), bases_com1[1].into()];
+        let bases2 = vec![bases[0].into(), bases[1].into()];
+        let pi0_valid = DLogPoK::verify(&proof.pi0, Some(CONTEXT_PI0), &[bases1, bases2], &[(*com1).into(), proof.com1], Some(vec![vec![(0, 0), (1, 0)]]));
+        if !pi0_valid {
+            println!("Failed to verify BBS+ device proof, proof.pi0 did not verify");
+            return false;
+        }
+        let com1 = proof.com1;
+
+        let mut link = Vec::new();
+        proof.m_commit_t.into_affine().serialize_compressed(&mut link).unwrap();
+
+        if !proof.bbs_pok.verify(bbs_params, bbs_pk, &link) {
+            println!("Failed to verify BBS+ device proof, bbs_pok did not verify");
+            return false;
+        }
+
+        // Verify the hidden-m linking proof: knowledge of (m, r) opening
+        // `com0 + com1 = g^m h^r`, under the same challenge `bbs_pok.c`
+        // (reduced into G::ScalarField) used to hide m in `bbs_pok` above.
+        let g = bases[0].into();
+        let h = bases[1].into();
+        let com_m = *com0 + com1;
+        let c = reduce_scalar::<E::ScalarField, G::ScalarField>(proof.bbs_pok.c);
+        let lhs = g * proof.s_m + h * proof.s_r + com_m * c;
+        if lhs != proof.m_commit_t {
+            println!("Failed to verify BBS+ device proof, hidden-m linking proof did not verify");
+            return false;
+        }
+
+        true
+    }
+}
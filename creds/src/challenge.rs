@@ -0,0 +1,102 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT license.
+
+// A verifier-issued challenge that replaces relying solely on
+// `SHOW_PROOF_VALIDITY_SECONDS`/the prover's own clock for show proof
+// freshness: the verifier picks a random `nonce` and a `not_after` deadline,
+// the prover folds both (plus `audience`) into the show proof's context via
+// `ProofSpecInternal::nonce`/`audience`/`not_after` (see `show_context`), and
+// `verify_show`/`verify_show_mdl` reject the proof unless it carries back the
+// exact challenge the verifier issued. `NonceLedger` gives the verifier a way
+// to also reject a second presentation of the same challenge, independent of
+// wall-clock synchronization between prover and verifier.
+
+use std::collections::HashMap;
+
+use ark_std::rand::{thread_rng, RngCore};
+use serde::{Deserialize, Serialize};
+
+/// A single-use presentation challenge issued by a verifier.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VerifierChallenge {
+    pub nonce: [u8; 32],
+    /// The identifier of the verifier issuing this challenge; see
+    /// `ProofSpec::audience`.
+    pub audience: String,
+    /// UNIX timestamp (seconds) after which a show proof carrying this
+    /// challenge must be rejected, regardless of the prover's own clock.
+    pub not_after: u64,
+}
+
+impl VerifierChallenge {
+    /// Issues a fresh challenge for `audience`, valid until `now + ttl_seconds`.
+    pub fn new(audience: String, now: u64, ttl_seconds: u64) -> Self {
+        let mut nonce = [0u8; 32];
+        thread_rng().fill_bytes(&mut nonce);
+        VerifierChallenge { nonce, audience, not_after: now + ttl_seconds }
+    }
+}
+
+/// A verifier's short-lived record of challenge nonces it has already
+/// accepted a show proof for, so a captured proof can't be replayed against
+/// the same verifier a second time within the challenge's validity window.
+/// Entries are purged once their `not_after` deadline has passed, so the
+/// ledger doesn't grow without bound.
+#[derive(Default)]
+pub struct NonceLedger {
+    seen: HashMap<[u8; 32], u64>,
+}
+
+impl NonceLedger {
+    pub fn new() -> Self {
+        NonceLedger { seen: HashMap::new() }
+    }
+
+    /// Returns `true` and records `challenge.nonce` if it has not been seen
+    /// before and `challenge.not_after` has not yet passed; returns `false`
+    /// (without recording it) otherwise. Callers should treat `false` as a
+    /// replay or an expired challenge and reject the presentation.
+    pub fn check_and_record(&mut self, challenge: &VerifierChallenge, now: u64) -> bool {
+        self.seen.retain(|_, not_after| *not_after > now);
+
+        if now > challenge.not_after || self.seen.contains_key(&challenge.nonce) {
+            return false;
+        }
+        self.seen.insert(challenge.nonce, challenge.not_after);
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_and_record_accepts_fresh_challenge_once() {
+        let challenge = VerifierChallenge::new("https://verifier.example".to_string(), 1_000, 300);
+        let mut ledger = NonceLedger::new();
+
+        assert!(ledger.check_and_record(&challenge, 1_010));
+        assert!(!ledger.check_and_record(&challenge, 1_020), "replayed nonce must be rejected");
+    }
+
+    #[test]
+    fn test_check_and_record_rejects_expired_challenge() {
+        let challenge = VerifierChallenge::new("https://verifier.example".to_string(), 1_000, 300);
+        let mut ledger = NonceLedger::new();
+
+        assert!(!ledger.check_and_record(&challenge, 1_301));
+    }
+
+    #[test]
+    fn test_check_and_record_purges_expired_entries() {
+        let early = VerifierChallenge::new("https://verifier.example".to_string(), 1_000, 100);
+        let later = VerifierChallenge::new("https://verifier.example".to_string(), 1_200, 300);
+        let mut ledger = NonceLedger::new();
+
+        assert!(ledger.check_and_record(&early, 1_010));
+        // `early` has now expired; recording `later` should purge it from the ledger.
+        assert!(ledger.check_and_record(&later, 1_210));
+        assert_eq!(ledger.seen.len(), 1);
+    }
+}
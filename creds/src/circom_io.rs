@@ -0,0 +1,142 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT license.
+
+// Ingests circom's own compiler output directly -- the `.r1cs` constraint
+// system and the `.sym` symbol table it emits alongside it -- instead of
+// requiring a pre-digested `io_locations.sym` CSV to be handed to us by an
+// external script. This lets a circuit author compile *any* relation with
+// circom (custom JWT claim shapes, mDL fields, ...) and get back the
+// `IOLocations` Crescent needs to build a `ClientState`, without hand-writing
+// the wire layout.
+//
+// circom's symbol table lists every signal in the circuit, in declaration
+// order, as `wireIdx,origIdx,componentIdx,signalName`. Only the first
+// `1 + n_pub_out + n_pub_in` wires (wire 0 is the constant `one` signal) are
+// public, in that order -- which is exactly the layout Groth16's
+// `gamma_abc_g1` uses, so the wire index doubles as the location that
+// `IOLocations::get_io_location` callers index into directly.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{self, ErrorKind};
+
+use crate::structs::IOLocations;
+
+fn read_u32(bytes: &[u8], offset: usize) -> io::Result<u32> {
+    bytes
+        .get(offset..offset + 4)
+        .map(|b| u32::from_le_bytes(b.try_into().unwrap()))
+        .ok_or_else(|| io::Error::new(ErrorKind::UnexpectedEof, "r1cs file truncated"))
+}
+
+fn read_u64(bytes: &[u8], offset: usize) -> io::Result<u64> {
+    bytes
+        .get(offset..offset + 8)
+        .map(|b| u64::from_le_bytes(b.try_into().unwrap()))
+        .ok_or_else(|| io::Error::new(ErrorKind::UnexpectedEof, "r1cs file truncated"))
+}
+
+/// Number of public output/input wires declared in a circom `.r1cs` file's
+/// header section, read directly from circom's binary format: magic +
+/// version + section count, then one `type(u32) + size(u64) + payload`
+/// section per entry. The header section (type 1) starts with
+/// `field_size(u32) + prime(field_size bytes) + n_wires(u32) + n_pub_out(u32)
+/// + n_pub_in(u32) + ...`.
+fn read_r1cs_public_counts(r1cs_path: &str) -> io::Result<(u32, u32)> {
+    let data = fs::read(r1cs_path)?;
+
+    if data.len() < 12 || &data[0..4] != b"r1cs" {
+        return Err(io::Error::new(ErrorKind::InvalidData, "not a circom r1cs file (bad magic)"));
+    }
+
+    let n_sections = read_u32(&data, 8)?;
+    let mut pos = 12usize;
+    for _ in 0..n_sections {
+        let section_type = read_u32(&data, pos)?;
+        let section_size = read_u64(&data, pos + 4)? as usize;
+        let payload_start = pos + 12;
+
+        if section_type == 1 {
+            let field_size = read_u32(&data, payload_start)? as usize;
+            let after_prime = payload_start + 4 + field_size;
+            let n_pub_out = read_u32(&data, after_prime + 4)?;
+            let n_pub_in = read_u32(&data, after_prime + 8)?;
+            return Ok((n_pub_out, n_pub_in));
+        }
+
+        pos = payload_start + section_size;
+    }
+
+    Err(io::Error::new(ErrorKind::InvalidData, "r1cs file has no header section"))
+}
+
+/// Parses `sym_data` (the contents of a circom `.sym` file), keeping only
+/// the main component's public signals -- wires `1..=n_pub_out + n_pub_in`
+/// -- and builds the `name -> location` map `IOLocations` wraps.
+fn parse_public_signals(sym_data: &str, n_pub_out: u32, n_pub_in: u32) -> BTreeMap<String, usize> {
+    let last_public_wire = n_pub_out + n_pub_in;
+    let mut public_io_locations = BTreeMap::default();
+
+    for line in sym_data.lines() {
+        let parts: Vec<&str> = line.split(',').collect();
+        if parts.len() != 4 {
+            continue;
+        }
+        let Ok(wire_idx) = parts[0].parse::<u32>() else {
+            continue;
+        };
+        if wire_idx == 0 || wire_idx > last_public_wire {
+            continue;
+        }
+        // Names are emitted as `main.<signal>` (or `main.<signal>[i]` for
+        // array entries); strip the component prefix to match the naming
+        // `IOLocations::get_io_location` callers already use (`exp_value`,
+        // `<attr>_digest`, ...).
+        let name = parts[3].strip_prefix("main.").unwrap_or(parts[3]).to_string();
+        public_io_locations.insert(name, wire_idx as usize);
+    }
+
+    public_io_locations
+}
+
+/// Builds an `IOLocations` straight from circom's own `.r1cs`/`.sym` compiler
+/// output, in place of the pre-digested `io_locations.sym` CSV the example
+/// app's fixed RS256 circuit used to ship instead.
+pub fn io_locations_from_circom(r1cs_path: &str, sym_path: &str) -> io::Result<IOLocations> {
+    let (n_pub_out, n_pub_in) = read_r1cs_public_counts(r1cs_path)?;
+    let sym_data = fs::read_to_string(sym_path)?;
+
+    Ok(IOLocations {
+        public_io_locations: parse_public_signals(&sym_data, n_pub_out, n_pub_in),
+        ..Default::default()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_public_signals_keeps_only_main_public_wires() {
+        let sym_data = "\
+0,0,0,main.one\n\
+1,1,0,main.exp_value\n\
+2,2,0,main.pubkey_0\n\
+3,3,0,main.secret_salt\n\
+4,4,1,sub.internal_wire\n";
+
+        let locations = parse_public_signals(sym_data, 0, 2);
+        assert_eq!(locations.len(), 2);
+        assert_eq!(locations.get("exp_value"), Some(&1));
+        assert_eq!(locations.get("pubkey_0"), Some(&2));
+        assert_eq!(locations.get("secret_salt"), None);
+    }
+
+    #[test]
+    fn test_parse_public_signals_ignores_malformed_lines() {
+        let sym_data = "not,enough,columns\n1,1,0,main.exp_value\n";
+        let locations = parse_public_signals(sym_data, 0, 1);
+        assert_eq!(locations.len(), 1);
+        assert_eq!(locations.get("exp_value"), Some(&1));
+    }
+}
@@ -0,0 +1,320 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT license.
+
+// Minimal CBOR support for COSE_Sign1 (RFC 8152) / CWT (RFC 8392) credentials,
+// so the prover can accept CBOR-encoded verifiable credentials alongside
+// base64url JSON JWTs without pulling in a general-purpose CBOR crate just to
+// walk four well-known top-level items.
+//
+// A COSE_Sign1 object is the CBOR array
+//   [ protected: bstr, unprotected: map, payload: bstr, signature: bstr ]
+// (optionally wrapped in CBOR tag 18, `application/cose; cose-type="cose-sign1"`).
+// `protected` is itself CBOR-encoded bytes containing a map; the only entry
+// this module needs out of it is label 1 (`alg`). The bytes that are
+// actually signed are the deterministically-encoded `Sig_structure`
+// (RFC 8152 section 4.4):
+//   [ "Signature1", protected, external_aad, payload ]
+// with `external_aad` the empty byte string for a bare CWT.
+//
+// CWT claims (RFC 8392) are a CBOR map keyed by small integer labels (1 =
+// iss, 4 = exp, ...) rather than JSON object keys, so claim config entries
+// for a `credtype: "cwt"` credential are named by the decimal label (e.g.
+// `"4"` for `exp`) instead of a JSON field name.
+
+use std::io::{self, ErrorKind};
+use serde_json::{json, Value};
+
+/// COSE algorithm identifiers this module understands (RFC 8152 section 8.1).
+const COSE_ALG_ES256: i64 = -7;
+const COSE_ALG_RS256: i64 = -257;
+
+pub struct CoseSign1 {
+    pub protected: Vec<u8>,
+    pub payload: Vec<u8>,
+    pub signature: Vec<u8>,
+}
+
+fn eof() -> io::Error {
+    io::Error::new(ErrorKind::UnexpectedEof, "cbor item truncated")
+}
+
+pub(crate) fn major_minor(data: &[u8], pos: usize) -> io::Result<(u8, u8)> {
+    let byte = *data.get(pos).ok_or_else(eof)?;
+    Ok((byte >> 5, byte & 0x1f))
+}
+
+fn read_be(data: &[u8], pos: usize, n: usize) -> io::Result<u64> {
+    let bytes = data.get(pos..pos + n).ok_or_else(eof)?;
+    Ok(bytes.iter().fold(0u64, |acc, b| (acc << 8) | *b as u64))
+}
+
+/// Reads the (possibly multi-byte) length/value that follows a CBOR initial
+/// byte's low 5 bits, returning it alongside the offset right after it.
+pub(crate) fn read_length(data: &[u8], pos: usize, minor: u8) -> io::Result<(u64, usize)> {
+    match minor {
+        0..=23 => Ok((minor as u64, pos + 1)),
+        24 => Ok((*data.get(pos + 1).ok_or_else(eof)? as u64, pos + 2)),
+        25 => Ok((read_be(data, pos + 1, 2)?, pos + 3)),
+        26 => Ok((read_be(data, pos + 1, 4)?, pos + 5)),
+        27 => Ok((read_be(data, pos + 1, 8)?, pos + 9)),
+        _ => Err(io::Error::new(ErrorKind::InvalidData, "unsupported CBOR length encoding")),
+    }
+}
+
+/// Returns the offset right after the CBOR item starting at `pos`, without
+/// interpreting its value. Used to skip over map/array entries (like the
+/// COSE_Sign1 unprotected header) this module doesn't otherwise need.
+pub(crate) fn skip_item(data: &[u8], pos: usize) -> io::Result<usize> {
+    let (major, minor) = major_minor(data, pos)?;
+    match major {
+        0 | 1 => Ok(read_length(data, pos, minor)?.1),
+        2 | 3 => {
+            let (len, next) = read_length(data, pos, minor)?;
+            Ok(next + len as usize)
+        }
+        4 => {
+            let (len, mut next) = read_length(data, pos, minor)?;
+            for _ in 0..len {
+                next = skip_item(data, next)?;
+            }
+            Ok(next)
+        }
+        5 => {
+            let (len, mut next) = read_length(data, pos, minor)?;
+            for _ in 0..len * 2 {
+                next = skip_item(data, next)?;
+            }
+            Ok(next)
+        }
+        6 => {
+            let (_, next) = read_length(data, pos, minor)?;
+            skip_item(data, next)
+        }
+        7 => match minor {
+            0..=23 => Ok(pos + 1),
+            24 => Ok(pos + 2),
+            25 => Ok(pos + 3),
+            26 => Ok(pos + 5),
+            27 => Ok(pos + 9),
+            _ => Err(io::Error::new(ErrorKind::InvalidData, "unsupported CBOR simple value")),
+        },
+        _ => unreachable!("major type is 3 bits"),
+    }
+}
+
+pub(crate) fn read_int(data: &[u8], pos: usize) -> io::Result<(i64, usize)> {
+    let (major, minor) = major_minor(data, pos)?;
+    let (val, next) = read_length(data, pos, minor)?;
+    match major {
+        0 => Ok((val as i64, next)),
+        1 => Ok((-1 - val as i64, next)),
+        _ => Err(io::Error::new(ErrorKind::InvalidData, "expected a CBOR integer")),
+    }
+}
+
+pub(crate) fn read_bstr(data: &[u8], pos: usize) -> io::Result<(&[u8], usize)> {
+    let (major, minor) = major_minor(data, pos)?;
+    if major != 2 {
+        return Err(io::Error::new(ErrorKind::InvalidData, "expected a CBOR byte string"));
+    }
+    let (len, next) = read_length(data, pos, minor)?;
+    let bytes = data.get(next..next + len as usize).ok_or_else(eof)?;
+    Ok((bytes, next + len as usize))
+}
+
+/// Parses a (possibly tag-18-wrapped) COSE_Sign1 CBOR array into its four
+/// components, leaving the unprotected header map unparsed (Crescent has no
+/// use for anything in it).
+pub fn decode_cose_sign1(data: &[u8]) -> io::Result<CoseSign1> {
+    let (major, minor) = major_minor(data, 0)?;
+    let pos = if major == 6 {
+        read_length(data, 0, minor)?.1
+    } else {
+        0
+    };
+
+    let (major, minor) = major_minor(data, pos)?;
+    if major != 4 {
+        return Err(io::Error::new(ErrorKind::InvalidData, "COSE_Sign1 is not a CBOR array"));
+    }
+    let (len, pos) = read_length(data, pos, minor)?;
+    if len != 4 {
+        return Err(io::Error::new(ErrorKind::InvalidData, format!("COSE_Sign1 array has {} elements, expected 4", len)));
+    }
+
+    let (protected, pos) = read_bstr(data, pos)?;
+    let pos = skip_item(data, pos)?; // unprotected map
+    let (payload, pos) = read_bstr(data, pos)?;
+    let (signature, _pos) = read_bstr(data, pos)?;
+
+    Ok(CoseSign1 {
+        protected: protected.to_vec(),
+        payload: payload.to_vec(),
+        signature: signature.to_vec(),
+    })
+}
+
+/// Extracts the `alg` (label 1) entry from a COSE_Sign1's protected header
+/// bytes -- itself a CBOR-encoded map.
+pub fn protected_header_alg(protected: &[u8]) -> io::Result<i64> {
+    let (major, minor) = major_minor(protected, 0)?;
+    if major != 5 {
+        return Err(io::Error::new(ErrorKind::InvalidData, "protected header is not a CBOR map"));
+    }
+    let (len, mut pos) = read_length(protected, 0, minor)?;
+    for _ in 0..len {
+        let (key, value_pos) = read_int(protected, pos)?;
+        if key == 1 {
+            return Ok(read_int(protected, value_pos)?.0);
+        }
+        pos = skip_item(protected, value_pos)?;
+    }
+    Err(io::Error::new(ErrorKind::InvalidData, "protected header is missing the alg (label 1) entry"))
+}
+
+/// Maps a COSE algorithm identifier onto the `alg` strings Crescent already
+/// supports for JWTs, so `credtype: "cwt"` credentials reuse the existing
+/// per-algorithm signature/public-key handling.
+pub fn cose_alg_to_jwt_alg(alg: i64) -> Option<&'static str> {
+    match alg {
+        COSE_ALG_ES256 => Some("ES256"),
+        COSE_ALG_RS256 => Some("RS256"),
+        _ => None,
+    }
+}
+
+fn encode_length(major: u8, len: u64) -> Vec<u8> {
+    let mut out = Vec::new();
+    if len < 24 {
+        out.push((major << 5) | len as u8);
+    } else if len <= u8::MAX as u64 {
+        out.push((major << 5) | 24);
+        out.push(len as u8);
+    } else if len <= u16::MAX as u64 {
+        out.push((major << 5) | 25);
+        out.extend_from_slice(&(len as u16).to_be_bytes());
+    } else if len <= u32::MAX as u64 {
+        out.push((major << 5) | 26);
+        out.extend_from_slice(&(len as u32).to_be_bytes());
+    } else {
+        out.push((major << 5) | 27);
+        out.extend_from_slice(&len.to_be_bytes());
+    }
+    out
+}
+
+fn encode_bstr(bytes: &[u8]) -> Vec<u8> {
+    let mut out = encode_length(2, bytes.len() as u64);
+    out.extend_from_slice(bytes);
+    out
+}
+
+fn encode_tstr(s: &str) -> Vec<u8> {
+    let mut out = encode_length(3, s.len() as u64);
+    out.extend_from_slice(s.as_bytes());
+    out
+}
+
+/// Builds the `Sig_structure` bytes (RFC 8152 section 4.4) a COSE_Sign1's
+/// signature actually covers, given its protected header and payload byte
+/// strings (`external_aad` is empty, as it is for a bare CWT). Also returns
+/// the offset of `payload`'s bytes within the returned buffer, so callers
+/// can translate a byte interval found in `payload` alone (see
+/// `find_value_interval_cbor`) into an offset into the witnessed message.
+pub fn build_sig_structure(protected: &[u8], payload: &[u8]) -> (Vec<u8>, usize) {
+    let mut out = encode_length(4, 4);
+    out.extend(encode_tstr("Signature1"));
+    out.extend(encode_bstr(protected));
+    out.extend(encode_bstr(&[]));
+    let payload_header = encode_length(2, payload.len() as u64);
+    out.extend_from_slice(&payload_header);
+    let payload_offset = out.len();
+    out.extend_from_slice(payload);
+    (out, payload_offset)
+}
+
+/// CBOR-aware sibling of `prep_inputs::find_value_interval`: given a CWT
+/// payload (a CBOR map keyed by integer claim labels, per RFC 8392) and a
+/// claim label, returns the `[l, r)` byte interval of that claim's *value*
+/// within `payload`, for the circuit's substring-matching gadgets to locate
+/// claims the same way they locate JSON claim offsets.
+pub fn find_value_interval_cbor(payload: &[u8], claim_label: i64) -> io::Result<(usize, usize)> {
+    let (major, minor) = major_minor(payload, 0)?;
+    if major != 5 {
+        return Err(io::Error::new(ErrorKind::InvalidData, "CWT payload is not a CBOR map"));
+    }
+    let (len, mut pos) = read_length(payload, 0, minor)?;
+    for _ in 0..len {
+        let (key, value_start) = read_int(payload, pos)?;
+        let value_end = skip_item(payload, value_start)?;
+        if key == claim_label {
+            return Ok((value_start, value_end));
+        }
+        pos = value_end;
+    }
+    Err(io::Error::new(ErrorKind::InvalidData, format!("claim label {} not found in CWT payload", claim_label)))
+}
+
+/// Decodes a CWT claim's value into a `serde_json::Value`, for claim types
+/// Crescent's claim-reveal/hash machinery already understands. Only
+/// integers and text strings are supported -- the same "number"/"string"
+/// claim types `prepare_prover_claim_inputs` packs for JWT claims.
+pub fn decode_claim_value_cbor(payload: &[u8], claim_label: i64) -> io::Result<Value> {
+    let (l, _r) = find_value_interval_cbor(payload, claim_label)?;
+    let (major, minor) = major_minor(payload, l)?;
+    match major {
+        0 | 1 => Ok(json!(read_int(payload, l)?.0)),
+        3 => {
+            let (len, next) = read_length(payload, l, minor)?;
+            let bytes = payload.get(next..next + len as usize).ok_or_else(eof)?;
+            let s = std::str::from_utf8(bytes).map_err(|_| io::Error::new(ErrorKind::InvalidData, "CWT claim text string is not valid UTF-8"))?;
+            Ok(json!(s))
+        }
+        _ => Err(io::Error::new(ErrorKind::InvalidData, format!("CWT claim {} has an unsupported CBOR value type", claim_label))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // [ h'A10126', {}, h'A101676261722E62617A', h'0102' ]
+    // protected = {1: -7} (alg: ES256), payload = {1: "bar.baz"}, signature = 0x0102
+    fn sample_cose_sign1() -> Vec<u8> {
+        vec![
+            0x84, // array(4)
+            0x43, 0xa1, 0x01, 0x26, // bstr(3): protected = {1: -7}
+            0xa0, // unprotected map (empty)
+            0x4a, 0xa1, 0x01, 0x67, 0x62, 0x61, 0x72, 0x2e, 0x62, 0x61, 0x7a, // bstr(10): payload = {1: "bar.baz"}
+            0x42, 0x01, 0x02, // bstr(2): signature
+        ]
+    }
+
+    #[test]
+    fn test_decode_cose_sign1_splits_the_four_elements() {
+        let cose = decode_cose_sign1(&sample_cose_sign1()).unwrap();
+        assert_eq!(cose.signature, vec![0x01, 0x02]);
+        assert_eq!(protected_header_alg(&cose.protected).unwrap(), COSE_ALG_ES256);
+        assert_eq!(cose_alg_to_jwt_alg(COSE_ALG_ES256), Some("ES256"));
+    }
+
+    #[test]
+    fn test_find_value_interval_cbor_locates_claim_value_bytes() {
+        let cose = decode_cose_sign1(&sample_cose_sign1()).unwrap();
+        let (l, r) = find_value_interval_cbor(&cose.payload, 1).unwrap();
+        assert_eq!(&cose.payload[l..r], &[0x67, 0x62, 0x61, 0x72, 0x2e, 0x62, 0x61, 0x7a][..]);
+        assert_eq!(decode_claim_value_cbor(&cose.payload, 1).unwrap(), json!("bar.baz"));
+    }
+
+    #[test]
+    fn test_find_value_interval_cbor_missing_label_errors() {
+        let cose = decode_cose_sign1(&sample_cose_sign1()).unwrap();
+        assert!(find_value_interval_cbor(&cose.payload, 4).is_err());
+    }
+
+    #[test]
+    fn test_build_sig_structure_reports_payload_offset() {
+        let (sig_structure, payload_offset) = build_sig_structure(&[0xa1, 0x01, 0x26], b"hello");
+        assert_eq!(&sig_structure[payload_offset..payload_offset + 5], b"hello");
+    }
+}
@@ -1,11 +1,25 @@
 // Copyright (c) Microsoft Corporation.
 // Licensed under the MIT license.
 
-use chrono::{DateTime, Datelike, Local};
+use chrono::{DateTime, Datelike, Local, NaiveDate, TimeZone, Utc};
+use thiserror::Error;
 
-// The function ymd_to_ordinal() and supporting functions are ported from 
+// The function ymd_to_ordinal() and supporting functions are ported from
 //    https://github.com/python/cpython/blob/54b5e4da8a4c6ae527ab238fcd6b9ba0a3ed0fc7/Lib/datetime.py#L63
 
+/// Why a day-count calculation in this module failed. Replaces the old
+/// convention of `assert!`-ing on bad input, which would abort the whole
+/// process (or, for the wasm build, poison the WebAssembly instance).
+#[derive(Debug, Error)]
+pub enum DateError {
+    #[error("month must be in 1..=12, got {0}")]
+    InvalidMonth(usize),
+    #[error("day must be in 1..={max_day} for the given month/year, got {day}")]
+    InvalidDay { day: usize, max_day: usize },
+    #[error("age {age} is not reachable from year {year}")]
+    AgeNotReachable { age: usize, year: usize },
+}
+
 // usize::MAX is a placeholder for indexing purposes.
 const DAYS_IN_MONTH: [usize; 13] = [usize::MAX, 31, 28, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31];
 const DAYS_BEFORE_MONTH : [usize; 13] = [usize::MAX, 0, 31, 59, 90, 120, 151, 181, 212, 243, 273, 304, 334];
@@ -19,46 +33,109 @@ fn days_before_year(year: usize) -> usize {
     y*365 + y/4 + y/100 + y/400
 }
 // Returns the number of days in that month in that year.
-fn days_in_month(year: usize, month: usize) -> usize {
-    assert!((1..=12).contains(&month));
+fn days_in_month(year: usize, month: usize) -> Result<usize, DateError> {
+    if !(1..=12).contains(&month) {
+        return Err(DateError::InvalidMonth(month));
+    }
     if month == 2 && is_leap(year) {
-        return 29;
+        return Ok(29);
     }
-    DAYS_IN_MONTH[month]
+    Ok(DAYS_IN_MONTH[month])
 }
 // Returns the number of days in the year preceding the first day of the given month.
-fn days_before_month(year: usize, month: usize) -> usize {
-    assert!((1..=12).contains(&month), "month must be in 1..12");
+fn days_before_month(year: usize, month: usize) -> Result<usize, DateError> {
+    if !(1..=12).contains(&month) {
+        return Err(DateError::InvalidMonth(month));
+    }
     let extra_day = if month > 2 && is_leap(year) { 1 } else { 0 };
-    DAYS_BEFORE_MONTH[month] + extra_day
+    Ok(DAYS_BEFORE_MONTH[month] + extra_day)
 }
 // Converts year, month, day to ordinal, considering 01-Jan-0001 as day 1.
-fn ymd_to_ordinal(year: usize, month: usize, day: usize) -> usize {
-    assert!((1..=12).contains(&month), "month must be in 1..12");
-    let dim = days_in_month(year, month);
-    assert!(1 <= day && day <= dim, "day must be in 1..{}", dim);
-    days_before_year(year) + days_before_month(year, month) + day
-}  
+fn ymd_to_ordinal(year: usize, month: usize, day: usize) -> Result<usize, DateError> {
+    if !(1..=12).contains(&month) {
+        return Err(DateError::InvalidMonth(month));
+    }
+    let dim = days_in_month(year, month)?;
+    if !(1 <= day && day <= dim) {
+        return Err(DateError::InvalidDay { day, max_day: dim });
+    }
+    Ok(days_before_year(year) + days_before_month(year, month)? + day)
+}
 
 
 
 
-pub fn days_to_be_age(age : usize) -> usize {
+/// Unix timestamp (seconds, UTC midnight) of January 1st of `year`. Reuses
+/// `ymd_to_ordinal` rather than pulling in a second date-arithmetic path, by
+/// taking the day-count relative to the 1970-01-01 epoch.
+pub(crate) fn unix_seconds_for_year_start(year: usize) -> Result<i64, DateError> {
+    let ordinal = ymd_to_ordinal(year, 1, 1)?;
+    let epoch_ordinal = ymd_to_ordinal(1970, 1, 1)?;
+    Ok((ordinal as i64 - epoch_ordinal as i64) * 86400)
+}
+
+/// Where `days_to_be_age` gets "today" from. `chrono::Local::now()` has no
+/// real implementation under `wasm32-unknown-unknown` (no system clock or
+/// timezone), so the wasm build needs to source the current date from the
+/// host JS environment instead -- see [`WasmClock`]. Abstracting this out
+/// also makes `days_to_be_age` testable against a fixed date.
+pub trait Clock {
+    fn today(&self) -> NaiveDate;
+}
 
-    let local: DateTime<Local> = Local::now();
-    let today = local.date_naive();
+/// The default clock for native builds: the host's local date.
+pub struct NativeClock;
+
+impl Clock for NativeClock {
+    fn today(&self) -> NaiveDate {
+        let local: DateTime<Local> = Local::now();
+        local.date_naive()
+    }
+}
+
+/// A clock backed by a unix-seconds source, e.g. the wasm build's
+/// `js_now_seconds` import, since there is no local timezone to speak of in
+/// that environment.
+pub struct WasmClock<F: Fn() -> u64> {
+    now_seconds: F,
+}
+
+impl<F: Fn() -> u64> WasmClock<F> {
+    pub fn new(now_seconds: F) -> Self {
+        WasmClock { now_seconds }
+    }
+}
+
+impl<F: Fn() -> u64> Clock for WasmClock<F> {
+    fn today(&self) -> NaiveDate {
+        let secs = (self.now_seconds)() as i64;
+        Utc.timestamp_opt(secs, 0)
+            .single()
+            .expect("js_now_seconds should be a valid unix timestamp")
+            .date_naive()
+    }
+}
+
+pub fn days_to_be_age(age : usize, clock: &dyn Clock) -> Result<usize, DateError> {
+
+    let today = clock.today();
     let year = today.year() as usize;
     let month = today.month() as usize;
     let mut day = today.day() as usize;
 
-    let today_stamp = ymd_to_ordinal(year, month, day);
+    let today_stamp = ymd_to_ordinal(year, month, day)?;
     if month == 2 && day == 29 {
         day = 28;
     }
-    let past_stamp = ymd_to_ordinal(year - age, month, day);
+    if age >= year {
+        return Err(DateError::AgeNotReachable { age, year });
+    }
+    let past_stamp = ymd_to_ordinal(year - age, month, day)?;
 
-    assert!(today_stamp > past_stamp);
+    if today_stamp <= past_stamp {
+        return Err(DateError::AgeNotReachable { age, year });
+    }
     println!("To be {} years old, you must be {} days old", age, today_stamp - past_stamp);
 
-    today_stamp - past_stamp
+    Ok(today_stamp - past_stamp)
 }
\ No newline at end of file
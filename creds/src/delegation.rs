@@ -0,0 +1,289 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT license.
+
+//! UCAN-style delegated, attenuated presentation grants.
+//!
+//! A [`DelegationGrant`] lets a credential holder (the *issuer*) authorize
+//! another party (the *audience*) to produce a show proof on the holder's
+//! behalf, restricted to a subset of `crescent://` disclosure uids
+//! (`wasm_lib::DiscUid`) and valid only until `not_after`. Grants chain: the
+//! audience of one grant can re-delegate by issuing a further grant, but
+//! [`verify_delegation_chain`] only accepts a chain whose disclosures
+//! attenuate (never grow past) their parent's, mirroring how UCAN
+//! capabilities may only narrow as they're re-delegated.
+
+use p256::ecdsa::signature::hazmat::{PrehashSigner, PrehashVerifier};
+use p256::ecdsa::{Signature, SigningKey, VerifyingKey};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeSet;
+use thiserror::Error;
+
+/// Why a delegation grant or chain failed to verify.
+#[derive(Debug, Error)]
+pub enum DelegationError {
+    #[error("delegation chain is empty")]
+    EmptyChain,
+    #[error("grant public key is not a valid SEC1-encoded p256 point")]
+    InvalidPublicKey,
+    #[error("grant signature is malformed")]
+    MalformedSignature,
+    #[error("grant signature did not verify")]
+    InvalidSignature,
+    #[error("grant expired: not_after {not_after} <= now {now}")]
+    Expired { not_after: i64, now: i64 },
+    #[error("grant issuer does not match the previous grant's audience")]
+    IssuerNotParentAudience,
+    #[error("disclosure {0:?} is not permitted by this delegation chain")]
+    DisclosureNotPermitted(String),
+}
+
+/// A single signed, time-bounded link in a delegation chain: "`issuer_pubkey`
+/// authorizes `audience_pubkey` to show only `allowed_disclosures`, until
+/// `not_after`". `issuer_pubkey`/`audience_pubkey` are SEC1-encoded
+/// (uncompressed) p256 points, matching `TestDevice::get_public_key`'s key
+/// encoding; `signature` is a raw (r, s) ECDSA signature over
+/// [`DelegationGrant::signed_bytes`], matching `TestDevice::sign`'s
+/// prehash-then-sign convention.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DelegationGrant {
+    pub issuer_pubkey: Vec<u8>,
+    pub audience_pubkey: Vec<u8>,
+    pub allowed_disclosures: Vec<String>,
+    pub not_after: i64,
+    pub signature: Vec<u8>,
+}
+
+impl DelegationGrant {
+    fn signed_bytes(
+        issuer_pubkey: &[u8],
+        audience_pubkey: &[u8],
+        allowed_disclosures: &[String],
+        not_after: i64,
+    ) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(issuer_pubkey);
+        bytes.extend_from_slice(audience_pubkey);
+        for disclosure in allowed_disclosures {
+            bytes.extend_from_slice(disclosure.as_bytes());
+            bytes.push(0);
+        }
+        bytes.extend_from_slice(&not_after.to_le_bytes());
+        bytes
+    }
+
+    /// Issues a grant from `issuer_key` to `audience_pubkey`, signing it so
+    /// [`verify_delegation_chain`] can check it later.
+    pub fn issue(
+        issuer_key: &SigningKey,
+        audience_pubkey: &VerifyingKey,
+        allowed_disclosures: Vec<String>,
+        not_after: i64,
+    ) -> Self {
+        let issuer_pubkey = issuer_key.verifying_key().to_sec1_bytes().to_vec();
+        let audience_pubkey = audience_pubkey.to_sec1_bytes().to_vec();
+        let digest = Sha256::digest(Self::signed_bytes(
+            &issuer_pubkey,
+            &audience_pubkey,
+            &allowed_disclosures,
+            not_after,
+        ));
+        let signature: Signature = issuer_key
+            .sign_prehash(&digest)
+            .expect("failed to sign delegation grant");
+
+        DelegationGrant {
+            issuer_pubkey,
+            audience_pubkey,
+            allowed_disclosures,
+            not_after,
+            signature: signature.to_bytes().to_vec(),
+        }
+    }
+
+    fn verify_signature(&self) -> Result<(), DelegationError> {
+        let issuer_key = VerifyingKey::from_sec1_bytes(&self.issuer_pubkey)
+            .map_err(|_| DelegationError::InvalidPublicKey)?;
+        let signature = Signature::from_slice(&self.signature)
+            .map_err(|_| DelegationError::MalformedSignature)?;
+        let digest = Sha256::digest(Self::signed_bytes(
+            &self.issuer_pubkey,
+            &self.audience_pubkey,
+            &self.allowed_disclosures,
+            self.not_after,
+        ));
+        issuer_key
+            .verify_prehash(&digest, &signature)
+            .map_err(|_| DelegationError::InvalidSignature)
+    }
+}
+
+/// Verifies an ordered delegation chain (root grant first) as of `now`
+/// (unix seconds): every grant's signature must verify, none may be expired,
+/// and -- other than the root -- each grant's issuer must be the previous
+/// grant's audience. Returns the intersection of every grant's
+/// `allowed_disclosures` (the set the final audience may actually show) and
+/// that audience's public key, which the caller should bind into
+/// `presentation_message`.
+pub fn verify_delegation_chain(
+    chain: &[DelegationGrant],
+    now: i64,
+) -> Result<(BTreeSet<String>, Vec<u8>), DelegationError> {
+    let (first, rest) = chain.split_first().ok_or(DelegationError::EmptyChain)?;
+
+    first.verify_signature()?;
+    if first.not_after <= now {
+        return Err(DelegationError::Expired { not_after: first.not_after, now });
+    }
+    let mut allowed: BTreeSet<String> = first.allowed_disclosures.iter().cloned().collect();
+    let mut audience_pubkey = &first.audience_pubkey;
+
+    for grant in rest {
+        grant.verify_signature()?;
+        if grant.not_after <= now {
+            return Err(DelegationError::Expired { not_after: grant.not_after, now });
+        }
+        if &grant.issuer_pubkey != audience_pubkey {
+            return Err(DelegationError::IssuerNotParentAudience);
+        }
+        let this_allowed: BTreeSet<String> = grant.allowed_disclosures.iter().cloned().collect();
+        allowed = allowed.intersection(&this_allowed).cloned().collect();
+        audience_pubkey = &grant.audience_pubkey;
+    }
+
+    Ok((allowed, audience_pubkey.clone()))
+}
+
+/// Checks that every uid in `disc_uids` is covered by `allowed`, the
+/// intersection [`verify_delegation_chain`] returned.
+pub fn check_disclosures_permitted(
+    disc_uids: &[String],
+    allowed: &BTreeSet<String>,
+) -> Result<(), DelegationError> {
+    for uid in disc_uids {
+        if !allowed.contains(uid) {
+            return Err(DelegationError::DisclosureNotPermitted(uid.clone()));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::thread_rng;
+
+    fn keypair() -> SigningKey {
+        SigningKey::random(&mut thread_rng())
+    }
+
+    #[test]
+    fn test_single_grant_round_trip() {
+        let issuer = keypair();
+        let audience = keypair();
+        let grant = DelegationGrant::issue(
+            &issuer,
+            audience.verifying_key(),
+            vec!["crescent://over_18".to_string()],
+            i64::MAX,
+        );
+        let (allowed, audience_pubkey) = verify_delegation_chain(&[grant], 0).unwrap();
+        assert_eq!(allowed, BTreeSet::from(["crescent://over_18".to_string()]));
+        assert_eq!(audience_pubkey, audience.verifying_key().to_sec1_bytes().to_vec());
+    }
+
+    #[test]
+    fn test_chain_attenuates_to_intersection() {
+        let root_issuer = keypair();
+        let mid = keypair();
+        let leaf = keypair();
+
+        let root_grant = DelegationGrant::issue(
+            &root_issuer,
+            mid.verifying_key(),
+            vec!["crescent://over_18".to_string(), "crescent://over_21".to_string()],
+            i64::MAX,
+        );
+        let mid_grant = DelegationGrant::issue(
+            &mid,
+            leaf.verifying_key(),
+            vec!["crescent://over_18".to_string()],
+            i64::MAX,
+        );
+
+        let (allowed, audience_pubkey) =
+            verify_delegation_chain(&[root_grant, mid_grant], 0).unwrap();
+        assert_eq!(allowed, BTreeSet::from(["crescent://over_18".to_string()]));
+        assert_eq!(audience_pubkey, leaf.verifying_key().to_sec1_bytes().to_vec());
+    }
+
+    #[test]
+    fn test_rejects_expired_grant() {
+        let issuer = keypair();
+        let audience = keypair();
+        let grant = DelegationGrant::issue(
+            &issuer,
+            audience.verifying_key(),
+            vec!["crescent://over_18".to_string()],
+            100,
+        );
+        let err = verify_delegation_chain(&[grant], 200).unwrap_err();
+        assert!(matches!(err, DelegationError::Expired { not_after: 100, now: 200 }));
+    }
+
+    #[test]
+    fn test_rejects_broken_chain_link() {
+        let root_issuer = keypair();
+        let mid = keypair();
+        let impostor = keypair();
+        let leaf = keypair();
+
+        let root_grant = DelegationGrant::issue(
+            &root_issuer,
+            mid.verifying_key(),
+            vec!["crescent://over_18".to_string()],
+            i64::MAX,
+        );
+        // Signed by `impostor`, not `mid` -- the chain's audience/issuer links don't match.
+        let mid_grant = DelegationGrant::issue(
+            &impostor,
+            leaf.verifying_key(),
+            vec!["crescent://over_18".to_string()],
+            i64::MAX,
+        );
+
+        let err = verify_delegation_chain(&[root_grant, mid_grant], 0).unwrap_err();
+        assert!(matches!(err, DelegationError::IssuerNotParentAudience));
+    }
+
+    #[test]
+    fn test_rejects_tampered_grant() {
+        let issuer = keypair();
+        let audience = keypair();
+        let mut grant = DelegationGrant::issue(
+            &issuer,
+            audience.verifying_key(),
+            vec!["crescent://over_18".to_string()],
+            i64::MAX,
+        );
+        grant.allowed_disclosures.push("crescent://over_21".to_string());
+        let err = verify_delegation_chain(&[grant], 0).unwrap_err();
+        assert!(matches!(err, DelegationError::InvalidSignature));
+    }
+
+    #[test]
+    fn test_disclosure_not_permitted() {
+        let issuer = keypair();
+        let audience = keypair();
+        let grant = DelegationGrant::issue(
+            &issuer,
+            audience.verifying_key(),
+            vec!["crescent://over_18".to_string()],
+            i64::MAX,
+        );
+        let (allowed, _) = verify_delegation_chain(&[grant], 0).unwrap();
+        let err = check_disclosures_permitted(&["crescent://over_21".to_string()], &allowed)
+            .unwrap_err();
+        assert!(matches!(err, DelegationError::DisclosureNotPermitted(_)));
+    }
+}
@@ -19,8 +19,8 @@ const CONTEXT_PI0 : &[u8] = "creating sigma proof pi0 for linking proof".as_byte
 const CONTEXT_PI1 : &[u8] = "creating sigma proof pi1 for linking proof".as_bytes();
 
 pub struct ECDSASig {
-    pub r: BigUint, 
-    pub s: BigUint, 
+    pub r: BigUint,
+    pub s: BigUint,
     pub digest: Vec<u8>
 }
 pub struct TestDevice {
@@ -28,6 +28,32 @@ pub struct TestDevice {
     public_key : VerifyingKey
 }
 
+/// Provisions the key bound into a `device_bound` credential and signs a
+/// presentation-message digest with it, so `run_show` isn't tied to one
+/// specific signing backend. [`TestDevice`] below is the original
+/// software/PEM-file-backed implementation; `device_ctap2::Ctap2Device`
+/// (behind the `ctap2` feature) backs it with a real CTAP2/WebAuthn
+/// platform authenticator instead, returning a [`crate::DeviceSignature`]
+/// the authenticator actually produced rather than a raw ECDSA signature in
+/// every case.
+pub trait DeviceSigner {
+    /// The public key to bind into the credential during `Prepare`.
+    fn get_public_key(&self) -> (BigUint, BigUint);
+    /// Signs `digest` (the SHA-256 hash of the presentation message, or --
+    /// for a CTAP2 backend -- the `get-assertion` challenge), returning the
+    /// device signature in whatever form this backend produces it.
+    fn sign(&self, digest: &[u8]) -> crate::DeviceSignature;
+}
+
+impl DeviceSigner for TestDevice {
+    fn get_public_key(&self) -> (BigUint, BigUint) {
+        TestDevice::get_public_key(self)
+    }
+    fn sign(&self, digest: &[u8]) -> crate::DeviceSignature {
+        crate::DeviceSignature::RawEcdsa(TestDevice::sign(self, digest))
+    }
+}
+
 impl ECDSASig {
     pub fn new_from_bytes(digest: &[u8], sig_bytes: &[u8]) -> Self {
         assert!(sig_bytes.len() == 64);
@@ -65,17 +91,24 @@ impl TestDevice {
         sig.to_bytes().to_vec()
     }
     pub fn get_public_key(&self) -> (BigUint, BigUint) {
-        let pk_bytes = self.public_key.to_sec1_bytes(); 
+        let pk_bytes = self.public_key.to_sec1_bytes();
         assert!(pk_bytes[0] == 0x04);// make sure it's uncompressed
         let pk_bytes = &pk_bytes[1..];
         assert!(pk_bytes.len() == 64);
         let (pk_x, pk_y) = pk_bytes.split_at(32);
         let pk_x = BigUint::from_bytes_be(pk_x);
         let pk_y = BigUint::from_bytes_be(pk_y);
-        
+
         (pk_x, pk_y)
     }
-}    
+    /// This device's raw signing scalar, reduced into `F` -- lets
+    /// [`crate::vrf`] derive a scoped pseudonym from the same secret that
+    /// backs this device's ECDSA signatures, without exposing the p256
+    /// `SigningKey` itself.
+    pub fn secret_scalar<F: PrimeField>(&self) -> F {
+        F::from_be_bytes_mod_order(self.keypair.to_bytes().as_slice())
+    }
+}
 
 
 
@@ -121,7 +154,7 @@ impl<G: Group> DeviceProof<G> {
         let bases2 = vec![com0.bases[0].into(), com0.bases[1].into()];
         let scalars1 = vec![com1_orig.m, com1_orig.r];
         let scalars2 = vec![com1.m, com1.r];
-        let pi0 = DLogPoK::prove(Some(CONTEXT_PI0), &[com1_orig.c, com1.c], &[bases1, bases2], &[scalars1, scalars2], Some(vec![(0,0)]));
+        let pi0 = DLogPoK::prove(Some(CONTEXT_PI0), &[com1_orig.c, com1.c], &[bases1, bases2], &[scalars1, scalars2], Some(vec![vec![(0, 0), (1, 0)]]));
 
         let mut sha2 = Sha256::new();
         sha2.update(CONTEXT_E);
@@ -174,7 +207,7 @@ impl<G: Group> DeviceProof<G> {
         //  {(m, r0, r1) : com1_orig = G1^m H1^r1  AND  com1 = G0^m H0^r0}
         let bases1 = vec![bases_com1[0].into(), bases_com1[1].into()];
         let bases2 = vec![bases[0].into(), bases[1].into()];
-        let pi0_valid = DLogPoK::verify(&proof.pi0, Some(CONTEXT_PI0), &[bases1, bases2], &[(*com1).into(), proof.com1], Some(vec![(0,0)]));
+        let pi0_valid = DLogPoK::verify(&proof.pi0, Some(CONTEXT_PI0), &[bases1, bases2], &[(*com1).into(), proof.com1], Some(vec![vec![(0, 0), (1, 0)]]));
         if !pi0_valid {
             println!("Failed to verify device proof, proof.pi0 did not verify");
             return false;
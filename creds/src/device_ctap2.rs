@@ -0,0 +1,88 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT license.
+
+//! A [`crate::device::DeviceSigner`] backed by a real CTAP2/FIDO2 platform
+//! authenticator (a security key, or a phone/laptop's built-in one) instead
+//! of [`crate::device::TestDevice`]'s PEM file on disk. Uses `ctap-hid-fido2`
+//! to talk CTAP2 over USB HID: `make_credential` during `Prepare` provisions
+//! the device key the credential gets bound to (the authenticator attests
+//! to its own P-256 public key, rather than one generated in software and
+//! written to a file); `get_assertion` during `Show` signs the presentation
+//! message digest as the CTAP2 challenge, and the resulting assertion is
+//! carried in `show_proof.device_proof` as `DeviceBindingProof::WebAuthn`.
+
+use std::error::Error;
+
+use ctap_hid_fido2::fidokey::{FidoKeyHid, GetAssertionArgsBuilder, MakeCredentialArgsBuilder};
+use ctap_hid_fido2::{Cfg, FidoKeyHidFactory};
+use num_bigint::BigUint;
+use p256::ecdsa::VerifyingKey;
+use p256::pkcs8::DecodePublicKey;
+
+use crate::device::DeviceSigner;
+use crate::webauthn::Assertion;
+use crate::DeviceSignature;
+
+/// A device-bound credential's key lives on the authenticator itself; this
+/// only caches the `rp_id`/`credential_id` `make_credential` returned plus
+/// the attested public key, so repeated `get_assertion` calls during `Show`
+/// don't need to re-provision a credential.
+pub struct Ctap2Device {
+    device: FidoKeyHid,
+    rp_id: String,
+    credential_id: Vec<u8>,
+    public_key: VerifyingKey,
+}
+
+impl Ctap2Device {
+    /// Provisions a fresh device-bound credential via CTAP2 `make_credential`
+    /// (the authenticator's equivalent of [`crate::device::TestDevice::new_with_keygen`]),
+    /// prompting for user presence/verification on the authenticator itself.
+    pub fn new_with_keygen(rp_id: &str, pin: Option<&str>) -> Result<Self, Box<dyn Error>> {
+        let device = FidoKeyHidFactory::create(&Cfg::init())?;
+        let challenge = vec![0u8; 32]; // unused by the prover; only the attestation is kept
+
+        let att = device.make_credential(
+            &MakeCredentialArgsBuilder::new(rp_id, &challenge)
+                .pin(pin.unwrap_or(""))
+                .build(),
+        )?;
+
+        let public_key = VerifyingKey::from_public_key_der(&att.credential_public_key.der)?;
+
+        Ok(Self {
+            device,
+            rp_id: rp_id.to_string(),
+            credential_id: att.credential_descriptor.id.clone(),
+            public_key,
+        })
+    }
+}
+
+impl DeviceSigner for Ctap2Device {
+    fn get_public_key(&self) -> (BigUint, BigUint) {
+        let pk_bytes = self.public_key.to_sec1_bytes();
+        assert!(pk_bytes[0] == 0x04); // uncompressed
+        let (x, y) = pk_bytes[1..].split_at(32);
+        (BigUint::from_bytes_be(x), BigUint::from_bytes_be(y))
+    }
+
+    /// Signs `digest` as a CTAP2 `get_assertion` challenge, returning the
+    /// authenticator's raw assertion rather than a bare ECDSA signature --
+    /// `verify_device_binding_proof`'s `WebAuthn` path checks the signature
+    /// against `authenticator_data || sha256(client_data_json)` directly, so
+    /// there's no ZK proof step on the prover side for this mode.
+    fn sign(&self, digest: &[u8]) -> DeviceSignature {
+        let assertion = self.device.get_assertion(
+            &GetAssertionArgsBuilder::new(&self.rp_id, digest)
+                .credential_id(&self.credential_id)
+                .build(),
+        ).expect("CTAP2 get_assertion failed");
+
+        DeviceSignature::WebAuthn(Assertion {
+            authenticator_data: assertion.auth_data,
+            client_data_json: assertion.client_data_json,
+            signature: assertion.signature,
+        })
+    }
+}
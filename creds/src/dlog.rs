@@ -6,11 +6,11 @@ use crate::utils::msm_select;
 use ark_ec::CurveGroup;
 use ark_ec::Group;
 use ark_ec::VariableBaseMSM;
-use ark_ff::Field;
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
-use ark_std::{end_timer, rand::thread_rng, start_timer, UniformRand};
+use ark_std::{end_timer, rand::thread_rng, start_timer, UniformRand, Zero};
 use merlin::Transcript;
 
+use crate::transcript::ProofTranscript;
 use crate::utils::add_to_transcript;
 
 #[derive(Clone, Debug, Default, CanonicalSerialize, CanonicalDeserialize)]
@@ -19,6 +19,18 @@ pub struct DLogPoK<G: Group> {
     pub s: Vec<Vec<G::ScalarField>>,
 }
 
+/// Wire-format companion to [`DLogPoK`] that drops the redundant responses
+/// `eq_classes` implies: within each class only the first `(statement, position)`
+/// is kept, since `verify` can recompute the rest as equal to it before
+/// recomputing the challenge. Positions outside any class are transmitted in full.
+/// The index map needed to reinflate them isn't stored here -- it's exactly
+/// `eq_classes`, which the verifier already holds as part of the proof spec.
+#[derive(Clone, Debug, Default, CanonicalSerialize, CanonicalDeserialize)]
+pub struct CompressedDLogPoK<G: Group> {
+    pub c: G::ScalarField,
+    pub s: Vec<Vec<G::ScalarField>>,
+}
+
 // helper struct to store a commitment c = g1^m * g2^r
 #[derive(Clone, Debug, CanonicalSerialize, CanonicalDeserialize)]
 pub struct PedersenOpening<G: CurveGroup> {
@@ -33,15 +45,39 @@ impl<G: Group> DLogPoK<G> {
     /// in their respective bases -- bases[1], bases[2], ... bases[n]
     ///     y[i] = \prod_{i=0}^n bases[i]^scalars[i]
     /// Optionally, the context is bound to the proof.
-    /// Optionally, when n=2, specify a set of positions to prove equality of scalars across the different statements.
-    /// For each pair (i,j) in eq_pos, the proof ensures that scalars[0][i] == scalars[1][j]. 
-    /// TODO (perf): shrink the proof size by compressing the responses since they're the same for all the equal positions
+    /// Optionally, `eq_classes` asserts equality of scalars across an arbitrary number of
+    /// statements: each class is a list of `(statement_index, position_index)` pairs, and the
+    /// proof ensures all positions named within a class hold the same scalar (e.g. the class
+    /// `[(0, i), (1, j), (2, k)]` asserts `scalars[0][i] == scalars[1][j] == scalars[2][k]`).
+    /// Pass the same `eq_classes` to [`DLogPoK::compress`] to shrink the proof before
+    /// serializing it, dropping the responses verification would recompute anyway.
     pub fn prove(
         context: Option<&[u8]>,
         y: &[G],
         bases: &[Vec<G>],
         scalars: &[Vec<G::ScalarField>],
-        eq_pos: Option<Vec<(usize, usize)>>,
+        eq_classes: Option<Vec<Vec<(usize, usize)>>>,
+    ) -> Self
+    where
+        G: CurveGroup + VariableBaseMSM,
+    {
+        let mut ts = Transcript::new(&[0u8]);
+        Self::prove_with_transcript(&mut ts, context, y, bases, scalars, eq_classes)
+    }
+
+    /// Same as [`DLogPoK::prove`], generic over the transcript backing the
+    /// Fiat-Shamir challenge. `prove` above is the normal entry point
+    /// (merlin-backed); pass a [`crate::transcript::PoseidonTranscript`]
+    /// instead when this proof needs to be cheaply re-verified inside a
+    /// downstream SNARK circuit -- e.g. as part of composing several
+    /// credential proofs recursively.
+    pub fn prove_with_transcript<T: ProofTranscript>(
+        ts: &mut T,
+        context: Option<&[u8]>,
+        y: &[G],
+        bases: &[Vec<G>],
+        scalars: &[Vec<G::ScalarField>],
+        eq_classes: Option<Vec<Vec<(usize, usize)>>>,
     ) -> Self
     where
         G: CurveGroup + VariableBaseMSM,
@@ -53,9 +89,8 @@ impl<G: Group> DLogPoK<G> {
         let mut k = Vec::new();
         let mut r = Vec::new();
 
-        let mut ts: Transcript = Transcript::new(&[0u8]);
         let context = context.unwrap_or(b"");
-        add_to_transcript(&mut ts, b"context string", &context);
+        ts.absorb_bytes(context);
 
         for i in 0..y.len() {
             let mut ri = Vec::new();
@@ -66,19 +101,24 @@ impl<G: Group> DLogPoK<G> {
             r.push(ri);
         }
 
-        if eq_pos.is_some() {
-            assert!(y.len() == 2);
-
-            for (i,j) in eq_pos.unwrap().iter() {
-                r[1][*j] = r[0][*i];
+        // Reuse a single fresh blinding across every position in a class, so the
+        // responses derived from it below come out equal too.
+        if let Some(classes) = eq_classes.as_ref() {
+            for class in classes {
+                if let Some(&(stmt0, pos0)) = class.first() {
+                    let r_shared = r[stmt0][pos0];
+                    for &(stmt, pos) in class.iter().skip(1) {
+                        r[stmt][pos] = r_shared;
+                    }
+                }
             }
         }
 
         for i in 0..y.len() {
             // add the bases, k and y to the transcript
-            add_to_transcript(&mut ts, b"num_bases", &bases[i].len());
+            ts.absorb_bytes(&(bases[i].len() as u64).to_le_bytes());
             for j in 0..bases[i].len() {
-                add_to_transcript(&mut ts, b"base", &bases[i][j]);
+                ts.absorb_group(&bases[i][j]);
             }
 
             let mut scalars = vec![];
@@ -89,14 +129,12 @@ impl<G: Group> DLogPoK<G> {
             let ki = msm_select::<G>(&bases_affine, &scalars);
 
             k.push(ki);
-            add_to_transcript(&mut ts, b"k", &k[i]);
-            add_to_transcript(&mut ts, b"y", &y[i]);
+            ts.absorb_group(&k[i]);
+            ts.absorb_group(&y[i]);
         }
 
         // get the challenge
-        let mut c_bytes = [0u8; 31];
-        ts.challenge_bytes(&[0u8], &mut c_bytes);
-        let c = G::ScalarField::from_random_bytes(&c_bytes).unwrap();
+        let c = ts.challenge::<G::ScalarField>();
 
         let mut s = Vec::new();
         for i in 0..y.len() {
@@ -119,17 +157,35 @@ impl<G: Group> DLogPoK<G> {
         context: Option<&[u8]>,
         bases: &[Vec<G>],
         y: &[G],
-        eq_pos: Option<Vec<(usize, usize)>>,
+        eq_classes: Option<Vec<Vec<(usize, usize)>>>,
     ) -> bool
     where
-        G: CurveGroup + VariableBaseMSM,    
+        G: CurveGroup + VariableBaseMSM,
+    {
+        let mut ts = Transcript::new(&[0u8]);
+        self.verify_with_transcript(&mut ts, context, bases, y, eq_classes)
+    }
+
+    /// Same as [`DLogPoK::verify`], generic over the transcript backing the
+    /// Fiat-Shamir challenge -- see [`DLogPoK::prove_with_transcript`]. The
+    /// verifier's transcript backend and sequence of absorptions must match
+    /// whatever the prover used, or the recomputed challenge simply won't match.
+    pub fn verify_with_transcript<T: ProofTranscript>(
+        &self,
+        ts: &mut T,
+        context: Option<&[u8]>,
+        bases: &[Vec<G>],
+        y: &[G],
+        eq_classes: Option<Vec<Vec<(usize, usize)>>>,
+    ) -> bool
+    where
+        G: CurveGroup + VariableBaseMSM,
     {
         // compute the challenge
         // serialize and hash the bases, k and y
         let dl_verify_timer = start_timer!(|| format!("DlogPoK verify y.len = {}", y.len()));
-        let mut ts: Transcript = Transcript::new(&[0u8]);
         let context = context.unwrap_or(b"");
-        add_to_transcript(&mut ts, b"context string", &context);
+        ts.absorb_bytes(context);
 
         let mut recomputed_k = Vec::new();
         for i in 0..y.len() {
@@ -144,29 +200,30 @@ impl<G: Group> DLogPoK<G> {
             let recomputed_ki = msm_select::<G>(&bases_affine, &scalars);
             recomputed_k.push(recomputed_ki);
 
-            add_to_transcript(&mut ts, b"num_bases", &bases[i].len());
+            ts.absorb_bytes(&(bases[i].len() as u64).to_le_bytes());
             for j in 0..bases[i].len() {
-                add_to_transcript(&mut ts, b"base", &bases[i][j]);
+                ts.absorb_group(&bases[i][j]);
             }
-            add_to_transcript(&mut ts, b"k", &recomputed_ki);
-            add_to_transcript(&mut ts, b"y", &y[i]);
+            ts.absorb_group(&recomputed_ki);
+            ts.absorb_group(&y[i]);
         }
 
-        if eq_pos.is_some() {
-            assert!(y.len() == 2);
-
-            for (i,j) in eq_pos.unwrap().iter() {
-                if self.s[0][*i] != self.s[1][*j] {
-                    println!("DLogPoK verification failed: eq_pos mismatch");
-                    return false;
+        if let Some(classes) = eq_classes.as_ref() {
+            for class in classes {
+                if let Some(&(stmt0, pos0)) = class.first() {
+                    let s0 = self.s[stmt0][pos0];
+                    for &(stmt, pos) in class.iter().skip(1) {
+                        if self.s[stmt][pos] != s0 {
+                            println!("DLogPoK verification failed: eq_classes mismatch");
+                            return false;
+                        }
+                    }
                 }
             }
-        }        
+        }
 
         // get the challenge
-        let mut c_bytes = [0u8; 31];
-        ts.challenge_bytes(&[0u8], &mut c_bytes);
-        let c = G::ScalarField::from_random_bytes(&c_bytes).unwrap();
+        let c = ts.challenge::<G::ScalarField>();
 
         end_timer!(dl_verify_timer);
 
@@ -174,6 +231,34 @@ impl<G: Group> DLogPoK<G> {
         c == self.c
     }
 
+    /// Drop every response whose value `eq_classes` already pins equal to another
+    /// one in the proof, i.e. all but the first `(statement, position)` of each
+    /// class. `verify`/[`CompressedDLogPoK::decompress`] reconstructs them, so this
+    /// is lossless with respect to verification -- just a smaller wire encoding.
+    pub fn compress(&self, eq_classes: &[Vec<(usize, usize)>]) -> CompressedDLogPoK<G> {
+        let mut omit = std::collections::HashSet::new();
+        for class in eq_classes {
+            for &(stmt, pos) in class.iter().skip(1) {
+                omit.insert((stmt, pos));
+            }
+        }
+
+        let s = self
+            .s
+            .iter()
+            .enumerate()
+            .map(|(i, si)| {
+                si.iter()
+                    .enumerate()
+                    .filter(|(j, _)| !omit.contains(&(i, *j)))
+                    .map(|(_, v)| *v)
+                    .collect()
+            })
+            .collect();
+
+        CompressedDLogPoK { c: self.c, s }
+    }
+
     // Computes Pedersen commitments
     pub fn pedersen_commit(
         m: &G::ScalarField,
@@ -208,6 +293,427 @@ impl<G: Group> DLogPoK<G> {
         }
         bases_g
     }
+
+    /// `n+1` bases for a vector Pedersen commitment to `n` messages: `g_1, .., g_n`
+    /// for the messages, followed by `h` for the blinding -- the same layout
+    /// [`DLogPoK::pedersen_commit`] uses for its single-message `[g, h]`.
+    pub fn derive_vector_pedersen_bases(n: usize) -> Vec<G::Affine>
+    where
+        G: CurveGroup,
+    {
+        let mut bases_g: Vec<G::Affine> = Vec::with_capacity(n + 1);
+        for i in 1..=n {
+            bases_g.push(hash_to_curve_vartime::<G>(&format!(
+                "Pedersen vector commitment base {}",
+                i
+            )));
+        }
+        bases_g.push(hash_to_curve_vartime::<G>(
+            "Pedersen vector commitment blinding base",
+        ));
+        bases_g
+    }
+
+    /// Computes the vector Pedersen commitment `C = h^r * prod_i g_i^{m_i}`.
+    pub fn vector_pedersen_commit(
+        m: &[G::ScalarField],
+        bases: &[G::Affine],
+    ) -> VectorPedersenOpening<G>
+    where
+        G: CurveGroup + VariableBaseMSM,
+    {
+        assert_eq!(bases.len(), m.len() + 1, "one base per message, plus the blinding base");
+        let mut rng = thread_rng();
+        let r = G::ScalarField::rand(&mut rng);
+        let mut scalars = m.to_vec();
+        scalars.push(r);
+        let c = msm_select::<G>(bases, &scalars);
+        VectorPedersenOpening {
+            bases: bases.to_vec(),
+            m: m.to_vec(),
+            r,
+            c,
+        }
+    }
+}
+
+// helper struct to store a vector commitment C = h^r * prod g_i^{m_i}
+#[derive(Clone, Debug, CanonicalSerialize, CanonicalDeserialize)]
+pub struct VectorPedersenOpening<G: CurveGroup> {
+    pub bases: Vec<G::Affine>,
+    pub m: Vec<G::ScalarField>,
+    pub r: G::ScalarField,
+    pub c: G,
+}
+
+/// Selective-disclosure proof over a [`VectorPedersenOpening`]: reveals the
+/// messages named in `disclosed` in the clear, and proves knowledge of every
+/// other message plus the blinding via a [`DLogPoK`] over the residual bases
+/// (the undisclosed message bases, plus `h`). The verifier subtracts each
+/// disclosed `g_i^{m_i}` from `C` to get the residual commitment the DLogPoK
+/// is checked against, so one vector commitment to all of an attribute set
+/// can back any number of selective-disclosure proofs over it, rather than
+/// needing a separate Pedersen commitment (and proof) per attribute.
+#[derive(Clone, Debug, CanonicalSerialize, CanonicalDeserialize)]
+pub struct PartialOpeningProof<G: Group> {
+    pub pok: DLogPoK<G>,
+}
+
+impl<G: Group> PartialOpeningProof<G> {
+    /// `disclosed` is the set of `(index, value)` pairs to reveal in the clear;
+    /// every index of `com.m` not named there stays hidden.
+    pub fn prove(
+        context: Option<&[u8]>,
+        com: &VectorPedersenOpening<G>,
+        disclosed: &[(usize, G::ScalarField)],
+    ) -> Self
+    where
+        G: CurveGroup + VariableBaseMSM,
+    {
+        let n = com.m.len();
+        for &(idx, val) in disclosed {
+            assert_eq!(
+                com.m[idx], val,
+                "PartialOpeningProof: disclosed value does not match the committed message at index {}",
+                idx
+            );
+        }
+        let disclosed_idx: std::collections::HashSet<usize> =
+            disclosed.iter().map(|(i, _)| *i).collect();
+
+        let mut residual_bases = Vec::new();
+        let mut residual_scalars = Vec::new();
+        for i in 0..n {
+            if !disclosed_idx.contains(&i) {
+                residual_bases.push(com.bases[i].into());
+                residual_scalars.push(com.m[i]);
+            }
+        }
+        residual_bases.push((*com.bases.last().unwrap()).into());
+        residual_scalars.push(com.r);
+
+        let mut y = com.c;
+        for &(idx, val) in disclosed {
+            y -= com.bases[idx] * val;
+        }
+
+        let pok = DLogPoK::prove(context, &[y], &[residual_bases], &[residual_scalars], None);
+        PartialOpeningProof { pok }
+    }
+
+    /// Checks the proof against the public commitment `c` and `bases` (the
+    /// same `n+1` bases used to create it), and the `disclosed` values the
+    /// prover is revealing.
+    pub fn verify(
+        &self,
+        context: Option<&[u8]>,
+        c: &G,
+        bases: &[G::Affine],
+        disclosed: &[(usize, G::ScalarField)],
+    ) -> bool
+    where
+        G: CurveGroup + VariableBaseMSM,
+    {
+        let n = bases.len() - 1;
+        let disclosed_idx: std::collections::HashSet<usize> =
+            disclosed.iter().map(|(i, _)| *i).collect();
+
+        let mut residual_bases = Vec::new();
+        for i in 0..n {
+            if !disclosed_idx.contains(&i) {
+                residual_bases.push(bases[i].into());
+            }
+        }
+        residual_bases.push((*bases.last().unwrap()).into());
+
+        let mut y = *c;
+        for &(idx, val) in disclosed {
+            if idx >= n {
+                println!("PartialOpeningProof verification failed: disclosed index out of range");
+                return false;
+            }
+            y -= bases[idx] * val;
+        }
+
+        self.pok.verify(context, &[residual_bases], &[y], None)
+    }
+}
+
+/// Width-`w` signed-digit (wNAF) table for one fixed base: `table[d]` holds
+/// `(2d+1) * base` for `d` in `0..2^{w-1}`. Only odd multiples are stored,
+/// since a wNAF digit's sign is applied via point negation (free for elliptic
+/// curve points) rather than doubling the table.
+fn build_wnaf_table<G: CurveGroup>(base: &G::Affine, window: usize) -> Vec<G::Affine> {
+    let half = 1usize << (window - 1);
+    let base: G = (*base).into();
+    let double = base + base;
+    let mut table = Vec::with_capacity(half);
+    let mut cur = base;
+    table.push(cur.into_affine());
+    for _ in 1..half {
+        cur += double;
+        table.push(cur.into_affine());
+    }
+    table
+}
+
+/// Signed-digit windowed-NAF decomposition of `scalar`, least-significant digit
+/// first. Every digit is `0` or odd, and lies in `(-2^{w-1}, 2^{w-1})`.
+fn wnaf_digits(scalar: &num_bigint::BigUint, window: usize) -> Vec<i32> {
+    use num_bigint::BigUint;
+    assert!((2..=20).contains(&window), "window width out of range");
+
+    let zero = BigUint::from(0u32);
+    let one = BigUint::from(1u32);
+    let two = BigUint::from(2u32);
+    let modulus = &one << window;
+    let half = 1u32 << (window - 1);
+    let mut k = scalar.clone();
+    let mut digits = Vec::new();
+
+    while k != zero {
+        if &k % &two == one {
+            let window_val: u32 = (&k % &modulus)
+                .to_u32_digits()
+                .first()
+                .copied()
+                .unwrap_or(0);
+            let digit = if window_val >= half {
+                window_val as i32 - (1i32 << window)
+            } else {
+                window_val as i32
+            };
+            digits.push(digit);
+            if digit >= 0 {
+                k -= BigUint::from(digit as u32);
+            } else {
+                k += BigUint::from((-digit) as u32);
+            }
+        } else {
+            digits.push(0);
+        }
+        k >>= 1usize;
+    }
+
+    digits
+}
+
+/// Evaluates `scalar * base` from its precomputed wNAF table by accumulating
+/// window digits MSB-first: double once per digit, then add (or subtract,
+/// for a negative digit) the table entry for that digit's magnitude.
+fn wnaf_scalar_mul<G: CurveGroup>(table: &[G::Affine], digits: &[i32]) -> G {
+    let mut acc = G::zero();
+    for &d in digits.iter().rev() {
+        acc += acc;
+        if d != 0 {
+            let p: G = table[(d.unsigned_abs() as usize - 1) / 2].into();
+            acc += if d > 0 { p } else { -p };
+        }
+    }
+    acc
+}
+
+/// Precomputes wNAF tables for a fixed set of bases (e.g. the two Pedersen
+/// generators) once, then evaluates commitments by window accumulation
+/// instead of a general-purpose MSM on every call. Worthwhile exactly when
+/// `bases` is reused across many commitments.
+pub struct FixedBaseCommitter<G: CurveGroup> {
+    bases: Vec<G::Affine>,
+    window: usize,
+    tables: Vec<Vec<G::Affine>>,
+}
+
+impl<G: CurveGroup> FixedBaseCommitter<G> {
+    /// Width-4 wNAF tables (8 precomputed points per base) are a reasonable
+    /// default for the 2-3 base case Pedersen commitments need; callers with
+    /// different tradeoffs can use [`FixedBaseCommitter::with_window`].
+    pub fn new(bases: &[G::Affine]) -> Self {
+        Self::with_window(bases, 4)
+    }
+
+    pub fn with_window(bases: &[G::Affine], window: usize) -> Self {
+        let tables = bases.iter().map(|b| build_wnaf_table::<G>(b, window)).collect();
+        FixedBaseCommitter {
+            bases: bases.to_vec(),
+            window,
+            tables,
+        }
+    }
+
+    /// `sum_i scalars[i] * bases[i]`, evaluated against the precomputed tables.
+    pub fn commit_vec(&self, scalars: &[G::ScalarField]) -> G {
+        assert_eq!(scalars.len(), self.bases.len());
+        scalars
+            .iter()
+            .zip(&self.tables)
+            .map(|(s, table)| {
+                let digits = wnaf_digits(&crate::utils::scalar_to_biguint(s), self.window);
+                wnaf_scalar_mul::<G>(table, &digits)
+            })
+            .fold(G::zero(), |acc, p| acc + p)
+    }
+
+    /// Pedersen commitment `g1^m * g2^r` over this committer's two bases.
+    pub fn commit(&self, m: &G::ScalarField, r: &G::ScalarField) -> PedersenOpening<G> {
+        assert_eq!(self.bases.len(), 2, "commit expects exactly 2 bases, like pedersen_commit");
+        let c = self.commit_vec(&[*m, *r]);
+        PedersenOpening {
+            bases: self.bases.clone(),
+            m: *m,
+            r: *r,
+            c,
+        }
+    }
+}
+
+impl<G: Group> CompressedDLogPoK<G> {
+    /// Reinflate the responses `DLogPoK::compress` dropped. `stmt_lens[i]` must be
+    /// `bases[i].len()` from the original statements -- the verifier already has
+    /// this from its own copy of `bases`, so it doesn't need to be carried on the wire.
+    pub fn decompress(&self, stmt_lens: &[usize], eq_classes: &[Vec<(usize, usize)>]) -> DLogPoK<G> {
+        let mut omitted = std::collections::HashSet::new();
+        for class in eq_classes {
+            for &(stmt, pos) in class.iter().skip(1) {
+                omitted.insert((stmt, pos));
+            }
+        }
+
+        let mut s: Vec<Vec<Option<G::ScalarField>>> =
+            stmt_lens.iter().map(|&n| vec![None; n]).collect();
+        for (i, si) in self.s.iter().enumerate() {
+            let mut transmitted = si.iter();
+            for j in 0..stmt_lens[i] {
+                if !omitted.contains(&(i, j)) {
+                    let v = *transmitted.next().expect("CompressedDLogPoK: truncated response vector");
+                    s[i][j] = Some(v);
+                }
+            }
+        }
+
+        for class in eq_classes {
+            if let Some(&(stmt0, pos0)) = class.first() {
+                let rep = s[stmt0][pos0].expect("CompressedDLogPoK: class representative not transmitted");
+                for &(stmt, pos) in class.iter().skip(1) {
+                    s[stmt][pos] = Some(rep);
+                }
+            }
+        }
+
+        let s = s
+            .into_iter()
+            .map(|si| si.into_iter().map(|v| v.expect("CompressedDLogPoK: missing response")).collect())
+            .collect();
+
+        DLogPoK { c: self.c, s }
+    }
+
+    /// Verify directly against the compressed wire form: reinflate the dropped
+    /// responses from `eq_classes` and `bases`' shapes, then defer to [`DLogPoK::verify`].
+    pub fn verify(
+        &self,
+        context: Option<&[u8]>,
+        bases: &[Vec<G>],
+        y: &[G],
+        eq_classes: Option<Vec<Vec<(usize, usize)>>>,
+    ) -> bool
+    where
+        G: CurveGroup + VariableBaseMSM,
+    {
+        let stmt_lens: Vec<usize> = bases.iter().map(|b| b.len()).collect();
+        let classes = eq_classes.clone().unwrap_or_default();
+        let full = self.decompress(&stmt_lens, &classes);
+        full.verify(context, bases, y, eq_classes)
+    }
+}
+
+const CONTEXT_SET_MEMBERSHIP: &[u8] = "set membership proof".as_bytes();
+
+/// Proves that a Pedersen-committed attribute equals one element of a public
+/// set `S = {s_0, .., s_{k-1}}`, without revealing which one, via a
+/// Cramer-Damgard-Schoenmakers OR-composition of Schnorr proofs: for the true
+/// index the branch is a real proof that `Com(x)/G^{s_i} == H^r`; every other
+/// branch is simulated by picking its response and challenge at random and
+/// back-computing the commitment they imply. The per-branch challenges are
+/// constrained to sum to the overall Fiat-Shamir challenge, so a cheating
+/// prover who doesn't know a true index would need to guess that challenge.
+#[derive(Clone, Debug, CanonicalSerialize, CanonicalDeserialize)]
+pub struct SetMembershipProof<G: Group> {
+    pub c: Vec<G::ScalarField>,
+    pub z: Vec<G::ScalarField>,
+}
+
+impl<G: Group> SetMembershipProof<G> {
+    pub fn prove(com: &PedersenOpening<G>, set: &[G::ScalarField]) -> Self
+    where
+        G: CurveGroup + VariableBaseMSM,
+    {
+        let true_index = set
+            .iter()
+            .position(|s| *s == com.m)
+            .expect("set membership proof: committed value is not a member of the set");
+
+        let g: G = com.bases[0].into();
+        let h: G = com.bases[1].into();
+        let mut rng = thread_rng();
+
+        let mut c = vec![G::ScalarField::zero(); set.len()];
+        let mut z = vec![G::ScalarField::zero(); set.len()];
+        let mut k = Vec::with_capacity(set.len());
+        let mut k_true = G::ScalarField::zero();
+
+        for (j, s_j) in set.iter().enumerate() {
+            let y_j = com.c - g * s_j;
+            if j == true_index {
+                k_true = G::ScalarField::rand(&mut rng);
+                k.push(h * k_true);
+            } else {
+                c[j] = G::ScalarField::rand(&mut rng);
+                z[j] = G::ScalarField::rand(&mut rng);
+                k.push(h * z[j] + y_j * c[j]);
+            }
+        }
+
+        let mut ts = Transcript::new(&[0u8]);
+        add_to_transcript(&mut ts, b"context string", &CONTEXT_SET_MEMBERSHIP);
+        for (s_j, k_j) in set.iter().zip(k.iter()) {
+            add_to_transcript(&mut ts, b"set element", s_j);
+            add_to_transcript(&mut ts, b"k", k_j);
+        }
+        let c_total = ts.challenge_scalar::<G::ScalarField>(b"set membership challenge");
+
+        let c_others: G::ScalarField = c.iter().enumerate().filter(|(j, _)| *j != true_index).map(|(_, c_j)| *c_j).sum();
+        c[true_index] = c_total - c_others;
+        z[true_index] = k_true - c[true_index] * com.r;
+
+        SetMembershipProof { c, z }
+    }
+
+    pub fn verify(&self, com: &PedersenOpening<G>, set: &[G::ScalarField]) -> bool
+    where
+        G: CurveGroup + VariableBaseMSM,
+    {
+        if self.c.len() != set.len() || self.z.len() != set.len() {
+            println!("SetMembershipProof verification failed: length mismatch");
+            return false;
+        }
+
+        let g: G = com.bases[0].into();
+        let h: G = com.bases[1].into();
+
+        let mut ts = Transcript::new(&[0u8]);
+        add_to_transcript(&mut ts, b"context string", &CONTEXT_SET_MEMBERSHIP);
+        for (j, s_j) in set.iter().enumerate() {
+            let y_j = com.c - g * s_j;
+            let k_j = h * self.z[j] + y_j * self.c[j];
+            add_to_transcript(&mut ts, b"set element", s_j);
+            add_to_transcript(&mut ts, b"k", &k_j);
+        }
+
+        let c_total = ts.challenge_scalar::<G::ScalarField>(b"set membership challenge");
+
+        let c_sum: G::ScalarField = self.c.iter().sum();
+        c_sum == c_total
+    }
 }
 
 #[cfg(test)]
@@ -241,7 +747,7 @@ mod tests {
             &[y, y],
             &[bases.clone(), bases.clone()],
             &[scalars.clone(), scalars.clone()],
-            None
+            None,
         );
 
         // verify with the wrong bases
@@ -250,7 +756,7 @@ mod tests {
             Some(context),
             &[wrong_bases.clone(), wrong_bases.clone()],
             &[y, y],
-            None
+            None,
         );
         assert!(!wrong_bases_result, "Verification should fail with the wrong bases");
 
@@ -260,7 +766,7 @@ mod tests {
             Some(wrong_context),
             &[bases.clone(), bases.clone()],
             &[y, y],
-            None
+            None,
         );
         assert!(!wrong_context_result, "Verification should fail with the wrong context data");
 
@@ -269,7 +775,7 @@ mod tests {
             Some(context),
             &[bases.clone(), bases.clone()],
             &[y, y],
-            None
+            None,
         );
 
         assert!(result);
@@ -320,20 +826,226 @@ mod tests {
         let y2 = msm_select(bases2, scalars2);
         let bases1_proj : Vec<G1> = bases1.iter().map(|x| (*x).into()).collect();
         let bases2_proj : Vec<G1> = bases2.iter().map(|x| (*x).into()).collect();
-        
+        let eq_classes: Vec<Vec<(usize, usize)>> = eq_pos.iter().map(|&(i, j)| vec![(0, i), (1, j)]).collect();
+
         let pok = DLogPoK::<G1>::prove(
             None,
             &[y1, y2],
             &[bases1_proj.clone(), bases2_proj.clone()],
             &[scalars1.clone(), scalars2.clone()],
-            Some(eq_pos.to_vec())
+            Some(eq_classes.clone()),
         );
 
         pok.verify(
             None,
             &[bases1_proj, bases2_proj],
             &[y1, y2],
-            Some(eq_pos.to_vec())
+            Some(eq_classes),
         )
     }
+
+    #[test]
+    fn test_dleq_three_statements() {
+        // Link the same scalar (e.g. a credential's attribute hash) across
+        // three independent statements in one proof, which a two-statement
+        // `eq_pos` couldn't express.
+        let num_terms = 4;
+        let rng = &mut test_rng();
+        let mut bases : Vec<G1A> = vec![];
+        let mut scalars = vec![F::zero(); num_terms];
+        for i in 0..num_terms {
+            bases.push(G1::rand(rng).into());
+            scalars[i] = F::rand(rng);
+        }
+        let linking_value = F::rand(rng);
+
+        let build_statement = |linked_pos: usize| {
+            let mut s = scalars.clone();
+            s[linked_pos] = linking_value;
+            let y = msm_select(&bases, &s);
+            (s, y)
+        };
+
+        let (scalars0, y0) = build_statement(0);
+        let (scalars1, y1) = build_statement(1);
+        let (scalars2, y2) = build_statement(2);
+        let bases_proj : Vec<G1> = bases.iter().map(|x| (*x).into()).collect();
+
+        let eq_classes = vec![vec![(0, 0), (1, 1), (2, 2)]];
+
+        let pok = DLogPoK::<G1>::prove(
+            None,
+            &[y0, y1, y2],
+            &[bases_proj.clone(), bases_proj.clone(), bases_proj.clone()],
+            &[scalars0.clone(), scalars1.clone(), scalars2],
+            Some(eq_classes.clone()),
+        );
+
+        assert!(pok.verify(
+            None,
+            &[bases_proj.clone(), bases_proj.clone(), bases_proj.clone()],
+            &[y0, y1, y2],
+            Some(eq_classes),
+        ));
+
+        // Mismatched linking value in one statement should fail verification.
+        let (scalars2_wrong, y2_wrong) = {
+            let mut s = scalars.clone();
+            s[2] = F::rand(rng);
+            let y = msm_select(&bases, &s);
+            (s, y)
+        };
+        let eq_classes = vec![vec![(0, 0), (1, 1), (2, 2)]];
+        let bad_pok = DLogPoK::<G1>::prove(
+            None,
+            &[y0, y1, y2_wrong],
+            &[bases_proj.clone(), bases_proj.clone(), bases_proj.clone()],
+            &[scalars0.clone(), scalars1.clone(), scalars2_wrong],
+            Some(eq_classes.clone()),
+        );
+        assert!(!bad_pok.verify(
+            None,
+            &[bases_proj.clone(), bases_proj.clone(), bases_proj],
+            &[y0, y1, y2_wrong],
+            Some(eq_classes),
+        ));
+    }
+
+    #[test]
+    fn test_dleq_compression_round_trips() {
+        let num_terms = 4;
+        let rng = &mut test_rng();
+        let mut bases : Vec<G1A> = vec![];
+        let mut scalars = vec![F::zero(); num_terms];
+        for i in 0..num_terms {
+            bases.push(G1::rand(rng).into());
+            scalars[i] = F::rand(rng);
+        }
+        let linking_value = F::rand(rng);
+
+        let build_statement = |linked_pos: usize| {
+            let mut s = scalars.clone();
+            s[linked_pos] = linking_value;
+            let y = msm_select(&bases, &s);
+            (s, y)
+        };
+
+        let (scalars0, y0) = build_statement(0);
+        let (scalars1, y1) = build_statement(1);
+        let bases_proj : Vec<G1> = bases.iter().map(|x| (*x).into()).collect();
+        let eq_classes = vec![vec![(0, 0), (1, 1)]];
+
+        let pok = DLogPoK::<G1>::prove(
+            None,
+            &[y0, y1],
+            &[bases_proj.clone(), bases_proj.clone()],
+            &[scalars0, scalars1],
+            Some(eq_classes.clone()),
+        );
+
+        let compressed = pok.compress(&eq_classes);
+        // One response is dropped: the linked position in the second statement.
+        assert_eq!(compressed.s[0].len(), num_terms);
+        assert_eq!(compressed.s[1].len(), num_terms - 1);
+
+        assert!(compressed.verify(None, &[bases_proj.clone(), bases_proj.clone()], &[y0, y1], Some(eq_classes.clone())));
+
+        let stmt_lens = vec![num_terms, num_terms];
+        let decompressed = compressed.decompress(&stmt_lens, &eq_classes);
+        assert_eq!(decompressed.s, pok.s);
+    }
+
+    #[test]
+    fn test_partial_opening_proof_discloses_subset() {
+        let rng = &mut test_rng();
+        let n = 4;
+        let bases = DLogPoK::<G1>::derive_vector_pedersen_bases(n);
+        let messages: Vec<F> = (0..n).map(|_| F::rand(rng)).collect();
+        let com = DLogPoK::<G1>::vector_pedersen_commit(&messages, &bases);
+
+        let disclosed = vec![(1, messages[1]), (3, messages[3])];
+        let proof = PartialOpeningProof::<G1>::prove(None, &com, &disclosed);
+        assert!(proof.verify(None, &com.c, &bases, &disclosed));
+
+        // A wrong disclosed value is rejected.
+        let mut wrong = disclosed.clone();
+        wrong[0].1 = F::rand(rng);
+        assert!(!proof.verify(None, &com.c, &bases, &wrong));
+    }
+
+    #[test]
+    fn test_partial_opening_proof_empty_and_full_disclosure() {
+        let rng = &mut test_rng();
+        let n = 3;
+        let bases = DLogPoK::<G1>::derive_vector_pedersen_bases(n);
+        let messages: Vec<F> = (0..n).map(|_| F::rand(rng)).collect();
+        let com = DLogPoK::<G1>::vector_pedersen_commit(&messages, &bases);
+
+        // Nothing disclosed: proof of knowledge of the whole opening.
+        let proof = PartialOpeningProof::<G1>::prove(None, &com, &[]);
+        assert!(proof.verify(None, &com.c, &bases, &[]));
+
+        // Everything disclosed: only the blinding remains hidden.
+        let disclosed: Vec<(usize, F)> = messages.iter().enumerate().map(|(i, m)| (i, *m)).collect();
+        let proof = PartialOpeningProof::<G1>::prove(None, &com, &disclosed);
+        assert!(proof.verify(None, &com.c, &bases, &disclosed));
+    }
+
+    #[test]
+    fn test_fixed_base_committer_matches_pedersen_commit() {
+        let bases = DLogPoK::<G1>::derive_pedersen_bases();
+        let committer = FixedBaseCommitter::<G1>::new(&bases);
+
+        let rng = &mut test_rng();
+        for _ in 0..5 {
+            let m = F::rand(rng);
+            let r = F::rand(rng);
+            let expected = msm_select::<G1>(&bases, &[m, r]);
+            let opening = committer.commit(&m, &r);
+            assert_eq!(opening.c, expected);
+            assert_eq!(committer.commit_vec(&[m, r]), expected);
+        }
+    }
+
+    #[test]
+    fn test_fixed_base_committer_window_sizes_agree() {
+        let bases = DLogPoK::<G1>::derive_pedersen_bases();
+        let rng = &mut test_rng();
+        let m = F::rand(rng);
+        let r = F::rand(rng);
+        let expected = msm_select::<G1>(&bases, &[m, r]);
+
+        for window in [2usize, 3, 4, 8] {
+            let committer = FixedBaseCommitter::<G1>::with_window(&bases, window);
+            assert_eq!(committer.commit_vec(&[m, r]), expected, "window = {}", window);
+        }
+    }
+
+    #[test]
+    fn test_set_membership_proof() {
+        let bases = DLogPoK::<G1>::derive_pedersen_bases();
+        let set: Vec<F> = (0..8).map(F::from).collect();
+
+        let com = DLogPoK::<G1>::pedersen_commit(&set[3], &bases);
+        let proof = SetMembershipProof::prove(&com, &set);
+        assert!(proof.verify(&com, &set));
+
+        // Wrong set, expect failure
+        let wrong_set: Vec<F> = (10..18).map(F::from).collect();
+        assert!(!proof.verify(&com, &wrong_set));
+
+        // Tampered proof, expect failure
+        let mut bad_proof = proof.clone();
+        bad_proof.z[0] = bad_proof.z[0] + F::from(1u64);
+        assert!(!bad_proof.verify(&com, &set));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_set_membership_proof_not_a_member() {
+        let bases = DLogPoK::<G1>::derive_pedersen_bases();
+        let set: Vec<F> = (0..8).map(F::from).collect();
+        let com = DLogPoK::<G1>::pedersen_commit(&F::from(100u64), &bases);
+        SetMembershipProof::prove(&com, &set);
+    }
 }
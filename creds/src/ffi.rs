@@ -0,0 +1,327 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT license.
+
+//! C ABI entry points for embedding the prover/verifier in non-Rust hosts
+//! (mobile wallets, C++ services), analogous to how librustzcash wraps
+//! Groth16 proving/verification behind `extern "C"` functions operating on
+//! raw pointers and byte buffers instead of a Rust API. Heavy, rarely
+//! changing parameters (the range-proof proving/verifying keys, Groth16
+//! verifying key, IO locations) are loaded once from a scheme's
+//! `CachePaths` into an opaque context via
+//! `crescent_prover_context_new`/`crescent_verifier_context_new` and reused
+//! across calls -- the per-call data (client state, proof spec, show
+//! proof) crosses the boundary as length-prefixed byte buffers using the
+//! crate's existing `CanonicalSerialize`/`CanonicalDeserialize` encoding
+//! (see `utils::write_to_bytes`/`utils::read_from_bytes`) rather than the
+//! file paths `run_prover`/`run_show`/`run_verifier` read and write.
+//!
+//! Every entry point that takes a context returns a [`CrescentStatus`]
+//! (the context constructors return null on failure instead, and the
+//! `*_free` functions return nothing); `Ok` means the output
+//! buffer (if any) was written and must be released with
+//! `crescent_free_buffer`. Any other status means no output buffer was
+//! produced. `crescent_verify_show`'s output is always a JSON encoding of
+//! the verification outcome, even when verification itself failed --
+//! `CrescentStatus::VerificationFailed` just flags that case for callers
+//! who don't want to parse the JSON to find out.
+
+use std::ffi::CStr;
+use std::os::raw::c_char;
+use std::slice;
+
+use ark_bn254::Bn254 as ECPairing;
+
+use crate::challenge::VerifierChallenge;
+use crate::groth16rand::ClientState;
+use crate::rangeproof::RangeProofPK;
+use crate::structs::{GenericInputsJSON, IOLocations};
+use crate::utils::{read_from_bytes, read_from_file, write_to_bytes};
+use crate::{create_client_state, create_show_proof, verify_show, CachePaths, DeviceSignature, ProofSpec, VerifierParams};
+
+/// Status returned by every `crescent_*` entry point in this module.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CrescentStatus {
+    Ok = 0,
+    NullPointer = -1,
+    InvalidUtf8 = -2,
+    InvalidJson = -3,
+    InvalidPath = -4,
+    DeserializationFailed = -5,
+    ProofGenerationFailed = -6,
+    VerificationFailed = -7,
+}
+
+/// Parameters a prover needs to create show proofs for one credential
+/// scheme (e.g. `rs256`), loaded once from the scheme's `CachePaths` and
+/// reused across many `crescent_create_client_state`/
+/// `crescent_create_show_proof` calls.
+pub struct CrescentProverContext {
+    paths: CachePaths,
+    range_pk: RangeProofPK<'static, ECPairing>,
+    io_locations: IOLocations,
+}
+
+/// Parameters a verifier needs to check show proofs for one credential
+/// scheme, loaded once and reused across many `crescent_verify_show` calls
+/// instead of re-reading `CachePaths` every time (as the `crescent` CLI's
+/// `run_verifier` does).
+pub struct CrescentVerifierContext {
+    vp: VerifierParams<ECPairing>,
+}
+
+unsafe fn cstr_to_str<'a>(ptr: *const c_char) -> Result<&'a str, CrescentStatus> {
+    if ptr.is_null() {
+        return Err(CrescentStatus::NullPointer);
+    }
+    CStr::from_ptr(ptr).to_str().map_err(|_| CrescentStatus::InvalidUtf8)
+}
+
+unsafe fn buf_to_slice<'a>(ptr: *const u8, len: usize) -> Result<&'a [u8], CrescentStatus> {
+    if len > 0 && ptr.is_null() {
+        return Err(CrescentStatus::NullPointer);
+    }
+    Ok(if len == 0 { &[] } else { slice::from_raw_parts(ptr, len) })
+}
+
+/// Writes `bytes` into a freshly allocated buffer and hands its pointer and
+/// length back through `out_ptr`/`out_len`. The caller must release it with
+/// [`crescent_free_buffer`].
+unsafe fn emit_buffer(bytes: Vec<u8>, out_ptr: *mut *mut u8, out_len: *mut usize) {
+    let boxed = bytes.into_boxed_slice();
+    *out_len = boxed.len();
+    *out_ptr = Box::into_raw(boxed) as *mut u8;
+}
+
+/// Releases a buffer previously returned through an `out_ptr`/`out_len`
+/// pair by any `crescent_*` entry point in this module.
+#[no_mangle]
+pub unsafe extern "C" fn crescent_free_buffer(ptr: *mut u8, len: usize) {
+    if ptr.is_null() {
+        return;
+    }
+    drop(Box::from_raw(slice::from_raw_parts_mut(ptr, len)));
+}
+
+/// Loads a prover context from `base_path`'s `CachePaths` (the same layout
+/// `crescent prepare`/`crescent show` use). Returns null on failure.
+#[no_mangle]
+pub unsafe extern "C" fn crescent_prover_context_new(base_path: *const c_char) -> *mut CrescentProverContext {
+    let base_path = match cstr_to_str(base_path) {
+        Ok(s) => s,
+        Err(_) => return std::ptr::null_mut(),
+    };
+    let paths = CachePaths::new_from_str(base_path);
+    let range_pk: RangeProofPK<'static, ECPairing> = match read_from_file(&paths.range_pk) {
+        Ok(pk) => pk,
+        Err(_) => return std::ptr::null_mut(),
+    };
+    let io_locations = IOLocations::new(&paths.io_locations);
+    Box::into_raw(Box::new(CrescentProverContext { paths, range_pk, io_locations }))
+}
+
+/// Releases a context returned by [`crescent_prover_context_new`].
+#[no_mangle]
+pub unsafe extern "C" fn crescent_prover_context_free(ctx: *mut CrescentProverContext) {
+    if !ctx.is_null() {
+        drop(Box::from_raw(ctx));
+    }
+}
+
+/// Loads a verifier context from `base_path`'s `CachePaths`. Returns null
+/// on failure.
+#[no_mangle]
+pub unsafe extern "C" fn crescent_verifier_context_new(base_path: *const c_char) -> *mut CrescentVerifierContext {
+    let base_path = match cstr_to_str(base_path) {
+        Ok(s) => s,
+        Err(_) => return std::ptr::null_mut(),
+    };
+    let paths = CachePaths::new_from_str(base_path);
+    let vp = match VerifierParams::<ECPairing>::new(&paths) {
+        Ok(vp) => vp,
+        Err(_) => return std::ptr::null_mut(),
+    };
+    Box::into_raw(Box::new(CrescentVerifierContext { vp }))
+}
+
+/// Releases a context returned by [`crescent_verifier_context_new`].
+#[no_mangle]
+pub unsafe extern "C" fn crescent_verifier_context_free(ctx: *mut CrescentVerifierContext) {
+    if !ctx.is_null() {
+        drop(Box::from_raw(ctx));
+    }
+}
+
+/// Runs the prover's Groth16 witness generation/proving step, the
+/// byte-buffer equivalent of `create_client_state` + `write_to_file`.
+/// `prover_aux_json` may be null (zero `prover_aux_len`) for credential
+/// types that don't carry auxiliary data.
+#[no_mangle]
+pub unsafe extern "C" fn crescent_create_client_state(
+    ctx: *const CrescentProverContext,
+    prover_inputs_json: *const u8,
+    prover_inputs_len: usize,
+    prover_aux_json: *const u8,
+    prover_aux_len: usize,
+    credtype: *const c_char,
+    out_ptr: *mut *mut u8,
+    out_len: *mut usize,
+) -> CrescentStatus {
+    let ctx = match ctx.as_ref() {
+        Some(ctx) => ctx,
+        None => return CrescentStatus::NullPointer,
+    };
+    let credtype = match cstr_to_str(credtype) {
+        Ok(s) => s,
+        Err(e) => return e,
+    };
+    let prover_inputs_bytes = match buf_to_slice(prover_inputs_json, prover_inputs_len) {
+        Ok(b) => b,
+        Err(e) => return e,
+    };
+    let prover_inputs: serde_json::Map<String, serde_json::Value> = match serde_json::from_slice(prover_inputs_bytes) {
+        Ok(v) => v,
+        Err(_) => return CrescentStatus::InvalidJson,
+    };
+    let prover_aux = if prover_aux_len == 0 {
+        None
+    } else {
+        let bytes = match buf_to_slice(prover_aux_json, prover_aux_len) {
+            Ok(b) => b,
+            Err(e) => return e,
+        };
+        match std::str::from_utf8(bytes) {
+            Ok(s) => Some(s.to_string()),
+            Err(_) => return CrescentStatus::InvalidUtf8,
+        }
+    };
+
+    let client_state = match create_client_state(&ctx.paths, &GenericInputsJSON { prover_inputs }, prover_aux.as_ref(), credtype) {
+        Ok(cs) => cs,
+        Err(_) => return CrescentStatus::ProofGenerationFailed,
+    };
+
+    emit_buffer(write_to_bytes(&client_state), out_ptr, out_len);
+    CrescentStatus::Ok
+}
+
+/// Creates a show proof from a serialized `ClientState`, the byte-buffer
+/// equivalent of `create_show_proof` + `write_to_file`.
+///
+/// `device_signature_raw_ecdsa` may be null (zero length) for credentials
+/// that aren't device bound; only the raw-ECDSA device binding is
+/// reachable through this entry point (see `DeviceSignature::RawEcdsa`).
+#[no_mangle]
+pub unsafe extern "C" fn crescent_create_show_proof(
+    ctx: *const CrescentProverContext,
+    client_state_bytes: *const u8,
+    client_state_len: usize,
+    proof_spec_json: *const u8,
+    proof_spec_len: usize,
+    device_signature_raw_ecdsa: *const u8,
+    device_signature_len: usize,
+    out_ptr: *mut *mut u8,
+    out_len: *mut usize,
+) -> CrescentStatus {
+    let ctx = match ctx.as_ref() {
+        Some(ctx) => ctx,
+        None => return CrescentStatus::NullPointer,
+    };
+    let client_state_bytes = match buf_to_slice(client_state_bytes, client_state_len) {
+        Ok(b) => b,
+        Err(e) => return e,
+    };
+    let mut client_state: ClientState<ECPairing> = match read_from_bytes(client_state_bytes.to_vec()) {
+        Ok(cs) => cs,
+        Err(_) => return CrescentStatus::DeserializationFailed,
+    };
+    let proof_spec_bytes = match buf_to_slice(proof_spec_json, proof_spec_len) {
+        Ok(b) => b,
+        Err(e) => return e,
+    };
+    let proof_spec: ProofSpec = match serde_json::from_slice(proof_spec_bytes) {
+        Ok(ps) => ps,
+        Err(_) => return CrescentStatus::InvalidJson,
+    };
+    let device_signature = if device_signature_len == 0 {
+        None
+    } else {
+        match buf_to_slice(device_signature_raw_ecdsa, device_signature_len) {
+            Ok(b) => Some(DeviceSignature::RawEcdsa(b.to_vec())),
+            Err(e) => return e,
+        }
+    };
+
+    let show_proof = match create_show_proof(&mut client_state, &ctx.range_pk, &ctx.io_locations, &proof_spec, device_signature) {
+        Ok(sp) => sp,
+        Err(_) => return CrescentStatus::ProofGenerationFailed,
+    };
+
+    emit_buffer(write_to_bytes(&show_proof), out_ptr, out_len);
+    CrescentStatus::Ok
+}
+
+/// Verifies a show proof, the byte-buffer equivalent of `verify_show` +
+/// the `crescent` CLI's `run_verifier`. The output buffer is always a JSON
+/// object `{"verified": bool, "revealed": {...}, "satisfied_time_predicates":
+/// [...], "error": string|null}`, written even when verification fails, so
+/// a caller that wants the failure reason doesn't need a second call.
+///
+/// `expected_challenge_json` may be null (zero length) if the proof isn't
+/// expected to be bound to a verifier-issued challenge.
+#[no_mangle]
+pub unsafe extern "C" fn crescent_verify_show(
+    ctx: *const CrescentVerifierContext,
+    show_proof_bytes: *const u8,
+    show_proof_len: usize,
+    proof_spec_json: *const u8,
+    proof_spec_len: usize,
+    expected_challenge_json: *const u8,
+    expected_challenge_len: usize,
+    out_ptr: *mut *mut u8,
+    out_len: *mut usize,
+) -> CrescentStatus {
+    let ctx = match ctx.as_ref() {
+        Some(ctx) => ctx,
+        None => return CrescentStatus::NullPointer,
+    };
+    let show_proof_bytes = match buf_to_slice(show_proof_bytes, show_proof_len) {
+        Ok(b) => b,
+        Err(e) => return e,
+    };
+    let show_proof = match read_from_bytes(show_proof_bytes.to_vec()) {
+        Ok(sp) => sp,
+        Err(_) => return CrescentStatus::DeserializationFailed,
+    };
+    let proof_spec_bytes = match buf_to_slice(proof_spec_json, proof_spec_len) {
+        Ok(b) => b,
+        Err(e) => return e,
+    };
+    let proof_spec: ProofSpec = match serde_json::from_slice(proof_spec_bytes) {
+        Ok(ps) => ps,
+        Err(_) => return CrescentStatus::InvalidJson,
+    };
+    let expected_challenge: Option<VerifierChallenge> = if expected_challenge_len == 0 {
+        None
+    } else {
+        let bytes = match buf_to_slice(expected_challenge_json, expected_challenge_len) {
+            Ok(b) => b,
+            Err(e) => return e,
+        };
+        match serde_json::from_slice(bytes) {
+            Ok(c) => Some(c),
+            Err(_) => return CrescentStatus::InvalidJson,
+        }
+    };
+
+    let outcome = verify_show(&ctx.vp, &show_proof, &proof_spec, expected_challenge.as_ref());
+    let status = if outcome.verified { CrescentStatus::Ok } else { CrescentStatus::VerificationFailed };
+    let body = serde_json::json!({
+        "verified": outcome.verified,
+        "revealed": outcome.revealed,
+        "satisfied_time_predicates": outcome.satisfied_time_predicates,
+        "error": outcome.failure.map(|e| e.to_string()),
+    });
+    emit_buffer(body.to_string().into_bytes(), out_ptr, out_len);
+    status
+}
@@ -3,7 +3,9 @@
 
 use crate::{
     dlog::{DLogPoK, PedersenOpening},
-    rangeproof::{RangeProof, RangeProofPK, RangeProofVK},
+    ipa_rangeproof::{IpaRangeProof, IpaRangeProofPK, IpaRangeProofVK},
+    rangeproof::{AggregatedRangeProof, BoundedRangeProof, RangeProof, RangeProofPK, RangeProofVK},
+    sig_rangeproof::{RangeProofParams, RangeProof as CCS08RangeProof, SetMembershipProof},
     structs::{IOLocations, PublicIOType},
     utils::msm_select
 };
@@ -49,6 +51,52 @@ pub struct ShowRange<E: Pairing> {
     pub range_proof: RangeProof<E>,
 }
 
+/// An unlinkable showing that a committed input lies in `[0, 2^n)`, via the
+/// transparent-setup IPA scheme (see `ipa_rangeproof`) rather than the KZG
+/// polynomial commitment [`ShowRange`] uses -- no trusted setup, at the
+/// cost of `O(n)` group operations to verify instead of `O(1)` pairings.
+#[derive(Clone, Debug, CanonicalSerialize, CanonicalDeserialize)]
+pub struct ShowRangeIpa<E: Pairing> {
+    pub range_proof: IpaRangeProof<E::G1>,
+}
+
+/// An unlinkable showing that a committed input lies in the arbitrary
+/// interval `[a, b)`, via the same KZG-based polynomial commitment
+/// `ShowRange` uses (see `rangeproof::BoundedRangeProof`) -- unlike
+/// `ShowRangeCCS08`, this reuses the `RangeProofPK`/`RangeProofVK` setup
+/// callers already have for `show_range`, at the cost of two `[0, 2^n)`
+/// sub-proofs instead of one.
+#[derive(Clone, Debug, CanonicalSerialize, CanonicalDeserialize)]
+pub struct ShowBoundedRange<E: Pairing> {
+    pub range_proof: BoundedRangeProof<E>,
+}
+
+/// An unlinkable showing that `k` committed inputs all lie in `[0, 2^n)`, via
+/// a single aggregated KZG proof (see `rangeproof::AggregatedRangeProof`) --
+/// use this instead of calling `show_range` `k` times when a credential
+/// discloses several bounded attributes at once, since the dominant KZG
+/// commit/open cost is paid once rather than `k` times.
+#[derive(Clone, Debug, CanonicalSerialize, CanonicalDeserialize)]
+pub struct ShowRangeAggregated<E: Pairing> {
+    pub range_proof: AggregatedRangeProof<E>,
+}
+
+/// An unlinkable showing that a committed input lies in the arbitrary
+/// interval `[a, b]`, via the CCS08 signature-based scheme -- unlike
+/// [`ShowRange`], `a`/`b` aren't restricted to `[0, 2^n)`.
+#[derive(Clone, Debug, CanonicalSerialize, CanonicalDeserialize)]
+pub struct ShowRangeCCS08<E: Pairing> {
+    pub range_proof: CCS08RangeProof<E>,
+}
+
+/// An unlinkable showing that a committed input is a member of the finite
+/// set `Phi` signed into `RangeProofParams::setup_for_set`, e.g.
+/// `country in {...}`.
+#[derive(Clone, Debug, CanonicalSerialize, CanonicalDeserialize)]
+pub struct ShowSetMembership<E: Pairing> {
+    pub set_membership_proof: SetMembershipProof<E>,
+}
+
 #[derive(Clone, Debug, CanonicalSerialize, CanonicalDeserialize)]
 pub struct ShowECDSA<E: Pairing> {
     pub spartan_proof: Vec<u8>,
@@ -216,25 +264,113 @@ impl<E: Pairing> ClientState<E> {
         ShowRange { range_proof }
     }
 
+    /// Prove that a certain input to the groth16 proof is in `[0, 2^n)`,
+    /// via the transparent-setup IPA scheme (see `ipa_rangeproof`) -- use
+    /// this instead of `show_range` when a KZG trusted setup isn't
+    /// acceptable for the deployment.
+    pub fn show_range_ipa(
+        &self,
+        ped_open: &PedersenOpening<E::G1>,
+        n: usize,
+        range_pk: &IpaRangeProofPK<E::G1>,
+    ) -> ShowRangeIpa<E> {
+        assert!(n < 64);
+        let bound = <E as Pairing>::ScalarField::from(1u64 << n);
+        assert!(ped_open.m < bound);
+
+        let range_proof = IpaRangeProofPK::<E::G1>::prove_n_bits(ped_open, n, range_pk);
+        ShowRangeIpa { range_proof }
+    }
+
+    /// Prove that a certain input to the groth16 proof lies in the
+    /// arbitrary interval `[a, b)`, via the KZG-based scheme (see
+    /// `rangeproof::BoundedRangeProof`) -- use this instead of
+    /// `show_range` when `b - a` doesn't fit `[0, 2^n)` for a small `n`,
+    /// e.g. an age predicate like `[18, 150)`.
+    pub fn show_bounded_range(
+        &self,
+        ped_open: &PedersenOpening<E::G1>,
+        a: u64,
+        b: u64,
+        range_pk: &RangeProofPK<E>,
+    ) -> ShowBoundedRange<E> {
+        let range_proof = BoundedRangeProof::prove_range(ped_open, a, b, &range_pk.powers);
+        ShowBoundedRange { range_proof }
+    }
+
+    /// Prove that `k` committed inputs are each in `[0, 2^n)`, amortizing
+    /// the KZG commit/open cost across all `k` of them -- use this instead
+    /// of calling `show_range` once per input (see
+    /// `rangeproof::AggregatedRangeProof`).
+    pub fn show_range_aggregated(
+        &self,
+        ped_opens: &[PedersenOpening<E::G1>],
+        n: usize,
+        range_pk: &RangeProofPK<E>,
+    ) -> ShowRangeAggregated<E> {
+        assert!(n < 64);
+        let bound = <E as Pairing>::ScalarField::from(1u64 << n);
+        for ped_open in ped_opens {
+            assert!(ped_open.m < bound);
+        }
+
+        let range_proof =
+            AggregatedRangeProof::prove_n_bits_aggregated(ped_opens, n, &range_pk.powers);
+        ShowRangeAggregated { range_proof }
+    }
+
+    /// Prove that a certain input to the groth16 proof lies in the
+    /// arbitrary interval `[a, b]`, via the CCS08 signature-based scheme
+    /// (see `sig_rangeproof`) rather than bit decomposition -- use this
+    /// instead of `show_range` when `b - a` doesn't fit `[0, 2^n)` for a
+    /// small `n`, e.g. an age predicate like `[18, 150]`.
+    pub fn show_range_ccs08(
+        &self,
+        ped_open: &PedersenOpening<E::G1>,
+        a: u64,
+        b: u64,
+        range_params: &RangeProofParams<E>,
+    ) -> ShowRangeCCS08<E> {
+        let range_proof = CCS08RangeProof::prove(ped_open, a, b, range_params);
+        ShowRangeCCS08 { range_proof }
+    }
 
+    /// Prove that a certain input to the groth16 proof is a member of the
+    /// finite set `Phi` signed into `range_params` (built via
+    /// `RangeProofParams::setup_for_set`), e.g. `country in {...}`.
+    pub fn show_set_membership(
+        &self,
+        ped_open: &PedersenOpening<E::G1>,
+        value: u64,
+        range_params: &RangeProofParams<E>,
+    ) -> ShowSetMembership<E> {
+        let set_membership_proof = SetMembershipProof::prove(ped_open, value, range_params);
+        ShowSetMembership { set_membership_proof }
+    }
 }
 
 
 
 impl<E: Pairing> ShowGroth16<E> {
-    pub fn verify(
+    /// Computes the folded public-input commitment `com_inputs` the Groth16
+    /// equation `e(A,B) = e(alpha,beta)*e(com_inputs,gamma)*e(C,delta)` is
+    /// checked against, and checks the (pairing-free) proof of knowledge of
+    /// the hidden/committed inputs. Factored out of `verify` so a batch
+    /// verifier can fold many proofs' Groth16 equations into one random
+    /// linear combination while still checking each proof's PoK and
+    /// `com_inputs` independently -- only the pairing itself is deferred.
+    pub fn prepare_verify(
         &self,
         vk: &VerifyingKey<E>,
         pvk: &PreparedVerifyingKey<E>,
         context: Option<&[u8]>,
         io_types: &[PublicIOType],
         public_inputs: &[E::ScalarField],
-    ) -> bool
+    ) -> (E::G1, bool)
     where
         E: Pairing,
-        E::G1 : CurveGroup + VariableBaseMSM,      
+        E::G1 : CurveGroup + VariableBaseMSM,
     {
-        let groth16_timer = start_timer!(||"Verify Groth16 show proof");
         let mut com_inputs = self.com_hidden_inputs + pvk.vk.gamma_abc_g1[0];
 
         let mut public_input_index = 0;
@@ -274,6 +410,27 @@ impl<E: Pairing> ShowGroth16<E> {
         bases.push(hidden_input_bases);
         y.push(self.com_hidden_inputs);
 
+        let dlog_pok_valid = self.pok_inputs.verify(context, &bases, &y, None);
+
+        (com_inputs, dlog_pok_valid)
+    }
+
+    pub fn verify(
+        &self,
+        vk: &VerifyingKey<E>,
+        pvk: &PreparedVerifyingKey<E>,
+        context: Option<&[u8]>,
+        io_types: &[PublicIOType],
+        public_inputs: &[E::ScalarField],
+    ) -> bool
+    where
+        E: Pairing,
+        E::G1 : CurveGroup + VariableBaseMSM,
+    {
+        let groth16_timer = start_timer!(||"Verify Groth16 show proof");
+
+        let (com_inputs, dlog_pok_valid) = self.prepare_verify(vk, pvk, context, io_types, public_inputs);
+
         let t = start_timer!(||"Groth16 verify proof with prepared inputs");
         let groth16_result = Groth16::<E>::verify_proof_with_prepared_inputs(
             pvk,
@@ -281,7 +438,7 @@ impl<E: Pairing> ShowGroth16<E> {
             &com_inputs
         );
         let groth16_valid = match groth16_result {
-            Ok(b) => b, 
+            Ok(b) => b,
             Err(e) => {
                 println!("Failed to verify Groth16 proof with error: {:?}", e);
                 false
@@ -289,8 +446,6 @@ impl<E: Pairing> ShowGroth16<E> {
         };
         end_timer!(t);
 
-        let dlog_pok_valid = self.pok_inputs.verify(context, &bases, &y, None);
-        
         end_timer!(groth16_timer);
 
         groth16_valid && dlog_pok_valid
@@ -317,3 +472,105 @@ impl<E: Pairing> ShowRange<E> {
         self.range_proof.verify_n_bits(ped_com, &bases, n, range_vk)
     }
 }
+
+impl<E: Pairing> ShowRangeIpa<E> {
+    pub fn verify(
+        &self,
+        ped_com: &E::G1,
+        n: usize,
+        range_vk: &IpaRangeProofVK<E::G1>,
+        io_locations: &IOLocations,
+        pvk: &PreparedVerifyingKey<E>,
+        input_label: &str,
+    ) -> bool {
+        let input_pos = io_locations.get_io_location(input_label).unwrap();
+        let bases = [
+            pvk.vk.gamma_abc_g1[input_pos].into(),
+            pvk.vk.delta_g1.into(),
+        ];
+
+        self.range_proof.verify_n_bits(ped_com, &bases, n, range_vk)
+    }
+}
+
+impl<E: Pairing> ShowBoundedRange<E> {
+    pub fn verify(
+        &self,
+        ped_com: &E::G1,
+        a: u64,
+        b: u64,
+        range_vk: &RangeProofVK<E>,
+        io_locations: &IOLocations,
+        pvk: &PreparedVerifyingKey<E>,
+        input_label: &str,
+    ) -> bool {
+        let input_pos = io_locations.get_io_location(input_label).unwrap();
+        let bases = [
+            pvk.vk.gamma_abc_g1[input_pos].into(),
+            pvk.vk.delta_g1.into(),
+        ];
+
+        self.range_proof.verify_range(ped_com, &bases, a, b, range_vk)
+    }
+}
+
+impl<E: Pairing> ShowRangeAggregated<E> {
+    pub fn verify(
+        &self,
+        ped_coms: &[E::G1],
+        n: usize,
+        range_vk: &RangeProofVK<E>,
+        io_locations: &IOLocations,
+        pvk: &PreparedVerifyingKey<E>,
+        input_labels: &[&str],
+    ) -> bool {
+        let bases = input_labels
+            .iter()
+            .map(|label| {
+                let input_pos = io_locations.get_io_location(label).unwrap();
+                [pvk.vk.gamma_abc_g1[input_pos].into(), pvk.vk.delta_g1.into()]
+            })
+            .collect::<Vec<[E::G1; 2]>>();
+
+        self.range_proof.verify_n_bits_aggregated(ped_coms, &bases, n, range_vk)
+    }
+}
+
+impl<E: Pairing> ShowRangeCCS08<E> {
+    pub fn verify(
+        &self,
+        ped_com: &E::G1,
+        a: u64,
+        b: u64,
+        range_params: &RangeProofParams<E>,
+        io_locations: &IOLocations,
+        pvk: &PreparedVerifyingKey<E>,
+        input_label: &str,
+    ) -> bool {
+        let input_pos = io_locations.get_io_location(input_label).unwrap();
+        let bases = [
+            pvk.vk.gamma_abc_g1[input_pos].into(),
+            pvk.vk.delta_g1.into(),
+        ];
+
+        self.range_proof.verify(ped_com, &bases, a, b, range_params)
+    }
+}
+
+impl<E: Pairing> ShowSetMembership<E> {
+    pub fn verify(
+        &self,
+        range_params: &RangeProofParams<E>,
+        io_locations: &IOLocations,
+        pvk: &PreparedVerifyingKey<E>,
+        input_label: &str,
+    ) -> bool {
+        let input_pos = io_locations.get_io_location(input_label).unwrap();
+        let bases = [
+            pvk.vk.gamma_abc_g1[input_pos].into(),
+            pvk.vk.delta_g1.into(),
+        ];
+
+        self.set_membership_proof.verify(&bases, range_params)
+    }
+}
@@ -4,16 +4,22 @@
 use std::{fs, path::PathBuf, error::Error};
 use ark_bn254::{Bn254 as ECPairing, Fr};
 use ark_crypto_primitives::snark::SNARK;
-use ark_ec::pairing::Pairing;
+use ark_ec::pairing::{Pairing, PairingOutput};
+use ark_ec::CurveGroup;
 use ark_ff::PrimeField;
 use ark_groth16::{Groth16, PreparedVerifyingKey, ProvingKey, VerifyingKey};
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, SerializationError};
-use ark_std::{end_timer, rand::thread_rng, start_timer};
+use ark_std::{end_timer, rand::thread_rng, start_timer, Zero};
+use merlin::Transcript;
+use thiserror::Error as ThisError;
+use transcript::ProofTranscript;
 
 use groth16rand::{ShowGroth16, ShowRange};
 use num_bigint::BigUint;
 use num_traits::Num;
-use prep_inputs::{create_proof_spec_internal, pem_to_inputs, unpack_int_to_string_unquoted};
+use prep_inputs::{create_proof_spec_internal, pem_to_inputs, unpack_int_to_string_unquoted, SigAlg};
+use daystamp::{Clock, NativeClock};
+use challenge::VerifierChallenge;
 use serde::{Deserialize, Serialize};
 use serde_json::{json,Value};
 use sha2::{Digest, Sha256};
@@ -24,7 +30,7 @@ use crate::structs::{PublicIOType, IOLocations, GenericInputsJSON};
 use crate::groth16rand::ClientState;
 use crate::utils::utc_now_seconds;
 use crate::device::{DeviceProof, ECDSASig};
-use crate::daystamp::days_to_be_age;
+use crate::dlog::PedersenOpening;
 
 
 #[cfg(not(feature = "wasm"))]
@@ -39,21 +45,85 @@ pub use wasm_lib::create_show_proof_wasm;
 #[cfg(feature = "wasm")]
 pub mod wasm_lib;
 
+#[cfg(feature = "ffi")]
+pub mod ffi;
+
+#[cfg(feature = "ctap2")]
+pub mod device_ctap2;
+
+pub mod circom_io;
+pub mod cose;
 pub mod daystamp;
 pub mod dlog;
 pub mod groth16rand;
+pub mod ipa_rangeproof;
 pub mod prep_inputs;
 pub mod rangeproof;
+pub mod serde_support;
+pub mod sig_rangeproof;
 pub mod structs;
+pub mod transcript;
 pub mod utils;
 pub mod device;
+pub mod bbs_device;
+pub mod challenge;
+pub mod revocation;
+pub mod webauthn;
+pub mod sdjwt;
+pub mod snarkjs;
+pub mod show_context;
+pub mod vrf;
+pub mod delegation;
 
 const RANGE_PROOF_INTERVAL_BITS: usize = 32;
 const SHOW_PROOF_VALIDITY_SECONDS: u64 = 300;    // The verifier only accepts proofs fresher than this
 pub const DEFAULT_PROOF_SPEC : &str = r#"{"revealed" : ["email"]}"#;
 
+/// Version of the `ShowProof` wire layout (field set/order under
+/// `CanonicalSerialize`). Bumped whenever that layout changes, so a verifier
+/// can reject a proof it can't parse correctly instead of silently
+/// misreading its bytes. `MIN_SUPPORTED_PROOF_FORMAT_VERSION` is the oldest
+/// layout this build's `verify_show`/`verify_show_mdl` still accept.
+pub const PROOF_FORMAT_VERSION: u32 = 1;
+pub const MIN_SUPPORTED_PROOF_FORMAT_VERSION: u32 = 1;
+
 pub type CrescentPairing = ECPairing;
 pub type CrescentFr = Fr;
+type ECPairingG1 = <ECPairing as Pairing>::G1;
+
+/// Embeds a possibly-negative predicate bound into the scalar field. `as u64`
+/// alone would be wrong for negative values: it reinterprets the two's
+/// complement bit pattern rather than reducing `-|v|` mod the field's prime.
+fn fr_from_i64(v: i64) -> Fr {
+    if v >= 0 {
+        Fr::from(v as u64)
+    } else {
+        -Fr::from((-v) as u64)
+    }
+}
+
+/// Re-bases `opening`'s committed value to `value - lower`, so a
+/// `show_range` bit-decomposition proof over the result proves `value >=
+/// lower`. This is exactly what the pre-predicate age check did inline.
+fn shift_opening_ge(opening: &PedersenOpening<<ECPairing as Pairing>::G1>, lower: i64) -> PedersenOpening<<ECPairing as Pairing>::G1> {
+    let lower = fr_from_i64(lower);
+    let mut shifted = opening.clone();
+    shifted.m -= lower;
+    shifted.c -= shifted.bases[0] * lower;
+    shifted
+}
+
+/// Re-bases `opening`'s committed value to `upper - value` (negating the
+/// whole Pedersen opening preserves `c = bases[0]*m + bases[1]*r`), so a
+/// `show_range` proof over the result proves `value <= upper`.
+fn shift_opening_le(opening: &PedersenOpening<<ECPairing as Pairing>::G1>, upper: i64) -> PedersenOpening<<ECPairing as Pairing>::G1> {
+    let upper = fr_from_i64(upper);
+    let mut shifted = opening.clone();
+    shifted.m = upper - shifted.m;
+    shifted.r = -shifted.r;
+    shifted.c = shifted.bases[0] * upper - shifted.c;
+    shifted
+}
 
 /// Parameters required to create Groth16 proofs
 #[derive(Clone, Debug, CanonicalSerialize, CanonicalDeserialize)]
@@ -69,9 +139,14 @@ impl<E: Pairing> ProverParams<E> {
     }
 }
 
-/// Parameters required to create show/presentation proofs
-#[derive(Clone, Debug, CanonicalSerialize, CanonicalDeserialize)]
+/// Parameters required to create show/presentation proofs. Besides the
+/// default b64url-wrapped `CanonicalSerialize` encoding, this also supports
+/// an optional self-describing JSON encoding (`serde`, field-by-field, with
+/// `range_pk` as a base64 string) for non-Rust verifiers -- see
+/// `serde_support`.
+#[derive(Clone, Debug, CanonicalSerialize, CanonicalDeserialize, Serialize, Deserialize)]
 pub struct ShowParams<'b, E: Pairing> {
+    #[serde(with = "serde_support")]
     range_pk: RangeProofPK<'b, E>
 }
 impl<'b, E: Pairing> ShowParams<'b, E> {
@@ -81,14 +156,21 @@ impl<'b, E: Pairing> ShowParams<'b, E> {
     }
 }
 
-/// Parameters required to verify show/presentation proofs
-#[derive(Clone, Debug, CanonicalSerialize, CanonicalDeserialize)]
+/// Parameters required to verify show/presentation proofs. Besides the
+/// default b64url-wrapped `CanonicalSerialize` encoding, this also supports
+/// an optional self-describing JSON encoding (`serde`, field-by-field, with
+/// group elements as base64 strings) for non-Rust verifiers -- see
+/// `serde_support`.
+#[derive(Clone, Debug, CanonicalSerialize, CanonicalDeserialize, Serialize, Deserialize)]
 pub struct VerifierParams<E: Pairing> {
+    #[serde(with = "serde_support")]
     pub vk : VerifyingKey<E>,
+    #[serde(with = "serde_support")]
     pub pvk : PreparedVerifyingKey<E>,
+    #[serde(with = "serde_support")]
     pub range_vk: RangeProofVK<E>,
     pub io_locations_str: String, // Stored as String since IOLocations does not implement CanonicalSerialize
-    pub issuer_pem: String, 
+    pub issuer_pem: String,
     pub config_str: String
 }
 impl<E: Pairing> VerifierParams<E> {
@@ -99,10 +181,86 @@ impl<E: Pairing> VerifierParams<E> {
         let io_locations_str = std::fs::read_to_string(&paths.io_locations)?;
         let issuer_pem = std::fs::read_to_string(&paths.issuer_pem)?;
         let config_str = std::fs::read_to_string(&paths.config)?;
+        let jwks = std::fs::read_to_string(&paths.issuer_jwks).ok();
+        let config = crate::prep_inputs::parse_config(&config_str)
+            .map_err(|e| SerializationError::IoError(std::io::Error::new(std::io::ErrorKind::Other, e.to_string())))?;
+        let kid = config.get("issuer_kid").and_then(|v| v.as_str());
+        let issuer_pem = crate::prep_inputs::resolve_issuer_pem(&issuer_pem, jwks.as_deref(), kid)
+            .map_err(|e| SerializationError::IoError(std::io::Error::new(std::io::ErrorKind::Other, e.to_string())))?;
         Ok(Self{vk, pvk, range_vk, io_locations_str, issuer_pem, config_str})
     }
 }
 
+/// The comparison a [`Predicate`] asks to be proven about an attribute.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PredicateOp {
+    GreaterThanOrEqual,
+    LessThanOrEqual,
+    Between,
+}
+
+/// A numeric range condition on a credential attribute, e.g. "age >= 18" or
+/// "10000 <= salary <= 50000". Proved in zero knowledge with the same
+/// shifted-commitment bit-range proof `range_over_year` uses for age checks
+/// -- `range_over_year` is sugar for a `GreaterThanOrEqual` predicate over a
+/// day-count bound, see `create_proof_spec_internal`. `value2` is only used
+/// (and required) for `Between`, which holds the inclusive upper bound.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Predicate {
+    pub attr: String,
+    pub op: PredicateOp,
+    pub value: i64,
+    pub value2: Option<i64>,
+}
+
+/// A request to prove a numeric time claim (e.g. `exp`, `nbf`) satisfies a
+/// verifier-supplied bound, without revealing the claim's actual value --
+/// sugar over [`Predicate`] specialized for the two comparisons a "is this
+/// credential currently valid" check needs: `greater_than` (for `exp`, to
+/// show it hasn't lapsed as of the verifier's clock) and `less_than` (for
+/// `nbf`, to show it's already started). Exactly one of the two must be
+/// set; see `create_proof_spec_internal`, which turns each entry into a
+/// `RangeBound` alongside `predicates` and `range_over_year`. The bound is
+/// folded into the show proof's context the same way every other range
+/// check is (see `show_context::compute`), so a prover can't satisfy the
+/// proof against one verifier time and present it as satisfying another.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TimePredicate {
+    pub claim: String,
+    pub greater_than: Option<i64>,
+    pub less_than: Option<i64>,
+}
+
+/// A single range condition that has been resolved down to an inclusive
+/// bound (or pair of bounds) in the attribute's own units, ready to be
+/// proven by `create_show_proof_mdl`/checked by `verify_show_mdl`.
+#[derive(Serialize, Clone, Copy, Debug)]
+pub(crate) enum RangeBound {
+    GreaterThanOrEqual(i64),
+    LessThanOrEqual(i64),
+    Between(i64, i64),
+}
+
+/// How a `device_bound` show proof's device signature should be interpreted.
+/// `RawEcdsa` is the original behavior: the device key is shown, in zero
+/// knowledge, to have signed `presentation_message` (see
+/// `device::DeviceProof`). `WebAuthn` instead carries a real CTAP2/FIDO2
+/// authenticator assertion (see `webauthn::Assertion`), checked in the open
+/// against the opened `device_key_0`/`device_key_1` commitments rather than
+/// hidden behind a SNARK -- see `webauthn::WebAuthnDeviceBinding`.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeviceBindingMode {
+    RawEcdsa,
+    WebAuthn,
+}
+
+impl Default for DeviceBindingMode {
+    fn default() -> Self {
+        DeviceBindingMode::RawEcdsa
+    }
+}
+
 // Proof specification describing what is to be proven during a Show proof.  Currently supporting selective disclosure
 // of attributes as field elements or hashed values, and range proofs.
 // The range proof for the expiration date ("exp" for JWT, "valid_until" for mDL) is in the future is always done.
@@ -110,41 +268,149 @@ impl<E: Pairing> VerifierParams<E> {
 pub struct ProofSpec {
     pub revealed: Vec<String>,
     pub range_over_year: Option<std::collections::BTreeMap<String, u64>>,
+    pub predicates: Option<Vec<Predicate>>,
+    /// Time-validity checks against numeric claims like `exp`/`nbf` -- see
+    /// [`TimePredicate`].
+    pub time_predicates: Option<Vec<TimePredicate>>,
     pub presentation_message: Option<Vec<u8>>,
     pub device_bound: Option<bool>,
+    /// How the device signature should be interpreted; `None` means
+    /// `DeviceBindingMode::RawEcdsa`, matching the behavior before this
+    /// field existed.
+    pub device_binding: Option<DeviceBindingMode>,
+    /// The identifier of the verifier this proof is intended for (e.g. its
+    /// domain). Bound into the show proof's context; `verify_show`/
+    /// `verify_show_mdl` take the verifier's own expectation as a separate
+    /// parameter and reject the proof unless it matches this field, so a
+    /// proof shown to one verifier can't be replayed against another.
+    pub audience: Option<String>,
+    /// A fresh challenge the verifier issued for this presentation, bound
+    /// into the show proof's context alongside `audience`. Unlike
+    /// `presentation_message` (which the device signs), `nonce` is only
+    /// checked by `verify_show`/`verify_show_mdl` against what the verifier
+    /// expects -- a cryptographic alternative to relying solely on
+    /// `SHOW_PROOF_VALIDITY_SECONDS` for freshness.
+    pub nonce: Option<Vec<u8>>,
+    /// The UNIX timestamp (seconds) after which this show proof must be
+    /// rejected, taken from the verifier's `challenge::VerifierChallenge`
+    /// and bound into the context alongside `audience`/`nonce`. When set,
+    /// `verify_show`/`verify_show_mdl` check freshness against this
+    /// verifier-chosen deadline instead of `SHOW_PROOF_VALIDITY_SECONDS`
+    /// measured from the prover's own clock.
+    pub not_after: Option<u64>,
+}
+
+impl ProofSpec {
+    /// Encodes this proof spec as MessagePack, a more compact alternative to
+    /// the JSON encoding used by `serde_json::from_str`/`to_string` elsewhere
+    /// in this crate. Intended for presentations destined for QR codes or
+    /// other size-constrained transports.
+    pub fn to_msgpack(&self) -> Result<Vec<u8>, Box<dyn Error>> {
+        Ok(rmp_serde::to_vec(self)?)
+    }
+
+    /// Decodes a proof spec previously encoded with [`ProofSpec::to_msgpack`].
+    pub fn from_msgpack(bytes: &[u8]) -> Result<Self, Box<dyn Error>> {
+        Ok(rmp_serde::from_slice(bytes)?)
+    }
 }
 
 #[derive(Serialize)]
 pub(crate) struct ProofSpecInternal {
     pub revealed: Vec<String>,
-    pub range_over_year: Vec<(String, u64)>,
-    pub hashed: Vec<String>, 
+    pub range_checks: Vec<(String, RangeBound)>,
+    pub hashed: Vec<String>,
     pub presentation_message : Option<Vec<u8>>,
     pub device_bound: bool,
+    /// How the device signature passed to `create_show_proof`/
+    /// `create_show_proof_mdl` should be interpreted; see `DeviceBindingMode`.
+    pub device_binding: DeviceBindingMode,
+    /// CBOR-encoded EC2 COSE_Key (see `webauthn::CosePublicKey::from_cbor`)
+    /// of the authenticator a `device_bound` show proof's WebAuthn
+    /// assertion must verify against. `None` if the credential is not bound
+    /// to a WebAuthn authenticator.
+    pub device_public_key: Option<Vec<u8>>,
+    /// Whether the WebAuthn assertion's user-presence/user-verification
+    /// flags (CTAP2 section 6.1) must be set; only meaningful when
+    /// `device_public_key` is `Some`.
+    pub device_up_required: bool,
+    pub device_uv_required: bool,
+    /// The relying party's SHA256 RP ID hash, which a `device_binding:
+    /// WebAuthn` show proof's authenticator assertion must carry in its
+    /// `authenticatorData`. `None` if the credential is not WebAuthn device
+    /// bound.
+    pub device_rp_id_hash: Option<Vec<u8>>,
+    /// The issuer's signature algorithm, resolved once from the config's
+    /// `alg` field (see `SigAlg::from_config`) instead of being re-guessed
+    /// from the issuer PEM's key shape at each use site.
+    pub sig_alg: SigAlg,
+    /// See `ProofSpec::audience`.
+    pub audience: Option<String>,
+    /// See `ProofSpec::nonce`.
+    pub nonce: Option<Vec<u8>>,
+    /// See `ProofSpec::not_after`.
+    pub not_after: Option<u64>,
     pub config_str: String,
     pub claim_types: std::collections::BTreeMap<String, String>, // claim name -> claim type
 }
 
+/// Verifies a CTAP2 authenticator assertion as the holder-of-device check
+/// for a `device_bound` show proof whose config carries a `device_cose_key`
+/// (see `ProofSpecInternal::device_public_key`), upgrading `device_bound`
+/// from a bare boolean into a cryptographically enforced binding.
+pub fn verify_device_assertion(proof_spec: &ProofSpecInternal, assertion: &webauthn::Assertion) -> Result<(), Box<dyn Error>> {
+    if !proof_spec.device_bound {
+        return_error!("Proof spec is not device bound; no authenticator assertion is expected");
+    }
+    let device_public_key = proof_spec.device_public_key.as_ref()
+        .ok_or("Proof spec's config does not carry a device_cose_key; this credential is not WebAuthn device bound")?;
+    let presentation_message = proof_spec.presentation_message.as_ref()
+        .ok_or("Proof spec is device bound but is missing the presentation message")?;
+    let rp_id_hash = proof_spec.device_rp_id_hash.as_ref()
+        .ok_or("Proof spec's config does not carry a device_rp_id_hash; this credential is not WebAuthn device bound")?;
+
+    let cose_key = webauthn::CosePublicKey::from_cbor(device_public_key)?;
+    webauthn::verify_assertion(assertion, presentation_message, &cose_key, rp_id_hash, proof_spec.device_up_required, proof_spec.device_uv_required)
+}
+
+/// A `device_bound` show proof's device-binding proof: either a hidden
+/// zero-knowledge proof of knowledge of an ECDSA signature
+/// (`DeviceBindingMode::RawEcdsa`), or an open WebAuthn assertion checked
+/// against the opened commitments (`DeviceBindingMode::WebAuthn`).
+#[derive(Clone, Debug, CanonicalSerialize, CanonicalDeserialize)]
+pub enum DeviceBindingProof<G: CurveGroup> {
+    Zk(DeviceProof<G>),
+    WebAuthn(webauthn::WebAuthnDeviceBinding<G>),
+}
+
 /// Structure to hold all the parts of a show/presentation proof
 #[derive(Clone, Debug, CanonicalSerialize, CanonicalDeserialize)]
 pub struct ShowProof<E: Pairing> {
+    pub format_version: u32, // see `PROOF_FORMAT_VERSION`
     pub show_groth16: ShowGroth16<E>,
     pub show_range_exp: ShowRange<E>, // non-expired range proof (always performed)
-    pub show_range_attr: Vec<ShowRange<E>>, // selective attribute range proofs
-    pub revealed_inputs: Vec<E::ScalarField>, 
+    pub show_range_attr: Vec<Vec<ShowRange<E>>>, // selective attribute range proofs; one entry per `Predicate`/`range_over_year` attr, two for a `between` predicate
+    pub revealed_inputs: Vec<E::ScalarField>,
     pub revealed_preimages: Option<String>,
-    pub inputs_len: usize, 
+    pub inputs_len: usize,
     pub cur_time: u64,
-    pub device_proof: Option<DeviceProof<E::G1>>
+    pub device_proof: Option<DeviceBindingProof<E::G1>>
 }
 
 /// Central struct to configure the paths data stored between operations
+#[derive(Clone)]
 pub struct CachePaths {
    pub _base: String,
    pub jwt : String,
    pub issuer_pem : String,
+   /// Optional JWK Set (RFC 7517) alternative to `issuer_pem`, for an
+   /// issuer that rotates keys or publishes more than one. When this file
+   /// exists, it takes precedence over `issuer_pem` and the right key is
+   /// selected by `kid` -- see `prep_inputs::resolve_issuer_pem`.
+   pub issuer_jwks : String,
    pub config : String,
    pub io_locations: String,
+   pub circom_sym: String,
    pub wasm: String,
    pub r1cs: String,
    pub _cache: String,
@@ -159,7 +425,8 @@ pub struct CachePaths {
    pub mdl_prover_aux: String,
    pub proof_spec: String,
    pub device_pub_pem: String,
-   pub device_prv_pem: String
+   pub device_prv_pem: String,
+   pub revocation_cascade: String
 }
 
 impl CachePaths {
@@ -188,8 +455,10 @@ impl CachePaths {
             _base: base_path_str.clone(),
             jwt: format!("{}token.jwt", base_path_str),
             issuer_pem: format!("{}issuer.pub", base_path_str),
+            issuer_jwks: format!("{}issuer.jwks", base_path_str),
             config: format!("{}config.json", base_path_str),
             io_locations: format!("{}io_locations.sym", base_path_str),
+            circom_sym: format!("{}main_c.sym", base_path_str),
             wasm: format!("{}main.wasm", base_path_str),
             r1cs: format!("{}main_c.r1cs", base_path_str),
             _cache: cache_path.clone(),
@@ -205,8 +474,21 @@ impl CachePaths {
             proof_spec: format!("{}proof_spec.json", &base_path_str),
             device_pub_pem: format!("{}device.pub", &base_path_str),
             device_prv_pem: format!("{}device.prv", &base_path_str),
-        }             
+            revocation_cascade: format!("{}revocation_cascade.json", &cache_path),
+        }
+    }
+}
+
+/// Checks whether `cred_uid` is revoked, using the filter cascade cached at
+/// `paths.revocation_cascade`. Credential types that don't publish a
+/// cascade (the file is simply absent) are treated as never revoked.
+pub fn check_revocation(paths: &CachePaths, cred_uid: &str) -> Result<bool, Box<dyn Error>> {
+    if fs::metadata(&paths.revocation_cascade).is_err() {
+        return Ok(false);
     }
+    let cascade_str = fs::read_to_string(&paths.revocation_cascade)?;
+    let cascade: revocation::FilterCascade = serde_json::from_str(&cascade_str)?;
+    Ok(cascade.check(cred_uid))
 }
 
 #[cfg(not(feature = "wasm"))]
@@ -246,9 +528,28 @@ pub fn run_zksetup(base_path: PathBuf) -> i32 {
 
     let config_str = fs::read_to_string(&paths.config).unwrap_or_else(|_| panic!("Unable to read config from {} ", paths.config));
     let prover_params = ProverParams{groth16_params: params, groth16_pvk: pvk, config_str};
-    write_to_file(&prover_params, &paths.prover_params);    
+    write_to_file(&prover_params, &paths.prover_params);
     end_timer!(serialize_timer);
 
+    // If circom's own symbol table was placed alongside the circuit (the
+    // case for a freshly-compiled custom relation), derive io_locations.sym
+    // from it directly rather than requiring it to already exist. Circuits
+    // that still ship a hand-built io_locations.sym (no main_c.sym present)
+    // are left untouched.
+    if fs::metadata(&paths.circom_sym).is_ok() {
+        match circom_io::io_locations_from_circom(&paths.r1cs, &paths.circom_sym) {
+            Ok(io_locations) => {
+                fs::write(&paths.io_locations, io_locations.to_csv())
+                    .unwrap_or_else(|e| panic!("Failed to write {}: {}", paths.io_locations, e));
+                println!("Generated {} from {}", paths.io_locations, paths.circom_sym);
+            }
+            Err(e) => println!(
+                "Warning: failed to import IO locations from {} ({}), leaving {} as-is",
+                paths.circom_sym, e, paths.io_locations
+            ),
+        }
+    }
+
     0
 }
 
@@ -303,7 +604,98 @@ pub fn create_client_state(paths : &CachePaths, prover_inputs: &GenericInputsJSO
     Ok(client_state)
 }
 
-pub fn create_show_proof(client_state: &mut ClientState<ECPairing>, range_pk : &RangeProofPK<ECPairing>, io_locations: &IOLocations, proof_spec: &ProofSpec, device_signature: Option<Vec<u8>>) -> Result<ShowProof<ECPairing>, Box<dyn Error>>
+/// The device-side signature supplied to `create_show_proof`/
+/// `create_show_proof_mdl` for a `device_bound` credential, matching the
+/// proof spec's `DeviceBindingMode`.
+pub enum DeviceSignature {
+    /// A raw P-256 ECDSA signature over `presentation_message` directly.
+    RawEcdsa(Vec<u8>),
+    /// A CTAP2/FIDO2 authenticator assertion.
+    WebAuthn(webauthn::Assertion),
+}
+
+/// Builds the device-binding proof for a `device_bound` show proof, checking
+/// that `device_signature`'s kind matches `proof_spec.device_binding` and
+/// dispatching to the hidden ZK proof (`RawEcdsa`) or the open WebAuthn
+/// binding (`WebAuthn`) accordingly. `com0`/`com1` are the show proof's
+/// `device_key_0`/`device_key_1` commitment openings; `aux_x`/`aux_y` are the
+/// device public key coordinates from the credential's aux data, only needed
+/// by the `RawEcdsa` path (see `device::DeviceProof::prove`).
+fn create_device_binding_proof(
+    proof_spec: &ProofSpecInternal,
+    device_signature: DeviceSignature,
+    com0: &PedersenOpening<ECPairingG1>,
+    com1: &PedersenOpening<ECPairingG1>,
+    aux_x: &BigUint,
+    aux_y: &BigUint,
+) -> Result<DeviceBindingProof<ECPairingG1>, Box<dyn Error>> {
+    let presentation_message = proof_spec.presentation_message.as_ref()
+        .ok_or("Proof spec is device bound but is missing the presentation message")?;
+
+    match (proof_spec.device_binding, device_signature) {
+        (DeviceBindingMode::RawEcdsa, DeviceSignature::RawEcdsa(sig_bytes)) => {
+            let sig = ECDSASig::new_from_bytes(presentation_message, &sig_bytes);
+            Ok(DeviceBindingProof::Zk(DeviceProof::prove(com0, com1, &sig, aux_x, aux_y)))
+        }
+        (DeviceBindingMode::WebAuthn, DeviceSignature::WebAuthn(assertion)) => {
+            Ok(DeviceBindingProof::WebAuthn(webauthn::WebAuthnDeviceBinding {
+                assertion,
+                com0: com0.clone(),
+                com1: com1.clone(),
+            }))
+        }
+        (DeviceBindingMode::RawEcdsa, DeviceSignature::WebAuthn(_)) => {
+            return_error!("Proof spec expects a raw ECDSA device signature, but a WebAuthn assertion was provided");
+        }
+        (DeviceBindingMode::WebAuthn, DeviceSignature::RawEcdsa(_)) => {
+            return_error!("Proof spec expects a WebAuthn assertion as the device signature, but a raw ECDSA signature was provided");
+        }
+    }
+}
+
+/// Verifies a `device_bound` show proof's device-binding proof against the
+/// show proof's `device_key_0`/`device_key_1` commitments (`com0`/`com1`,
+/// committed under `bases0`/`bases1`), dispatching on
+/// [`DeviceBindingProof`]'s variant.
+fn verify_device_binding_proof(
+    proof_spec: &ProofSpecInternal,
+    device_proof: &DeviceBindingProof<ECPairingG1>,
+    com0: &<ECPairingG1 as CurveGroup>::Affine,
+    com1: &<ECPairingG1 as CurveGroup>::Affine,
+    bases0: &[<ECPairingG1 as CurveGroup>::Affine],
+    bases1: &[<ECPairingG1 as CurveGroup>::Affine],
+) -> bool {
+    match device_proof {
+        DeviceBindingProof::Zk(dp) => DeviceProof::verify(dp, com0, com1, bases0, bases1),
+        DeviceBindingProof::WebAuthn(binding) => {
+            let (Some(presentation_message), Some(rp_id_hash)) =
+                (proof_spec.presentation_message.as_deref(), proof_spec.device_rp_id_hash.as_deref())
+            else {
+                return false;
+            };
+            binding.verify(
+                com0,
+                com1,
+                bases0,
+                bases1,
+                presentation_message,
+                rp_id_hash,
+                proof_spec.device_up_required,
+                proof_spec.device_uv_required,
+            )
+        }
+    }
+}
+
+pub fn create_show_proof(client_state: &mut ClientState<ECPairing>, range_pk : &RangeProofPK<ECPairing>, io_locations: &IOLocations, proof_spec: &ProofSpec, device_signature: Option<DeviceSignature>) -> Result<ShowProof<ECPairing>, Box<dyn Error>>
+{
+    create_show_proof_with_clock(client_state, range_pk, io_locations, proof_spec, device_signature, &NativeClock)
+}
+
+/// Same as [`create_show_proof`], but sources "today" (for `range_over_year`
+/// age thresholds) from `clock` instead of always using the host's local
+/// clock -- the wasm build has no real local clock, see `daystamp::Clock`.
+pub fn create_show_proof_with_clock(client_state: &mut ClientState<ECPairing>, range_pk : &RangeProofPK<ECPairing>, io_locations: &IOLocations, proof_spec: &ProofSpec, device_signature: Option<DeviceSignature>, clock: &dyn Clock) -> Result<ShowProof<ECPairing>, Box<dyn Error>>
 {
     // Create Groth16 rerandomized proof for showing
     let exp_value_pos = io_locations.get_io_location("exp_value").unwrap();
@@ -314,7 +706,13 @@ pub fn create_show_proof(client_state: &mut ClientState<ECPairing>, range_pk : &
         io_types[i] = PublicIOType::Revealed;
     }
 
-    let proof_spec = create_proof_spec_internal(proof_spec, &client_state.config_str)?;
+    let proof_spec = create_proof_spec_internal(proof_spec, &client_state.config_str, clock)?;
+
+    // for each range-checked attribute, set the position to Committed
+    for (attr, _) in &proof_spec.range_checks {
+        let io_loc = io_locations.get_io_location(&format!("{}_value", &attr)).unwrap();
+        io_types[io_loc - 1] = PublicIOType::Committed;
+    }
 
     // For the attributes revealed as field elements, we set the position to Revealed and send the value
     let mut revealed_inputs = vec![];
@@ -360,12 +758,13 @@ pub fn create_show_proof(client_state: &mut ClientState<ECPairing>, range_pk : &
         io_types[device_key_1_pos - 1] = PublicIOType::Committed;
     }
 
-    // Serialize the proof spec as the context
-    let context_str = serde_json::to_string(&proof_spec).unwrap();
-    let show_groth16 = client_state.show_groth16(Some(context_str.as_bytes()), &io_types);
-    
-    // Create fresh range proof 
+    // Bind the show proof to a canonical digest of the proof spec and the
+    // current time, rather than to its JSON serialization (see `show_context`).
     let time_sec = utc_now_seconds();
+    let context = show_context::compute(&proof_spec, io_locations, time_sec);
+    let show_groth16 = client_state.show_groth16(Some(&context[..]), &io_types);
+
+    // Create fresh range proof
     let cur_time = Fr::from( time_sec );
 
     let mut com_exp_value = client_state.committed_input_openings[0].clone();
@@ -373,46 +772,82 @@ pub fn create_show_proof(client_state: &mut ClientState<ECPairing>, range_pk : &
     com_exp_value.c -= com_exp_value.bases[0] * cur_time;
     let show_range_exp = client_state.show_range(&com_exp_value, RANGE_PROOF_INTERVAL_BITS, range_pk);
 
-    let device_proof = 
+    let device_proof =
     if proof_spec.device_bound {
         assert!(client_state.committed_input_openings.len() >= 3);
         let com0 = client_state.committed_input_openings[1].clone();
         let com1 = client_state.committed_input_openings[2].clone();
-        let sig = ECDSASig::new_from_bytes(&proof_spec.presentation_message.unwrap(), &device_signature.unwrap());
         let aux = serde_json::from_str::<Value>(client_state.aux.as_ref().unwrap()).unwrap();
         let aux = aux.as_object().unwrap();
         let x = BigUint::from_str_radix(aux["device_pub_x"].as_str().unwrap(), 10).unwrap();
         let y = BigUint::from_str_radix(aux["device_pub_y"].as_str().unwrap(), 10).unwrap();
         println!("Created device proof");
-        Some(DeviceProof::prove(&com0, &com1, &sig, &x, &y))
+        Some(create_device_binding_proof(&proof_spec, device_signature.unwrap(), &com0, &com1, &x, &y)?)
     } else {
         None
     };
 
     // Assemble proof
-    let revealed_preimages = if proof_spec.hashed.is_empty() { 
+    let revealed_preimages = if proof_spec.hashed.is_empty() {
         assert!(revealed_preimages.is_empty());
-        None 
+        None
     } else {
         Some(serde_json::to_string(&revealed_preimages).unwrap())
     };
-    let show_range_attr= vec![]; // no attribute range proofs for JWT yet
-    Ok(ShowProof{ show_groth16, show_range_exp, show_range_attr, revealed_inputs, revealed_preimages, inputs_len: client_state.inputs.len(), cur_time: time_sec, device_proof})
+    let mut show_range_attr: Vec<Vec<ShowRange<ECPairing>>> = vec![];
+    // The first committed input is always exp_value; device_key_0/device_key_1
+    // follow it when the credential is device bound, then the range-checked
+    // attributes.
+    let mut commitment_index = 1;
+    if proof_spec.device_bound {
+        commitment_index += 2;
+    }
+    // for each range-checked attribute, create fresh range proof(s) for its bound(s)
+    for (_, bound) in &proof_spec.range_checks {
+        let com_attr = client_state.committed_input_openings[commitment_index].clone();
+        let proofs = match bound {
+            RangeBound::GreaterThanOrEqual(lower) => {
+                vec![client_state.show_range(&shift_opening_ge(&com_attr, *lower), RANGE_PROOF_INTERVAL_BITS, range_pk)]
+            }
+            RangeBound::LessThanOrEqual(upper) => {
+                vec![client_state.show_range(&shift_opening_le(&com_attr, *upper), RANGE_PROOF_INTERVAL_BITS, range_pk)]
+            }
+            RangeBound::Between(lower, upper) => {
+                vec![
+                    client_state.show_range(&shift_opening_ge(&com_attr, *lower), RANGE_PROOF_INTERVAL_BITS, range_pk),
+                    client_state.show_range(&shift_opening_le(&com_attr, *upper), RANGE_PROOF_INTERVAL_BITS, range_pk),
+                ]
+            }
+        };
+
+        show_range_attr.push(proofs);
+        commitment_index += 1;
+    }
+
+    Ok(ShowProof{ format_version: PROOF_FORMAT_VERSION, show_groth16, show_range_exp, show_range_attr, revealed_inputs, revealed_preimages, inputs_len: client_state.inputs.len(), cur_time: time_sec, device_proof})
 }
 
 // TODO: refactor this function and create_show_proof into one
-pub fn create_show_proof_mdl(client_state: &mut ClientState<ECPairing>, range_pk : &RangeProofPK<ECPairing>, proof_spec: &ProofSpec, io_locations: &IOLocations, device_signature: Option<Vec<u8>>) -> Result<ShowProof<ECPairing>, Box<dyn Error>>
+pub fn create_show_proof_mdl(client_state: &mut ClientState<ECPairing>, range_pk : &RangeProofPK<ECPairing>, proof_spec: &ProofSpec, io_locations: &IOLocations, device_signature: Option<DeviceSignature>) -> Result<ShowProof<ECPairing>, Box<dyn Error>>
+{
+    create_show_proof_mdl_with_clock(client_state, range_pk, proof_spec, io_locations, device_signature, &NativeClock)
+}
+
+/// Same as [`create_show_proof_mdl`], but sources "today" (for
+/// `range_over_year` age thresholds) from `clock` -- see
+/// `create_show_proof_with_clock`.
+pub fn create_show_proof_mdl_with_clock(client_state: &mut ClientState<ECPairing>, range_pk : &RangeProofPK<ECPairing>, proof_spec: &ProofSpec, io_locations: &IOLocations, device_signature: Option<DeviceSignature>, clock: &dyn Clock) -> Result<ShowProof<ECPairing>, Box<dyn Error>>
 {
     // Create Groth16 rerandomized proof for showing
 
-    let proof_spec = create_proof_spec_internal(proof_spec, &client_state.config_str)?;
+    let proof_spec = create_proof_spec_internal(proof_spec, &client_state.config_str, clock)?;
 
     // commit the expiration date (for non-expired range proof)
     let valid_until_value_pos = io_locations.get_io_location("valid_until_value").unwrap();
     let mut io_types = vec![PublicIOType::Hidden; client_state.inputs.len()];
     io_types[valid_until_value_pos - 1] = PublicIOType::Committed;
-    // for each range proofed attribute, set the position to Committed
-    for (attr, _) in &proof_spec.range_over_year {
+    // for each range-checked attribute, set the position to Committed
+    for (attr, _) in &proof_spec.range_checks {
         let io_loc = io_locations.get_io_location(&format!("{}_value", &attr)).unwrap();
         io_types[io_loc - 1] = PublicIOType::Committed;
     }
@@ -458,19 +893,20 @@ pub fn create_show_proof_mdl(client_state: &mut ClientState<ECPairing>, range_pk
         io_types[device_key_1_pos - 1] = PublicIOType::Committed;
     }
 
-    // Serialize the proof spec as the context
-    let context_str = serde_json::to_string(&proof_spec).unwrap();
-    let show_groth16 = client_state.show_groth16(Some(context_str.as_bytes()), &io_types);    
-    
-    // Create fresh range proof for validUntil
+    // Bind the show proof to a canonical digest of the proof spec and the
+    // current time, rather than to its JSON serialization (see `show_context`).
     let time_sec = utc_now_seconds();
+    let context = show_context::compute(&proof_spec, io_locations, time_sec);
+    let show_groth16 = client_state.show_groth16(Some(&context[..]), &io_types);
+
+    // Create fresh range proof for validUntil
     let cur_time = Fr::from(time_sec);
 
     let mut com_valid_until_value = client_state.committed_input_openings[0].clone();
     com_valid_until_value.m -= cur_time;
     com_valid_until_value.c -= com_valid_until_value.bases[0] * cur_time;
     let show_range_exp = client_state.show_range(&com_valid_until_value, RANGE_PROOF_INTERVAL_BITS, range_pk);
-    let device_proof = 
+    let device_proof =
     if proof_spec.device_bound {
 
         if device_signature.is_none() {
@@ -480,48 +916,56 @@ pub fn create_show_proof_mdl(client_state: &mut ClientState<ECPairing>, range_pk
         assert!(client_state.committed_input_openings.len() >= 3);
         let com0 = client_state.committed_input_openings[1].clone();
         let com1 = client_state.committed_input_openings[2].clone();
-        let sig = ECDSASig::new_from_bytes(&proof_spec.presentation_message.unwrap(), &device_signature.unwrap());
         let aux = serde_json::from_str::<Value>(client_state.aux.as_ref().unwrap()).unwrap();
         let aux = aux.as_object().unwrap();
         let x = BigUint::from_str_radix(aux["device_pub_x"].as_str().unwrap(), 10).unwrap();
         let y = BigUint::from_str_radix(aux["device_pub_y"].as_str().unwrap(), 10).unwrap();
         println!("Created device proof");
-        Some(DeviceProof::prove(&com0, &com1, &sig, &x, &y))
+        Some(create_device_binding_proof(&proof_spec, device_signature.unwrap(), &com0, &com1, &x, &y)?)
     } else {
         None
     };
 
-    let revealed_preimages = if proof_spec.hashed.is_empty() { 
+    let revealed_preimages = if proof_spec.hashed.is_empty() {
         assert!(revealed_preimages.is_empty());
         None 
     } else {
         Some(serde_json::to_string(&revealed_preimages).unwrap())
     };
 
-    let mut show_range_attr= vec![];
+    let mut show_range_attr: Vec<Vec<ShowRange<ECPairing>>> = vec![];
     let mut commitment_index = 3; // skip the first 3 commitments (validUntil, device_key_0, device_key_1)
-    // for each range-proofed attribute, create a fresh range proof that the attribute is at least "age" years old // TODO: generalize to non-age attributes
-    for (_, age) in &proof_spec.range_over_year {
-        let days_in_age = Fr::from(days_to_be_age(*age) as u64);
-        let mut com_attr = client_state.committed_input_openings[commitment_index].clone();
-        com_attr.m -= days_in_age;
-        com_attr.c -= com_attr.bases[0] * days_in_age;
-
-        let show_range_a = client_state.show_range(&com_attr, RANGE_PROOF_INTERVAL_BITS, range_pk);       
+    // for each range-checked attribute, create fresh range proof(s) for its bound(s)
+    for (_, bound) in &proof_spec.range_checks {
+        let com_attr = client_state.committed_input_openings[commitment_index].clone();
+        let proofs = match bound {
+            RangeBound::GreaterThanOrEqual(lower) => {
+                vec![client_state.show_range(&shift_opening_ge(&com_attr, *lower), RANGE_PROOF_INTERVAL_BITS, range_pk)]
+            }
+            RangeBound::LessThanOrEqual(upper) => {
+                vec![client_state.show_range(&shift_opening_le(&com_attr, *upper), RANGE_PROOF_INTERVAL_BITS, range_pk)]
+            }
+            RangeBound::Between(lower, upper) => {
+                vec![
+                    client_state.show_range(&shift_opening_ge(&com_attr, *lower), RANGE_PROOF_INTERVAL_BITS, range_pk),
+                    client_state.show_range(&shift_opening_le(&com_attr, *upper), RANGE_PROOF_INTERVAL_BITS, range_pk),
+                ]
+            }
+        };
 
-        show_range_attr.push(show_range_a);
+        show_range_attr.push(proofs);
         commitment_index += 1;
     }
 
     // Assemble proof and return
-    Ok(ShowProof{ show_groth16, show_range_exp, show_range_attr, revealed_inputs, revealed_preimages, inputs_len: client_state.inputs.len(), cur_time: time_sec, device_proof})
+    Ok(ShowProof{ format_version: PROOF_FORMAT_VERSION, show_groth16, show_range_exp, show_range_attr, revealed_inputs, revealed_preimages, inputs_len: client_state.inputs.len(), cur_time: time_sec, device_proof})
 }
 
-fn sort_by_io_location(attrs: &[String], io_locations: &IOLocations) -> Vec<String> {
+pub(crate) fn sort_by_io_location(attrs: &[String], io_locations: &IOLocations, suffix: &str) -> Vec<String> {
     let mut attrs_with_locs: Vec<(usize, String)> = attrs
         .iter()
         .map(|attr| {
-            let io_loc = io_locations.get_io_location(&format!("{}_digest", attr)).unwrap();
+            let io_loc = io_locations.get_io_location(&format!("{}{}", attr, suffix)).unwrap();
             (io_loc, attr.clone())
         })
         .collect();
@@ -529,8 +973,113 @@ fn sort_by_io_location(attrs: &[String], io_locations: &IOLocations) -> Vec<Stri
     attrs_with_locs.into_iter().map(|(_, attr)| attr).collect()
 }
 
-pub fn verify_show(vp : &VerifierParams<ECPairing>, show_proof: &ShowProof<ECPairing>, proof_spec: &ProofSpec) -> (bool, String)
+/// Why [`verify_show`]/[`verify_show_mdl`] (and the batch-verification
+/// helpers built on the same checks) rejected a [`ShowProof`]. Replaces the
+/// old convention of `println!`-ing the reason and returning an opaque
+/// `false`, so a caller embedding this crate as a library can match on the
+/// concrete cause instead of parsing log output.
+#[derive(Debug, ThisError)]
+pub enum VerifyError {
+    #[error("failed to create internal proof spec: {0}")]
+    InvalidProofSpec(String),
+    #[error("show proof's audience does not match the verifier's expected audience")]
+    AudienceMismatch,
+    #[error("show proof's nonce does not match the verifier's expected nonce")]
+    NonceMismatch,
+    #[error("show proof's not_after does not match the verifier's expected challenge")]
+    NotAfterMismatch,
+    #[error("'{0}' is not declared in io_locations")]
+    UnknownIoLocation(String),
+    #[error("credential has hashed attributes, but the prover did not send revealed_preimages")]
+    MissingPreimages,
+    #[error("failed to deserialize revealed_preimages")]
+    InvalidPreimages,
+    #[error("preimage for hashed attribute '{0}' not provided by prover")]
+    MissingPreimage(String),
+    #[error("preimage for hashed attribute '{0}' has an unsupported type")]
+    UnsupportedPreimageType(String),
+    #[error("failed to convert issuer public key to input values")]
+    InvalidIssuerKey,
+    #[error("groth16 proof verification failed")]
+    Groth16Failed,
+    #[error("show proof expired {age_secs} seconds ago")]
+    Expired { age_secs: u64 },
+    #[error("verifier challenge expired at {not_after}")]
+    ChallengeExpired { not_after: u64 },
+    #[error("range proof for '{0}' failed")]
+    RangeProofFailed(String),
+    #[error("expected {expected} range proof(s) for attribute '{attr}', got {actual}")]
+    RangeProofCountMismatch { attr: String, expected: usize, actual: usize },
+    #[error("device-binding proof is missing from the show proof")]
+    DeviceProofMissing,
+    #[error("device-binding proof verification failed")]
+    DeviceProofFailed,
+    #[error("proof was valid, but failed to unpack '{0}' attribute")]
+    Unpack(String),
+    #[error("proof was valid, but failed to find hashed attribute '{0}'")]
+    MissingHashedAttribute(String),
+    #[error("show proof format version {found} is not supported by this verifier (supports {min}..={current})")]
+    UnsupportedProofVersion { found: u32, min: u32, current: u32 },
+}
+
+/// Shared by [`verify_show`]/[`verify_show_mdl`]: rejects a [`ShowProof`]
+/// whose `format_version` falls outside the range this build understands,
+/// before any cryptographic verification is attempted.
+fn check_proof_format_version(show_proof_version: u32) -> Result<(), VerifyError> {
+    if show_proof_version < MIN_SUPPORTED_PROOF_FORMAT_VERSION || show_proof_version > PROOF_FORMAT_VERSION {
+        return Err(VerifyError::UnsupportedProofVersion {
+            found: show_proof_version,
+            min: MIN_SUPPORTED_PROOF_FORMAT_VERSION,
+            current: PROOF_FORMAT_VERSION,
+        });
+    }
+    Ok(())
+}
+
+/// The result of verifying a [`ShowProof`]: either it's valid, with its
+/// revealed attributes, or it isn't, with the concrete [`VerifyError`] that
+/// rejected it.
+#[derive(Debug)]
+pub struct VerifyOutcome {
+    pub verified: bool,
+    pub revealed: serde_json::Map<String, Value>,
+    pub failure: Option<VerifyError>,
+    /// The `claim` of every `ProofSpec::time_predicates` entry this proof
+    /// was checked against. Always the full requested set when `verified`
+    /// is `true`: the range-proof checks above return early on the first
+    /// one that fails, so reaching `VerifyOutcome::ok` means every
+    /// requested time predicate held. Empty when `verified` is `false`.
+    pub satisfied_time_predicates: Vec<String>,
+}
+
+impl VerifyOutcome {
+    fn ok(revealed: serde_json::Map<String, Value>, satisfied_time_predicates: Vec<String>) -> Self {
+        VerifyOutcome { verified: true, revealed, failure: None, satisfied_time_predicates }
+    }
+
+    fn err(failure: VerifyError) -> Self {
+        VerifyOutcome { verified: false, revealed: serde_json::Map::new(), failure: Some(failure), satisfied_time_predicates: Vec::new() }
+    }
+}
+
+/// The `claim` of every `proof_spec.time_predicates` entry, captured before
+/// `create_proof_spec_internal` folds them into `ProofSpecInternal::range_checks`
+/// and the original names are lost.
+fn time_predicate_claim_names(proof_spec: &ProofSpec) -> Vec<String> {
+    proof_spec.time_predicates.as_ref()
+        .map(|preds| preds.iter().map(|p| p.claim.clone()).collect())
+        .unwrap_or_default()
+}
+
+pub fn verify_show(vp : &VerifierParams<ECPairing>, show_proof: &ShowProof<ECPairing>, proof_spec: &ProofSpec, expected_challenge: Option<&VerifierChallenge>) -> VerifyOutcome
 {
+    if let Err(e) = check_proof_format_version(show_proof.format_version) {
+        println!("{}", e);
+        return VerifyOutcome::err(e);
+    }
+
+    let time_predicate_claims = time_predicate_claim_names(proof_spec);
+
     let io_locations = IOLocations::new_from_str(&vp.io_locations_str);
     let exp_value_pos = io_locations.get_io_location("exp_value").unwrap();
     let mut io_types = vec![PublicIOType::Hidden; show_proof.inputs_len];
@@ -539,12 +1088,40 @@ pub fn verify_show(vp : &VerifierParams<ECPairing>, show_proof: &ShowProof<ECPai
         io_types[i] = PublicIOType::Revealed;
     }
 
-    let proof_spec = create_proof_spec_internal(proof_spec, &vp.config_str);
-    if proof_spec.is_err() {
-        println!("Failed to create internal proof spec");
-        return (false, "".to_string());
+    let proof_spec = match create_proof_spec_internal(proof_spec, &vp.config_str, &NativeClock) {
+        Ok(ps) => ps,
+        Err(e) => {
+            println!("Failed to create internal proof spec");
+            return VerifyOutcome::err(VerifyError::InvalidProofSpec(format!("{:?}", e)));
+        }
+    };
+
+    let expected_audience = expected_challenge.map(|c| c.audience.as_str());
+    let expected_nonce = expected_challenge.map(|c| &c.nonce[..]);
+    if proof_spec.audience.as_deref() != expected_audience {
+        println!("Show proof's audience does not match the verifier's expected audience");
+        return VerifyOutcome::err(VerifyError::AudienceMismatch);
+    }
+    if proof_spec.nonce.as_deref() != expected_nonce {
+        println!("Show proof's nonce does not match the verifier's expected nonce");
+        return VerifyOutcome::err(VerifyError::NonceMismatch);
+    }
+    if proof_spec.not_after != expected_challenge.map(|c| c.not_after) {
+        println!("Show proof's not_after does not match the verifier's expected challenge");
+        return VerifyOutcome::err(VerifyError::NotAfterMismatch);
+    }
+
+    // for each range-checked attribute, set the position to Committed
+    for (attr, _) in &proof_spec.range_checks {
+        let io_loc = match io_locations.get_io_location(&format!("{}_value", &attr)) {
+            Ok(loc) => loc,
+            Err(_) => {
+                println!("Asked to prove range for attribute {}, but did not find it in io_locations", attr);
+                return VerifyOutcome::err(VerifyError::UnknownIoLocation(format!("{}_value", attr)));
+            }
+        };
+        io_types[io_loc - 1] = PublicIOType::Committed;
     }
-    let proof_spec = proof_spec.unwrap();
 
     // Set disclosed attributes to Revealed
     for attr in &proof_spec.revealed {
@@ -552,7 +1129,7 @@ pub fn verify_show(vp : &VerifierParams<ECPairing>, show_proof: &ShowProof<ECPai
         if io_loc.is_err() {
             println!("Asked to reveal attribute {}, but did not find it in io_locations", attr);
             println!("IO locations: {:?}", io_locations.get_all_names());
-            return (false, "".to_string());
+            return VerifyOutcome::err(VerifyError::UnknownIoLocation(format!("{}_value", attr)));
         }
         let io_loc = io_loc.unwrap();
         io_types[io_loc - 1] = PublicIOType::Revealed;
@@ -562,22 +1139,25 @@ pub fn verify_show(vp : &VerifierParams<ECPairing>, show_proof: &ShowProof<ECPai
     let mut revealed_hashed = vec![];
     let mut preimages = json!(serde_json::Value::Null);
     if !proof_spec.hashed.is_empty() {
-        assert!(show_proof.revealed_preimages.is_some());
+        if show_proof.revealed_preimages.is_none() {
+            println!("Missing revealed_preimages for hashed attributes");
+            return VerifyOutcome::err(VerifyError::MissingPreimages);
+        }
         let preimages0 = serde_json::from_str::<Value>(show_proof.revealed_preimages.as_ref().unwrap());
         if preimages0.is_err() {
             println!("Failed to deserialize revealed_preimages");
-            return (false, "".to_string());
+            return VerifyOutcome::err(VerifyError::InvalidPreimages);
         }
         preimages = preimages0.unwrap();
 
-        let hashed_attributes = sort_by_io_location(&proof_spec.hashed, &io_locations);
-    
+        let hashed_attributes = sort_by_io_location(&proof_spec.hashed, &io_locations, "_digest");
+
         for attr in &hashed_attributes {
             let io_loc = io_locations.get_io_location(&format!("{}_digest", &attr));
             if io_loc.is_err() {
                 println!("Asked to reveal hashed attribute {}, but did not find it in io_locations", attr);
                 println!("IO locations: {:?}", io_locations.get_all_names());
-                return (false, "".to_string());
+                return VerifyOutcome::err(VerifyError::UnknownIoLocation(format!("{}_digest", attr)));
             }
             let io_loc = io_loc.unwrap();
             io_types[io_loc - 1] = PublicIOType::Revealed;
@@ -585,16 +1165,16 @@ pub fn verify_show(vp : &VerifierParams<ECPairing>, show_proof: &ShowProof<ECPai
             let preimage = preimages.get(attr);
             if preimage.is_none() {
                 println!("Error: preimage for hashed attribute {} not provided by prover", attr);
-                return(false, "".to_string());
+                return VerifyOutcome::err(VerifyError::MissingPreimage(attr.clone()));
             }
-            
+
             let data = match preimage.unwrap() {
                 Value::String(s) =>  {
                     s.as_bytes()
-                },     
+                },
                 _ =>  {
                     println!("Error: preimage has unsupported type");
-                    return(false, "".to_string());
+                    return VerifyOutcome::err(VerifyError::UnsupportedPreimageType(attr.clone()));
                 }
             };
             let digest = Sha256::digest(data);
@@ -614,33 +1194,47 @@ pub fn verify_show(vp : &VerifierParams<ECPairing>, show_proof: &ShowProof<ECPai
     }
 
     // Create an inputs vector with the revealed inputs and the issuer's public key
-    let public_key_inputs = pem_to_inputs::<<ECPairing as Pairing>::ScalarField>(&vp.issuer_pem);
+    let public_key_inputs = pem_to_inputs::<<ECPairing as Pairing>::ScalarField>(&vp.issuer_pem, proof_spec.sig_alg);
     if public_key_inputs.is_err() {
         print!("Error: Failed to convert issuer public key to input values");
-        return (false, "".to_string());
+        return VerifyOutcome::err(VerifyError::InvalidIssuerKey);
     }
 
     let mut inputs = vec![];
     inputs.extend(revealed_hashed);
     inputs.extend(public_key_inputs.unwrap());
     inputs.extend(show_proof.revealed_inputs.clone());
-    
-    let context_str = serde_json::to_string(&proof_spec).unwrap();
+
+    let context = show_context::compute(&proof_spec, &io_locations, show_proof.cur_time);
 
     let verify_timer = std::time::Instant::now();
-    let ret = show_proof.show_groth16.verify(&vp.vk, &vp.pvk, Some(context_str.as_bytes()), &io_types, &inputs);
+    let ret = show_proof.show_groth16.verify(&vp.vk, &vp.pvk, Some(&context[..]), &io_types, &inputs);
     if !ret {
         println!("show_groth16.verify failed");
-        return (false, "".to_string());
+        return VerifyOutcome::err(VerifyError::Groth16Failed);
     }
     let cur_time = Fr::from(show_proof.cur_time);
     let now_seconds = utc_now_seconds();
-    let delta = now_seconds.saturating_sub(show_proof.cur_time);
-    println!("Proof created {} seconds ago", delta);    
 
-    if delta > SHOW_PROOF_VALIDITY_SECONDS {
-        println!("Invalid show proof -- older than {} seconds", SHOW_PROOF_VALIDITY_SECONDS);
-        return (false, "".to_string());
+    // When the verifier issued a `VerifierChallenge`, its `not_after` --
+    // already checked above to be the one this proof committed to -- is a
+    // verifier-chosen deadline and replaces `SHOW_PROOF_VALIDITY_SECONDS`
+    // measured from the prover's own (unauthenticated) clock.
+    match expected_challenge {
+        Some(challenge) => {
+            if now_seconds > challenge.not_after {
+                println!("Invalid show proof -- verifier challenge expired at {}", challenge.not_after);
+                return VerifyOutcome::err(VerifyError::ChallengeExpired { not_after: challenge.not_after });
+            }
+        }
+        None => {
+            let delta = now_seconds.saturating_sub(show_proof.cur_time);
+            println!("Proof created {} seconds ago", delta);
+            if delta > SHOW_PROOF_VALIDITY_SECONDS {
+                println!("Invalid show proof -- older than {} seconds", SHOW_PROOF_VALIDITY_SECONDS);
+                return VerifyOutcome::err(VerifyError::Expired { age_secs: delta });
+            }
+        }
     }
 
     let mut ped_com_exp_value = show_proof.show_groth16.commited_inputs[0];
@@ -655,12 +1249,68 @@ pub fn verify_show(vp : &VerifierParams<ECPairing>, show_proof: &ShowProof<ECPai
     );
     if !ret {
         println!("show_range.verify failed");
-        return (false, "".to_string());
+        return VerifyOutcome::err(VerifyError::RangeProofFailed("exp_value".to_string()));
+    }
+
+    // The first committed input is always exp_value; device_key_0/device_key_1
+    // follow it when the credential is device bound, then the range-checked
+    // attributes -- mirroring the commitment layout `create_show_proof` built.
+    let mut range_attr_commitment_index = 1;
+    if proof_spec.device_bound {
+        range_attr_commitment_index += 2;
+    }
+    for (i, show_range_proofs) in show_proof.show_range_attr.iter().enumerate() {
+        let commitment_index = range_attr_commitment_index + i;
+        let (attr_name, bound) = &proof_spec.range_checks[i];
+        let attr_label = format!("{}_value", &attr_name);
+        let io_pos = match io_locations.get_io_location(&attr_label) {
+            Ok(loc) => loc,
+            Err(_) => {
+                println!("Asked to prove range for attribute {}, but did not find it in io_locations", attr_name);
+                return VerifyOutcome::err(VerifyError::UnknownIoLocation(attr_label));
+            }
+        };
+        let commitment = show_proof.show_groth16.commited_inputs[commitment_index];
+        let base = vp.pvk.vk.gamma_abc_g1[io_pos];
+
+        let checks = match bound {
+            RangeBound::GreaterThanOrEqual(lower) => vec![commitment - base * fr_from_i64(*lower)],
+            RangeBound::LessThanOrEqual(upper) => vec![base * fr_from_i64(*upper) - commitment],
+            RangeBound::Between(lower, upper) => vec![
+                commitment - base * fr_from_i64(*lower),
+                base * fr_from_i64(*upper) - commitment,
+            ],
+        };
+
+        if show_range_proofs.len() != checks.len() {
+            println!("Expected {} range proof(s) for attribute {}, got {}", checks.len(), attr_name, show_range_proofs.len());
+            return VerifyOutcome::err(VerifyError::RangeProofCountMismatch {
+                attr: attr_name.clone(),
+                expected: checks.len(),
+                actual: show_range_proofs.len(),
+            });
+        }
+
+        for (ped_com_attr_value, show_range_proof) in checks.iter().zip(show_range_proofs) {
+            let ret = show_range_proof.verify(
+                ped_com_attr_value,
+                RANGE_PROOF_INTERVAL_BITS,
+                &vp.range_vk,
+                &io_locations,
+                &vp.pvk,
+                &attr_label,
+            );
+            if !ret {
+                println!("show_range_attr.verify failed for attribute {}", attr_name);
+                return VerifyOutcome::err(VerifyError::RangeProofFailed(attr_name.clone()));
+            }
+        }
+        println!("range proof(s) for {} succeeded", attr_name);
     }
 
     if proof_spec.device_bound {
         let device_key_0_pos = io_locations.get_io_location("device_key_0_value").unwrap();
-        let device_key_1_pos = io_locations.get_io_location("device_key_1_value").unwrap();        
+        let device_key_1_pos = io_locations.get_io_location("device_key_1_value").unwrap();
         let com0 = show_proof.show_groth16.commited_inputs[1];
         let com1 = show_proof.show_groth16.commited_inputs[2];
         let bases0 = vec![vp.pvk.vk.gamma_abc_g1[device_key_0_pos], vp.pvk.vk.delta_g1];
@@ -669,18 +1319,18 @@ pub fn verify_show(vp : &VerifierParams<ECPairing>, show_proof: &ShowProof<ECPai
             Some(dp) => dp,
             None => {
                 println!("DeviceProof.verify failed: device_proof missing in show_proof");
-                return (false, "Device proof missing in show_proof".to_string());
+                return VerifyOutcome::err(VerifyError::DeviceProofMissing);
             }
         };
-        let ret = DeviceProof::verify(device_proof, &com0.into(), &com1.into(), &bases0, &bases1);
+        let ret = verify_device_binding_proof(&proof_spec, device_proof, &com0.into(), &com1.into(), &bases0, &bases1);
         if !ret {
             println!("DeviceProof.verify failed");
-            return (false, "".to_string());            
+            return VerifyOutcome::err(VerifyError::DeviceProofFailed);
         }
         println!("Device proof verified successfully");
     }
-    
-    println!("Verification time: {:?}", verify_timer.elapsed());  
+
+    println!("Verification time: {:?}", verify_timer.elapsed());
 
     // Add the revealed attributes to the output, after converting from field element to string
     let mut revealed = serde_json::Map::<String, Value>::new();
@@ -694,7 +1344,7 @@ pub fn verify_show(vp : &VerifierParams<ECPairing>, show_proof: &ShowProof<ECPai
                 Ok(val) => json!(val),
                 Err(_) => {
                     println!("Error: Proof was valid, but failed to unpack '{}' attribute", attr_name);
-                    return (false, "".to_string());
+                    return VerifyOutcome::err(VerifyError::Unpack(attr_name));
                 }
             }
         };
@@ -706,7 +1356,7 @@ pub fn verify_show(vp : &VerifierParams<ECPairing>, show_proof: &ShowProof<ECPai
         let attr_value = preimages.get(attr_name);
         if attr_value.is_none() {
             println!("Error: Proof was valid, but failed to find hashed attribute '{}'", attr_name);
-            return(false, "".to_string());
+            return VerifyOutcome::err(VerifyError::MissingHashedAttribute(attr_name.clone()));
         }
         let value = match attr_value.unwrap() {
             Value::String(s) => {
@@ -718,29 +1368,53 @@ pub fn verify_show(vp : &VerifierParams<ECPairing>, show_proof: &ShowProof<ECPai
     }
 
 
-    (true, serde_json::to_string(&revealed).unwrap())
+    VerifyOutcome::ok(revealed, time_predicate_claims)
 }
 
-pub fn verify_show_mdl(vp : &VerifierParams<ECPairing>, show_proof: &ShowProof<ECPairing>, proof_spec: &ProofSpec) -> (bool, String)
+
+pub fn verify_show_mdl(vp : &VerifierParams<ECPairing>, show_proof: &ShowProof<ECPairing>, proof_spec: &ProofSpec, expected_challenge: Option<&VerifierChallenge>) -> VerifyOutcome
 {
-    let proof_spec = create_proof_spec_internal(proof_spec, &vp.config_str);
-    if proof_spec.is_err() {
-        println!("Failed to create internal proof spec: {:?}", proof_spec.err().unwrap());
-        return (false, "".to_string());
+    if let Err(e) = check_proof_format_version(show_proof.format_version) {
+        println!("{}", e);
+        return VerifyOutcome::err(e);
+    }
+
+    let time_predicate_claims = time_predicate_claim_names(proof_spec);
+
+    let proof_spec = match create_proof_spec_internal(proof_spec, &vp.config_str, &NativeClock) {
+        Ok(ps) => ps,
+        Err(e) => {
+            println!("Failed to create internal proof spec: {:?}", e);
+            return VerifyOutcome::err(VerifyError::InvalidProofSpec(format!("{:?}", e)));
+        }
+    };
+
+    let expected_audience = expected_challenge.map(|c| c.audience.as_str());
+    let expected_nonce = expected_challenge.map(|c| &c.nonce[..]);
+    if proof_spec.audience.as_deref() != expected_audience {
+        println!("Show proof's audience does not match the verifier's expected audience");
+        return VerifyOutcome::err(VerifyError::AudienceMismatch);
+    }
+    if proof_spec.nonce.as_deref() != expected_nonce {
+        println!("Show proof's nonce does not match the verifier's expected nonce");
+        return VerifyOutcome::err(VerifyError::NonceMismatch);
+    }
+    if proof_spec.not_after != expected_challenge.map(|c| c.not_after) {
+        println!("Show proof's not_after does not match the verifier's expected challenge");
+        return VerifyOutcome::err(VerifyError::NotAfterMismatch);
     }
-    let proof_spec = proof_spec.unwrap();
 
     let io_locations = IOLocations::new_from_str(&vp.io_locations_str);
     let valid_until_value_pos = io_locations.get_io_location("valid_until_value").unwrap();
     let mut io_types = vec![PublicIOType::Hidden; show_proof.inputs_len];
     io_types[valid_until_value_pos - 1] = PublicIOType::Committed;
-    // for each range proofed attribute, set the position to Committed
-    for (attr, _) in &proof_spec.range_over_year {
+    // for each range-checked attribute, set the position to Committed
+    for (attr, _) in &proof_spec.range_checks {
         let io_loc = match io_locations.get_io_location(&format!("{}_value", &attr)) {
             Ok(loc) => loc,
             Err(_) => {
                 println!("Asked to prove range for attribute {}, but did not find it in io_locations", attr);
-                return (false, "".to_string());
+                return VerifyOutcome::err(VerifyError::UnknownIoLocation(format!("{}_value", attr)));
             }
         };
         io_types[io_loc - 1] = PublicIOType::Committed;
@@ -756,7 +1430,7 @@ pub fn verify_show_mdl(vp : &VerifierParams<ECPairing>, show_proof: &ShowProof<E
         if io_loc.is_err() {
             println!("Asked to reveal attribute {}, but did not find it in io_locations", attr);
             println!("IO locations: {:?}", io_locations.get_all_names());
-            return (false, "".to_string());
+            return VerifyOutcome::err(VerifyError::UnknownIoLocation(format!("{}_value", attr)));
         }
         let io_loc = io_loc.unwrap();
         io_types[io_loc - 1] = PublicIOType::Revealed;
@@ -766,21 +1440,24 @@ pub fn verify_show_mdl(vp : &VerifierParams<ECPairing>, show_proof: &ShowProof<E
     let mut revealed_hashed = vec![];
     let mut preimages = json!(serde_json::Value::Null);
     if !proof_spec.hashed.is_empty() {
-        assert!(show_proof.revealed_preimages.is_some());
+        if show_proof.revealed_preimages.is_none() {
+            println!("Missing revealed_preimages for hashed attributes");
+            return VerifyOutcome::err(VerifyError::MissingPreimages);
+        }
         let preimages0 = serde_json::from_str::<Value>(show_proof.revealed_preimages.as_ref().unwrap());
         if preimages0.is_err() {
             println!("Failed to deserialize revealed_preimages");
-            return (false, "".to_string());
+            return VerifyOutcome::err(VerifyError::InvalidPreimages);
         }
         preimages = preimages0.unwrap();
-        let hashed_attributes = sort_by_io_location(&proof_spec.hashed, &io_locations);
-    
+        let hashed_attributes = sort_by_io_location(&proof_spec.hashed, &io_locations, "_digest");
+
         for attr in &hashed_attributes {
             let io_loc = io_locations.get_io_location(&format!("{}_digest", &attr));
             if io_loc.is_err() {
                 println!("Asked to reveal hashed attribute {}, but did not find it in io_locations", attr);
                 println!("IO locations: {:?}", io_locations.get_all_names());
-                return (false, "".to_string());
+                return VerifyOutcome::err(VerifyError::UnknownIoLocation(format!("{}_digest", attr)));
             }
             let io_loc = io_loc.unwrap();
             io_types[io_loc - 1] = PublicIOType::Revealed;
@@ -788,16 +1465,16 @@ pub fn verify_show_mdl(vp : &VerifierParams<ECPairing>, show_proof: &ShowProof<E
             let preimage = preimages.get(attr);
             if preimage.is_none() {
                 println!("Error: preimage for hashed attribute {} not provided by prover", attr);
-                return(false, "".to_string());
+                return VerifyOutcome::err(VerifyError::MissingPreimage(attr.clone()));
             }
-            
+
             let data = match preimage.unwrap() {
                 Value::String(s) =>  {
                     s.as_bytes()
-                },     
+                },
                 _ =>  {
                     println!("Error: preimage has unsupported type");
-                    return(false, "".to_string());
+                    return VerifyOutcome::err(VerifyError::UnsupportedPreimageType(attr.clone()));
                 }
             };
             let digest = Sha256::digest(data);
@@ -820,30 +1497,44 @@ pub fn verify_show_mdl(vp : &VerifierParams<ECPairing>, show_proof: &ShowProof<E
     let public_key_inputs = pem_to_pubkey_hash::<<ECPairing as Pairing>::ScalarField>(&vp.issuer_pem);
     if public_key_inputs.is_err() {
         print!("Error: Failed to convert issuer public key to input values");
-        return (false, "".to_string());
+        return VerifyOutcome::err(VerifyError::InvalidIssuerKey);
     }
     let mut inputs = vec![];
     inputs.extend(revealed_hashed);
     inputs.push(public_key_inputs.unwrap());
     inputs.extend(show_proof.revealed_inputs.clone());
-       
-    let context_str = serde_json::to_string(&proof_spec).unwrap();
+
+    let context = show_context::compute(&proof_spec, &io_locations, show_proof.cur_time);
 
     let verify_timer = std::time::Instant::now();
-    let ret: bool = show_proof.show_groth16.verify(&vp.vk, &vp.pvk, Some(context_str.as_bytes()), &io_types, &inputs);
+    let ret: bool = show_proof.show_groth16.verify(&vp.vk, &vp.pvk, Some(&context[..]), &io_types, &inputs);
     if !ret {
         println!("show_groth16.verify failed");
-        return (false, "".to_string());
+        return VerifyOutcome::err(VerifyError::Groth16Failed);
     }
     let cur_time = Fr::from(show_proof.cur_time);
     let now_seconds = utc_now_seconds();
-    let delta = now_seconds.saturating_sub(show_proof.cur_time);
-    println!("Proof created {} seconds ago", delta);    
 
-    if delta > SHOW_PROOF_VALIDITY_SECONDS {
-        println!("Invalid show proof -- older than {} seconds", SHOW_PROOF_VALIDITY_SECONDS);
-        return (false, "".to_string());
-    }  
+    // When the verifier issued a `VerifierChallenge`, its `not_after` --
+    // already checked above to be the one this proof committed to -- is a
+    // verifier-chosen deadline and replaces `SHOW_PROOF_VALIDITY_SECONDS`
+    // measured from the prover's own (unauthenticated) clock.
+    match expected_challenge {
+        Some(challenge) => {
+            if now_seconds > challenge.not_after {
+                println!("Invalid show proof -- verifier challenge expired at {}", challenge.not_after);
+                return VerifyOutcome::err(VerifyError::ChallengeExpired { not_after: challenge.not_after });
+            }
+        }
+        None => {
+            let delta = now_seconds.saturating_sub(show_proof.cur_time);
+            println!("Proof created {} seconds ago", delta);
+            if delta > SHOW_PROOF_VALIDITY_SECONDS {
+                println!("Invalid show proof -- older than {} seconds", SHOW_PROOF_VALIDITY_SECONDS);
+                return VerifyOutcome::err(VerifyError::Expired { age_secs: delta });
+            }
+        }
+    }
 
     let mut ped_com_valid_until_value = show_proof.show_groth16.commited_inputs[0];
     ped_com_valid_until_value -= vp.pvk.vk.gamma_abc_g1[valid_until_value_pos] * cur_time;
@@ -857,43 +1548,64 @@ pub fn verify_show_mdl(vp : &VerifierParams<ECPairing>, show_proof: &ShowProof<E
     );
     if !ret {
         println!("show_range_exp.verify failed");
-        return (false, "".to_string());
-    }      
+        return VerifyOutcome::err(VerifyError::RangeProofFailed("valid_until_value".to_string()));
+    }
 
-    for (i, show_range_attr) in show_proof.show_range_attr.iter().enumerate() {
+    for (i, show_range_proofs) in show_proof.show_range_attr.iter().enumerate() {
         let commitment_index = i + 3; // skip the first 3 (validUntil, device_key_0, device_key_1)
-        let attr_name = &proof_spec.range_over_year[i].0;
+        let (attr_name, bound) = &proof_spec.range_checks[i];
         let attr_label = format!("{}_value", &attr_name);
-        let age = proof_spec.range_over_year[i].1;
-        let days_in_age = Fr::from(days_to_be_age(age) as u64);
-        let mut ped_com_attr_value = show_proof.show_groth16.commited_inputs[commitment_index];
         let io_pos = match io_locations.get_io_location(&attr_label) {
             Ok(loc) => loc,
             Err(_) => {
                 println!("Asked to prove range for attribute {}, but did not find it in io_locations", attr_name);
-                return (false, "".to_string());
+                return VerifyOutcome::err(VerifyError::UnknownIoLocation(attr_label));
             }
         };
-        ped_com_attr_value -= vp.pvk.vk.gamma_abc_g1[io_pos] * days_in_age;
-
-        let ret = show_range_attr.verify(
-            &ped_com_attr_value,
-            RANGE_PROOF_INTERVAL_BITS,
-            &vp.range_vk,
-            &io_locations,
-            &vp.pvk,
-            &attr_label,
-        );
-        if !ret {
-            println!("show_range_attr.verify failed");
-            return (false, "".to_string());
+        let commitment = show_proof.show_groth16.commited_inputs[commitment_index];
+        let base = vp.pvk.vk.gamma_abc_g1[io_pos];
+
+        // `Between` needs two shifted views of the same commitment -- one
+        // proving the lower bound, one the upper -- everything else is a
+        // single proof, matching how `create_show_proof_mdl` built them.
+        let checks = match bound {
+            RangeBound::GreaterThanOrEqual(lower) => vec![commitment - base * fr_from_i64(*lower)],
+            RangeBound::LessThanOrEqual(upper) => vec![base * fr_from_i64(*upper) - commitment],
+            RangeBound::Between(lower, upper) => vec![
+                commitment - base * fr_from_i64(*lower),
+                base * fr_from_i64(*upper) - commitment,
+            ],
+        };
+
+        if show_range_proofs.len() != checks.len() {
+            println!("Expected {} range proof(s) for attribute {}, got {}", checks.len(), attr_name, show_range_proofs.len());
+            return VerifyOutcome::err(VerifyError::RangeProofCountMismatch {
+                attr: attr_name.clone(),
+                expected: checks.len(),
+                actual: show_range_proofs.len(),
+            });
+        }
+
+        for (ped_com_attr_value, show_range_proof) in checks.iter().zip(show_range_proofs) {
+            let ret = show_range_proof.verify(
+                ped_com_attr_value,
+                RANGE_PROOF_INTERVAL_BITS,
+                &vp.range_vk,
+                &io_locations,
+                &vp.pvk,
+                &attr_label,
+            );
+            if !ret {
+                println!("show_range_attr.verify failed for attribute {}", attr_name);
+                return VerifyOutcome::err(VerifyError::RangeProofFailed(attr_name.clone()));
+            }
         }
-        println!("range proof for {} such that age is over {} succeeded", attr_name, age);
+        println!("range proof(s) for {} succeeded", attr_name);
     }
 
     if proof_spec.device_bound {
         let device_key_0_pos = io_locations.get_io_location("device_key_0_value").unwrap();
-        let device_key_1_pos = io_locations.get_io_location("device_key_1_value").unwrap();        
+        let device_key_1_pos = io_locations.get_io_location("device_key_1_value").unwrap();
         let com0 = show_proof.show_groth16.commited_inputs[1];
         let com1 = show_proof.show_groth16.commited_inputs[2];
         let bases0 = vec![vp.pvk.vk.gamma_abc_g1[device_key_0_pos], vp.pvk.vk.delta_g1];
@@ -902,18 +1614,18 @@ pub fn verify_show_mdl(vp : &VerifierParams<ECPairing>, show_proof: &ShowProof<E
             Some(dp) => dp,
             None => {
                 println!("DeviceProof.verify failed: device_proof missing in show_proof");
-                return (false, "Device proof missing in show_proof".to_string());
+                return VerifyOutcome::err(VerifyError::DeviceProofMissing);
             }
         };
-        let ret = DeviceProof::verify(device_proof, &com0.into(), &com1.into(), &bases0, &bases1);
+        let ret = verify_device_binding_proof(&proof_spec, device_proof, &com0.into(), &com1.into(), &bases0, &bases1);
         if !ret {
             println!("DeviceProof.verify failed");
-            return (false, "".to_string());            
+            return VerifyOutcome::err(VerifyError::DeviceProofFailed);
         }
         println!("Device proof verified successfully");
     }
 
-    println!("Verification time: {:?}", verify_timer.elapsed());  
+    println!("Verification time: {:?}", verify_timer.elapsed());
 
     // Add the revealed attributes to the output, after converting from field element to string
     let mut revealed = serde_json::Map::<String, Value>::new();
@@ -927,7 +1639,7 @@ pub fn verify_show_mdl(vp : &VerifierParams<ECPairing>, show_proof: &ShowProof<E
                 Ok(val) => json!(val),
                 Err(_) => {
                     println!("Error: Proof was valid, but failed to unpack '{}' attribute", attr_name);
-                    return (false, "".to_string());
+                    return VerifyOutcome::err(VerifyError::Unpack(attr_name));
                 }
             }
         };
@@ -939,7 +1651,7 @@ pub fn verify_show_mdl(vp : &VerifierParams<ECPairing>, show_proof: &ShowProof<E
         let attr_value = preimages.get(attr_name);
         if attr_value.is_none() {
             println!("Error: Proof was valid, but failed to find hashed attribute '{}'", attr_name);
-            return(false, "".to_string());
+            return VerifyOutcome::err(VerifyError::MissingHashedAttribute(attr_name.clone()));
         }
         let value = match attr_value.unwrap() {
             Value::String(s) => {
@@ -950,9 +1662,428 @@ pub fn verify_show_mdl(vp : &VerifierParams<ECPairing>, show_proof: &ShowProof<E
         revealed.insert(attr_name.clone(), value);
     }
 
-    (true, serde_json::to_string(&revealed).unwrap())
+    VerifyOutcome::ok(revealed, time_predicate_claims)
+}
+
+
+/// Per-proof state `prepare_batch_proof` carries forward to
+/// `finish_batch_proof`, once a proof's Groth16 equation is known to hold
+/// (whether from the batch's aggregated pairing check or a per-proof
+/// fallback).
+struct PendingBatchProof<'a> {
+    show_proof: &'a ShowProof<ECPairing>,
+    proof_spec: ProofSpecInternal,
+    preimages: Value,
+    com_inputs: ECPairingG1,
+    time_predicate_claims: Vec<String>,
 }
 
+/// Runs every check `verify_show` performs before its Groth16 pairing check
+/// -- proof spec resolution, challenge matching, freshness, IO-type layout,
+/// and hashed-attribute preimage hashing -- and returns the state
+/// `finish_batch_proof` needs, plus the folded input commitment
+/// `com_inputs` the deferred pairing check runs against. Returns the
+/// [`VerifyError`] (after logging why) if any of these independent checks fail.
+fn prepare_batch_proof<'a>(
+    vp: &VerifierParams<ECPairing>,
+    show_proof: &'a ShowProof<ECPairing>,
+    proof_spec: &ProofSpec,
+    expected_challenge: Option<&VerifierChallenge>,
+    io_locations: &IOLocations,
+    exp_value_pos: usize,
+) -> Result<PendingBatchProof<'a>, VerifyError> {
+    let time_predicate_claims = time_predicate_claim_names(proof_spec);
+
+    let mut io_types = vec![PublicIOType::Hidden; show_proof.inputs_len];
+    io_types[exp_value_pos - 1] = PublicIOType::Committed;
+    for i in io_locations.get_public_key_indices() {
+        io_types[i] = PublicIOType::Revealed;
+    }
+
+    let proof_spec = match create_proof_spec_internal(proof_spec, &vp.config_str, &NativeClock) {
+        Ok(ps) => ps,
+        Err(e) => {
+            println!("Failed to create internal proof spec");
+            return Err(VerifyError::InvalidProofSpec(format!("{:?}", e)));
+        }
+    };
+
+    let expected_audience = expected_challenge.map(|c| c.audience.as_str());
+    let expected_nonce = expected_challenge.map(|c| &c.nonce[..]);
+    if proof_spec.audience.as_deref() != expected_audience {
+        println!("Show proof's audience does not match the verifier's expected audience");
+        return Err(VerifyError::AudienceMismatch);
+    }
+    if proof_spec.nonce.as_deref() != expected_nonce {
+        println!("Show proof's nonce does not match the verifier's expected nonce");
+        return Err(VerifyError::NonceMismatch);
+    }
+    if proof_spec.not_after != expected_challenge.map(|c| c.not_after) {
+        println!("Show proof's not_after does not match the verifier's expected challenge");
+        return Err(VerifyError::NotAfterMismatch);
+    }
+
+    let now_seconds = utc_now_seconds();
+    match expected_challenge {
+        Some(challenge) => {
+            if now_seconds > challenge.not_after {
+                println!("Invalid show proof -- verifier challenge expired at {}", challenge.not_after);
+                return Err(VerifyError::ChallengeExpired { not_after: challenge.not_after });
+            }
+        }
+        None => {
+            let delta = now_seconds.saturating_sub(show_proof.cur_time);
+            if delta > SHOW_PROOF_VALIDITY_SECONDS {
+                println!("Invalid show proof -- older than {} seconds", SHOW_PROOF_VALIDITY_SECONDS);
+                return Err(VerifyError::Expired { age_secs: delta });
+            }
+        }
+    }
+
+    for (attr, _) in &proof_spec.range_checks {
+        let io_loc = match io_locations.get_io_location(&format!("{}_value", &attr)) {
+            Ok(loc) => loc,
+            Err(_) => {
+                println!("Asked to prove range for attribute {}, but did not find it in io_locations", attr);
+                return Err(VerifyError::UnknownIoLocation(format!("{}_value", attr)));
+            }
+        };
+        io_types[io_loc - 1] = PublicIOType::Committed;
+    }
+
+    for attr in &proof_spec.revealed {
+        let io_loc = match io_locations.get_io_location(&format!("{}_value", &attr)) {
+            Ok(loc) => loc,
+            Err(_) => {
+                println!("Asked to reveal attribute {}, but did not find it in io_locations", attr);
+                println!("IO locations: {:?}", io_locations.get_all_names());
+                return Err(VerifyError::UnknownIoLocation(format!("{}_value", attr)));
+            }
+        };
+        io_types[io_loc - 1] = PublicIOType::Revealed;
+    }
+
+    let mut revealed_hashed = vec![];
+    let mut preimages = json!(serde_json::Value::Null);
+    if !proof_spec.hashed.is_empty() {
+        if show_proof.revealed_preimages.is_none() {
+            println!("Missing revealed_preimages for hashed attributes");
+            return Err(VerifyError::MissingPreimages);
+        }
+        let preimages0 = serde_json::from_str::<Value>(show_proof.revealed_preimages.as_ref().unwrap());
+        if preimages0.is_err() {
+            println!("Failed to deserialize revealed_preimages");
+            return Err(VerifyError::InvalidPreimages);
+        }
+        preimages = preimages0.unwrap();
+
+        let hashed_attributes = sort_by_io_location(&proof_spec.hashed, io_locations, "_digest");
+        for attr in &hashed_attributes {
+            let io_loc = match io_locations.get_io_location(&format!("{}_digest", &attr)) {
+                Ok(loc) => loc,
+                Err(_) => {
+                    println!("Asked to reveal hashed attribute {}, but did not find it in io_locations", attr);
+                    return Err(VerifyError::UnknownIoLocation(format!("{}_digest", attr)));
+                }
+            };
+            io_types[io_loc - 1] = PublicIOType::Revealed;
+
+            let preimage = preimages.get(attr);
+            if preimage.is_none() {
+                println!("Error: preimage for hashed attribute {} not provided by prover", attr);
+                return Err(VerifyError::MissingPreimage(attr.clone()));
+            }
+            let data = match preimage.unwrap() {
+                Value::String(s) => s.as_bytes(),
+                _ => {
+                    println!("Error: preimage has unsupported type");
+                    return Err(VerifyError::UnsupportedPreimageType(attr.clone()));
+                }
+            };
+            let digest = Sha256::digest(data);
+            let digest248 = &digest[0..digest.len()-1];
+            let digest_uint = utils::bits_to_num(digest248);
+            let digest_scalar = utils::biguint_to_scalar::<CrescentFr>(&digest_uint);
+            revealed_hashed.push(digest_scalar);
+        }
+    }
+
+    if proof_spec.device_bound {
+        let device_key_0_pos = io_locations.get_io_location("device_key_0_value").unwrap();
+        let device_key_1_pos = io_locations.get_io_location("device_key_1_value").unwrap();
+        io_types[device_key_0_pos - 1] = PublicIOType::Committed;
+        io_types[device_key_1_pos - 1] = PublicIOType::Committed;
+    }
+
+    let public_key_inputs = match pem_to_inputs::<<ECPairing as Pairing>::ScalarField>(&vp.issuer_pem, proof_spec.sig_alg) {
+        Ok(inputs) => inputs,
+        Err(_) => {
+            println!("Error: Failed to convert issuer public key to input values");
+            return Err(VerifyError::InvalidIssuerKey);
+        }
+    };
+
+    let mut inputs = vec![];
+    inputs.extend(revealed_hashed);
+    inputs.extend(public_key_inputs);
+    inputs.extend(show_proof.revealed_inputs.clone());
+
+    let context = show_context::compute(&proof_spec, io_locations, show_proof.cur_time);
+    let (com_inputs, dlog_pok_valid) = show_proof.show_groth16.prepare_verify(&vp.vk, &vp.pvk, Some(&context[..]), &io_types, &inputs);
+    if !dlog_pok_valid {
+        println!("show_groth16 proof of knowledge of inputs failed");
+        return Err(VerifyError::Groth16Failed);
+    }
+
+    Ok(PendingBatchProof { show_proof, proof_spec, preimages, com_inputs, time_predicate_claims })
+}
+
+/// Runs the range and device-binding proofs and assembles the revealed
+/// attributes for a proof already known to satisfy its Groth16 equation --
+/// mirrors the second half of `verify_show`.
+fn finish_batch_proof(vp: &VerifierParams<ECPairing>, pending: PendingBatchProof) -> VerifyOutcome {
+    let io_locations = IOLocations::new_from_str(&vp.io_locations_str);
+    let show_proof = pending.show_proof;
+    let proof_spec = &pending.proof_spec;
+    let time_predicate_claims = pending.time_predicate_claims;
+    let exp_value_pos = io_locations.get_io_location("exp_value").unwrap();
+    let cur_time = Fr::from(show_proof.cur_time);
+
+    let mut ped_com_exp_value = show_proof.show_groth16.commited_inputs[0];
+    ped_com_exp_value -= vp.pvk.vk.gamma_abc_g1[exp_value_pos] * cur_time;
+    let ret = show_proof.show_range_exp.verify(
+        &ped_com_exp_value,
+        RANGE_PROOF_INTERVAL_BITS,
+        &vp.range_vk,
+        &io_locations,
+        &vp.pvk,
+        "exp_value",
+    );
+    if !ret {
+        println!("show_range.verify failed");
+        return VerifyOutcome::err(VerifyError::RangeProofFailed("exp_value".to_string()));
+    }
+
+    let mut range_attr_commitment_index = 1;
+    if proof_spec.device_bound {
+        range_attr_commitment_index += 2;
+    }
+    for (i, show_range_proofs) in show_proof.show_range_attr.iter().enumerate() {
+        let commitment_index = range_attr_commitment_index + i;
+        let (attr_name, bound) = &proof_spec.range_checks[i];
+        let attr_label = format!("{}_value", &attr_name);
+        let io_pos = match io_locations.get_io_location(&attr_label) {
+            Ok(loc) => loc,
+            Err(_) => {
+                println!("Asked to prove range for attribute {}, but did not find it in io_locations", attr_name);
+                return VerifyOutcome::err(VerifyError::UnknownIoLocation(attr_label));
+            }
+        };
+        let commitment = show_proof.show_groth16.commited_inputs[commitment_index];
+        let base = vp.pvk.vk.gamma_abc_g1[io_pos];
+
+        let checks = match bound {
+            RangeBound::GreaterThanOrEqual(lower) => vec![commitment - base * fr_from_i64(*lower)],
+            RangeBound::LessThanOrEqual(upper) => vec![base * fr_from_i64(*upper) - commitment],
+            RangeBound::Between(lower, upper) => vec![
+                commitment - base * fr_from_i64(*lower),
+                base * fr_from_i64(*upper) - commitment,
+            ],
+        };
+
+        if show_range_proofs.len() != checks.len() {
+            println!("Expected {} range proof(s) for attribute {}, got {}", checks.len(), attr_name, show_range_proofs.len());
+            return VerifyOutcome::err(VerifyError::RangeProofCountMismatch {
+                attr: attr_name.clone(),
+                expected: checks.len(),
+                actual: show_range_proofs.len(),
+            });
+        }
+
+        for (ped_com_attr_value, show_range_proof) in checks.iter().zip(show_range_proofs) {
+            let ret = show_range_proof.verify(
+                ped_com_attr_value,
+                RANGE_PROOF_INTERVAL_BITS,
+                &vp.range_vk,
+                &io_locations,
+                &vp.pvk,
+                &attr_label,
+            );
+            if !ret {
+                println!("show_range_attr.verify failed for attribute {}", attr_name);
+                return VerifyOutcome::err(VerifyError::RangeProofFailed(attr_name.clone()));
+            }
+        }
+    }
+
+    if proof_spec.device_bound {
+        let device_key_0_pos = io_locations.get_io_location("device_key_0_value").unwrap();
+        let device_key_1_pos = io_locations.get_io_location("device_key_1_value").unwrap();
+        let com0 = show_proof.show_groth16.commited_inputs[1];
+        let com1 = show_proof.show_groth16.commited_inputs[2];
+        let bases0 = vec![vp.pvk.vk.gamma_abc_g1[device_key_0_pos], vp.pvk.vk.delta_g1];
+        let bases1 = vec![vp.pvk.vk.gamma_abc_g1[device_key_1_pos], vp.pvk.vk.delta_g1];
+        let device_proof = match show_proof.device_proof.as_ref() {
+            Some(dp) => dp,
+            None => {
+                println!("DeviceProof.verify failed: device_proof missing in show_proof");
+                return VerifyOutcome::err(VerifyError::DeviceProofMissing);
+            }
+        };
+        let ret = verify_device_binding_proof(proof_spec, device_proof, &com0.into(), &com1.into(), &bases0, &bases1);
+        if !ret {
+            println!("DeviceProof.verify failed");
+            return VerifyOutcome::err(VerifyError::DeviceProofFailed);
+        }
+    }
+
+    let mut revealed = serde_json::Map::<String, Value>::new();
+    for (revealed_idx, attr_name) in proof_spec.revealed.iter().enumerate() {
+        let attr_name = attr_name.clone() + "_value";
+        let claim_type = proof_spec.claim_types.get(attr_name.trim_end_matches("_value")).map(|s| s.as_str()).unwrap_or("");
+        let attr_value = if claim_type == "number" {
+            json!(show_proof.revealed_inputs[revealed_idx].into_bigint().to_string())
+        } else {
+            match unpack_int_to_string_unquoted(&show_proof.revealed_inputs[revealed_idx].into_bigint()) {
+                Ok(val) => json!(val),
+                Err(_) => {
+                    println!("Error: Proof was valid, but failed to unpack '{}' attribute", attr_name);
+                    return VerifyOutcome::err(VerifyError::Unpack(attr_name));
+                }
+            }
+        };
+        revealed.insert(attr_name.clone(), attr_value);
+    }
+
+    for attr_name in &proof_spec.hashed {
+        let attr_value = pending.preimages.get(attr_name);
+        if attr_value.is_none() {
+            println!("Error: Proof was valid, but failed to find hashed attribute '{}'", attr_name);
+            return VerifyOutcome::err(VerifyError::MissingHashedAttribute(attr_name.clone()));
+        }
+        let value = match attr_value.unwrap() {
+            Value::String(s) => json!(strip_quotes(s)),
+            _ => attr_value.unwrap().clone()
+        };
+        revealed.insert(attr_name.clone(), value);
+    }
+
+    VerifyOutcome::ok(revealed, time_predicate_claims)
+}
+
+/// Verifies many JWT-credential [`ShowProof`]s against a shared
+/// [`VerifierParams`] by folding their Groth16 equations into a single
+/// random linear combination (RLC), instead of paying one full pairing
+/// check per proof -- the standard batch-verification trick for Groth16:
+/// sample fresh scalars `r_i` from a transcript seeded by every proof's
+/// Groth16 component, then check (in the pairing target group's additive
+/// notation)
+///
+/// `sum_i r_i * e(A_i, B_i) == e(alpha, beta * sum_i r_i) + e(sum_i r_i *
+/// com_inputs_i, gamma) + e(sum_i r_i * C_i, delta)`
+///
+/// via one `multi_pairing` call, so only a single final exponentiation runs
+/// regardless of how many proofs are in the batch (the dominant cost of
+/// verifying many Groth16 proofs one at a time).
+///
+/// Each proof still gets its own `context_str`/audience/nonce/`not_after`
+/// check, its own range proofs, and its own device-binding proof (if any)
+/// -- batching only defers the Groth16 pairing check, which is the one
+/// part of verification that genuinely amortizes across proofs. If the
+/// aggregated pairing check fails, every proof that reached this stage is
+/// re-verified individually so a single bad proof doesn't fail the whole
+/// batch's results.
+///
+/// `expected_challenges[i]` is the challenge to check `proofs[i]` against,
+/// exactly as `verify_show`'s `expected_challenge` parameter. mDL
+/// credentials aren't supported here; call `verify_show_mdl` for those.
+pub fn verify_show_batch(
+    vp: &VerifierParams<ECPairing>,
+    proofs: &[(ShowProof<ECPairing>, ProofSpec)],
+    expected_challenges: &[Option<&VerifierChallenge>],
+) -> Vec<VerifyOutcome> {
+    assert_eq!(proofs.len(), expected_challenges.len(), "expected_challenges must have one entry per proof");
+
+    let io_locations = IOLocations::new_from_str(&vp.io_locations_str);
+    let exp_value_pos = io_locations.get_io_location("exp_value").unwrap();
+
+    let mut results: Vec<Option<VerifyOutcome>> = (0..proofs.len()).map(|_| None).collect();
+    let mut pending: Vec<(usize, PendingBatchProof)> = Vec::new();
+    for (i, (show_proof, proof_spec)) in proofs.iter().enumerate() {
+        match prepare_batch_proof(vp, show_proof, proof_spec, expected_challenges[i], &io_locations, exp_value_pos) {
+            Ok(p) => pending.push((i, p)),
+            Err(e) => results[i] = Some(VerifyOutcome::err(e)),
+        }
+    }
+
+    if pending.is_empty() {
+        return results.into_iter().map(|r| r.unwrap()).collect();
+    }
+
+    // Seed the batch's randomness from every pending proof's Groth16
+    // component -- a verifier whose r_i a prover could predict in advance
+    // could craft a set of invalid proofs whose errors cancel out in the
+    // aggregate without any single proof being valid.
+    let mut ts = Transcript::new(b"crescent batch verify show proof v1");
+    for (_, p) in &pending {
+        ts.append_commitment(b"rand_proof a", &p.show_proof.show_groth16.rand_proof.a);
+        ts.append_commitment(b"rand_proof b", &p.show_proof.show_groth16.rand_proof.b);
+        ts.append_commitment(b"rand_proof c", &p.show_proof.show_groth16.rand_proof.c);
+        ts.append_commitment(b"com_inputs", &p.com_inputs);
+    }
+    let mut coefficients = Vec::with_capacity(pending.len());
+    for _ in &pending {
+        coefficients.push(ts.challenge_scalar::<Fr>(b"batch coefficient"));
+    }
+
+    let mut g1_terms = Vec::with_capacity(pending.len() + 3);
+    let mut g2_terms = Vec::with_capacity(pending.len() + 3);
+    let mut c_acc = ECPairingG1::zero();
+    let mut inputs_acc = ECPairingG1::zero();
+    let mut r_sum = Fr::zero();
+    for ((_, p), r_i) in pending.iter().zip(coefficients.iter().copied()) {
+        g1_terms.push((p.show_proof.show_groth16.rand_proof.a.into_group() * r_i).into_affine());
+        g2_terms.push(p.show_proof.show_groth16.rand_proof.b);
+        c_acc += p.show_proof.show_groth16.rand_proof.c.into_group() * r_i;
+        inputs_acc += p.com_inputs * r_i;
+        r_sum += r_i;
+    }
+    g1_terms.push((-(vp.vk.alpha_g1.into_group() * r_sum)).into_affine());
+    g2_terms.push(vp.vk.beta_g2);
+    g1_terms.push((-inputs_acc).into_affine());
+    g2_terms.push(vp.vk.gamma_g2);
+    g1_terms.push((-c_acc).into_affine());
+    g2_terms.push(vp.vk.delta_g2);
+
+    let aggregate = ECPairing::multi_pairing(g1_terms, g2_terms);
+
+    if aggregate == PairingOutput::<ECPairing>::zero() {
+        for (i, p) in pending {
+            results[i] = Some(finish_batch_proof(vp, p));
+        }
+        return results.into_iter().map(|r| r.unwrap()).collect();
+    }
+
+    println!("Batch Groth16 check failed; falling back to per-proof verification");
+    for (i, p) in pending {
+        let groth16_valid = match Groth16::<ECPairing>::verify_proof_with_prepared_inputs(&vp.pvk, &p.show_proof.show_groth16.rand_proof, &p.com_inputs) {
+            Ok(valid) => valid,
+            Err(e) => {
+                println!("Failed to verify Groth16 proof with error: {:?}", e);
+                false
+            }
+        };
+        if !groth16_valid {
+            println!("show_groth16.verify failed");
+            results[i] = Some(VerifyOutcome::err(VerifyError::Groth16Failed));
+            continue;
+        }
+        results[i] = Some(finish_batch_proof(vp, p));
+    }
+    results.into_iter().map(|r| r.unwrap()).collect()
+}
+
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1026,10 +2157,10 @@ mod tests {
         let ps_raw = fs::read_to_string(&paths.proof_spec).expect("Proof spec file exists, but failed while reading it");
         let mut proof_spec : ProofSpec = serde_json::from_str(&ps_raw).unwrap();
         proof_spec.presentation_message = Some(pm.as_bytes().to_vec());
-        let device_signature = 
+        let device_signature =
         if proof_spec.device_bound.is_some() && proof_spec.device_bound.unwrap() {
             let device = TestDevice::new_from_file(&paths.device_prv_pem);
-            Some(device.sign(proof_spec.presentation_message.as_ref().unwrap()))
+            Some(DeviceSignature::RawEcdsa(device.sign(proof_spec.presentation_message.as_ref().unwrap())))
         } else {
             None
         };
@@ -1056,12 +2187,25 @@ mod tests {
         let ps_raw = fs::read_to_string(&paths.proof_spec).expect("Proof spec file exists, but failed while reading it");
         let mut proof_spec : ProofSpec = serde_json::from_str(&ps_raw).unwrap();
         proof_spec.presentation_message = Some(pm.as_bytes().to_vec());
-        let (verify_result, _data) = if cred_type == "mdl" {
-            verify_show_mdl(&vp, &show_proof, &proof_spec)
+        let expected_challenge = expected_challenge_for_test(&proof_spec);
+        let outcome = if cred_type == "mdl" {
+            verify_show_mdl(&vp, &show_proof, &proof_spec, expected_challenge.as_ref())
         } else {
-            verify_show(&vp, &show_proof, &proof_spec)
+            verify_show(&vp, &show_proof, &proof_spec, expected_challenge.as_ref())
         };
-        assert!(verify_result);
+        assert!(outcome.verified, "verification failed: {:?}", outcome.failure);
+    }
+
+    /// Rebuilds the `VerifierChallenge` a test proof spec's own `audience`/
+    /// `nonce`/`not_after` fields describe, mirroring what a real verifier
+    /// would have issued -- these tests have no out-of-band verifier/prover
+    /// exchange, so the proof spec file is the only source for it.
+    fn expected_challenge_for_test(proof_spec: &ProofSpec) -> Option<VerifierChallenge> {
+        let audience = proof_spec.audience.clone()?;
+        let nonce = proof_spec.nonce.as_ref()?;
+        let not_after = proof_spec.not_after?;
+        let nonce: [u8; 32] = nonce.as_slice().try_into().ok()?;
+        Some(VerifierChallenge { nonce, audience, not_after })
     }
 
 }
@@ -3,14 +3,16 @@
 
 use ark_groth16::{VerifyingKey,PreparedVerifyingKey};
 use ark_serialize::CanonicalSerialize;
-use crescent::device::TestDevice;
+use crescent::device::{DeviceSigner, TestDevice};
 use crescent::groth16rand::{ClientState, ShowGroth16};
 use crescent::rangeproof::{RangeProofPK, RangeProofVK};
 use crescent::utils::{read_from_file, string_to_byte_vec, write_to_file};
-use crescent::{create_client_state, create_show_proof, create_show_proof_mdl, run_zksetup, verify_show, verify_show_mdl, CachePaths, ShowProof, VerifierParams, ProofSpec};
+use crescent::{create_client_state, create_show_proof, create_show_proof_mdl, run_zksetup, verify_show, verify_show_mdl, CachePaths, ShowProof, VerifierParams, ProofSpec, DeviceSignature, DeviceBindingMode, MIN_SUPPORTED_PROOF_FORMAT_VERSION, PROOF_FORMAT_VERSION};
+use crescent::challenge::VerifierChallenge;
 use crescent::CrescentPairing;
-use crescent::prep_inputs::{prepare_prover_inputs, parse_config};
+use crescent::prep_inputs::{prepare_prover_inputs, parse_config, resolve_issuer_pem, jwt_header_kid};
 use crescent::structs::{GenericInputsJSON, IOLocations, ProverInput};
+use serde::Serialize;
 use serde_json::json;
 use sha2::{Digest, Sha256};
 use std::env::current_dir;
@@ -50,6 +52,9 @@ fn main() {
             let base_path = root.join(name_path);
             run_verifier(base_path, presentation_message);
         }
+        Command::Version { json } => {
+            run_version(json);
+        }
     }
 }
 
@@ -94,6 +99,12 @@ pub enum Command {
         #[structopt(long, about = "Optional presentation message to include in the proof.")]
         presentation_message: Option<String>,
     },
+
+    #[structopt(about = "Print the proof format version and prover/verifier capability set, for negotiating compatibility with a peer before running `show`/`verify`.")]
+    Version {
+        #[structopt(long, about = "Emit the version/capability info as JSON instead of plain text.")]
+        json: bool,
+    },
 }
 
 
@@ -111,7 +122,11 @@ pub fn run_prover(
     }
     else {
         let jwt = fs::read_to_string(&paths.jwt).unwrap_or_else(|_| panic!("Unable to read JWT file from {}", paths.jwt));
-        let issuer_pem = fs::read_to_string(&paths.issuer_pem).unwrap_or_else(|_| panic!("Unable to read issuer public key PEM from {} ", paths.issuer_pem));   
+        let issuer_pem = fs::read_to_string(&paths.issuer_pem).unwrap_or_else(|_| panic!("Unable to read issuer public key PEM from {} ", paths.issuer_pem));
+        let issuer_jwks = fs::read_to_string(&paths.issuer_jwks).ok();
+        let kid = jwt_header_kid(&jwt).expect("Failed to parse JWT header");
+        let issuer_pem = resolve_issuer_pem(&issuer_pem, issuer_jwks.as_deref(), kid.as_deref())
+            .expect("Failed to resolve issuer public key from JWKS");
         let device_pub_pem = fs::read_to_string(&paths.device_pub_pem).ok();
         let (prover_inputs_json, prover_aux_json, _public_ios_json) = 
             prepare_prover_inputs(&config, &jwt, &issuer_pem, device_pub_pem.as_deref()).expect("Failed to prepare prover inputs");    
@@ -199,6 +214,39 @@ fn load_proof_spec(proof_spec_file_path : &str, presentation_message: Option<Str
     ps
 }
 
+/// Reconstructs the `VerifierChallenge` a proof spec's own `audience`/
+/// `nonce`/`not_after` fields describe, so this CLI tool's verifier can
+/// check a show proof against the same challenge the proof spec file
+/// says was used, without a real out-of-band verifier/prover exchange.
+/// Returns `None` if the proof spec doesn't carry a full challenge triple.
+fn expected_challenge(proof_spec: &ProofSpec) -> Option<VerifierChallenge> {
+    let audience = proof_spec.audience.as_ref()?;
+    let nonce = proof_spec.nonce.as_ref()?;
+    let not_after = proof_spec.not_after?;
+    let nonce: [u8; 32] = nonce.as_slice().try_into().ok()?;
+    Some(VerifierChallenge { nonce, audience: audience.clone(), not_after })
+}
+
+/// Picks the [`DeviceSigner`] backend for a `device_bound` show proof based
+/// on the proof spec's `device_binding` mode: `RawEcdsa` keeps using the
+/// original PEM-file-backed `TestDevice`; `WebAuthn` uses a real CTAP2
+/// platform authenticator (behind the `ctap2` feature, since it talks to
+/// hardware over USB HID rather than reading a file).
+fn device_signer(device_prv_pem: &str, mode: DeviceBindingMode) -> Box<dyn DeviceSigner> {
+    match mode {
+        DeviceBindingMode::RawEcdsa => Box::new(TestDevice::new_from_file(device_prv_pem)),
+        #[cfg(feature = "ctap2")]
+        DeviceBindingMode::WebAuthn => {
+            Box::new(crescent::device_ctap2::Ctap2Device::new_with_keygen("crescent.example", None)
+                .expect("Failed to provision/open CTAP2 device credential"))
+        }
+        #[cfg(not(feature = "ctap2"))]
+        DeviceBindingMode::WebAuthn => {
+            panic!("WebAuthn device binding requires the `ctap2` feature (build with --features ctap2)");
+        }
+    }
+}
+
 pub fn run_show(
     base_path: PathBuf,
     presentation_message: Option<String>
@@ -208,21 +256,30 @@ pub fn run_show(
     let io_locations = IOLocations::new(&paths.io_locations);    
     let mut client_state: ClientState<CrescentPairing> = read_from_file(&paths.client_state).unwrap();
     let range_pk : RangeProofPK<CrescentPairing> = read_from_file(&paths.range_pk).unwrap();
-    
-    let show_proof = if client_state.credtype == "mdl" {
-        let pm = string_to_byte_vec(presentation_message);
-        create_show_proof_mdl(&mut client_state, &range_pk, pm.as_deref(), &io_locations, MDL_AGE_GREATER_THAN)  
-    } else {
-        let proof_spec = load_proof_spec(&paths.proof_spec, presentation_message);
 
-        let device_signature = 
-        if proof_spec.device_bound.is_some() && proof_spec.device_bound.unwrap() {
-            let device = TestDevice::new_from_file(&paths.device_prv_pem);
-            Some(device.sign(proof_spec.presentation_message.as_ref().unwrap()))
-        } else {
-            None
-        };
+    let mut proof_spec = load_proof_spec(&paths.proof_spec, presentation_message);
+    if client_state.credtype == "mdl" {
+        // mDL show proofs don't carry their own proof spec file (unlike
+        // JWTs), so the age predicate is the one place this CLI tool still
+        // has to supply a threshold itself rather than reading it from a
+        // matched schema -- there's no schema registry at this layer.
+        proof_spec.range_over_year = Some(std::collections::BTreeMap::from([
+            ("birth_date".to_string(), MDL_AGE_GREATER_THAN as u64),
+        ]));
+    }
+
+    let device_signature =
+    if proof_spec.device_bound.is_some() && proof_spec.device_bound.unwrap() {
+        let digest = proof_spec.presentation_message.as_ref().unwrap();
+        let signer = device_signer(&paths.device_prv_pem, proof_spec.device_binding.unwrap_or_default());
+        Some(signer.sign(digest))
+    } else {
+        None
+    };
 
+    let show_proof = if client_state.credtype == "mdl" {
+        create_show_proof_mdl(&mut client_state, &range_pk, &proof_spec, &io_locations, device_signature).unwrap()
+    } else {
         create_show_proof(&mut client_state, &range_pk, &io_locations, &proof_spec, device_signature).unwrap()
     };
     println!("Proving time: {:?}", proof_timer.elapsed());
@@ -232,6 +289,53 @@ pub fn run_show(
     write_to_file(&show_proof, &paths.show_proof);
 }
 
+/// Version/capability info for prover-verifier negotiation, modeled as a
+/// bundled handshake message rather than a bare version string: before a
+/// holder runs `show` and a verifier runs `verify`, both need to agree on
+/// the `ShowProof` wire layout (`protocol_version`) and on which
+/// credential types/disclosure kinds are understood. `device_binding_modes`
+/// is `None` (omitted, not `null`) on a build with no device-binding
+/// support at all, so an older verifier parsing a newer blob doesn't choke
+/// on a field it doesn't expect.
+#[derive(Debug, Serialize)]
+struct VersionInfo {
+    crate_version: &'static str,
+    /// (min_supported_proof_format_version, proof_format_version)
+    protocol_version: (u32, u32),
+    supported_cred_types: Vec<&'static str>,
+    supported_disclosure_kinds: Vec<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    device_binding_modes: Option<Vec<DeviceBindingMode>>,
+}
+
+impl VersionInfo {
+    fn current() -> Self {
+        VersionInfo {
+            crate_version: env!("CARGO_PKG_VERSION"),
+            protocol_version: (MIN_SUPPORTED_PROOF_FORMAT_VERSION, PROOF_FORMAT_VERSION),
+            supported_cred_types: vec!["jwt", "mdl"],
+            supported_disclosure_kinds: vec!["reveal", "range_over_year", "predicate"],
+            device_binding_modes: Some(vec![DeviceBindingMode::RawEcdsa, DeviceBindingMode::WebAuthn]),
+        }
+    }
+}
+
+pub fn run_version(json: bool) {
+    let info = VersionInfo::current();
+    if json {
+        println!("{}", serde_json::to_string(&info).unwrap());
+    } else {
+        println!("Crate version: {}", info.crate_version);
+        println!("Proof format version: {} (oldest supported: {})", info.protocol_version.1, info.protocol_version.0);
+        println!("Supported credential types: {}", info.supported_cred_types.join(", "));
+        println!("Supported disclosure kinds: {}", info.supported_disclosure_kinds.join(", "));
+        match &info.device_binding_modes {
+            Some(modes) => println!("Device binding supported: {:?}", modes),
+            None => println!("Device binding supported: no"),
+        }
+    }
+}
+
 pub fn run_verifier(base_path: PathBuf, presentation_message: Option<String>) {
     let paths = CachePaths::new(base_path);
     let show_proof : ShowProof<CrescentPairing> = read_from_file(&paths.show_proof).unwrap();
@@ -241,21 +345,28 @@ pub fn run_verifier(base_path: PathBuf, presentation_message: Option<String>) {
     let io_locations_str = std::fs::read_to_string(&paths.io_locations).unwrap();
     let issuer_pem = std::fs::read_to_string(&paths.issuer_pem).unwrap();
     let config_str = std::fs::read_to_string(&paths.config).unwrap();
+    let issuer_jwks = std::fs::read_to_string(&paths.issuer_jwks).ok();
+    let config = parse_config(&config_str).expect("Failed to parse config");
+    let kid = config.get("issuer_kid").and_then(|v| v.as_str());
+    let issuer_pem = resolve_issuer_pem(&issuer_pem, issuer_jwks.as_deref(), kid)
+        .expect("Failed to resolve issuer public key from JWKS");
     let vp = VerifierParams{vk, pvk, range_vk, io_locations_str, issuer_pem, config_str};
     
-    let (verify_result, data) = if show_proof.show_range2.is_some() {
-        let pm = string_to_byte_vec(presentation_message);
-        verify_show_mdl(&vp, &show_proof, pm.as_deref(), MDL_AGE_GREATER_THAN)
+    let proof_spec = load_proof_spec(&paths.proof_spec, presentation_message);
+    let outcome = if show_proof.show_range2.is_some() {
+        verify_show_mdl(&vp, &show_proof, &proof_spec, expected_challenge(&proof_spec).as_ref())
     } else {
-        let proof_spec = load_proof_spec(&paths.proof_spec, presentation_message);  
-        verify_show(&vp, &show_proof, &proof_spec)
+        verify_show(&vp, &show_proof, &proof_spec, expected_challenge(&proof_spec).as_ref())
     };
 
-    if verify_result {
-        println!("Verify succeeded, got data '{}'", data);
+    if outcome.verified {
+        println!("Verify succeeded, got data '{}'", serde_json::Value::Object(outcome.revealed));
+        if !outcome.satisfied_time_predicates.is_empty() {
+            println!("Satisfied time predicates: {:?}", outcome.satisfied_time_predicates);
+        }
     }
     else {
-        println!("Verify failed")
+        println!("Verify failed: {}", outcome.failure.map(|e| e.to_string()).unwrap_or_default());
     }
 
 }
\ No newline at end of file
@@ -5,10 +5,11 @@ use ark_ff::PrimeField;
 use jwt_simple::prelude::*;
 use p256::ecdsa::VerifyingKey;
 use p256::pkcs8::DecodePublicKey;
+use serde::{Serialize, Deserialize};
 use serde_json::Value;
 use serde_json::json;
 use lazy_static::lazy_static;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use num_bigint::{BigInt, BigUint};
 use num_traits::FromPrimitive;
 use std::ops::{Shl, BitAnd};
@@ -16,20 +17,127 @@ use std::error::Error;
 use std::fs;
 use ark_std::path::PathBuf;
 use ark_ff::BigInteger;
+use sha2::{Digest, Sha256};
+use crate::cose;
 use crate::return_error;
 use crate::ProofSpec;
 use crate::ProofSpecInternal;
+use crate::DeviceBindingMode;
+use crate::{Predicate, PredicateOp, RangeBound, TimePredicate};
+use crate::daystamp::{days_to_be_age, Clock};
 
 // If not set in config.json, the max_cred_len is set to this value. 
 const DEFAULT_MAX_TOKEN_LENGTH : usize = 2048;
 const CIRCOM_RS256_LIMB_BITS : usize = 121;
 const CIRCOM_ES256_LIMB_BITS : usize = 43;  // Limb size required by ecdsa-p256 circuit
 const MAX_FIELD_BYTE_LEN : usize = 31;  
+/// Distinguishes the shape of an issuer's public key for limb-encoding
+/// purposes: an RSA modulus (one big integer) vs. an EC point (an x and a
+/// y coordinate, each limb-encoded separately) vs. an Edwards point.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum KeyFamily {
+    Rsa,
+    Ec,
+    Ed25519,
+}
+
+/// Everything the limb-encoding/witness-preparation code needs to know
+/// about a signature algorithm. Adding a new algorithm or key size is a
+/// matter of adding one entry to [`ALG_DESCRIPTORS`] instead of a new
+/// match arm scattered across `pem_to_inputs`, `prepare_prover_inputs`,
+/// and `parse_config`.
+#[derive(Clone, Copy, Debug)]
+struct AlgDescriptor {
+    key_family: KeyFamily,
+    limb_bits: usize,
+    /// Expected RSA modulus size in bits, checked against a config's
+    /// `modulus_bits` field if present. `None` for EC algorithms.
+    modulus_bits: Option<usize>,
+    /// Expected EC curve name, checked against a config's `curve` field
+    /// if present. `None` for RSA algorithms.
+    curve: Option<&'static str>,
+    /// Whether Crescent has a wired-up signature verification path and
+    /// circuit-compatible limb encoding for this algorithm today. Kept
+    /// `false` for algorithms that are registered (so config validation
+    /// and error messages are uniform) but not yet implemented.
+    supported: bool,
+}
+
 lazy_static! {
-    static ref CRESCENT_SUPPORTED_ALGS: HashSet<&'static str> = {
+    static ref ALG_DESCRIPTORS: HashMap<&'static str, AlgDescriptor> = {
+        let mut m = HashMap::new();
+        m.insert("RS256", AlgDescriptor { key_family: KeyFamily::Rsa, limb_bits: CIRCOM_RS256_LIMB_BITS, modulus_bits: Some(2048), curve: None, supported: true });
+        m.insert("ES256", AlgDescriptor { key_family: KeyFamily::Ec, limb_bits: CIRCOM_ES256_LIMB_BITS, modulus_bits: None, curve: Some("P-256"), supported: true });
+        // Registered so a config can name these and get a clear "not available yet"
+        // error, rather than the generic "algorithm is unsupported" for an unknown
+        // string -- no verification path or circuit-compatible limbs exist yet.
+        m.insert("ES384", AlgDescriptor { key_family: KeyFamily::Ec, limb_bits: 64, modulus_bits: None, curve: Some("P-384"), supported: false });
+        m.insert("PS256", AlgDescriptor { key_family: KeyFamily::Rsa, limb_bits: CIRCOM_RS256_LIMB_BITS, modulus_bits: Some(2048), curve: None, supported: false });
+        m.insert("RS256-3072", AlgDescriptor { key_family: KeyFamily::Rsa, limb_bits: CIRCOM_RS256_LIMB_BITS, modulus_bits: Some(3072), curve: None, supported: false });
+        m.insert("RS256-4096", AlgDescriptor { key_family: KeyFamily::Rsa, limb_bits: CIRCOM_RS256_LIMB_BITS, modulus_bits: Some(4096), curve: None, supported: false });
+        m.insert("EdDSA", AlgDescriptor { key_family: KeyFamily::Ed25519, limb_bits: CIRCOM_ES256_LIMB_BITS, modulus_bits: None, curve: Some("Ed25519"), supported: false });
+        m
+    };
+}
+
+/// Looks up `alg`'s descriptor, rejecting both unknown algorithm names and
+/// algorithms that are registered but not yet implemented (`supported:
+/// false`); the two get distinct errors, so a registered-but-unavailable
+/// algorithm is reported as such rather than as an unknown name.
+fn alg_descriptor(alg: &str) -> Result<&'static AlgDescriptor, Box<dyn Error>> {
+    match ALG_DESCRIPTORS.get(alg) {
+        Some(descriptor) if descriptor.supported => Ok(descriptor),
+        Some(_) => return_error!(format!("{} algorithm is registered but has no circuit/verification backend available yet", alg)),
+        None => return_error!(format!("{} algorithm is unsupported", alg)),
+    }
+}
+
+/// A credential's issuer signature algorithm, resolved once (from a
+/// config's `alg` field) into a typed value instead of being re-guessed or
+/// re-matched on as a raw string at every call site -- see
+/// [`SigAlg::from_config`] and `pem_to_inputs`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SigAlg {
+    RS256,
+    PS256,
+    ES256,
+    EdDSA,
+}
+
+impl SigAlg {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SigAlg::RS256 => "RS256",
+            SigAlg::PS256 => "PS256",
+            SigAlg::ES256 => "ES256",
+            SigAlg::EdDSA => "EdDSA",
+        }
+    }
+
+    /// Resolves the declared `alg` out of a parsed config, rejecting both
+    /// unknown names and algorithms [`alg_descriptor`] knows about but
+    /// doesn't yet have a verification/limb-encoding backend for (e.g.
+    /// `PS256`, `EdDSA` today).
+    pub fn from_config(config: &serde_json::Map<String, Value>) -> Result<SigAlg, Box<dyn Error>> {
+        let alg_str = config.get("alg").and_then(|v| v.as_str()).ok_or("config is missing the 'alg' field")?;
+        alg_descriptor(alg_str)?;
+        match alg_str {
+            "RS256" => Ok(SigAlg::RS256),
+            "ES256" => Ok(SigAlg::ES256),
+            "PS256" => Ok(SigAlg::PS256),
+            "EdDSA" => Ok(SigAlg::EdDSA),
+            _ => return_error!(format!("{} algorithm is registered but SigAlg does not have a variant for it", alg_str)),
+        }
+    }
+}
+
+lazy_static! {
+    static ref CRESCENT_SUPPORTED_CREDTYPES: HashSet<&'static str> = {
         let mut set = HashSet::new();
-        set.insert("RS256");
-        set.insert("ES256");
+        set.insert("jwt");
+        set.insert("sd-jwt");
+        set.insert("mdl");
+        set.insert("cwt");
         set
     };
 }
@@ -40,50 +148,47 @@ lazy_static! {
         set.insert("credtype");
         set.insert("max_cred_len");
         set.insert("device_bound");
+        set.insert("curve");
+        set.insert("modulus_bits");
+        set.insert("device_cose_key");
+        set.insert("device_up_required");
+        set.insert("device_uv_required");
+        set.insert("device_rp_id_hash");
+        set.insert("issuer_kid");
         set
     };
 }
 
-pub fn pem_key_type(key : &str) -> Result<&str, &str> {
-
-        if RS256PublicKey::from_pem(key).is_ok() {
-            Ok("RS256")
-        } 
-        else if ES256PublicKey::from_pem(key).is_ok() {
-            Ok("ES256")
-        }
-        else {
-            Err("Unsupported algorithm")
-        }
-}
-
-pub fn pem_to_inputs<F>(issuer_pem : &str) -> Result<Vec<F>, Box<dyn std::error::Error>>
-    where F: PrimeField 
+pub fn pem_to_inputs<F>(issuer_pem : &str, sig_alg: SigAlg) -> Result<Vec<F>, Box<dyn std::error::Error>>
+    where F: PrimeField
 {
-    
-    let inputs = match pem_key_type(issuer_pem) {
-        Ok("RS256") => {
-            let issuer_pub = RS256PublicKey::from_pem(issuer_pem).unwrap();
-            let limbs = to_circom_ints(&issuer_pub.to_components().n, CIRCOM_RS256_LIMB_BITS)?;
+    let inputs = match sig_alg {
+        SigAlg::RS256 => {
+            let limb_bits = alg_descriptor("RS256")?.limb_bits;
+            let issuer_pub = RS256PublicKey::from_pem(issuer_pem)?;
+            let limbs = to_circom_ints(&issuer_pub.to_components().n, limb_bits)?;
             limbs.into_iter().map(|x| F::from_le_bytes_mod_order(&x.to_bytes_le().1)).collect::<Vec<F>>()
         }
-        Ok("ES256") =>  {
-            let issuer_pub = ES256PublicKey::from_pem(issuer_pem).unwrap();
-            let x = &issuer_pub.public_key().to_bytes_uncompressed()[1..33];    // byte 1 is 0x04, per SEC1 `Elliptic-Curve-Point-to-Octet-String` 
+        SigAlg::ES256 =>  {
+            let limb_bits = alg_descriptor("ES256")?.limb_bits;
+            let issuer_pub = ES256PublicKey::from_pem(issuer_pem)?;
+            let x = &issuer_pub.public_key().to_bytes_uncompressed()[1..33];    // byte 0 is 0x04, per SEC1 `Elliptic-Curve-Point-to-Octet-String`
             let y = &issuer_pub.public_key().to_bytes_uncompressed()[33..65];
-            let limbs_x = to_circom_ints(x, CIRCOM_ES256_LIMB_BITS)?;
-            let limbs_y = to_circom_ints(y, CIRCOM_ES256_LIMB_BITS)?;
+            let limbs_x = to_circom_ints(x, limb_bits)?;
+            let limbs_y = to_circom_ints(y, limb_bits)?;
             let limbs_x_fe = limbs_x.into_iter().map(|a| F::from_le_bytes_mod_order(&a.to_bytes_le().1)).collect::<Vec<F>>();
             let limbs_y_fe = limbs_y.into_iter().map(|a| F::from_le_bytes_mod_order(&a.to_bytes_le().1)).collect::<Vec<F>>();
             let mut limbs = limbs_x_fe;
             limbs.extend(limbs_y_fe);
             limbs
         }
-        Err(e) =>  {
-            return Err(e.into());
-        }
-        _ => {
-            return Err("unknown error".into())
+        SigAlg::PS256 | SigAlg::EdDSA => {
+            // Registered in `ALG_DESCRIPTORS` but has no circuit-compatible
+            // limb encoding wired up yet; `alg_descriptor` surfaces the
+            // same "not available yet" error a config referencing it would
+            // already get from `SigAlg::from_config`.
+            alg_descriptor(sig_alg.as_str())?;
+            unreachable!("alg_descriptor would have already returned an error for an unsupported algorithm");
         }
     };
 
@@ -91,21 +196,93 @@ pub fn pem_to_inputs<F>(issuer_pem : &str) -> Result<Vec<F>, Box<dyn std::error:
 
 }
 
+/// A single RSA entry of a JWK Set (RFC 7517), as published at an issuer's
+/// `jwks_uri`. Only the fields `resolve_issuer_pem` needs to pick out and
+/// rebuild the right RSA public key are modeled; anything else in a real
+/// JWK (`use`, `alg`, `x5c`, ...) is ignored via `serde(flatten)`.
+#[derive(Serialize, Deserialize)]
+struct Jwk {
+    kid: Option<String>,
+    /// Base64url (no padding) modulus, per RFC 7518 section 6.3.1.
+    n: String,
+    /// Base64url (no padding) public exponent, per RFC 7518 section 6.3.1.
+    e: String,
+    #[serde(flatten)]
+    _other: JsonMap,
+}
+
+#[derive(Serialize, Deserialize)]
+struct JwkSet {
+    keys: Vec<Jwk>,
+}
+
+/// Extracts the `kid` header claim from a JWT, if present, without
+/// verifying the token -- mirrors the header decoding `prepare_prover_inputs`
+/// already does, since the prover needs the `kid` before it has picked (and
+/// can verify against) an issuer key.
+pub fn jwt_header_kid(token_str: &str) -> Result<Option<String>, Box<dyn Error>> {
+    let jwt_header_b64 = token_str.split('.').next().ok_or("Missing JWT header")?;
+    let jwt_header : Value = serde_json::from_slice(&base64_url::decode(jwt_header_b64)?)?;
+    Ok(jwt_header.get("kid").and_then(|v| v.as_str()).map(|s| s.to_string()))
+}
+
+/// Resolves an issuer's RSA public key to a single PEM string, so every
+/// existing `RS256PublicKey::from_pem(issuer_pem)` call site (in this file
+/// and in `lib.rs`) keeps working unchanged regardless of whether the
+/// caller has one issuer PEM or a JWKS with several keys.
+///
+/// `jwks` is the contents of a JWK Set file (`CachePaths::issuer_jwks`), or
+/// `None` if the deployment only has a single `issuer_pem` file. When a JWKS
+/// is given, `kid` selects which entry to use -- the prover reads it from
+/// the JWT it holds ([`jwt_header_kid`]), the verifier reads it from
+/// `config["issuer_kid"]`, since it never sees the JWT itself. A `kid` that
+/// matches no key in the JWKS (or an empty JWKS) is an error, rather than
+/// silently falling back to some key, so a rotation mismatch is diagnosable
+/// instead of producing a confusing proof-generation or verification
+/// failure downstream. When no `kid` is given at all, the JWKS's first key
+/// is used.
+pub fn resolve_issuer_pem(issuer_pem: &str, jwks: Option<&str>, kid: Option<&str>) -> Result<String, Box<dyn Error>> {
+    let jwks = match jwks {
+        Some(jwks) => jwks,
+        None => return Ok(issuer_pem.to_string()),
+    };
+
+    let jwk_set : JwkSet = serde_json::from_str(jwks)?;
+    let jwk = match kid {
+        Some(kid) => jwk_set.keys.iter().find(|k| k.kid.as_deref() == Some(kid))
+            .ok_or_else(|| format!("No key with kid '{}' found in issuer JWKS", kid))?,
+        None => jwk_set.keys.first().ok_or("Issuer JWKS contains no keys")?,
+    };
+
+    let n = base64_url::decode(&jwk.n)?;
+    let e = base64_url::decode(&jwk.e)?;
+    Ok(RS256PublicKey::from_components(&n, &e)?.to_pem()?)
+}
+
 type JsonMap = serde_json::Map<String, Value>;
 
 pub fn prepare_prover_inputs(config : &serde_json::Map<String, Value>, token_str : &str, issuer_pem : &str, device_pub_pem : Option<&str>) -> 
 Result<(JsonMap, JsonMap, JsonMap), Box<dyn Error>>
 {
 
-    let issuer_pub = match config["alg"].as_str().unwrap() {
-        "RS256" => RS256PublicKey::from_pem(issuer_pem)?,
-        _ => return_error!("Unsupported algorithm"),
-    };    
+    let alg_str = config["alg"].as_str().unwrap();
 
-    let claims_limited_set = issuer_pub.verify_token::<NoCustomClaims>(token_str, None);
-    if claims_limited_set.is_err() {
-        return_error!("Token failed to verify");
+    match alg_str {
+        "RS256" => {
+            let issuer_pub = RS256PublicKey::from_pem(issuer_pem)?;
+            if issuer_pub.verify_token::<NoCustomClaims>(token_str, None).is_err() {
+                return_error!("Token failed to verify");
+            }
+        }
+        "ES256" => {
+            let issuer_pub = ES256PublicKey::from_pem(issuer_pem)?;
+            if issuer_pub.verify_token::<NoCustomClaims>(token_str, None).is_err() {
+                return_error!("Token failed to verify");
+            }
+        }
+        _ => return_error!(format!("Unsupported algorithm {}", alg_str)),
     }
+    let limb_bits = alg_descriptor(alg_str)?.limb_bits;
 
     let mut parts = token_str.split('.');
     let jwt_header_b64 = parts.next().ok_or("Missing JWT header")?;
@@ -153,9 +330,20 @@ Result<(JsonMap, JsonMap, JsonMap), Box<dyn Error>>
     prover_inputs_json.insert("message".to_string(), json!(padded_m.into_iter().map(|c| c.to_string()).collect::<Vec<_>>()));
 
     // Signature
-    let alg_str = config["alg"].as_str().unwrap();
     if alg_str == "RS256" {
-        let limbs = b64_to_circom_limbs(signature_b64, CIRCOM_RS256_LIMB_BITS)?;
+        let limbs = b64_to_circom_limbs(signature_b64, limb_bits)?;
+        prover_inputs_json.insert("signature".to_string(), json!(limbs));
+    }
+    else if alg_str == "ES256" {
+        // ES256 JWS signatures are the raw, fixed-size `r || s` scalar pair
+        // (RFC 7518 section 3.4), not an ASN.1 DER sequence -- 32 bytes each
+        // for P-256.
+        let sig_bytes = base64_url::decode(signature_b64)?;
+        if sig_bytes.len() != 64 {
+            return_error!("ES256 signature is not the expected 64 raw r||s bytes");
+        }
+        let mut limbs = to_circom_limbs(&sig_bytes[..32], limb_bits)?;
+        limbs.extend(to_circom_limbs(&sig_bytes[32..], limb_bits)?);
         prover_inputs_json.insert("signature".to_string(), json!(limbs));
     }
     else {
@@ -164,11 +352,22 @@ Result<(JsonMap, JsonMap, JsonMap), Box<dyn Error>>
 
     // Issuer's public key
     if alg_str == "RS256" {
+        let issuer_pub = RS256PublicKey::from_pem(issuer_pem)?;
         let modulus_bytes = issuer_pub.to_components().n;
-        let limbs = to_circom_limbs(&modulus_bytes, CIRCOM_RS256_LIMB_BITS)?;
+        let limbs = to_circom_limbs(&modulus_bytes, limb_bits)?;
         prover_inputs_json.insert("modulus".to_string(), json!(limbs));
         public_ios_json.insert("modulus".to_string(), json!(limbs));
     }
+    else if alg_str == "ES256" {
+        let issuer_pub = ES256PublicKey::from_pem(issuer_pem)?;
+        let uncompressed = issuer_pub.public_key().to_bytes_uncompressed();
+        let x = &uncompressed[1..33];    // byte 0 is 0x04, per SEC1 `Elliptic-Curve-Point-to-Octet-String`
+        let y = &uncompressed[33..65];
+        let mut limbs = to_circom_limbs(x, limb_bits)?;
+        limbs.extend(to_circom_limbs(y, limb_bits)?);
+        prover_inputs_json.insert("pubkey".to_string(), json!(limbs));
+        public_ios_json.insert("pubkey".to_string(), json!(limbs));
+    }
     else {
         return_error!(format!("Unsupported algorithm {}", alg_str));
     }
@@ -188,14 +387,402 @@ Result<(JsonMap, JsonMap, JsonMap), Box<dyn Error>>
 
 }
 
+/// Same as [`prepare_prover_inputs`], but for an SD-JWT presentation --
+/// `<jwt>~<disclosure1>~<disclosure2>~...~` -- instead of a plain three-part
+/// JWT. The JWT's payload carries `_sd` digests (and `{"...": digest}`
+/// markers inside arrays) in place of the claims it selectively discloses;
+/// each disclosure is `base64url(JSON([salt, name, value]))` for an object
+/// property, or `base64url(JSON([salt, value]))` for an array element. This
+/// verifies every given disclosure against the digests the credential
+/// actually committed to, reconstructs the logical claims object by merging
+/// the disclosed values back in, and proves over it exactly like a plain
+/// JWT: the disclosure text is witnessed right after the signed
+/// header.payload (the signature/hash only covers the latter, so this
+/// doesn't change what's being verified), so `prepare_prover_claim_inputs`'s
+/// substring search can still locate each disclosed claim's bytes.
+pub fn prepare_prover_inputs_sd(config : &serde_json::Map<String, Value>, sd_jwt_str : &str, issuer_pem : &str, device_pub_pem : Option<&str>) ->
+Result<(JsonMap, JsonMap, JsonMap), Box<dyn Error>>
+{
+    let mut segments = sd_jwt_str.split('~');
+    let jwt_str = segments.next().ok_or("Missing JWT in SD-JWT presentation")?;
+    let disclosures_b64: Vec<&str> = segments.filter(|s| !s.is_empty()).collect();
+
+    let alg_str = config["alg"].as_str().unwrap();
+
+    match alg_str {
+        "RS256" => {
+            let issuer_pub = RS256PublicKey::from_pem(issuer_pem)?;
+            if issuer_pub.verify_token::<NoCustomClaims>(jwt_str, None).is_err() {
+                return_error!("Token failed to verify");
+            }
+        }
+        "ES256" => {
+            let issuer_pub = ES256PublicKey::from_pem(issuer_pem)?;
+            if issuer_pub.verify_token::<NoCustomClaims>(jwt_str, None).is_err() {
+                return_error!("Token failed to verify");
+            }
+        }
+        _ => return_error!(format!("Unsupported algorithm {}", alg_str)),
+    }
+    let limb_bits = alg_descriptor(alg_str)?.limb_bits;
+
+    let mut parts = jwt_str.split('.');
+    let jwt_header_b64 = parts.next().ok_or("Missing JWT header")?;
+    let claims_b64 = parts.next().ok_or("Missing JWT claims")?;
+    let signature_b64 = parts.next().ok_or("Missing JWT signature")?;
+
+    let jwt_header_decoded = String::from_utf8(base64_url::decode(jwt_header_b64)?)?;
+    let claims_decoded = String::from_utf8(base64_url::decode(claims_b64)?)?;
+
+    let mut claims: Value =
+        serde_json::from_slice(&Base64UrlSafeNoPadding::decode_to_vec(claims_b64, None)?)?;
+
+    if let Some(sd_alg) = claims.get("_sd_alg").and_then(|v| v.as_str()) {
+        if sd_alg != "sha-256" {
+            return_error!(format!("Unsupported _sd_alg {}, only sha-256 is supported", sd_alg));
+        }
+    }
+
+    // Every digest the credential actually committed to, wherever in the
+    // claims tree it appears: `_sd` arrays (object-property disclosures, see
+    // `resolve_sd_claims`) and `{"...": digest}` markers (array-element
+    // disclosures).
+    let committed_digests = collect_sd_digests(&claims);
+
+    let mut object_disclosures: HashMap<String, (String, Value)> = HashMap::new();
+    let mut array_disclosures: HashMap<String, Value> = HashMap::new();
+    let mut disclosure_texts: Vec<String> = Vec::with_capacity(disclosures_b64.len());
+
+    for disclosure_b64 in &disclosures_b64 {
+        // Per the spec, the digest is computed over the disclosure exactly
+        // as it's transmitted -- its base64url text -- not over the decoded
+        // JSON.
+        let digest = base64_url::encode(Sha256::digest(disclosure_b64.as_bytes()).as_slice());
+        if !committed_digests.contains(&digest) {
+            return_error!(format!("Disclosure digest {} is not committed to by the credential", digest));
+        }
+
+        let disclosure_json = String::from_utf8(base64_url::decode(disclosure_b64)?)?;
+        let elements: Value = serde_json::from_str(&disclosure_json)?;
+        let elements = elements.as_array().ok_or("Disclosure is not a JSON array")?;
+
+        match elements.len() {
+            3 => {
+                let name = elements[1].as_str().ok_or("Disclosure claim name is not a string")?.to_string();
+                object_disclosures.insert(digest, (name, elements[2].clone()));
+            }
+            2 => {
+                array_disclosures.insert(digest, elements[1].clone());
+            }
+            n => return_error!(format!("Disclosure has {} elements, expected 2 (array element) or 3 (object property)", n)),
+        }
+
+        disclosure_texts.push(disclosure_json);
+    }
+
+    resolve_sd_claims(&mut claims, &object_disclosures, &array_disclosures);
+    let claims = claims;
+
+    // Convert the base64 encoded header and payload to UTF-8 integers in
+    // base-10, same as a plain JWT, then append each disclosure's own
+    // base64url text: the circuit witnesses and decodes them the same way
+    // it does the header/payload, so disclosed claims can be located by
+    // substring search like any other claim.
+    let mut header_utf8 = to_utf8_integers(jwt_header_b64);
+    header_utf8.push('.' as u32);
+    let payload_utf8 = to_utf8_integers(claims_b64);
+
+    let mut prepad_m = header_utf8.clone();
+    prepad_m.append(&mut payload_utf8.clone());
+
+    let padded_m = sha256_padding(&prepad_m);
+    let msg_len_after_sha2_padding = padded_m.len() as u64;
+
+    let mut full_m = padded_m;
+    for disclosure_b64 in &disclosures_b64 {
+        full_m.append(&mut to_utf8_integers(disclosure_b64));
+    }
+
+    if full_m.len() as u64 > config["max_cred_len"].as_u64().unwrap() {
+        let errmsg = format!("Error: SD-JWT too large. Signed header+payload ({} bytes after SHA256 padding) plus {} disclosure(s) comes to {} bytes total, but maximum length supported is {} bytes.\nThe config file value `max_cred_len` would have to be increased to at least {} bytes (currently config['max_cred_len'] = {})",
+            msg_len_after_sha2_padding, disclosures_b64.len(), full_m.len(),
+            base64_decoded_size(config["max_cred_len"].as_u64().unwrap()),
+            full_m.len(), config["max_cred_len"].as_u64().unwrap());
+        return_error!(errmsg);
+    }
+
+    // Add additional zero padding for Circom
+    while full_m.len() < config["max_cred_len"].as_u64().unwrap() as usize {
+        full_m.push(0);
+    }
+
+    // Begin creating prover's output. Everthing must have string type for Circom
+    let mut prover_inputs_json = serde_json::Map::new();
+    let mut public_ios_json = serde_json::Map::new();
+    let mut prover_aux_json = serde_json::Map::new();
+    prover_inputs_json.insert("message".to_string(), json!(full_m.into_iter().map(|c| c.to_string()).collect::<Vec<_>>()));
+
+    // Signature
+    if alg_str == "RS256" {
+        let limbs = b64_to_circom_limbs(signature_b64, limb_bits)?;
+        prover_inputs_json.insert("signature".to_string(), json!(limbs));
+    }
+    else if alg_str == "ES256" {
+        let sig_bytes = base64_url::decode(signature_b64)?;
+        if sig_bytes.len() != 64 {
+            return_error!("ES256 signature is not the expected 64 raw r||s bytes");
+        }
+        let mut limbs = to_circom_limbs(&sig_bytes[..32], limb_bits)?;
+        limbs.extend(to_circom_limbs(&sig_bytes[32..], limb_bits)?);
+        prover_inputs_json.insert("signature".to_string(), json!(limbs));
+    }
+    else {
+        return_error!(format!("Unsupported algorithm {}", alg_str));
+    }
+
+    // Issuer's public key
+    if alg_str == "RS256" {
+        let issuer_pub = RS256PublicKey::from_pem(issuer_pem)?;
+        let modulus_bytes = issuer_pub.to_components().n;
+        let limbs = to_circom_limbs(&modulus_bytes, limb_bits)?;
+        prover_inputs_json.insert("modulus".to_string(), json!(limbs));
+        public_ios_json.insert("modulus".to_string(), json!(limbs));
+    }
+    else if alg_str == "ES256" {
+        let issuer_pub = ES256PublicKey::from_pem(issuer_pem)?;
+        let uncompressed = issuer_pub.public_key().to_bytes_uncompressed();
+        let x = &uncompressed[1..33];    // byte 0 is 0x04, per SEC1 `Elliptic-Curve-Point-to-Octet-String`
+        let y = &uncompressed[33..65];
+        let mut limbs = to_circom_limbs(x, limb_bits)?;
+        limbs.extend(to_circom_limbs(y, limb_bits)?);
+        prover_inputs_json.insert("pubkey".to_string(), json!(limbs));
+        public_ios_json.insert("pubkey".to_string(), json!(limbs));
+    }
+    else {
+        return_error!(format!("Unsupported algorithm {}", alg_str));
+    }
+
+    // Other values the prover needs
+    prover_inputs_json.insert("message_padded_bytes".to_string(), json!(msg_len_after_sha2_padding.to_string()));
+    let period_idx = header_utf8.len() - 1;
+    prover_inputs_json.insert("period_idx".to_string(), json!(period_idx.to_string()));
+
+    // Decoded text used only to locate claim bytes by substring search --
+    // mirrors the plain-JWT header/payload reconstruction, then keeps
+    // appending one padded segment per disclosure so a disclosed claim is
+    // found at the same offset the circuit's own base64 decoding produces.
+    let mut header_and_payload = format!("{}{}{}", jwt_header_decoded, base_64_decoded_header_padding(period_idx)?, claims_decoded);
+    let mut running_b64_len = period_idx + claims_b64.len();
+    for (disclosure_b64, disclosure_json) in disclosures_b64.iter().zip(disclosure_texts.iter()) {
+        header_and_payload.push_str(&base_64_decoded_header_padding(running_b64_len)?);
+        header_and_payload.push_str(disclosure_json);
+        running_b64_len += disclosure_b64.len();
+    }
+
+    prepare_prover_claim_inputs(&header_and_payload, config, &claims, &mut prover_inputs_json)?;
+    prepare_prover_aux(&header_and_payload, config, &claims, device_pub_pem, &mut prover_aux_json)?;
+
+    Ok((prover_inputs_json, prover_aux_json, public_ios_json))
+
+}
+
+/// Collects every digest an SD-JWT claims tree commits to: entries of `_sd`
+/// arrays (which the spec allows at any object level, not just the top) and
+/// the digest inside an array-element `{"...": digest}` marker.
+pub(crate) fn collect_sd_digests(claims: &Value) -> HashSet<String> {
+    let mut digests = HashSet::new();
+    collect_sd_digests_rec(claims, &mut digests);
+    digests
+}
+
+fn collect_sd_digests_rec(value: &Value, digests: &mut HashSet<String>) {
+    match value {
+        Value::Object(map) => {
+            if let Some(sd) = map.get("_sd").and_then(|v| v.as_array()) {
+                digests.extend(sd.iter().filter_map(|d| d.as_str().map(String::from)));
+            }
+            for (key, v) in map {
+                if key != "_sd" {
+                    collect_sd_digests_rec(v, digests);
+                }
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                match array_marker_digest(item) {
+                    Some(digest) => { digests.insert(digest); }
+                    None => collect_sd_digests_rec(item, digests),
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+// An array-element disclosure's placeholder, `{"...": "<digest>"}`.
+fn array_marker_digest(value: &Value) -> Option<String> {
+    value.as_object()
+        .filter(|o| o.len() == 1)
+        .and_then(|o| o.get("..."))
+        .and_then(|d| d.as_str())
+        .map(String::from)
+}
+
+/// Merges disclosed object properties and array elements back into `claims`,
+/// replacing `_sd` arrays and `"..."` markers with the values the holder
+/// chose to reveal. Digests with no matching disclosure -- decoys, or
+/// claims the holder is keeping hidden -- are left unrevealed and simply
+/// dropped from the reconstructed object.
+pub(crate) fn resolve_sd_claims(claims: &mut Value, object_disclosures: &HashMap<String, (String, Value)>, array_disclosures: &HashMap<String, Value>) {
+    match claims {
+        Value::Object(map) => {
+            if let Some(Value::Array(sd)) = map.remove("_sd") {
+                for digest in sd.iter().filter_map(|d| d.as_str()) {
+                    if let Some((name, value)) = object_disclosures.get(digest) {
+                        map.insert(name.clone(), value.clone());
+                    }
+                }
+            }
+            map.remove("_sd_alg");
+            for v in map.values_mut() {
+                resolve_sd_claims(v, object_disclosures, array_disclosures);
+            }
+        }
+        Value::Array(items) => {
+            let mut resolved = Vec::with_capacity(items.len());
+            for mut item in std::mem::take(items) {
+                match array_marker_digest(&item) {
+                    Some(digest) => {
+                        if let Some(value) = array_disclosures.get(&digest) {
+                            resolved.push(value.clone());
+                        }
+                    }
+                    None => {
+                        resolve_sd_claims(&mut item, object_disclosures, array_disclosures);
+                        resolved.push(item);
+                    }
+                }
+            }
+            *items = resolved;
+        }
+        _ => {}
+    }
+}
+
+fn bytes_to_u32_vec(bytes: &[u8]) -> Vec<u32> {
+    bytes.iter().map(|&b| b as u32).collect()
+}
+
+/// Same as [`prepare_prover_inputs`], but for a CWT -- a COSE_Sign1-wrapped
+/// CBOR credential (see [`crate::cose`]) -- instead of a base64url JSON JWT.
+/// The bytes actually covered by the signature are COSE's `Sig_structure`,
+/// not the payload alone, so that's what is SHA-256 padded and witnessed as
+/// `message`; claim offsets found in the payload (a CBOR map keyed by
+/// integer labels, per RFC 8392, so claim config entries are named by the
+/// decimal label, e.g. `"4"` for `exp`) are shifted by the payload's offset
+/// within `message` before being recorded.
+pub fn prepare_prover_inputs_cwt(config : &serde_json::Map<String, Value>, cwt_bytes : &[u8], issuer_pem : &str, device_pub_pem : Option<&str>) ->
+Result<(JsonMap, JsonMap, JsonMap), Box<dyn Error>>
+{
+    let alg_str = config["alg"].as_str().unwrap();
+
+    let cose_sign1 = cose::decode_cose_sign1(cwt_bytes)?;
+    let cose_alg = cose::protected_header_alg(&cose_sign1.protected)?;
+    let cose_alg_str = cose::cose_alg_to_jwt_alg(cose_alg).ok_or(format!("Unsupported COSE algorithm identifier {}", cose_alg))?;
+    if cose_alg_str != alg_str {
+        return_error!(format!("Config alg {} does not match the COSE protected header's alg {}", alg_str, cose_alg_str));
+    }
+
+    let (sig_structure, payload_offset) = cose::build_sig_structure(&cose_sign1.protected, &cose_sign1.payload);
+
+    match alg_str {
+        "RS256" => {
+            let issuer_pub = RS256PublicKey::from_pem(issuer_pem)?;
+            let components = issuer_pub.to_components();
+            let rsa_pub = rsa::RsaPublicKey::new(rsa::BigUint::from_bytes_be(&components.n), rsa::BigUint::from_bytes_be(&components.e))?;
+            let verifying_key = rsa::pkcs1v15::VerifyingKey::<Sha256>::new(rsa_pub);
+            let signature = rsa::pkcs1v15::Signature::try_from(cose_sign1.signature.as_slice())?;
+            rsa::signature::Verifier::verify(&verifying_key, &sig_structure, &signature).map_err(|_| "COSE_Sign1 signature failed to verify")?;
+        }
+        "ES256" => {
+            let issuer_pub = ES256PublicKey::from_pem(issuer_pem)?;
+            let verifying_key = p256::ecdsa::VerifyingKey::from_sec1_bytes(&issuer_pub.public_key().to_bytes_uncompressed())?;
+            let signature = p256::ecdsa::Signature::from_slice(&cose_sign1.signature)?;
+            p256::ecdsa::signature::Verifier::verify(&verifying_key, &sig_structure, &signature).map_err(|_| "COSE_Sign1 signature failed to verify")?;
+        }
+        _ => return_error!(format!("Unsupported algorithm {}", alg_str)),
+    }
+    let limb_bits = alg_descriptor(alg_str)?.limb_bits;
+
+    let message_utf8 = bytes_to_u32_vec(&sig_structure);
+    let mut padded_m = sha256_padding(&message_utf8);
+    let msg_len_after_sha2_padding = padded_m.len() as u64;
+
+    if msg_len_after_sha2_padding > config["max_cred_len"].as_u64().unwrap() {
+        return_error!(format!("Error: CWT too large. Current Sig_structure is {} bytes ({} bytes after SHA256 padding), but maximum length supported is {} bytes.",
+            sig_structure.len(), msg_len_after_sha2_padding, config["max_cred_len"].as_u64().unwrap()));
+    }
+
+    while padded_m.len() < config["max_cred_len"].as_u64().unwrap() as usize {
+        padded_m.push(0);
+    }
+
+    let mut prover_inputs_json = serde_json::Map::new();
+    let mut public_ios_json = serde_json::Map::new();
+    let mut prover_aux_json = serde_json::Map::new();
+    prover_inputs_json.insert("message".to_string(), json!(padded_m.into_iter().map(|c| c.to_string()).collect::<Vec<_>>()));
+
+    // Signature
+    if alg_str == "RS256" {
+        let limbs = to_circom_limbs(&cose_sign1.signature, limb_bits)?;
+        prover_inputs_json.insert("signature".to_string(), json!(limbs));
+    } else {
+        if cose_sign1.signature.len() != 64 {
+            return_error!("ES256 COSE_Sign1 signature is not the expected 64 raw r||s bytes");
+        }
+        let mut limbs = to_circom_limbs(&cose_sign1.signature[..32], limb_bits)?;
+        limbs.extend(to_circom_limbs(&cose_sign1.signature[32..], limb_bits)?);
+        prover_inputs_json.insert("signature".to_string(), json!(limbs));
+    }
+
+    // Issuer's public key
+    if alg_str == "RS256" {
+        let issuer_pub = RS256PublicKey::from_pem(issuer_pem)?;
+        let limbs = to_circom_limbs(&issuer_pub.to_components().n, limb_bits)?;
+        prover_inputs_json.insert("modulus".to_string(), json!(limbs));
+        public_ios_json.insert("modulus".to_string(), json!(limbs));
+    } else {
+        let issuer_pub = ES256PublicKey::from_pem(issuer_pem)?;
+        let uncompressed = issuer_pub.public_key().to_bytes_uncompressed();
+        let mut limbs = to_circom_limbs(&uncompressed[1..33], limb_bits)?;
+        limbs.extend(to_circom_limbs(&uncompressed[33..65], limb_bits)?);
+        prover_inputs_json.insert("pubkey".to_string(), json!(limbs));
+        public_ios_json.insert("pubkey".to_string(), json!(limbs));
+    }
+
+    prover_inputs_json.insert("message_padded_bytes".to_string(), json!(msg_len_after_sha2_padding.to_string()));
+    prover_inputs_json.insert("payload_idx".to_string(), json!(payload_offset.to_string()));
+
+    let mut claims = serde_json::Map::new();
+    for key in config.keys() {
+        if CRESCENT_CONFIG_KEYS.contains(key.as_str()) {
+            continue;
+        }
+        let claim_label = key.parse::<i64>().map_err(|_| format!("CWT claim config key {} is not an integer claim label", key))?;
+        claims.insert(key.clone(), cose::decode_claim_value_cbor(&cose_sign1.payload, claim_label)?);
+    }
+    let claims = Value::Object(claims);
+
+    prepare_prover_claim_inputs_cwt(&cose_sign1.payload, payload_offset, config, &claims, &mut prover_inputs_json)?;
+    prepare_prover_aux("", config, &claims, device_pub_pem, &mut prover_aux_json)?;
+
+    Ok((prover_inputs_json, prover_aux_json, public_ios_json))
+}
+
 // For each of the claims that are specified in the config file, the prover will need some info about each one
 // (e.g., the value, where in the payload it starts and ends)
 fn prepare_prover_claim_inputs(header_and_payload: &str, config: &serde_json::Map<String, Value>, claims: &Value, prover_inputs_json : &mut  serde_json::Map<String, Value>) -> Result<(), Box<dyn Error>> {
     let msg = header_and_payload;
-
-    if !is_minified(msg) {
-        return_error!("JSON is not minified, Circom circuit will fail.")
-    }
     let keys = config.keys();
 
     for key in keys {
@@ -245,13 +832,79 @@ fn prepare_prover_claim_inputs(header_and_payload: &str, config: &serde_json::Ma
                 }
             }
         }
+
+        if entry.contains_key("reveal_bytes") {
+            let reveal_bytes = entry["reveal_bytes"].as_bool().ok_or(format!("reveal_bytes for claim {} is not of type bool", name))?;
+            if reveal_bytes {
+                let max_claim_byte_len = entry["max_claim_byte_len"].as_u64().unwrap();    // validated by load_config
+                let bytes = reveal_claim_bytes(&claims[name], max_claim_byte_len.try_into()?)?;
+                prover_inputs_json.insert(format!("{}_value", name), json!(bytes));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// CBOR-aware sibling of [`prepare_prover_claim_inputs`] for `credtype:
+/// "cwt"` credentials: claims are located with
+/// [`cose::find_value_interval_cbor`] against the raw payload bytes instead
+/// of a substring search over JSON text, and the resulting interval is
+/// shifted by `payload_offset` to land on the witnessed `message` (the
+/// padded `Sig_structure`, which embeds `payload` at that offset) rather
+/// than on `payload` alone.
+fn prepare_prover_claim_inputs_cwt(payload: &[u8], payload_offset: usize, config: &serde_json::Map<String, Value>, claims: &Value, prover_inputs_json : &mut serde_json::Map<String, Value>) -> Result<(), Box<dyn Error>> {
+    for key in config.keys() {
+        if CRESCENT_CONFIG_KEYS.contains(key.as_str()) {
+            continue;
+        }
+
+        let name = key.as_str();
+        let entry = config[name].as_object().ok_or(format!("Config file entry for claim {}, does not have object type", name))?;
+        let type_string = entry["type"].as_str().ok_or(format!("Config file entry for claim {}, is missing 'type'", name))?;
+        let claim_label = name.parse::<i64>().map_err(|_| format!("CWT claim config key {} is not an integer claim label", name))?;
+
+        let (value_l, value_r) = cose::find_value_interval_cbor(payload, claim_label)?;
+        let claim_l = payload_offset + value_l;
+        let claim_r = payload_offset + value_r;
+
+        prover_inputs_json.insert(format!("{}_l", name), json!(claim_l.to_string()));
+        prover_inputs_json.insert(format!("{}_r", name), json!(claim_r.to_string()));
+
+        if entry.contains_key("reveal") {
+            let reveal = entry["reveal"].as_bool().ok_or(format!("reveal for claim {} is not of type bool", name))?;
+            if reveal {
+                match type_string {
+                    "number" => {
+                        prover_inputs_json.insert(format!("{}_value", name), json!(claims[name].clone().to_string()));
+                    }
+                    "string" => {
+                        let max_claim_byte_len = entry["max_claim_byte_len"].as_u64().unwrap();    // validated by load_config
+                        let packed = pack_string_to_int_unquoted(claims[name].as_str().ok_or("invalid_type")?, max_claim_byte_len.try_into()?)?;
+                        prover_inputs_json.insert(format!("{}_value", name), json!(packed));
+                    }
+                    _ => {
+                        return_error!("Can only reveal number types and string types as a single field element for now. See also `reveal_bytes`.")
+                    }
+                }
+            }
+        }
+
+        if entry.contains_key("reveal_bytes") {
+            let reveal_bytes = entry["reveal_bytes"].as_bool().ok_or(format!("reveal_bytes for claim {} is not of type bool", name))?;
+            if reveal_bytes {
+                let max_claim_byte_len = entry["max_claim_byte_len"].as_u64().unwrap();    // validated by load_config
+                let bytes = reveal_claim_bytes(&claims[name], max_claim_byte_len.try_into()?)?;
+                prover_inputs_json.insert(format!("{}_value", name), json!(bytes));
+            }
+        }
     }
 
     Ok(())
 }
 
 // The prover needs the pre-images of the hashed attributes, and optionally, the device public key.
-// The digests are outputs of the circuit and made available to the prover during witness generation. 
+// The digests are outputs of the circuit and made available to the prover during witness generation.
 // When showing the credential, if the prover selectively discloses a hashed attribute, they need the
 // preimage to send to the verifier.
 fn prepare_prover_aux(_header_and_payload: &str, config: &serde_json::Map<String, Value>, claims: &Value, device_key_pem : Option<&str>, prover_aux_json : &mut  serde_json::Map<String, Value>) -> Result<(), Box<dyn Error>> {
@@ -305,6 +958,26 @@ fn prepare_prover_aux(_header_and_payload: &str, config: &serde_json::Map<String
     Ok(())
 }
 
+/// Reveals a claim's value as an array of byte-sized field elements (one
+/// UTF-8 byte per element), zero-padded to `max_claim_byte_len`, instead of
+/// packing it into a single field element via `pack_string_to_int`. Unlike
+/// that single-field-element path, this isn't limited to `number`/`string`
+/// claims or to `max_claim_byte_len <= 31`: any claim whose JSON
+/// representation fits in `max_claim_byte_len` bytes can be revealed this
+/// way, which covers claims too long for one field element (full URLs,
+/// DIDs, long `aud` arrays) as well as array/object claim types.
+fn reveal_claim_bytes(claim_value: &Value, max_claim_byte_len: usize) -> Result<Vec<String>, Box<dyn Error>> {
+    let mut bytes = match claim_value {
+        Value::String(s) => s.as_bytes().to_vec(),
+        _ => claim_value.to_string().into_bytes(),
+    };
+    if bytes.len() > max_claim_byte_len {
+        return_error!(format!("Claim too large ({} bytes) to reveal as bytes, largest allowed by configuration is {} bytes", bytes.len(), max_claim_byte_len));
+    }
+    bytes.resize(max_claim_byte_len, 0);
+    Ok(bytes.into_iter().map(|b| b.to_string()).collect())
+}
+
 fn get_domain(s: &str) -> Result<&str, Box<std::io::Error>> {
     match s.find('@') {
         Some(at_index) => Ok(&s[at_index + 1..]),
@@ -353,81 +1026,122 @@ pub fn unpack_int_to_string_unquoted(s_int: &ark_ff::BigInteger256) -> Result<St
     Ok(crate::utils::strip_quotes(string.unwrap().as_str()).to_string())
 }
 
-fn find_value_interval(msg: &str, claim_name: &str, type_string: &str) -> Result<(usize, usize), Box<dyn Error>> {
-    let l = msg.find(claim_name).ok_or(format!("Failed to find claim {} in token payload", claim_name))?;
-    let value_start = l + claim_name.len();
-    let mut r = 0;
-    match type_string {
-        "string" => {
-            let close_quote = msg[value_start+2..].find("\"").ok_or(format!("Parse error, no closing quote, claim {}", claim_name))?;
-            r = close_quote + value_start + 3;
-        },
-        "number" => {
-            for (i, c) in msg[value_start + 1..].chars().enumerate() {
-                if "0123456789".find(c).is_none() {
-                    r = value_start + 1 + i;
-                    break;
-                }
+fn skip_ws(bytes: &[u8], mut pos: usize) -> usize {
+    while matches!(bytes.get(pos), Some(b' ') | Some(b'\t') | Some(b'\n') | Some(b'\r')) {
+        pos += 1;
+    }
+    pos
+}
+
+/// Consumes exactly one JSON value starting at `pos`, honoring string
+/// escape sequences (`\"`, `\\`, `\uXXXX`, ...), balanced `{}`/`[]`
+/// nesting, and number/bool/null grammar, and returns the offset right
+/// after it.
+fn scan_json_value(bytes: &[u8], pos: usize) -> Result<usize, Box<dyn Error>> {
+    match bytes.get(pos) {
+        Some(b'"') => scan_json_string(bytes, pos),
+        Some(b'{') => scan_json_container(bytes, pos, b'{', b'}'),
+        Some(b'[') => scan_json_container(bytes, pos, b'[', b']'),
+        _ if bytes[pos..].starts_with(b"true") => Ok(pos + 4),
+        _ if bytes[pos..].starts_with(b"false") => Ok(pos + 5),
+        _ if bytes[pos..].starts_with(b"null") => Ok(pos + 4),
+        Some(&c) if c == b'-' || c.is_ascii_digit() => Ok(scan_json_number(bytes, pos)),
+        _ => return_error!("Parse error, unrecognized JSON value"),
+    }
+}
+
+fn scan_json_string(bytes: &[u8], pos: usize) -> Result<usize, Box<dyn Error>> {
+    let mut i = pos + 1; // skip the opening quote
+    loop {
+        match bytes.get(i) {
+            None => return_error!("Parse error, unterminated JSON string"),
+            Some(b'"') => return Ok(i + 1),
+            Some(b'\\') => {
+                i += if bytes.get(i + 1) == Some(&b'u') { 6 } else { 2 };
             }
-        },
-        "bool" => {
-            for (i, c) in msg[value_start + 1..].chars().enumerate() {
-                if "truefalse".find(c).is_none() {
-                    r = value_start + 1 + i;
-                    break;
-                }
-            }            
-        },
-        "null" => {
-            r = value_start + 4;
-        }, 
-        "array" => {
-            let mut nested_level = 0;
-            for (i, c) in msg[value_start..].chars().enumerate() {
-                if c == '[' {
-                    nested_level += 1;
-                }
-                else if c == ']' {
-                    nested_level -= 1;
-                    if nested_level == 0 {
-                        r = value_start + i + 1;
-                        break;
-                    }
-                }
+            Some(_) => i += 1,
+        }
+    }
+}
+
+fn scan_json_container(bytes: &[u8], pos: usize, open: u8, close: u8) -> Result<usize, Box<dyn Error>> {
+    let mut depth = 0u32;
+    let mut i = pos;
+    loop {
+        match bytes.get(i) {
+            None => return_error!("Parse error, unbalanced JSON container"),
+            Some(b'"') => i = scan_json_string(bytes, i)?,
+            Some(&c) if c == open => {
+                depth += 1;
+                i += 1;
             }
-        },
-        "object" => {
-            let mut nested_level = 0;
-            for (i, c) in msg[value_start..].chars().enumerate() {
-                if c == '{' {
-                    nested_level += 1;
-                }
-                else if c == '}' {
-                    nested_level -= 1;
-                    if nested_level == 0 {
-                        r = value_start + i + 1;
-                        break;
-                    }
+            Some(&c) if c == close => {
+                depth -= 1;
+                i += 1;
+                if depth == 0 {
+                    return Ok(i);
                 }
             }
-        },
-        _ => return_error!(format!("Unsupported claim type: {}", type_string)),
+            Some(_) => i += 1,
+        }
     }
-    Ok((l,r))
 }
 
-fn is_minified(msg: &str) -> bool {
-    // Check for extra spaces, e.g.,
-    //     "exp" : 123456789
-    // is not sufficiently minified, but
-    //     "exp":123456789
-    // is minified. Our Circom circuit currently does not support extra space(s).
-    if msg.contains("\": ") {
-        return false;
+fn scan_json_number(bytes: &[u8], pos: usize) -> usize {
+    let mut i = pos;
+    if bytes.get(i) == Some(&b'-') {
+        i += 1;
+    }
+    let digits = |i: &mut usize| while matches!(bytes.get(*i), Some(c) if c.is_ascii_digit()) { *i += 1; };
+    digits(&mut i);
+    if bytes.get(i) == Some(&b'.') {
+        i += 1;
+        digits(&mut i);
+    }
+    if matches!(bytes.get(i), Some(b'e') | Some(b'E')) {
+        i += 1;
+        if matches!(bytes.get(i), Some(b'+') | Some(b'-')) {
+            i += 1;
+        }
+        digits(&mut i);
     }
-    true
+    i
 }
-    
+
+// Given the byte offset just after a matched `"claim_name"` key, skips
+// optional whitespace and the `:` separator, then consumes exactly one
+// JSON value with `scan_json_value` and returns its `[l, r)` byte interval
+// -- `l` being the start of `claim_name` itself, to match the original
+// (possibly non-minified) payload the Circom circuit is witnessed with.
+// This tolerates whitespace and string-value escape sequences that the
+// previous ad-hoc scan silently mis-parsed.
+fn find_value_interval(msg: &str, claim_name: &str, type_string: &str) -> Result<(usize, usize), Box<dyn Error>> {
+    let bytes = msg.as_bytes();
+    let l = msg.find(claim_name).ok_or(format!("Failed to find claim {} in token payload", claim_name))?;
+
+    let pos = skip_ws(bytes, l + claim_name.len());
+    if bytes.get(pos) != Some(&b':') {
+        return_error!(format!("Parse error, expected ':' after claim {}", claim_name));
+    }
+    let value_start = skip_ws(bytes, pos + 1);
+
+    let matches_type = match type_string {
+        "string" => bytes.get(value_start) == Some(&b'"'),
+        "number" => matches!(bytes.get(value_start), Some(&b'-')) || matches!(bytes.get(value_start), Some(c) if c.is_ascii_digit()),
+        "bool" => bytes[value_start..].starts_with(b"true") || bytes[value_start..].starts_with(b"false"),
+        "null" => bytes[value_start..].starts_with(b"null"),
+        "array" => bytes.get(value_start) == Some(&b'['),
+        "object" => bytes.get(value_start) == Some(&b'{'),
+        _ => return_error!(format!("Unsupported claim type: {}", type_string)),
+    };
+    if !matches_type {
+        return_error!(format!("Parse error, claim {} is not of the configured type '{}'", claim_name, type_string));
+    }
+
+    let r = scan_json_value(bytes, value_start)?;
+    Ok((l, r))
+}
+
 // This function creates zero-padding to go between the JSON header and payload
 // in order to match what the Circom base64 decoder outputs.
 // If the header must include padding "=" or "==" to be a multiple of four for base64
@@ -526,9 +1240,27 @@ pub fn parse_config(config_str: &str) -> Result<serde_json::Map<String, Value>,
     }
 
     let alg_copy = config.get("alg").unwrap().clone();
-    let alg = alg_copy.as_str().ok_or("alg field is not a string")?;    
-    if !CRESCENT_SUPPORTED_ALGS.contains(alg) {
-        return_error!(format!("{} algorithm is unsupported", config["alg"]));
+    let alg = alg_copy.as_str().ok_or("alg field is not a string")?;
+    let descriptor = alg_descriptor(alg)?;
+
+    if let Some(curve) = config.get("curve") {
+        let curve = curve.as_str().ok_or("curve field is not a string")?;
+        if descriptor.curve != Some(curve) {
+            return_error!(format!("{} algorithm does not use curve {}", alg, curve));
+        }
+    }
+    if let Some(modulus_bits) = config.get("modulus_bits") {
+        let modulus_bits = modulus_bits.as_u64().ok_or("modulus_bits field is not an integer")?;
+        if descriptor.modulus_bits != Some(modulus_bits as usize) {
+            return_error!(format!("{} algorithm does not use a {}-bit modulus", alg, modulus_bits));
+        }
+    }
+
+    if config.contains_key("credtype") {
+        let credtype = config.get("credtype").unwrap().as_str().ok_or("credtype field is not a string")?;
+        if !CRESCENT_SUPPORTED_CREDTYPES.contains(credtype) {
+            return_error!(format!("{} credtype is unsupported", credtype));
+        }
     }
 
     // Set defaults
@@ -564,12 +1296,17 @@ pub fn parse_config(config_str: &str) -> Result<serde_json::Map<String, Value>,
         config.insert("device_key_1".to_string(), serde_json::from_str(&device_key_entry)?);
     }
 
-    // For all the config entries about claims (e.g, "email", "exp", etc.) make sure that if the claim 
+    // For all the config entries about claims (e.g, "email", "exp", etc.) make sure that if the claim
     // is to be revealed, that max_claim_byte_len is set
     for (key, _) in config.clone() {
         if !CRESCENT_CONFIG_KEYS.contains(key.as_str()) {
             let claim_entry = config.get(key.as_str()).unwrap().as_object().ok_or("expected object type")?.clone();
-            if claim_entry.contains_key("reveal") && claim_entry["reveal"].as_bool().unwrap_or(false) && !claim_entry.contains_key("max_claim_byte_len") {
+            let reveal = claim_entry.contains_key("reveal") && claim_entry["reveal"].as_bool().unwrap_or(false);
+            let reveal_bytes = claim_entry.contains_key("reveal_bytes") && claim_entry["reveal_bytes"].as_bool().unwrap_or(false);
+            if reveal && reveal_bytes {
+                return_error!(format!("Config entry for claim {} has both 'reveal' and 'reveal_bytes' set", key));
+            }
+            if (reveal || reveal_bytes) && !claim_entry.contains_key("max_claim_byte_len") {
                 return_error!(format!("Config entry for claim {} has reveal flag set but is missing 'max_claim_byte_len'", key));
             }
         }
@@ -579,10 +1316,114 @@ pub fn parse_config(config_str: &str) -> Result<serde_json::Map<String, Value>,
 }
 
 
+/// The type of a credential attribute, as declared by its config entry's
+/// `type` field. A single source of truth for what the range/predicate and
+/// SD-JWT features are each allowed to do with an attribute, checked once
+/// up front by `validate_attributes` rather than left to whichever circuit-
+/// input-building code path happens to touch the attribute first.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum AttrType {
+    StringType,
+    Integer,
+    Date,
+    Boolean,
+}
+
+impl AttrType {
+    fn parse(type_string: &str) -> Option<Self> {
+        match type_string {
+            "string" => Some(AttrType::StringType),
+            "number" => Some(AttrType::Integer),
+            "date" => Some(AttrType::Date),
+            "boolean" => Some(AttrType::Boolean),
+            _ => None,
+        }
+    }
+
+    /// Inverse of [`AttrType::parse`], for building [`ProofSpecInternal::claim_types`]
+    /// (which callers compare against the config's original type strings, e.g. `"number"`).
+    fn as_config_str(&self) -> &'static str {
+        match self {
+            AttrType::StringType => "string",
+            AttrType::Integer => "number",
+            AttrType::Date => "date",
+            AttrType::Boolean => "boolean",
+        }
+    }
+}
+
+/// Builds the attribute-name -> type map from every non-reserved entry in
+/// `config` (the reserved, non-attribute keys are `CRESCENT_CONFIG_KEYS`).
+fn build_attribute_schema(config: &serde_json::Map<String, Value>) -> Result<HashMap<String, AttrType>, Box<dyn Error>> {
+    let mut schema = HashMap::new();
+    for (name, entry) in config {
+        if CRESCENT_CONFIG_KEYS.contains(name.as_str()) {
+            continue;
+        }
+        let entry = entry.as_object().ok_or(format!("Config file entry for claim {}, does not have object type", name))?;
+        let type_string = entry.get("type").and_then(|t| t.as_str()).ok_or(format!("Config file entry for claim {}, is missing 'type'", name))?;
+        let attr_type = AttrType::parse(type_string).ok_or(format!("Config file entry for claim {} has unsupported type '{}'", name, type_string))?;
+        schema.insert(name.clone(), attr_type);
+    }
+    Ok(schema)
+}
+
+/// Validates every attribute a `ProofSpec` names against `schema` before any
+/// of it is used to build circuit inputs: unknown attribute names, repeated
+/// entries in `revealed`, and predicates over non-numeric attributes are all
+/// rejected here with an error naming the offending attribute, rather than
+/// surfacing later as an opaque circuit-input failure.
+fn validate_attributes(proof_spec: &ProofSpec, schema: &HashMap<String, AttrType>) -> Result<(), Box<dyn Error>> {
+    let mut seen = HashSet::new();
+    for attr in &proof_spec.revealed {
+        if !schema.contains_key(attr.as_str()) {
+            return_error!(format!("Attribute {} in 'revealed' is not declared in the config", attr));
+        }
+        if !seen.insert(attr.as_str()) {
+            return_error!(format!("Attribute {} is listed more than once in 'revealed'", attr));
+        }
+    }
+
+    if let Some(predicates) = &proof_spec.predicates {
+        for predicate in predicates {
+            match schema.get(predicate.attr.as_str()) {
+                None => return_error!(format!("Predicate attribute {} is not declared in the config", predicate.attr)),
+                Some(AttrType::Integer) => {}
+                Some(other) => return_error!(format!("Predicate attribute {} has type '{:?}', but predicates are only supported for numeric claims", predicate.attr, other)),
+            }
+        }
+    }
+
+    if let Some(range_over_year) = &proof_spec.range_over_year {
+        for attr in range_over_year.keys() {
+            match schema.get(attr.as_str()) {
+                None => return_error!(format!("range_over_year attribute {} is not declared in the config", attr)),
+                Some(AttrType::Integer) => {}
+                Some(other) => return_error!(format!("range_over_year attribute {} has type '{:?}', but range checks are only supported for numeric claims", attr, other)),
+            }
+        }
+    }
+
+    if let Some(time_predicates) = &proof_spec.time_predicates {
+        for predicate in time_predicates {
+            match schema.get(predicate.claim.as_str()) {
+                None => return_error!(format!("time_predicates claim {} is not declared in the config", predicate.claim)),
+                Some(AttrType::Integer) => {}
+                Some(other) => return_error!(format!("time_predicates claim {} has type '{:?}', but time predicates are only supported for numeric claims", predicate.claim, other)),
+            }
+        }
+    }
+
+    Ok(())
+}
+
 // Create the internal version of the ProofSpec object.  This combines information from the config file and the
-// provided ProofSpec to create a mode detailed object. 
-pub(crate) fn create_proof_spec_internal(proof_spec: &ProofSpec, config_str: &str) -> Result<ProofSpecInternal, Box<dyn Error>> {
+// provided ProofSpec to create a mode detailed object.
+pub(crate) fn create_proof_spec_internal(proof_spec: &ProofSpec, config_str: &str, clock: &dyn Clock) -> Result<ProofSpecInternal, Box<dyn Error>> {
     let config = parse_config(config_str)?;
+    let schema = build_attribute_schema(&config)?;
+    validate_attributes(proof_spec, &schema)?;
+
     let mut revealed = vec![];
     let mut hashed = vec![];
     for attr in &proof_spec.revealed {
@@ -594,17 +1435,240 @@ pub(crate) fn create_proof_spec_internal(proof_spec: &ProofSpec, config_str: &st
             revealed.push(attr.to_string());
         }
     }
-    // Convert range_over_year from ProofSpec (which must be JSON-compatible) to Vec<(String, usize)>
-    let range_over_year = match &proof_spec.range_over_year {
-        Some(map) => map.iter().map(|(k, v)| (k.clone(), *v)).collect(),
+    // `range_over_year` is sugar for a `GreaterThanOrEqual` predicate over the
+    // day-count `days_to_be_age(age)` converts to (see `create_show_proof_mdl`).
+    let mut range_checks: Vec<(String, RangeBound)> = match &proof_spec.range_over_year {
+        Some(map) => map
+            .iter()
+            .map(|(k, age)| -> Result<(String, RangeBound), Box<dyn Error>> {
+                let days = days_to_be_age(*age as usize, clock)?;
+                Ok((k.clone(), RangeBound::GreaterThanOrEqual(days as i64)))
+            })
+            .collect::<Result<Vec<_>, _>>()?,
         None => Vec::new(),
     };
+
+    if let Some(predicates) = &proof_spec.predicates {
+        for predicate in predicates {
+            range_checks.push((predicate.attr.clone(), resolve_predicate(predicate, &config)?));
+        }
+    }
+
+    if let Some(time_predicates) = &proof_spec.time_predicates {
+        for predicate in time_predicates {
+            range_checks.push((predicate.claim.clone(), resolve_time_predicate(predicate)?));
+        }
+    }
+
     let presentation_message = proof_spec.presentation_message.clone();
     let device_bound = proof_spec.device_bound.unwrap_or(false);
+    let device_binding = proof_spec.device_binding.unwrap_or(DeviceBindingMode::RawEcdsa);
+    let audience = proof_spec.audience.clone();
+    let nonce = proof_spec.nonce.clone();
+    let not_after = proof_spec.not_after;
 
     if device_bound && proof_spec.presentation_message.is_none() {
         return_error!("Proof spec indicates the credential is device bound, but is missing the presentation message");
     }
 
-    Ok(ProofSpecInternal {revealed, hashed, range_over_year, presentation_message, device_bound, config_str: config_str.to_owned()})
+    // A `device_cose_key` entry in the config opts a device-bound credential
+    // into WebAuthn/CTAP2 assertion verification (see `webauthn::verify_assertion`)
+    // rather than just requiring a presentation message.
+    let device_public_key = match config.get("device_cose_key") {
+        Some(key) => Some(base64_url::decode(key.as_str().ok_or("device_cose_key field is not a string")?)
+            .map_err(|e| format!("device_cose_key is not valid base64url: {}", e))?),
+        None => None,
+    };
+    let device_up_required = match config.get("device_up_required") {
+        Some(v) => v.as_bool().ok_or("device_up_required field is not a boolean")?,
+        None => true,
+    };
+    let device_uv_required = match config.get("device_uv_required") {
+        Some(v) => v.as_bool().ok_or("device_uv_required field is not a boolean")?,
+        None => false,
+    };
+    let device_rp_id_hash = match config.get("device_rp_id_hash") {
+        Some(hash) => Some(base64_url::decode(hash.as_str().ok_or("device_rp_id_hash field is not a string")?)
+            .map_err(|e| format!("device_rp_id_hash is not valid base64url: {}", e))?),
+        None => None,
+    };
+    let sig_alg = SigAlg::from_config(&config)?;
+    let claim_types: std::collections::BTreeMap<String, String> = schema.iter().map(|(name, attr_type)| (name.clone(), attr_type.as_config_str().to_string())).collect();
+
+    Ok(ProofSpecInternal {revealed, hashed, range_checks, presentation_message, device_bound, device_binding, device_public_key, device_up_required, device_uv_required, device_rp_id_hash, sig_alg, audience, nonce, not_after, config_str: config_str.to_owned(), claim_types})
+}
+
+/// Validates that `predicate.attr` names a numeric claim in `config` and
+/// resolves it to a `RangeBound`, erroring on an out-of-order `between`
+/// range or a missing `value2`.
+fn resolve_predicate(predicate: &Predicate, config: &serde_json::Map<String, Value>) -> Result<RangeBound, Box<dyn Error>> {
+    let claim_entry = config.get(predicate.attr.as_str()).ok_or(format!("Predicate attribute {} not found in config", predicate.attr))?;
+    let claim_type = claim_entry.get("type").and_then(|t| t.as_str()).ok_or(format!("Config file entry for claim {} is missing 'type'", predicate.attr))?;
+    if claim_type != "number" {
+        return_error!(format!("Predicate attribute {} has type '{}', but predicates are only supported for numeric claims", predicate.attr, claim_type));
+    }
+
+    match predicate.op {
+        PredicateOp::GreaterThanOrEqual => Ok(RangeBound::GreaterThanOrEqual(predicate.value)),
+        PredicateOp::LessThanOrEqual => Ok(RangeBound::LessThanOrEqual(predicate.value)),
+        PredicateOp::Between => {
+            let value2 = predicate.value2.ok_or(format!("Predicate for {} has op 'between' but is missing 'value2'", predicate.attr))?;
+            if value2 < predicate.value {
+                return_error!(format!("Predicate for {} has value2 ({}) less than value ({})", predicate.attr, value2, predicate.value));
+            }
+            Ok(RangeBound::Between(predicate.value, value2))
+        }
+    }
+}
+
+/// Resolves a [`TimePredicate`] to the `RangeBound` proving its claim
+/// satisfies the verifier-chosen bound, e.g. `{"claim": "exp",
+/// "greater_than": now}` becomes `RangeBound::GreaterThanOrEqual(now)`.
+/// Exactly one of `greater_than`/`less_than` must be set -- `validate_attributes`
+/// has already checked `claim` itself is a declared numeric attribute.
+fn resolve_time_predicate(predicate: &TimePredicate) -> Result<RangeBound, Box<dyn Error>> {
+    match (predicate.greater_than, predicate.less_than) {
+        (Some(_), Some(_)) => return_error!(format!("time_predicates claim {} sets both 'greater_than' and 'less_than'; exactly one is expected", predicate.claim)),
+        (None, None) => return_error!(format!("time_predicates claim {} sets neither 'greater_than' nor 'less_than'", predicate.claim)),
+        (Some(bound), None) => Ok(RangeBound::GreaterThanOrEqual(bound)),
+        (None, Some(bound)) => Ok(RangeBound::LessThanOrEqual(bound)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn digest_b64(disclosure_b64: &str) -> String {
+        base64_url::encode(Sha256::digest(disclosure_b64.as_bytes()).as_slice())
+    }
+
+    #[test]
+    fn test_resolve_sd_claims_merges_object_property_disclosure() {
+        let salt_name_value = base64_url::encode(r#"["2GLC42sKQveCfGfryNRN9w","given_name","John"]"#.as_bytes());
+        let digest = digest_b64(&salt_name_value);
+
+        let mut claims: Value = serde_json::from_str(&format!(
+            r#"{{"_sd": ["{}"], "_sd_alg": "sha-256", "iss": "issuer"}}"#, digest
+        )).unwrap();
+
+        let committed = collect_sd_digests(&claims);
+        assert!(committed.contains(&digest));
+
+        let mut object_disclosures = HashMap::new();
+        object_disclosures.insert(digest, ("given_name".to_string(), json!("John")));
+        let array_disclosures = HashMap::new();
+
+        resolve_sd_claims(&mut claims, &object_disclosures, &array_disclosures);
+
+        assert_eq!(claims["given_name"], json!("John"));
+        assert!(claims.get("_sd").is_none());
+        assert!(claims.get("_sd_alg").is_none());
+    }
+
+    #[test]
+    fn test_resolve_sd_claims_merges_array_element_disclosure_and_drops_decoys() {
+        let mut claims: Value = serde_json::from_str(
+            r#"{"nationalities": [{"...": "disclosed-digest"}, {"...": "decoy-digest"}]}"#
+        ).unwrap();
+
+        let committed = collect_sd_digests(&claims);
+        assert!(committed.contains("disclosed-digest"));
+        assert!(committed.contains("decoy-digest"));
+
+        let object_disclosures = HashMap::new();
+        let mut array_disclosures = HashMap::new();
+        array_disclosures.insert("disclosed-digest".to_string(), json!("US"));
+
+        resolve_sd_claims(&mut claims, &object_disclosures, &array_disclosures);
+
+        assert_eq!(claims["nationalities"], json!(["US"]));
+    }
+
+    #[test]
+    fn test_resolve_sd_claims_ignores_undisclosed_object_property() {
+        let mut claims: Value = serde_json::from_str(
+            r#"{"_sd": ["never-disclosed-digest"], "iss": "issuer"}"#
+        ).unwrap();
+
+        let object_disclosures = HashMap::new();
+        let array_disclosures = HashMap::new();
+        resolve_sd_claims(&mut claims, &object_disclosures, &array_disclosures);
+
+        assert_eq!(claims.as_object().unwrap().len(), 1);
+        assert_eq!(claims["iss"], json!("issuer"));
+    }
+
+    #[test]
+    fn test_proof_spec_msgpack_roundtrip_yields_identical_internal_spec() {
+        let config_str = r#"{
+            "alg": "RS256",
+            "age": { "type": "number" }
+        }"#;
+
+        let proof_spec = ProofSpec {
+            revealed: vec![],
+            range_over_year: None,
+            predicates: Some(vec![Predicate { attr: "age".to_string(), op: PredicateOp::GreaterThanOrEqual, value: 18, value2: None }]),
+            time_predicates: None,
+            presentation_message: Some(b"some presentation message".to_vec()),
+            device_bound: Some(true),
+            device_binding: None,
+            audience: None,
+            nonce: None,
+            not_after: None,
+        };
+
+        let packed = proof_spec.to_msgpack().expect("Failed to encode proof spec as msgpack");
+        let unpacked = ProofSpec::from_msgpack(&packed).expect("Failed to decode proof spec from msgpack");
+
+        let original_internal = create_proof_spec_internal(&proof_spec, config_str, &crate::daystamp::NativeClock).expect("Failed to build internal spec from original");
+        let unpacked_internal = create_proof_spec_internal(&unpacked, config_str, &crate::daystamp::NativeClock).expect("Failed to build internal spec from unpacked");
+
+        assert_eq!(original_internal.revealed, unpacked_internal.revealed);
+        assert_eq!(original_internal.hashed, unpacked_internal.hashed);
+        assert_eq!(original_internal.presentation_message, unpacked_internal.presentation_message);
+        assert_eq!(original_internal.device_bound, unpacked_internal.device_bound);
+        assert_eq!(format!("{:?}", original_internal.range_checks), format!("{:?}", unpacked_internal.range_checks));
+    }
+
+    #[test]
+    fn test_resolve_time_predicate_requires_exactly_one_bound() {
+        let exp_gt = TimePredicate { claim: "exp".to_string(), greater_than: Some(1700000000), less_than: None };
+        assert!(matches!(resolve_time_predicate(&exp_gt).unwrap(), RangeBound::GreaterThanOrEqual(1700000000)));
+
+        let nbf_lt = TimePredicate { claim: "nbf".to_string(), greater_than: None, less_than: Some(1700000000) };
+        assert!(matches!(resolve_time_predicate(&nbf_lt).unwrap(), RangeBound::LessThanOrEqual(1700000000)));
+
+        let both = TimePredicate { claim: "exp".to_string(), greater_than: Some(1), less_than: Some(2) };
+        assert!(resolve_time_predicate(&both).is_err());
+
+        let neither = TimePredicate { claim: "exp".to_string(), greater_than: None, less_than: None };
+        assert!(resolve_time_predicate(&neither).is_err());
+    }
+
+    #[test]
+    fn test_validate_attributes_rejects_unknown_or_non_numeric_time_predicate_claim() {
+        let schema: HashMap<String, AttrType> = [
+            ("exp".to_string(), AttrType::Integer),
+            ("name".to_string(), AttrType::StringType),
+        ].into_iter().collect();
+
+        let make_spec = |claim: &str| ProofSpec {
+            revealed: vec![],
+            range_over_year: None,
+            predicates: None,
+            time_predicates: Some(vec![TimePredicate { claim: claim.to_string(), greater_than: Some(1700000000), less_than: None }]),
+            presentation_message: None,
+            device_bound: None,
+            device_binding: None,
+            audience: None,
+            nonce: None,
+            not_after: None,
+        };
+
+        assert!(validate_attributes(&make_spec("exp"), &schema).is_ok());
+        assert!(validate_attributes(&make_spec("missing"), &schema).is_err());
+        assert!(validate_attributes(&make_spec("name"), &schema).is_err());
+    }
 }
\ No newline at end of file
@@ -3,6 +3,7 @@
 
 use crate::{
     dlog::{DLogPoK, PedersenOpening},
+    transcript::ProofTranscript,
     utils::add_to_transcript,
 };
 use ark_ec::pairing::Pairing;
@@ -46,6 +47,12 @@ impl<E: Pairing> RangeProofPK<'_, E> {
             powers_of_g[0].into(),
         ];
 
+        // The SHPLONK-style combined opening (see `prove_n_bits`/`verify_n_bits`)
+        // needs the verifier to commit to small public polynomials -- degree up
+        // to 1 -- without the prover's help, so expose the first two plain
+        // powers of g (not the hiding gamma_g basis above) for that purpose.
+        let g_powers: [E::G1; 2] = [powers_of_g[0].into(), powers_of_g[1].into()];
+
         let powers = ark_poly_commit::kzg10::Powers::<E> {
             powers_of_g: ark_std::borrow::Cow::Owned(powers_of_g),
             powers_of_gamma_g: ark_std::borrow::Cow::Owned(powers_of_gamma_g),
@@ -65,6 +72,7 @@ impl<E: Pairing> RangeProofPK<'_, E> {
             RangeProofVK {
                 kzg_vk,
                 com_f_basis,
+                g_powers,
             },
         )
     }
@@ -75,20 +83,36 @@ impl<E: Pairing> RangeProofPK<'_, E> {
 pub struct RangeProofVK<E: Pairing> {
     pub kzg_vk: ark_poly_commit::kzg10::VerifierKey<E>,
     pub com_f_basis: [E::G1; 4],
+    pub g_powers: [E::G1; 2],
 }
 
 /// A range proofthat a value is in [0,2^n). Following the notation in https://hackmd.io/@dabo/B1U4kx8XI
+///
+/// `com_g` is opened at `rho` and `rho*w`, and `com_w_hat` (the linear
+/// combination `com_f * f_coeff + com_q * q_coeff`, see `verify_n_bits`) is
+/// opened at `rho`. By default these three openings are combined into a
+/// single SHPLONK-style opening (`com_h`/`proof_shplonk`, see
+/// `prove_n_bits`/`verify_n_bits`) instead of carrying three separate
+/// `Proof<E>` objects. Build with `--features legacy_rangeproof_opening` to
+/// fall back to the original three-proof `batch_check` path.
 #[derive(Clone, Debug, CanonicalSerialize, CanonicalDeserialize, Default)]
 pub struct RangeProof<E: Pairing> {
     pub com_f: ark_poly_commit::kzg10::Commitment<E>,
     pub com_g: ark_poly_commit::kzg10::Commitment<E>,
     pub eval_g: E::ScalarField,
-    pub proof_g: ark_poly_commit::kzg10::Proof<E>,
     pub eval_gw: E::ScalarField,
-    pub proof_gw: ark_poly_commit::kzg10::Proof<E>,
     pub com_q: ark_poly_commit::kzg10::Commitment<E>,
     pub eval_w_hat: E::ScalarField,
+    #[cfg(feature = "legacy_rangeproof_opening")]
+    pub proof_g: ark_poly_commit::kzg10::Proof<E>,
+    #[cfg(feature = "legacy_rangeproof_opening")]
+    pub proof_gw: ark_poly_commit::kzg10::Proof<E>,
+    #[cfg(feature = "legacy_rangeproof_opening")]
     pub proof_w_hat: ark_poly_commit::kzg10::Proof<E>,
+    #[cfg(not(feature = "legacy_rangeproof_opening"))]
+    pub com_h: ark_poly_commit::kzg10::Commitment<E>,
+    #[cfg(not(feature = "legacy_rangeproof_opening"))]
+    pub proof_shplonk: ark_poly_commit::kzg10::Proof<E>,
     pub dleq_proof: DLogPoK<E::G1>,
 }
 
@@ -98,12 +122,19 @@ impl<E: Pairing> RangeProof<E> {
             com_f: ark_poly_commit::kzg10::Commitment::default(),
             com_g: ark_poly_commit::kzg10::Commitment::default(),
             eval_g: E::ScalarField::zero(),
-            proof_g: ark_poly_commit::kzg10::Proof::default(),
             eval_gw: E::ScalarField::zero(),
-            proof_gw: ark_poly_commit::kzg10::Proof::default(),
             com_q: ark_poly_commit::kzg10::Commitment::default(),
             eval_w_hat: E::ScalarField::zero(),
+            #[cfg(feature = "legacy_rangeproof_opening")]
+            proof_g: ark_poly_commit::kzg10::Proof::default(),
+            #[cfg(feature = "legacy_rangeproof_opening")]
+            proof_gw: ark_poly_commit::kzg10::Proof::default(),
+            #[cfg(feature = "legacy_rangeproof_opening")]
             proof_w_hat: ark_poly_commit::kzg10::Proof::default(),
+            #[cfg(not(feature = "legacy_rangeproof_opening"))]
+            com_h: ark_poly_commit::kzg10::Commitment::default(),
+            #[cfg(not(feature = "legacy_rangeproof_opening"))]
+            proof_shplonk: ark_poly_commit::kzg10::Proof::default(),
             dleq_proof: DLogPoK::default(),
         }
     }
@@ -242,19 +273,18 @@ impl<E: Pairing> RangeProof<E> {
             ],
             &[vec![ped_open.m, ped_open.r], com_f_scalars],
             Some(vec![(0, 3)]),
+            None,
         );
 
         // create a commitment to g
         let (com_g, rand_g) = KZG10::commit(powers, &g_blinded, Some(2), Some(&mut rng)).unwrap(); // Opened twice
 
-        let mut ts = Transcript::new(&[0u8]);
+        let mut ts = Transcript::new(b"crescent rangeproof");
         add_to_transcript(&mut ts, b"com_f", &com_f);
         add_to_transcript(&mut ts, b"com_g", &com_g);
 
         // get the challenge
-        let mut c_bytes = [0u8; 31];
-        ts.challenge_bytes(&[0u8], &mut c_bytes);
-        let c = E::ScalarField::from_random_bytes(&c_bytes).unwrap();
+        let c = ts.challenge_scalar::<E::ScalarField>(b"rangeproof challenge c");
         let c_sq = c.square();
 
         let mut q2_c = q2.clone();
@@ -269,24 +299,12 @@ impl<E: Pairing> RangeProof<E> {
 
         add_to_transcript(&mut ts, b"com_q", &com_q);
         // get another challenge
-        let mut rho_bytes = [0u8; 31];
-        ts.challenge_bytes(&[0u8], &mut rho_bytes);
-        let rho = E::ScalarField::from_random_bytes(&rho_bytes).unwrap();
+        let rho = ts.challenge_scalar::<E::ScalarField>(b"rangeproof challenge rho");
 
-        // open com_g at rho and rho*w
+        // com_g is opened at rho and rho*w
         let eval_g = g_blinded.evaluate(&rho);
-        let proof_g =
-            KZG10::<E, DensePolynomial<E::ScalarField>>::open(powers, &g_blinded, rho, &rand_g)
-                .unwrap();
-
-        let eval_gw = g_blinded.evaluate(&(rho * domain.element(1)));
-        let proof_gw = KZG10::<E, DensePolynomial<E::ScalarField>>::open(
-            powers,
-            &g_blinded,
-            rho * domain.element(1),
-            &rand_g,
-        )
-        .unwrap();
+        let rho_w = rho * domain.element(1);
+        let eval_gw = g_blinded.evaluate(&rho_w);
 
         // Compute w_hat = f.(rho^n - 1)/(rho - 1) + q.(rho^n - 1)
         let q_coeff = rho.pow([n as u64]) - E::ScalarField::one();
@@ -318,23 +336,136 @@ impl<E: Pairing> RangeProof<E> {
         rand_w_hat.blinding_polynomial =
             rand_f_term.blinding_polynomial + rand_q_term.blinding_polynomial;
 
-        // open com_w_hat at rho
+        // com_w_hat is opened at rho
         let eval_w_hat = w_hat.evaluate(&rho);
-        let proof_w_hat =
-            KZG10::<E, DensePolynomial<E::ScalarField>>::open(powers, &w_hat, rho, &rand_w_hat)
-                .unwrap();
 
-        RangeProof {
-            com_f,
-            com_g,
-            com_q,
-            eval_g,
-            eval_gw,
-            proof_g,
-            proof_gw,
-            eval_w_hat,
-            proof_w_hat,
-            dleq_proof,
+        #[cfg(feature = "legacy_rangeproof_opening")]
+        {
+            let proof_g = KZG10::<E, DensePolynomial<E::ScalarField>>::open(
+                powers, &g_blinded, rho, &rand_g,
+            )
+            .unwrap();
+            let proof_gw = KZG10::<E, DensePolynomial<E::ScalarField>>::open(
+                powers, &g_blinded, rho_w, &rand_g,
+            )
+            .unwrap();
+            let proof_w_hat = KZG10::<E, DensePolynomial<E::ScalarField>>::open(
+                powers, &w_hat, rho, &rand_w_hat,
+            )
+            .unwrap();
+
+            RangeProof {
+                com_f,
+                com_g,
+                com_q,
+                eval_g,
+                eval_gw,
+                proof_g,
+                proof_gw,
+                eval_w_hat,
+                proof_w_hat,
+                dleq_proof,
+            }
+        }
+        #[cfg(not(feature = "legacy_rangeproof_opening"))]
+        {
+            // Combine the three openings {(com_g, rho), (com_g, rho*w),
+            // (com_w_hat, rho)} into a single SHPLONK-style opening
+            // (Boneh, Drake, Fisch, Gabizon, "Halo Infinite" sec. "Reducing
+            // multiple evaluations to one"): a combined per-point-set
+            // quotient `com_h`, and one final opening proof `proof_shplonk`
+            // that the corresponding linearization polynomial evaluates to
+            // zero at a fresh point `z`. This replaces three `Proof<E>`
+            // objects with one commitment and one proof, and the verifier
+            // checks a single opening (`KZG10::check`) instead of
+            // batch-checking three (`batch_check`).
+            add_to_transcript(&mut ts, b"eval_g", &eval_g);
+            add_to_transcript(&mut ts, b"eval_gw", &eval_gw);
+            add_to_transcript(&mut ts, b"eval_w_hat", &eval_w_hat);
+            let gamma = ts.challenge_scalar::<E::ScalarField>(b"rangeproof challenge gamma");
+
+            // com_g's point set is {rho, rho*w}; com_w_hat's is {rho}, which
+            // is already contained in com_g's -- so their union S is just
+            // {rho, rho*w}.
+            let linear = |root: E::ScalarField| {
+                DensePolynomial::from_coefficients_vec(vec![-root, E::ScalarField::one()])
+            };
+
+            // r_g interpolates (rho, eval_g), (rho*w, eval_gw)
+            let slope_g = (eval_gw - eval_g) / (rho_w - rho);
+            let r_g =
+                DensePolynomial::from_coefficients_vec(vec![eval_g - slope_g * rho, slope_g]);
+            let z_g = &linear(rho) * &linear(rho_w);
+            let g_minus_rg = &g_blinded - &r_g;
+            let q_g = &g_minus_rg / &z_g;
+
+            // r_w is the constant eval_w_hat
+            let r_w = DensePolynomial::from_coefficients_vec(vec![eval_w_hat]);
+            let z_w = linear(rho);
+            let w_hat_minus_rw = &w_hat - &r_w;
+            let q_w = &w_hat_minus_rw / &z_w;
+
+            let mut q_w_gamma = q_w;
+            q_w_gamma.coeffs.iter_mut().for_each(|x| *x *= gamma);
+            let h_poly = &q_g + &q_w_gamma;
+
+            let (com_h, rand_h) = KZG10::commit(powers, &h_poly, Some(1), Some(&mut rng)).unwrap();
+
+            add_to_transcript(&mut ts, b"com_h", &com_h);
+            let z = ts.challenge_scalar::<E::ScalarField>(b"rangeproof challenge z");
+
+            // Z_{S \ S_g}(z) = 1 (S_g == S); Z_{S \ S_w}(z) = z - rho*w
+            let z_s_at_z = (z - rho) * (z - rho_w);
+            let z_s_minus_w_at_z = z - rho_w;
+
+            // L(X) = (g_blinded(X)-r_g(X)) + gamma*(z-rho*w)*(w_hat(X)-r_w(X)) - Z_S(z)*h(X)
+            // L(z) = 0 by construction (checked by the verifier without
+            // knowing g_blinded/w_hat/h themselves, only their commitments)
+            let mut w_hat_term = w_hat_minus_rw;
+            w_hat_term
+                .coeffs
+                .iter_mut()
+                .for_each(|x| *x *= gamma * z_s_minus_w_at_z);
+
+            let mut h_term = h_poly;
+            h_term.coeffs.iter_mut().for_each(|x| *x *= z_s_at_z);
+
+            let l_poly = &(&g_minus_rg + &w_hat_term) - &h_term;
+
+            let mut rand_h_term = rand_h;
+            rand_h_term
+                .blinding_polynomial
+                .coeffs
+                .iter_mut()
+                .for_each(|x| *x *= z_s_at_z);
+
+            let mut rand_w_hat_term = rand_w_hat;
+            rand_w_hat_term
+                .blinding_polynomial
+                .coeffs
+                .iter_mut()
+                .for_each(|x| *x *= gamma * z_s_minus_w_at_z);
+
+            let mut rand_l = Randomness::empty();
+            rand_l.blinding_polynomial = rand_g.blinding_polynomial
+                + rand_w_hat_term.blinding_polynomial
+                - rand_h_term.blinding_polynomial;
+
+            let proof_shplonk =
+                KZG10::<E, DensePolynomial<E::ScalarField>>::open(powers, &l_poly, z, &rand_l)
+                    .unwrap();
+
+            RangeProof {
+                com_f,
+                com_g,
+                com_q,
+                eval_g,
+                eval_gw,
+                com_h,
+                proof_shplonk,
+                eval_w_hat,
+                dleq_proof,
+            }
         }
     }
 
@@ -349,44 +480,87 @@ impl<E: Pairing> RangeProof<E> {
         let domain = Radix2EvaluationDomain::<E::ScalarField>::new(n).unwrap();
 
         // rederive the challenges
-        let mut ts = Transcript::new(&[0u8]);
+        let mut ts = Transcript::new(b"crescent rangeproof");
         add_to_transcript(&mut ts, b"com_f", &self.com_f);
         add_to_transcript(&mut ts, b"com_g", &self.com_g);
 
         // get the challenge
-        let mut c_bytes = [0u8; 31];
-        ts.challenge_bytes(&[0u8], &mut c_bytes);
-        let c = E::ScalarField::from_random_bytes(&c_bytes).unwrap();
+        let c = ts.challenge_scalar::<E::ScalarField>(b"rangeproof challenge c");
 
         add_to_transcript(&mut ts, b"com_q", &self.com_q);
 
         // get another challenge
-        let mut rho_bytes = [0u8; 31];
-        ts.challenge_bytes(&[0u8], &mut rho_bytes);
-        let rho = E::ScalarField::from_random_bytes(&rho_bytes).unwrap();
+        let rho = ts.challenge_scalar::<E::ScalarField>(b"rangeproof challenge rho");
+        let rho_w = rho * domain.element(1);
 
         // verify the openings
         let q_coeff = rho.pow([n as u64]) - E::ScalarField::one();
         let f_coeff = q_coeff / (rho - E::ScalarField::one());
         let com_w_hat: Commitment<E> = Commitment((self.com_f.0 * f_coeff + self.com_q.0 * q_coeff).into());
 
-        let rng = &mut thread_rng();
-        let ret = KZG10::<E, DensePolynomial<E::ScalarField>>::batch_check(
-            &vk.kzg_vk,
-            &[self.com_g, self.com_g, com_w_hat],
-            &[rho, rho * domain.element(1), rho],
-            &[self.eval_g, self.eval_gw, self.eval_w_hat],
-            &[self.proof_g, self.proof_gw, self.proof_w_hat],
-            rng,
-        );
-        match ret{
-            Ok(ret) => if !ret {
-                println!("Error verifying range proof, batch_check failed ");
-                return false;
-            },
-            Err(ret) => {
-                println!("Error verifying range proof, batch_check failed with error: {ret:?} ");
-                return false;
+        #[cfg(feature = "legacy_rangeproof_opening")]
+        {
+            let rng = &mut thread_rng();
+            let ret = KZG10::<E, DensePolynomial<E::ScalarField>>::batch_check(
+                &vk.kzg_vk,
+                &[self.com_g, self.com_g, com_w_hat],
+                &[rho, rho_w, rho],
+                &[self.eval_g, self.eval_gw, self.eval_w_hat],
+                &[self.proof_g, self.proof_gw, self.proof_w_hat],
+                rng,
+            );
+            match ret{
+                Ok(ret) => if !ret {
+                    println!("Error verifying range proof, batch_check failed ");
+                    return false;
+                },
+                Err(ret) => {
+                    println!("Error verifying range proof, batch_check failed with error: {ret:?} ");
+                    return false;
+                }
+            }
+        }
+        #[cfg(not(feature = "legacy_rangeproof_opening"))]
+        {
+            // rederive gamma and z, then check the single combined SHPLONK opening
+            add_to_transcript(&mut ts, b"eval_g", &self.eval_g);
+            add_to_transcript(&mut ts, b"eval_gw", &self.eval_gw);
+            add_to_transcript(&mut ts, b"eval_w_hat", &self.eval_w_hat);
+            let gamma = ts.challenge_scalar::<E::ScalarField>(b"rangeproof challenge gamma");
+
+            add_to_transcript(&mut ts, b"com_h", &self.com_h);
+            let z = ts.challenge_scalar::<E::ScalarField>(b"rangeproof challenge z");
+
+            // Com(r_g): r_g interpolates (rho, eval_g), (rho*w, eval_gw)
+            let slope_g = (self.eval_gw - self.eval_g) / (rho_w - rho);
+            let com_r_g =
+                vk.g_powers[0] * (self.eval_g - slope_g * rho) + vk.g_powers[1] * slope_g;
+            // Com(r_w): r_w is the constant eval_w_hat
+            let com_r_w = vk.g_powers[0] * self.eval_w_hat;
+
+            let z_s_at_z = (z - rho) * (z - rho_w);
+            let z_s_minus_w_at_z = z - rho_w;
+
+            let com_l = (self.com_g.0 - com_r_g)
+                + (com_w_hat.0 - com_r_w) * (gamma * z_s_minus_w_at_z)
+                - self.com_h.0 * z_s_at_z;
+
+            let ret = KZG10::<E, DensePolynomial<E::ScalarField>>::check(
+                &vk.kzg_vk,
+                &Commitment(com_l.into()),
+                z,
+                E::ScalarField::zero(),
+                &self.proof_shplonk,
+            );
+            match ret {
+                Ok(ret) => if !ret {
+                    println!("Error verifying range proof, shplonk check failed ");
+                    return false;
+                },
+                Err(ret) => {
+                    println!("Error verifying range proof, shplonk check failed with error: {ret:?} ");
+                    return false;
+                }
             }
         }
 
@@ -420,10 +594,478 @@ impl<E: Pairing> RangeProof<E> {
                 &[bases.to_vec(), vk.com_f_basis.to_vec(),],
                 &[*ped_com, self.com_f.0.into()],
                 Some(vec![(0, 3)]),
+                None,
             )
     }
 }
 
+/// A range proof that a Pedersen-committed value lies in the arbitrary
+/// interval `[a, b)`, rather than only `[0, 2^n)`. Built from the standard
+/// two-sided reduction: `elem - a` and `(b-1) - elem` are each proved to be
+/// in `[0, 2^n)`, where `n` is the smallest power-of-two bit count with
+/// `2^n >= b - a`.
+///
+/// Negating/shifting a Pedersen commitment by a public constant is affine
+/// on the opening it proves knowledge of, so the shifted commitments don't
+/// need a fresh commitment or a DLEQ of their own: `c - a*bases[0]` opens to
+/// `elem - a` under the *same* blinding `r`, and `(b-1)*bases[0] - c` opens
+/// to `(b-1) - elem` under blinding `-r`. Each side's own internal DLEQ (see
+/// `RangeProof::prove_n_bits`) then binds its `com_f` to that shifted
+/// commitment, so `verify_range` just needs to recompute the same shifts
+/// from the caller's original commitment before checking each side.
+#[derive(Clone, Debug, CanonicalSerialize, CanonicalDeserialize)]
+pub struct BoundedRangeProof<E: Pairing> {
+    pub n: usize,
+    pub lower_proof: RangeProof<E>,
+    pub upper_proof: RangeProof<E>,
+}
+
+impl<E: Pairing> BoundedRangeProof<E> {
+    /// Smallest power-of-two bit count `n` with `2^n >= width`.
+    fn bits_for_width(width: u64) -> usize {
+        assert!(width > 0, "range must be non-empty");
+        let mut n = 1usize;
+        while (1u128 << n) < width as u128 {
+            n *= 2;
+        }
+        n
+    }
+
+    /// Proves that `ped_open`'s committed value lies in `[a, b)`.
+    pub fn prove_range(ped_open: &PedersenOpening<E::G1>, a: u64, b: u64, powers: &Powers<E>) -> Self {
+        assert!(a < b, "empty range");
+        let n = Self::bits_for_width(b - a);
+
+        let g: E::G1 = ped_open.bases[0].into();
+        let a_scalar = E::ScalarField::from(a);
+        let b_minus_one_scalar = E::ScalarField::from(b - 1);
+
+        let lower_open = PedersenOpening {
+            bases: ped_open.bases.clone(),
+            m: ped_open.m - a_scalar,
+            r: ped_open.r,
+            c: ped_open.c - g * a_scalar,
+        };
+        let upper_open = PedersenOpening {
+            bases: ped_open.bases.clone(),
+            m: b_minus_one_scalar - ped_open.m,
+            r: -ped_open.r,
+            c: g * b_minus_one_scalar - ped_open.c,
+        };
+
+        let lower_proof = RangeProof::prove_n_bits(&lower_open, n, powers);
+        let upper_proof = RangeProof::prove_n_bits(&upper_open, n, powers);
+
+        BoundedRangeProof { n, lower_proof, upper_proof }
+    }
+
+    /// Verifies that the value behind `ped_com` lies in `[a, b)`.
+    pub fn verify_range(&self, ped_com: &E::G1, bases: &[E::G1; 2], a: u64, b: u64, vk: &RangeProofVK<E>) -> bool {
+        if a >= b || Self::bits_for_width(b - a) != self.n {
+            println!("Range proof failed to verify, bounds don't match the proof's bit width");
+            return false;
+        }
+
+        let a_scalar = E::ScalarField::from(a);
+        let b_minus_one_scalar = E::ScalarField::from(b - 1);
+
+        let lower_com = *ped_com - bases[0] * a_scalar;
+        let upper_com = bases[0] * b_minus_one_scalar - *ped_com;
+
+        self.lower_proof.verify_n_bits(&lower_com, bases, self.n, vk)
+            && self.upper_proof.verify_n_bits(&upper_com, bases, self.n, vk)
+    }
+}
+
+
+/// A single range proof that every value committed in `ped_opens` lies in
+/// `[0, 2^n)`, amortizing the dominant KZG commit/open cost across all
+/// `k = ped_opens.len()` values instead of running `k` independent
+/// [`RangeProof`]s.
+///
+/// The `k` values' bit decompositions are laid out end to end in one
+/// evaluation domain of size `k*n` (value `j` occupies domain positions
+/// `[j*n, (j+1)*n)`, each block satisfying the same "doubling" recurrence
+/// `RangeProof::prove_n_bits` uses), so there's only one `com_g` and one
+/// opening of it at `rho`/`rho*w`. Every block's three constraint checks
+/// are folded into the single committed quotient `com_q` via ascending
+/// powers of one Fiat-Shamir challenge `c` (`c^{2j}` for block `j`'s "f(r_j)
+/// = g(r_j)" check, `c^{2j+1}` for its "g(s_j) is boolean" check, and
+/// `c^{2k}` for the one shared doubling-relation check, which vanishes on
+/// the whole domain once multiplied by `prod_j (X - s_j)` -- so unlike the
+/// per-block checks it doesn't need its own quotient).
+///
+/// `com_f` necessarily stays a *vector* of `k` per-value commitments rather
+/// than a single aggregate (there's no way to bind a single low-degree
+/// polynomial's coefficients to each `elem_j` positionally), but the DLEQ
+/// linking them to the `k` Pedersen commitments is still a single proof:
+/// `DLogPoK::prove`'s `eq_classes` links `ped_opens[j]`'s value scalar to
+/// `com_f[j]`'s value scalar for every `j` in one shared transcript/challenge.
+#[derive(Clone, Debug, CanonicalSerialize, CanonicalDeserialize)]
+pub struct AggregatedRangeProof<E: Pairing> {
+    pub k: usize,
+    pub n: usize,
+    pub com_f: Vec<Commitment<E>>,
+    pub com_g: Commitment<E>,
+    pub eval_g: E::ScalarField,
+    pub proof_g: ark_poly_commit::kzg10::Proof<E>,
+    pub eval_gw: E::ScalarField,
+    pub proof_gw: ark_poly_commit::kzg10::Proof<E>,
+    pub com_q: Commitment<E>,
+    pub eval_w_hat: E::ScalarField,
+    pub proof_w_hat: ark_poly_commit::kzg10::Proof<E>,
+    pub dleq_proof: DLogPoK<E::G1>,
+}
+
+impl<E: Pairing> AggregatedRangeProof<E> {
+    /// Proves that every value in `ped_opens` is in `[0, 2^n)`.
+    pub fn prove_n_bits_aggregated(
+        ped_opens: &[PedersenOpening<E::G1>],
+        n: usize,
+        powers: &Powers<E>,
+    ) -> Self {
+        let k = ped_opens.len();
+        assert!(k > 0, "must aggregate at least one value");
+        assert!(n.is_power_of_two(), "n must be a power of two");
+        let kn = k * n;
+        assert!(powers.powers_of_g.len() >= kn + 2, "Not enough powers of g");
+
+        let mut rng = thread_rng();
+        let domain = Radix2EvaluationDomain::<E::ScalarField>::new(kn).unwrap();
+        let domain_elements = domain.elements().collect::<Vec<E::ScalarField>>();
+        let one = DensePolynomial::from_coefficients_vec(vec![E::ScalarField::one()]);
+
+        // lay the k values' bit decompositions end to end, block j at [j*n, (j+1)*n)
+        let mut g_evals = vec![E::ScalarField::zero(); kn];
+        for (j, ped_open) in ped_opens.iter().enumerate() {
+            let elem_bits = ped_open
+                .m
+                .into_bigint()
+                .to_bits_le()
+                .iter()
+                .map(|x| if *x { E::ScalarField::one() } else { E::ScalarField::zero() })
+                .take(n)
+                .collect::<Vec<_>>();
+            let base = j * n;
+            g_evals[base + n - 1] = elem_bits[n - 1];
+            for i in (0..=n - 2).rev() {
+                g_evals[base + i] = g_evals[base + i + 1].double() + elem_bits[i];
+            }
+        }
+        let g = DensePolynomial::from_coefficients_vec(domain.ifft(&g_evals));
+
+        let blinding_poly =
+            DensePolynomial::<E::ScalarField>::rand(2, &mut rng).mul_by_vanishing_poly(domain);
+        let g_blinded = &g + &blinding_poly;
+
+        let mut gw_blinded = g_blinded.clone();
+        for i in 0..kn {
+            gw_blinded.coeffs[i] *= domain_elements[i];
+        }
+        for i in kn..kn + 3 {
+            gw_blinded.coeffs[i] *= domain_elements[i - kn];
+        }
+
+        // per-block f_j, com_f_j, q1_j = (g-f_j)/(X-r_j), q2_j = g(1-g)/(X-s_j)
+        let mut f_polys = Vec::with_capacity(k);
+        let mut com_fs = Vec::with_capacity(k);
+        let mut rand_fs = Vec::with_capacity(k);
+        let mut q1s = Vec::with_capacity(k);
+        let mut q2s = Vec::with_capacity(k);
+        let mut end_points = Vec::with_capacity(k);
+
+        for (j, ped_open) in ped_opens.iter().enumerate() {
+            let elem = ped_open.m;
+            let f_j = DensePolynomial::<E::ScalarField>::from_coefficients_vec(vec![elem; 1]);
+            let r_j = domain.element(j * n);
+            let s_j = domain.element(j * n + n - 1);
+            end_points.push(s_j);
+
+            let q1_j = &(&g_blinded - &f_j)
+                / &DensePolynomial::from_coefficients_vec(vec![-r_j, E::ScalarField::one()]);
+            let q2_j = &(&g_blinded * &(&one - &g_blinded))
+                / &DensePolynomial::from_coefficients_vec(vec![-s_j, E::ScalarField::one()]);
+
+            let (com_f_j, rand_f_j) = KZG10::commit(powers, &f_j, Some(1), Some(&mut rng)).unwrap();
+
+            f_polys.push(f_j);
+            com_fs.push(com_f_j);
+            rand_fs.push(rand_f_j);
+            q1s.push(q1_j);
+            q2s.push(q2_j);
+        }
+
+        // the one shared doubling-relation check: (g-2gw)(1-g+2gw) vanishes at every
+        // domain point except each block's own last point, so multiplying by
+        // prod_j (X - s_j) makes it vanish on the whole domain.
+        let mut gw2 = gw_blinded.clone();
+        gw2.coeffs.iter_mut().for_each(|x| *x *= E::ScalarField::from(2u8));
+        let g_2gw = &g_blinded - &gw2;
+        let mut w3 = &g_2gw * &(&one - &g_2gw);
+        let mut z_end = one.clone();
+        for &s_j in &end_points {
+            z_end = &z_end * &DensePolynomial::from_coefficients_vec(vec![-s_j, E::ScalarField::one()]);
+        }
+        w3 = &w3 * &z_end;
+        let (q3, _rem3) = w3.divide_by_vanishing_poly(domain).unwrap();
+        debug_assert!(_rem3.is_zero());
+
+        // link every block's value to its com_f_j via one aggregated DLEQ
+        let mut com_f_basis = powers
+            .powers_of_gamma_g
+            .iter()
+            .take(3)
+            .map(|&x| x.into())
+            .collect::<Vec<E::G1>>();
+        com_f_basis.push(powers.powers_of_g[0].into());
+
+        let mut dleq_y = Vec::with_capacity(2 * k);
+        let mut dleq_bases = Vec::with_capacity(2 * k);
+        let mut dleq_scalars = Vec::with_capacity(2 * k);
+        for ped_open in ped_opens {
+            dleq_y.push(ped_open.c);
+            dleq_bases.push(ped_open.bases.iter().map(|&x| x.into()).collect::<Vec<E::G1>>());
+            dleq_scalars.push(vec![ped_open.m, ped_open.r]);
+        }
+        for (j, rand_f_j) in rand_fs.iter().enumerate() {
+            dleq_y.push(com_fs[j].0.into());
+            dleq_bases.push(com_f_basis.clone());
+            let mut com_f_scalars = rand_f_j.blinding_polynomial.coeffs.to_vec();
+            com_f_scalars.push(ped_opens[j].m);
+            dleq_scalars.push(com_f_scalars);
+        }
+        let eq_classes = (0..k).map(|j| vec![(j, 0), (k + j, 3)]).collect::<Vec<_>>();
+        let dleq_proof = DLogPoK::<E::G1>::prove(
+            None,
+            &dleq_y,
+            &dleq_bases,
+            &dleq_scalars,
+            Some(eq_classes),
+        );
+
+        // create a commitment to g and take the challenge combining all per-block checks
+        let (com_g, rand_g) = KZG10::commit(powers, &g_blinded, Some(2), Some(&mut rng)).unwrap();
+
+        let mut ts = Transcript::new(b"crescent aggregated rangeproof");
+        add_to_transcript(&mut ts, b"k", &k);
+        for com_f_j in &com_fs {
+            add_to_transcript(&mut ts, b"com_f", com_f_j);
+        }
+        add_to_transcript(&mut ts, b"com_g", &com_g);
+
+        let c = ts.challenge_scalar::<E::ScalarField>(b"rangeproof challenge c");
+
+        // q = sum_j (c^{2j} q1_j + c^{2j+1} q2_j) + c^{2k} q3
+        let mut q = DensePolynomial::<E::ScalarField>::from_coefficients_vec(vec![E::ScalarField::zero()]);
+        let mut c_pow = E::ScalarField::one();
+        for j in 0..k {
+            let mut term1 = q1s[j].clone();
+            term1.coeffs.iter_mut().for_each(|x| *x *= c_pow);
+            q = &q + &term1;
+            c_pow *= c;
+
+            let mut term2 = q2s[j].clone();
+            term2.coeffs.iter_mut().for_each(|x| *x *= c_pow);
+            q = &q + &term2;
+            c_pow *= c;
+        }
+        let mut term3 = q3.clone();
+        term3.coeffs.iter_mut().for_each(|x| *x *= c_pow);
+        q = &q + &term3;
+
+        let (com_q, rand_q) = KZG10::commit(powers, &q, Some(1), Some(&mut rng)).unwrap();
+
+        add_to_transcript(&mut ts, b"com_q", &com_q);
+        let rho = ts.challenge_scalar::<E::ScalarField>(b"rangeproof challenge rho");
+
+        // open com_g at rho and rho*w (amortized across all k values)
+        let eval_g = g_blinded.evaluate(&rho);
+        let proof_g =
+            KZG10::<E, DensePolynomial<E::ScalarField>>::open(powers, &g_blinded, rho, &rand_g)
+                .unwrap();
+
+        let eval_gw = g_blinded.evaluate(&(rho * domain.element(1)));
+        let proof_gw = KZG10::<E, DensePolynomial<E::ScalarField>>::open(
+            powers,
+            &g_blinded,
+            rho * domain.element(1),
+            &rand_g,
+        )
+        .unwrap();
+
+        // w_hat = sum_j f_j * f_coeff_j + q * q_coeff, where f_coeff_j cancels
+        // block j's own "-f_j" term inside q1_j once multiplied through (see
+        // RangeProof::prove_n_bits for the single-value version of this trick).
+        let q_coeff = rho.pow([kn as u64]) - E::ScalarField::one();
+
+        let mut w_hat = DensePolynomial::<E::ScalarField>::from_coefficients_vec(vec![E::ScalarField::zero()]);
+        let mut rand_w_hat = Randomness::empty();
+        let mut c_pow = E::ScalarField::one();
+        for (j, f_j) in f_polys.iter().enumerate() {
+            let r_j = domain.element(j * n);
+            let f_coeff_j = c_pow * q_coeff / (rho - r_j);
+
+            let mut f_term = f_j.clone();
+            f_term.coeffs.iter_mut().for_each(|x| *x *= f_coeff_j);
+            w_hat = &w_hat + &f_term;
+
+            let mut rand_f_term = rand_fs[j].clone();
+            rand_f_term
+                .blinding_polynomial
+                .coeffs
+                .iter_mut()
+                .for_each(|x| *x *= f_coeff_j);
+            rand_w_hat.blinding_polynomial =
+                rand_w_hat.blinding_polynomial + rand_f_term.blinding_polynomial;
+
+            c_pow *= c * c;
+        }
+        let mut q_term = q.clone();
+        q_term.coeffs.iter_mut().for_each(|x| *x *= q_coeff);
+        w_hat = &w_hat + &q_term;
+
+        let mut rand_q_term = rand_q.clone();
+        rand_q_term
+            .blinding_polynomial
+            .coeffs
+            .iter_mut()
+            .for_each(|x| *x *= q_coeff);
+        rand_w_hat.blinding_polynomial =
+            rand_w_hat.blinding_polynomial + rand_q_term.blinding_polynomial;
+
+        let eval_w_hat = w_hat.evaluate(&rho);
+        let proof_w_hat =
+            KZG10::<E, DensePolynomial<E::ScalarField>>::open(powers, &w_hat, rho, &rand_w_hat)
+                .unwrap();
+
+        AggregatedRangeProof {
+            k,
+            n,
+            com_f: com_fs,
+            com_g,
+            com_q,
+            eval_g,
+            eval_gw,
+            proof_g,
+            proof_gw,
+            eval_w_hat,
+            proof_w_hat,
+            dleq_proof,
+        }
+    }
+
+    /// Verifies that every value behind `ped_coms` is in `[0, 2^n)`. `bases[j]`
+    /// are the Pedersen bases for `ped_coms[j]` -- aggregated values need not
+    /// share the same bases (e.g. they may be committed at different Groth16
+    /// IO positions, which changes the first base).
+    pub fn verify_n_bits_aggregated(
+        &self,
+        ped_coms: &[E::G1],
+        bases: &[[E::G1; 2]],
+        n: usize,
+        vk: &RangeProofVK<E>,
+    ) -> bool {
+        let k = self.k;
+        if k == 0 || k != ped_coms.len() || k != bases.len() || k != self.com_f.len() || n != self.n {
+            println!("Aggregated range proof failed to verify, length/bit-width mismatch");
+            return false;
+        }
+        let kn = k * n;
+        let domain = Radix2EvaluationDomain::<E::ScalarField>::new(kn).unwrap();
+
+        // rederive the challenges
+        let mut ts = Transcript::new(b"crescent aggregated rangeproof");
+        add_to_transcript(&mut ts, b"k", &k);
+        for com_f_j in &self.com_f {
+            add_to_transcript(&mut ts, b"com_f", com_f_j);
+        }
+        add_to_transcript(&mut ts, b"com_g", &self.com_g);
+
+        let c = ts.challenge_scalar::<E::ScalarField>(b"rangeproof challenge c");
+
+        add_to_transcript(&mut ts, b"com_q", &self.com_q);
+        let rho = ts.challenge_scalar::<E::ScalarField>(b"rangeproof challenge rho");
+
+        let q_coeff = rho.pow([kn as u64]) - E::ScalarField::one();
+
+        let mut com_w_hat_point = self.com_q.0 * q_coeff;
+        let mut c_pow = E::ScalarField::one();
+        for j in 0..k {
+            let r_j = domain.element(j * n);
+            let f_coeff_j = c_pow * q_coeff / (rho - r_j);
+            com_w_hat_point += self.com_f[j].0 * f_coeff_j;
+            c_pow *= c * c;
+        }
+        let com_w_hat: Commitment<E> = Commitment(com_w_hat_point.into());
+
+        let rng = &mut thread_rng();
+        let ret = KZG10::<E, DensePolynomial<E::ScalarField>>::batch_check(
+            &vk.kzg_vk,
+            &[self.com_g, self.com_g, com_w_hat],
+            &[rho, rho * domain.element(1), rho],
+            &[self.eval_g, self.eval_gw, self.eval_w_hat],
+            &[self.proof_g, self.proof_gw, self.proof_w_hat],
+            rng,
+        );
+        match ret {
+            Ok(ret) => {
+                if !ret {
+                    println!("Error verifying aggregated range proof, batch_check failed");
+                    return false;
+                }
+            }
+            Err(ret) => {
+                println!("Error verifying aggregated range proof, batch_check failed with error: {ret:?}");
+                return false;
+            }
+        }
+
+        // recompute the combined evaluation identity from eval_g/eval_gw alone
+        let mut eval_w = -self.eval_w_hat;
+        let mut c_pow = E::ScalarField::one();
+        let mut z_end_rho = E::ScalarField::one();
+        for j in 0..k {
+            let r_j = domain.element(j * n);
+            let s_j = domain.element(j * n + n - 1);
+            z_end_rho *= rho - s_j;
+
+            let eval_w1_partial = self.eval_g * q_coeff / (rho - r_j);
+            eval_w += c_pow * eval_w1_partial;
+            c_pow *= c;
+
+            let eval_w2 = self.eval_g
+                * (E::ScalarField::one() - self.eval_g)
+                * q_coeff
+                / (rho - s_j);
+            eval_w += c_pow * eval_w2;
+            c_pow *= c;
+        }
+        let eval_w3 = (self.eval_g - self.eval_gw.double())
+            * (E::ScalarField::one() - self.eval_g + self.eval_gw.double())
+            * z_end_rho;
+        eval_w += c_pow * eval_w3;
+
+        if !eval_w.is_zero() {
+            println!("Aggregated range proof failed to verify, eval_w is not zero");
+            return false;
+        }
+
+        let mut dleq_y = Vec::with_capacity(2 * k);
+        let mut dleq_bases = Vec::with_capacity(2 * k);
+        for (j, &ped_com) in ped_coms.iter().enumerate() {
+            dleq_y.push(ped_com);
+            dleq_bases.push(bases[j].to_vec());
+        }
+        for com_f_j in &self.com_f {
+            dleq_y.push(com_f_j.0.into());
+            dleq_bases.push(vk.com_f_basis.to_vec());
+        }
+        let eq_classes = (0..k).map(|j| vec![(j, 0), (k + j, 3)]).collect::<Vec<_>>();
+
+        self.dleq_proof
+            .verify(None, &dleq_bases, &dleq_y, Some(eq_classes))
+    }
+}
 
 #[cfg(test)]
 mod tests {
@@ -0,0 +1,173 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT license.
+
+// A compact revocation-checking structure, following the CRLite filter
+// cascade design: a sequence of salted Bloom filters that alternate between
+// encoding the revoked set and the false positives the previous level
+// produced against the complementary (valid) set. Level 0 is built over the
+// full revoked set `R`; any element of the valid set `S` that false-positives
+// against level 0 is collected and used to build level 1; any element of `R`
+// that false-positives against level 1 feeds level 2; and so on until a level
+// produces no false positives. Membership queries alternate through the
+// levels until one of them reports absence, giving O(levels) lookups. Each
+// level's filter takes `BITS_PER_ELEMENT` bits per id it encodes, rounded up
+// to a power of two (level 0 for a million revocations is 2 MiB).
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+const NUM_HASHES: usize = 2;
+const BITS_PER_ELEMENT: usize = 10;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct BloomFilter {
+    bits: Vec<u64>,
+    num_bits: usize,
+    salt: u64,
+}
+
+impl BloomFilter {
+    fn new(num_bits: usize, salt: u64) -> Self {
+        let num_bits = num_bits.max(64);
+        let num_words = num_bits.div_ceil(64);
+        BloomFilter { bits: vec![0u64; num_words], num_bits, salt }
+    }
+
+    fn hash_positions(&self, id: &str) -> [usize; NUM_HASHES] {
+        let mut positions = [0usize; NUM_HASHES];
+        for (i, pos) in positions.iter_mut().enumerate() {
+            let mut hasher = Sha256::new();
+            hasher.update(self.salt.to_le_bytes());
+            hasher.update((i as u64).to_le_bytes());
+            hasher.update(id.as_bytes());
+            let digest = hasher.finalize();
+            let idx = u64::from_le_bytes(digest[0..8].try_into().unwrap());
+            *pos = (idx % self.num_bits as u64) as usize;
+        }
+        positions
+    }
+
+    fn insert(&mut self, id: &str) {
+        for pos in self.hash_positions(id) {
+            self.bits[pos / 64] |= 1 << (pos % 64);
+        }
+    }
+
+    fn contains(&self, id: &str) -> bool {
+        self.hash_positions(id)
+            .iter()
+            .all(|&pos| self.bits[pos / 64] & (1 << (pos % 64)) != 0)
+    }
+}
+
+fn bloom_size_bits(num_elements: usize) -> usize {
+    (num_elements.max(1) * BITS_PER_ELEMENT).next_power_of_two()
+}
+
+/// A CRLite-style filter cascade over string-valued credential ids (e.g. the
+/// client helper's `cred_uid`, or an issuer-assigned credential serial
+/// number).
+#[derive(Clone, Debug, Serialize, Deserialize, Default)]
+pub struct FilterCascade {
+    levels: Vec<BloomFilter>,
+}
+
+impl FilterCascade {
+    /// Builds a cascade encoding `revoked` against the currently-valid set
+    /// `valid`. Levels alternate parity: even levels (0, 2, ..) are built
+    /// over a revoked-id set, odd levels over a valid-id set.
+    pub fn build(revoked: &[String], valid: &[String]) -> Self {
+        let mut levels = Vec::new();
+        let mut revoked_ambiguous = revoked.to_vec();
+        let mut valid_ambiguous = valid.to_vec();
+        let mut salt = 0u64;
+
+        loop {
+            let building_from_revoked = levels.len() % 2 == 0;
+            let this_level_set = if building_from_revoked { &revoked_ambiguous } else { &valid_ambiguous };
+            if this_level_set.is_empty() {
+                break;
+            }
+
+            let mut filter = BloomFilter::new(bloom_size_bits(this_level_set.len()), salt);
+            salt += 1;
+            for id in this_level_set.iter() {
+                filter.insert(id);
+            }
+
+            let other_set = if building_from_revoked { &valid_ambiguous } else { &revoked_ambiguous };
+            let false_positives: Vec<String> = other_set.iter().filter(|id| filter.contains(id)).cloned().collect();
+            levels.push(filter);
+
+            if false_positives.is_empty() {
+                break;
+            }
+            if building_from_revoked {
+                valid_ambiguous = false_positives;
+            } else {
+                revoked_ambiguous = false_positives;
+            }
+        }
+
+        FilterCascade { levels }
+    }
+
+    /// Returns `true` if `id` is revoked. Tests each level in turn: an
+    /// absence at an even (revoked-set) level means `id` was never in the
+    /// revoked set, so it is not revoked; an absence at an odd (valid-set)
+    /// level means `id` is not an innocent false positive, so it is revoked.
+    /// If `id` matches every level, it belongs to whichever set built the
+    /// final level (the cascade only terminates once that level has no
+    /// remaining ambiguity against the other side).
+    pub fn check(&self, id: &str) -> bool {
+        for (level, filter) in self.levels.iter().enumerate() {
+            if !filter.contains(id) {
+                return level % 2 != 0;
+            }
+        }
+        self.levels.len() % 2 != 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cascade_basic_membership() {
+        let revoked: Vec<String> = (0..50).map(|i| format!("revoked-{}", i)).collect();
+        let valid: Vec<String> = (0..500).map(|i| format!("valid-{}", i)).collect();
+
+        let cascade = FilterCascade::build(&revoked, &valid);
+
+        for id in &revoked {
+            assert!(cascade.check(id), "expected {} to be reported revoked", id);
+        }
+        for id in &valid {
+            assert!(!cascade.check(id), "expected {} to be reported valid", id);
+        }
+    }
+
+    #[test]
+    fn test_cascade_unknown_id() {
+        let revoked: Vec<String> = (0..10).map(|i| format!("revoked-{}", i)).collect();
+        let valid: Vec<String> = (0..10).map(|i| format!("valid-{}", i)).collect();
+        let cascade = FilterCascade::build(&revoked, &valid);
+
+        // An id that was never part of either set should (with overwhelming
+        // probability) be reported as not revoked.
+        assert!(!cascade.check("never-seen-before"));
+    }
+
+    #[test]
+    fn test_cascade_empty_revoked_set() {
+        let revoked: Vec<String> = vec![];
+        let valid: Vec<String> = (0..20).map(|i| format!("valid-{}", i)).collect();
+        let cascade = FilterCascade::build(&revoked, &valid);
+
+        assert!(cascade.levels.is_empty());
+        for id in &valid {
+            assert!(!cascade.check(id));
+        }
+    }
+}
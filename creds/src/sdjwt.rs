@@ -0,0 +1,227 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT license.
+
+// Renders a show proof's revealed/hashed attribute split -- the same
+// revealed/hashed partition `prep_inputs::create_proof_spec_internal` builds
+// from a `ProofSpec` -- as an SD-JWT-compatible presentation, so consumers
+// that already speak the SD-JWT selective-disclosure format
+// (https://www.ietf.org/archive/id/draft-ietf-oauth-sd-jwt-vc/) can accept
+// one. `hashed` attributes become salted disclosures digested into `_sd`;
+// `revealed` attributes are assumed to already be plaintext claims in
+// `issuer_jwt`. When the proof is device bound, a caller-supplied Key
+// Binding JWT (carrying `presentation_message` as its `nonce`) is appended
+// as the final `~`-separated segment.
+
+use std::collections::HashMap;
+use std::error::Error;
+
+use ark_std::rand::{thread_rng, RngCore};
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256};
+
+use crate::prep_inputs::{collect_sd_digests, resolve_sd_claims};
+use crate::return_error;
+use crate::ProofSpecInternal;
+use crate::DeviceBindingMode;
+
+/// A single salted disclosure, `[salt, name, value]` (SD-JWT section 5.2.1).
+pub struct Disclosure {
+    pub salt: String,
+    pub name: String,
+    pub value: Value,
+}
+
+impl Disclosure {
+    fn random_salt() -> String {
+        let mut bytes = [0u8; 16];
+        thread_rng().fill_bytes(&mut bytes);
+        base64_url::encode(&bytes)
+    }
+
+    pub fn new(name: String, value: Value) -> Self {
+        Self { salt: Self::random_salt(), name, value }
+    }
+
+    /// `base64url(JSON([salt, name, value]))`: the wire form of the
+    /// disclosure, both as it appears in the compact `~`-joined presentation
+    /// and as the preimage of the digest placed in `_sd`.
+    pub fn encode(&self) -> String {
+        base64_url::encode(&serde_json::to_vec(&Value::Array(vec![
+            Value::String(self.salt.clone()),
+            Value::String(self.name.clone()),
+            self.value.clone(),
+        ])).unwrap())
+    }
+
+    pub fn digest(&self) -> String {
+        base64_url::encode(Sha256::digest(self.encode().as_bytes()).as_slice())
+    }
+}
+
+fn decode_jwt_payload(jwt: &str) -> Result<Value, Box<dyn Error>> {
+    let payload_b64 = jwt.split('.').nth(1).ok_or("JWT is missing its payload segment")?;
+    Ok(serde_json::from_slice(&base64_url::decode(payload_b64)?)?)
+}
+
+/// Builds the `<issuer-jwt>~<disclosure>~...~<kb-jwt>` presentation for
+/// `proof_spec.hashed` attributes taken from `claim_values`. `issuer_jwt` is
+/// expected to already carry `proof_spec.revealed` as plaintext claims and
+/// `proof_spec.hashed` as `_sd` digests (i.e. it is the SD-JWT this crate's
+/// `credtype: "jwt-sd"` ingestion path already knows how to parse).
+pub fn create_presentation(
+    proof_spec: &ProofSpecInternal,
+    claim_values: &Map<String, Value>,
+    issuer_jwt: &str,
+    kb_jwt: Option<&str>,
+) -> Result<String, Box<dyn Error>> {
+    if proof_spec.device_bound && kb_jwt.is_none() {
+        return_error!("Proof spec is device bound, but no Key Binding JWT was supplied");
+    }
+    if !proof_spec.device_bound && kb_jwt.is_some() {
+        return_error!("A Key Binding JWT was supplied, but the proof spec is not device bound");
+    }
+
+    let mut parts = vec![issuer_jwt.to_string()];
+    for name in &proof_spec.hashed {
+        let value = claim_values.get(name).ok_or(format!("Attribute {} is not present in claim_values", name))?.clone();
+        parts.push(Disclosure::new(name.clone(), value).encode());
+    }
+    parts.push(kb_jwt.unwrap_or("").to_string());
+
+    Ok(parts.join("~"))
+}
+
+/// Parses and verifies a presentation built by [`create_presentation`],
+/// returning the fully-resolved claim set (revealed claims plus disclosed
+/// `hashed` attributes). Rejects a presentation that discloses an attribute
+/// `proof_spec` did not ask for, omits one it did, or -- when device
+/// bound -- whose Key Binding JWT's `nonce` does not match
+/// `proof_spec.presentation_message`.
+pub fn verify_presentation(presentation: &str, proof_spec: &ProofSpecInternal) -> Result<Map<String, Value>, Box<dyn Error>> {
+    let mut segments = presentation.split('~');
+    let issuer_jwt = segments.next().ok_or("Presentation is missing the issuer JWT segment")?;
+    let mut claims = decode_jwt_payload(issuer_jwt)?;
+    let sd_digests = collect_sd_digests(&claims);
+
+    let remaining: Vec<&str> = segments.collect();
+    let (disclosure_segments, kb_jwt) = match proof_spec.device_bound {
+        true => {
+            let (last, rest) = remaining.split_last().ok_or("Device-bound presentation is missing its Key Binding JWT segment")?;
+            (rest.to_vec(), Some(*last))
+        }
+        false => (remaining, None),
+    };
+
+    let mut object_disclosures: HashMap<String, (String, Value)> = HashMap::new();
+    let mut disclosed_names = vec![];
+    for disclosure_b64 in &disclosure_segments {
+        if disclosure_b64.is_empty() {
+            continue;
+        }
+        let decoded: Value = serde_json::from_slice(&base64_url::decode(disclosure_b64)?)?;
+        let triple = decoded.as_array().filter(|a| a.len() == 3).ok_or("Disclosure is not a [salt, name, value] triple")?;
+        let name = triple[1].as_str().ok_or("Disclosure name is not a string")?.to_string();
+        let digest = base64_url::encode(Sha256::digest(disclosure_b64.as_bytes()).as_slice());
+        if !sd_digests.contains(&digest) {
+            return_error!(format!("Disclosure for {} does not match any digest committed to by the issuer JWT", name));
+        }
+        disclosed_names.push(name.clone());
+        object_disclosures.insert(digest, (name, triple[2].clone()));
+    }
+
+    if disclosed_names.len() != proof_spec.hashed.len() || !proof_spec.hashed.iter().all(|n| disclosed_names.contains(n)) {
+        return_error!("Presentation discloses a different set of attributes than the proof spec requires");
+    }
+
+    resolve_sd_claims(&mut claims, &object_disclosures, &HashMap::new());
+
+    if let Some(kb_jwt) = kb_jwt {
+        let kb_payload = decode_jwt_payload(kb_jwt)?;
+        let nonce = kb_payload.get("nonce").and_then(|v| v.as_str()).ok_or("Key Binding JWT is missing its 'nonce' claim")?;
+        let expected_nonce = base64_url::encode(proof_spec.presentation_message.as_deref().unwrap_or(&[]));
+        if nonce != expected_nonce {
+            return_error!("Key Binding JWT's nonce does not match the proof's presentation message");
+        }
+    }
+
+    claims.as_object().cloned().ok_or_else(|| "Issuer JWT payload is not a JSON object".into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn sample_issuer_jwt(sd_digest: &str) -> String {
+        let header = base64_url::encode(b"{\"alg\":\"none\"}");
+        let payload = base64_url::encode(
+            format!(r#"{{"iss":"issuer","_sd":["{}"],"_sd_alg":"sha-256"}}"#, sd_digest).as_bytes(),
+        );
+        format!("{}.{}.", header, payload)
+    }
+
+    fn sample_proof_spec(hashed: Vec<String>, device_bound: bool, presentation_message: Option<Vec<u8>>) -> ProofSpecInternal {
+        ProofSpecInternal {
+            revealed: vec![],
+            range_checks: vec![],
+            hashed,
+            presentation_message,
+            device_bound,
+            device_binding: DeviceBindingMode::RawEcdsa,
+            device_public_key: None,
+            device_up_required: true,
+            device_uv_required: false,
+            device_rp_id_hash: None,
+            sig_alg: crate::prep_inputs::SigAlg::RS256,
+            audience: None,
+            nonce: None,
+            not_after: None,
+            config_str: "{}".to_string(),
+            claim_types: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_create_and_verify_presentation_roundtrip() {
+        let disclosure = Disclosure::new("given_name".to_string(), json!("John"));
+        let digest = disclosure.digest();
+        let issuer_jwt = sample_issuer_jwt(&digest);
+
+        let proof_spec = sample_proof_spec(vec!["given_name".to_string()], false, None);
+        let mut claim_values = Map::new();
+        claim_values.insert("given_name".to_string(), json!("John"));
+
+        // Build the presentation independently of `create_presentation` so the
+        // disclosure's salt (and thus its digest) matches what's embedded in
+        // `issuer_jwt` above.
+        let presentation = format!("{}~{}~", issuer_jwt, disclosure.encode());
+
+        let resolved = verify_presentation(&presentation, &proof_spec).unwrap();
+        assert_eq!(resolved["given_name"], json!("John"));
+        assert!(resolved.get("_sd").is_none());
+    }
+
+    #[test]
+    fn test_verify_presentation_rejects_undisclosed_required_attribute() {
+        let disclosure = Disclosure::new("given_name".to_string(), json!("John"));
+        let issuer_jwt = sample_issuer_jwt(&disclosure.digest());
+        let proof_spec = sample_proof_spec(vec!["given_name".to_string(), "family_name".to_string()], false, None);
+
+        let presentation = format!("{}~{}~", issuer_jwt, disclosure.encode());
+        assert!(verify_presentation(&presentation, &proof_spec).is_err());
+    }
+
+    #[test]
+    fn test_verify_presentation_rejects_kb_jwt_nonce_mismatch() {
+        let disclosure = Disclosure::new("given_name".to_string(), json!("John"));
+        let issuer_jwt = sample_issuer_jwt(&disclosure.digest());
+        let proof_spec = sample_proof_spec(vec!["given_name".to_string()], true, Some(b"expected-challenge".to_vec()));
+
+        let kb_header = base64_url::encode(b"{\"alg\":\"none\"}");
+        let kb_payload = base64_url::encode(br#"{"nonce":"d3JvbmctY2hhbGxlbmdl"}"#);
+        let kb_jwt = format!("{}.{}.", kb_header, kb_payload);
+
+        let presentation = format!("{}~{}~{}", issuer_jwt, disclosure.encode(), kb_jwt);
+        assert!(verify_presentation(&presentation, &proof_spec).is_err());
+    }
+}
@@ -0,0 +1,66 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT license.
+
+// Generic `serde` support for the arkworks `CanonicalSerialize`/
+// `CanonicalDeserialize` types used throughout this crate (group elements,
+// Groth16 keys, KZG commitments, ...), so the optional JSON wire format can
+// sit alongside the existing binary/b64url-blob format (`write_to_b64url`,
+// `read_from_b64url`) without hand-writing a `serde` impl for every
+// pairing-curve type. Apply via `#[serde(with = "serde_support")]` on a
+// struct field: the field is encoded as its own base64url string within the
+// surrounding JSON document, rather than flattening the whole document into
+// one opaque blob, so a non-Rust verifier can at least see the document's
+// shape even though each crypto field's contents remain an ark canonical
+// encoding.
+
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use serde::{de::Error as _, ser::Error as _, Deserialize, Deserializer, Serialize, Serializer};
+
+pub fn serialize<S, T>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+    T: CanonicalSerialize,
+{
+    let mut buf = Vec::new();
+    value.serialize_uncompressed(&mut buf).map_err(S::Error::custom)?;
+    base64_url::encode(&buf).serialize(serializer)
+}
+
+pub fn deserialize<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: CanonicalDeserialize,
+{
+    let s = String::deserialize(deserializer)?;
+    let buf = base64_url::decode(&s).map_err(D::Error::custom)?;
+    T::deserialize_uncompressed_unchecked(buf.as_slice()).map_err(D::Error::custom)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_bn254::Bn254;
+    use ark_ec::pairing::Pairing;
+    use ark_std::UniformRand;
+    use serde::{Deserialize, Serialize};
+
+    type E = Bn254;
+    type G1 = <E as Pairing>::G1;
+
+    #[derive(Serialize, Deserialize)]
+    struct Wrapper {
+        #[serde(with = "crate::serde_support")]
+        point: G1,
+    }
+
+    #[test]
+    fn test_roundtrip_through_json() {
+        let mut rng = ark_std::rand::thread_rng();
+        let point = G1::rand(&mut rng);
+        let wrapper = Wrapper { point };
+
+        let json = serde_json::to_string(&wrapper).unwrap();
+        let back: Wrapper = serde_json::from_str(&json).unwrap();
+        assert_eq!(wrapper.point, back.point);
+    }
+}
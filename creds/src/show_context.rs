@@ -0,0 +1,144 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT license.
+
+// Computes the digest `create_show_proof`/`create_show_proof_mdl` bind a show
+// proof to, and `verify_show`/`verify_show_mdl` recompute to check it. Built
+// as a tree of length-prefixed, domain-separated SHA256 hashes over the
+// individual `ProofSpecInternal` fields (plus the current time), rather than
+// over `serde_json::to_string(&proof_spec)`: the JSON string depends on
+// serde's field/key ordering and round-tripping, and mixes disclosure policy
+// with replay-protection material. Sections are built from a canonical
+// form of each collection (revealed/hashed sorted by IO location, range
+// checks by attribute name), so the digest is the same regardless of the
+// order the caller listed attributes in.
+
+use sha2::{Digest, Sha256};
+
+use crate::structs::IOLocations;
+use crate::{sort_by_io_location, DeviceBindingMode, ProofSpecInternal, RangeBound};
+
+const LABEL_TOP: &[u8] = b"crescent show context v1";
+const LABEL_REVEALED: &[u8] = b"crescent show context v1 / revealed";
+const LABEL_HASHED: &[u8] = b"crescent show context v1 / hashed";
+const LABEL_RANGE_CHECKS: &[u8] = b"crescent show context v1 / range checks";
+const LABEL_PRESENTATION_MESSAGE: &[u8] = b"crescent show context v1 / presentation message";
+const LABEL_DEVICE_BINDING: &[u8] = b"crescent show context v1 / device binding";
+const LABEL_AUDIENCE: &[u8] = b"crescent show context v1 / audience";
+const LABEL_NONCE: &[u8] = b"crescent show context v1 / nonce";
+const LABEL_NOT_AFTER: &[u8] = b"crescent show context v1 / not after";
+const LABEL_CUR_TIME: &[u8] = b"crescent show context v1 / cur time";
+const LABEL_CONFIG: &[u8] = b"crescent show context v1 / config";
+
+/// Hashes `bytes` under a distinct, length-prefixed label, so that e.g. an
+/// empty `hashed` section can never collide with an empty `revealed`
+/// section, and a section boundary can't be shifted by choosing adversarial
+/// field contents.
+fn section_digest(label: &'static [u8], bytes: &[u8]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(label);
+    hasher.update((bytes.len() as u64).to_le_bytes());
+    hasher.update(bytes);
+    hasher.finalize().into()
+}
+
+/// Encodes a list of strings as the concatenation of each entry's own
+/// length-prefixed bytes, so `["ab", "c"]` and `["a", "bc"]` never encode to
+/// the same byte string.
+fn encode_strings(strings: &[String]) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    for s in strings {
+        bytes.extend_from_slice(&(s.len() as u64).to_le_bytes());
+        bytes.extend_from_slice(s.as_bytes());
+    }
+    bytes
+}
+
+/// Encodes the resolved range checks, sorted by attribute name so the
+/// encoding doesn't depend on the order `range_over_year`/`predicates` were
+/// listed in.
+fn encode_range_checks(range_checks: &[(String, RangeBound)]) -> Vec<u8> {
+    let mut entries: Vec<&(String, RangeBound)> = range_checks.iter().collect();
+    entries.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let mut bytes = Vec::new();
+    for (attr, bound) in entries {
+        bytes.extend_from_slice(&(attr.len() as u64).to_le_bytes());
+        bytes.extend_from_slice(attr.as_bytes());
+        match bound {
+            RangeBound::GreaterThanOrEqual(v) => {
+                bytes.push(0);
+                bytes.extend_from_slice(&v.to_le_bytes());
+            }
+            RangeBound::LessThanOrEqual(v) => {
+                bytes.push(1);
+                bytes.extend_from_slice(&v.to_le_bytes());
+            }
+            RangeBound::Between(lo, hi) => {
+                bytes.push(2);
+                bytes.extend_from_slice(&lo.to_le_bytes());
+                bytes.extend_from_slice(&hi.to_le_bytes());
+            }
+        }
+    }
+    bytes
+}
+
+/// Encodes an optional byte string as a presence tag followed by the bytes,
+/// so `None` and `Some(b"")` never encode to the same byte string.
+fn encode_optional_bytes(bytes: Option<&[u8]>) -> Vec<u8> {
+    match bytes {
+        Some(b) => [&[1u8][..], b].concat(),
+        None => vec![0u8],
+    }
+}
+
+/// Computes the 32-byte context digest a show proof is bound to, from the
+/// resolved proof spec and the proving/verification time.
+pub(crate) fn compute(proof_spec: &ProofSpecInternal, io_locations: &IOLocations, cur_time: u64) -> [u8; 32] {
+    let revealed = sort_by_io_location(&proof_spec.revealed, io_locations, "_value");
+    let revealed_digest = section_digest(LABEL_REVEALED, &encode_strings(&revealed));
+
+    let hashed = sort_by_io_location(&proof_spec.hashed, io_locations, "_digest");
+    let hashed_digest = section_digest(LABEL_HASHED, &encode_strings(&hashed));
+
+    let range_digest = section_digest(LABEL_RANGE_CHECKS, &encode_range_checks(&proof_spec.range_checks));
+
+    let presentation_message_bytes = encode_optional_bytes(proof_spec.presentation_message.as_deref());
+    let presentation_message_digest = section_digest(LABEL_PRESENTATION_MESSAGE, &presentation_message_bytes);
+
+    let device_binding_byte = match (proof_spec.device_bound, proof_spec.device_binding) {
+        (false, _) => 0u8,
+        (true, DeviceBindingMode::RawEcdsa) => 1u8,
+        (true, DeviceBindingMode::WebAuthn) => 2u8,
+    };
+    let device_digest = section_digest(LABEL_DEVICE_BINDING, &[device_binding_byte]);
+
+    let audience_bytes = encode_optional_bytes(proof_spec.audience.as_deref().map(str::as_bytes));
+    let audience_digest = section_digest(LABEL_AUDIENCE, &audience_bytes);
+
+    let nonce_bytes = encode_optional_bytes(proof_spec.nonce.as_deref());
+    let nonce_digest = section_digest(LABEL_NONCE, &nonce_bytes);
+
+    let not_after_le = proof_spec.not_after.map(|t| t.to_le_bytes());
+    let not_after_bytes = encode_optional_bytes(not_after_le.as_ref().map(|b| &b[..]));
+    let not_after_digest = section_digest(LABEL_NOT_AFTER, &not_after_bytes);
+
+    let cur_time_digest = section_digest(LABEL_CUR_TIME, &cur_time.to_le_bytes());
+
+    let config_hash = Sha256::digest(proof_spec.config_str.as_bytes());
+    let config_digest = section_digest(LABEL_CONFIG, &config_hash);
+
+    let mut top = Sha256::new();
+    top.update(LABEL_TOP);
+    top.update(revealed_digest);
+    top.update(hashed_digest);
+    top.update(range_digest);
+    top.update(presentation_message_digest);
+    top.update(device_digest);
+    top.update(audience_digest);
+    top.update(nonce_digest);
+    top.update(not_after_digest);
+    top.update(cur_time_digest);
+    top.update(config_digest);
+    top.finalize().into()
+}
@@ -0,0 +1,279 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT license.
+
+// Converts between arkworks' `Proof<Bn254>`/`VerifyingKey<Bn254>` and the
+// JSON triad the wider circom/snarkjs toolchain reads and writes
+// (`proof.json`, `public.json`, `verification_key.json`), so a proof
+// produced by this crate's Groth16 setup can be handed to `snarkjs verify`
+// (or vice versa) without a from-scratch reimplementation on either side.
+//
+// snarkjs encodes every field element as a decimal string rather than raw
+// bytes, and encodes a G2 point's `Fq2` coordinates as `[c0, c1]` pairs in
+// the same component order arkworks' `Fq2 { c0, c1 }` uses -- so, unlike a
+// byte-serialized format, no endianness or component-swap is needed, only
+// decimal-string parsing.
+
+use std::error::Error;
+
+use ark_bn254::{Bn254, Fq, Fq2, Fr, G1Affine, G2Affine};
+use ark_ec::AffineRepr;
+use ark_groth16::{Proof, VerifyingKey};
+use num_bigint::BigUint;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+use crate::return_error;
+use crate::structs::IOLocations;
+use crate::utils::biguint_to_scalar;
+
+fn fq_to_decimal(f: &Fq) -> String {
+    let v: BigUint = (*f).into();
+    v.to_string()
+}
+
+fn fq_from_decimal(s: &str) -> Result<Fq, Box<dyn Error>> {
+    let v = BigUint::parse_bytes(s.as_bytes(), 10).ok_or(format!("{} is not a valid decimal field element", s))?;
+    Ok(biguint_to_scalar(&v))
+}
+
+fn fr_to_decimal(f: &Fr) -> String {
+    let v: BigUint = (*f).into();
+    v.to_string()
+}
+
+fn fr_from_decimal(s: &str) -> Result<Fr, Box<dyn Error>> {
+    let v = BigUint::parse_bytes(s.as_bytes(), 10).ok_or(format!("{} is not a valid decimal field element", s))?;
+    Ok(biguint_to_scalar(&v))
+}
+
+fn g1_to_snarkjs(p: &G1Affine) -> [String; 3] {
+    let (x, y) = p.xy().unwrap();
+    [fq_to_decimal(&x), fq_to_decimal(&y), "1".to_string()]
+}
+
+fn g1_from_snarkjs(p: &[String; 3]) -> Result<G1Affine, Box<dyn Error>> {
+    let x = fq_from_decimal(&p[0])?;
+    let y = fq_from_decimal(&p[1])?;
+    let point = G1Affine::new_unchecked(x, y);
+    if !point.is_on_curve() {
+        return_error!("snarkjs G1 point is not on curve");
+    }
+    Ok(point)
+}
+
+fn g2_to_snarkjs(p: &G2Affine) -> [[String; 2]; 3] {
+    let (x, y) = p.xy().unwrap();
+    [
+        [fq_to_decimal(&x.c0), fq_to_decimal(&x.c1)],
+        [fq_to_decimal(&y.c0), fq_to_decimal(&y.c1)],
+        ["1".to_string(), "0".to_string()],
+    ]
+}
+
+fn g2_from_snarkjs(p: &[[String; 2]; 3]) -> Result<G2Affine, Box<dyn Error>> {
+    let x = Fq2::new(fq_from_decimal(&p[0][0])?, fq_from_decimal(&p[0][1])?);
+    let y = Fq2::new(fq_from_decimal(&p[1][0])?, fq_from_decimal(&p[1][1])?);
+    let point = G2Affine::new_unchecked(x, y);
+    if !point.is_on_curve() {
+        return_error!("snarkjs G2 point is not on curve");
+    }
+    Ok(point)
+}
+
+/// Mirrors snarkjs's `proof.json`.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SnarkjsProof {
+    pub pi_a: [String; 3],
+    pub pi_b: [[String; 2]; 3],
+    pub pi_c: [String; 3],
+    pub protocol: String,
+    pub curve: String,
+}
+
+/// Mirrors snarkjs's `verification_key.json`.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SnarkjsVerificationKey {
+    pub protocol: String,
+    pub curve: String,
+    #[serde(rename = "nPublic")]
+    pub n_public: usize,
+    pub vk_alpha_1: [String; 3],
+    pub vk_beta_2: [[String; 2]; 3],
+    pub vk_gamma_2: [[String; 2]; 3],
+    pub vk_delta_2: [[String; 2]; 3],
+    #[serde(rename = "IC")]
+    pub ic: Vec<[String; 3]>,
+}
+
+/// Converts a Groth16 proof into snarkjs's `proof.json` shape.
+pub fn to_snarkjs_proof(proof: &Proof<Bn254>) -> SnarkjsProof {
+    SnarkjsProof {
+        pi_a: g1_to_snarkjs(&proof.a),
+        pi_b: g2_to_snarkjs(&proof.b),
+        pi_c: g1_to_snarkjs(&proof.c),
+        protocol: "groth16".to_string(),
+        curve: "bn128".to_string(),
+    }
+}
+
+/// Parses a snarkjs `proof.json` document into a Groth16 proof.
+pub fn from_snarkjs_proof(proof: &SnarkjsProof) -> Result<Proof<Bn254>, Box<dyn Error>> {
+    if proof.protocol != "groth16" {
+        return_error!(format!("Unsupported snarkjs protocol '{}', expected 'groth16'", proof.protocol));
+    }
+    if proof.curve != "bn128" {
+        return_error!(format!("Unsupported snarkjs curve '{}', expected 'bn128'", proof.curve));
+    }
+    Ok(Proof {
+        a: g1_from_snarkjs(&proof.pi_a)?,
+        b: g2_from_snarkjs(&proof.pi_b)?,
+        c: g1_from_snarkjs(&proof.pi_c)?,
+    })
+}
+
+/// Converts a Groth16 verifying key into snarkjs's `verification_key.json` shape.
+pub fn to_snarkjs_verification_key(vk: &VerifyingKey<Bn254>) -> SnarkjsVerificationKey {
+    SnarkjsVerificationKey {
+        protocol: "groth16".to_string(),
+        curve: "bn128".to_string(),
+        n_public: vk.gamma_abc_g1.len() - 1,
+        vk_alpha_1: g1_to_snarkjs(&vk.alpha_g1),
+        vk_beta_2: g2_to_snarkjs(&vk.beta_g2),
+        vk_gamma_2: g2_to_snarkjs(&vk.gamma_g2),
+        vk_delta_2: g2_to_snarkjs(&vk.delta_g2),
+        ic: vk.gamma_abc_g1.iter().map(g1_to_snarkjs).collect(),
+    }
+}
+
+/// Parses a snarkjs `verification_key.json` document into a Groth16 verifying key.
+pub fn from_snarkjs_verification_key(vk: &SnarkjsVerificationKey) -> Result<VerifyingKey<Bn254>, Box<dyn Error>> {
+    if vk.protocol != "groth16" {
+        return_error!(format!("Unsupported snarkjs protocol '{}', expected 'groth16'", vk.protocol));
+    }
+    if vk.curve != "bn128" {
+        return_error!(format!("Unsupported snarkjs curve '{}', expected 'bn128'", vk.curve));
+    }
+    if vk.ic.len() != vk.n_public + 1 {
+        return_error!(format!("snarkjs verification key declares nPublic={} but has {} IC entries", vk.n_public, vk.ic.len()));
+    }
+    Ok(VerifyingKey {
+        alpha_g1: g1_from_snarkjs(&vk.vk_alpha_1)?,
+        beta_g2: g2_from_snarkjs(&vk.vk_beta_2)?,
+        gamma_g2: g2_from_snarkjs(&vk.vk_gamma_2)?,
+        delta_g2: g2_from_snarkjs(&vk.vk_delta_2)?,
+        gamma_abc_g1: vk.ic.iter().map(g1_from_snarkjs).collect::<Result<Vec<_>, _>>()?,
+    })
+}
+
+/// Converts the `public.json` array of decimal-string field elements into a
+/// `BigUint` per public signal.
+pub fn from_snarkjs_public(public: &[String]) -> Result<Vec<BigUint>, Box<dyn Error>> {
+    public.iter()
+        .map(|s| BigUint::parse_bytes(s.as_bytes(), 10).ok_or_else(|| format!("{} is not a valid decimal field element", s).into()))
+        .collect()
+}
+
+/// Converts named public inputs into the ordered `public.json` vector
+/// snarkjs expects, using `io_locations` to translate a name into its
+/// witness position. `io_locations` positions are 1-indexed relative to the
+/// constant `one` wire (position 0), which snarkjs's `public.json` omits,
+/// so position `p` lands at `public.json[p - 1]`.
+pub fn to_snarkjs_public(named_inputs: &BTreeMap<String, BigUint>, io_locations: &IOLocations) -> Result<Vec<String>, Box<dyn Error>> {
+    // `public.json` only covers positions 1..=max (the constant `one` wire
+    // at position 0 is never part of it), so its length is the highest
+    // position `io_locations` declares, not the number of declared names.
+    let n = io_locations.public_io_locations.values().copied().max().unwrap_or(0);
+    let mut public = vec![None; n];
+    for (name, value) in named_inputs {
+        let pos = io_locations.get_io_location(name).map_err(|_| format!("{} not found in io_locations", name))?;
+        if pos == 0 || pos - 1 >= public.len() {
+            return_error!(format!("io_locations position {} for {} is out of range for public.json", pos, name));
+        }
+        public[pos - 1] = Some(value.to_string());
+    }
+    public.into_iter().enumerate()
+        .map(|(i, v)| v.ok_or_else(|| format!("No named input supplied for public.json position {}", i + 1).into()))
+        .collect()
+}
+
+/// Parses a `serde_json::Value` holding a snarkjs `public.json` array.
+pub fn public_json_from_value(value: &Value) -> Result<Vec<String>, Box<dyn Error>> {
+    value.as_array()
+        .ok_or("public.json is not a JSON array")?
+        .iter()
+        .map(|v| v.as_str().map(String::from).ok_or_else(|| "public.json entry is not a string".into()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_ec::CurveGroup;
+    use ark_std::{rand::thread_rng, UniformRand};
+
+    fn sample_vk() -> VerifyingKey<Bn254> {
+        let mut rng = thread_rng();
+        VerifyingKey {
+            alpha_g1: (G1Affine::generator() * Fr::rand(&mut rng)).into_affine(),
+            beta_g2: (G2Affine::generator() * Fr::rand(&mut rng)).into_affine(),
+            gamma_g2: (G2Affine::generator() * Fr::rand(&mut rng)).into_affine(),
+            delta_g2: (G2Affine::generator() * Fr::rand(&mut rng)).into_affine(),
+            gamma_abc_g1: vec![
+                (G1Affine::generator() * Fr::rand(&mut rng)).into_affine(),
+                (G1Affine::generator() * Fr::rand(&mut rng)).into_affine(),
+            ],
+        }
+    }
+
+    fn sample_proof() -> Proof<Bn254> {
+        let mut rng = thread_rng();
+        Proof {
+            a: (G1Affine::generator() * Fr::rand(&mut rng)).into_affine(),
+            b: (G2Affine::generator() * Fr::rand(&mut rng)).into_affine(),
+            c: (G1Affine::generator() * Fr::rand(&mut rng)).into_affine(),
+        }
+    }
+
+    #[test]
+    fn test_proof_roundtrip_through_snarkjs_json() {
+        let proof = sample_proof();
+        let snarkjs = to_snarkjs_proof(&proof);
+        let json = serde_json::to_string(&snarkjs).unwrap();
+        let parsed: SnarkjsProof = serde_json::from_str(&json).unwrap();
+        let proof2 = from_snarkjs_proof(&parsed).unwrap();
+
+        assert_eq!(proof.a, proof2.a);
+        assert_eq!(proof.b, proof2.b);
+        assert_eq!(proof.c, proof2.c);
+    }
+
+    #[test]
+    fn test_verification_key_roundtrip_through_snarkjs_json() {
+        let vk = sample_vk();
+        let snarkjs = to_snarkjs_verification_key(&vk);
+        let json = serde_json::to_string(&snarkjs).unwrap();
+        let parsed: SnarkjsVerificationKey = serde_json::from_str(&json).unwrap();
+        let vk2 = from_snarkjs_verification_key(&parsed).unwrap();
+
+        assert_eq!(vk.alpha_g1, vk2.alpha_g1);
+        assert_eq!(vk.beta_g2, vk2.beta_g2);
+        assert_eq!(vk.gamma_g2, vk2.gamma_g2);
+        assert_eq!(vk.delta_g2, vk2.delta_g2);
+        assert_eq!(vk.gamma_abc_g1, vk2.gamma_abc_g1);
+    }
+
+    #[test]
+    fn test_named_public_inputs_ordered_by_io_location() {
+        let io_locations = IOLocations::new_from_str("one,0\nexp_value,1\npubkey_0,2\n");
+        let mut named = BTreeMap::new();
+        named.insert("exp_value".to_string(), BigUint::from(1700000000u64));
+        named.insert("pubkey_0".to_string(), BigUint::from(42u64));
+
+        let public = to_snarkjs_public(&named, &io_locations).unwrap();
+        assert_eq!(public, vec!["1700000000".to_string(), "42".to_string()]);
+
+        let parsed = from_snarkjs_public(&public).unwrap();
+        assert_eq!(parsed, vec![BigUint::from(1700000000u64), BigUint::from(42u64)]);
+    }
+}
@@ -1,12 +1,15 @@
 // Copyright (c) Microsoft Corporation.
 // Licensed under the MIT license.
 
+use crate::return_error;
 use crate::utils::bigint_from_str;
 use ark_bn254::Bn254 as ECPairing;
 use num_bigint::BigUint;
 use num_traits::FromPrimitive;
 use serde_json::{Map, Value};
+use std::error::Error;
 use std::{collections::BTreeMap, io::ErrorKind};
+use thiserror::Error as ThisError;
 
 #[cfg(not(feature = "wasm"))]
 use ark_circom::CircomBuilder;
@@ -26,16 +29,126 @@ pub struct GenericInputsJSON {
 #[derive(Clone, Debug, Default)]
 pub struct IOLocations {
     pub public_io_locations: BTreeMap<String, usize>,
+    /// Witness index for each signal, populated only when `new_from_str`
+    /// is given circom's native 4-column `.sym` format -- the legacy
+    /// `name,location` CSV has no separate witness-index column.
+    witness_indices: BTreeMap<String, usize>,
+    /// Reverse of `witness_indices`, for `get_name_by_witness`.
+    by_witness_index: BTreeMap<usize, String>,
+    /// circom's component number for each signal, populated alongside
+    /// `witness_indices`.
+    component_numbers: BTreeMap<String, usize>,
 }
 
 /// An enum indication the type of each public io
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum PublicIOType {
     Revealed,
     Hidden,
     Committed,
 }
 
+/// A named-signal disclosure policy: which public-IO signals (keyed by the
+/// same names `IOLocations::get_io_location` accepts) a holder has agreed
+/// to reveal, hide, or commit to when generating a show proof.
+pub type DisclosurePolicy = BTreeMap<String, PublicIOType>;
+
+/// The values a `DisclosurePolicy` discloses, keyed by the same signal
+/// name as the policy: a signal's cleartext value for `Revealed`, or the
+/// value a commitment opening is generated for when `Committed`. `Hidden`
+/// signals have no entry in either map.
+#[derive(Clone, Debug, Default)]
+pub struct DisclosedValues {
+    pub revealed: BTreeMap<String, Vec<BigUint>>,
+    pub committed: BTreeMap<String, Vec<BigUint>>,
+}
+
+/// Looks up the public-IO position(s) `name` occupies, whether it's a
+/// scalar signal or a circom array signal collapsed by
+/// `IOLocations::get_array_locations`.
+fn positions_for(io_locations: &IOLocations, name: &str) -> Result<Vec<usize>, Box<dyn Error>> {
+    match io_locations.get_array_locations(name) {
+        Some(positions) => Ok(positions),
+        None => Ok(vec![io_locations.get_io_location(name)?]),
+    }
+}
+
+/// Reads `name`'s value(s) out of `prover_inputs`, however `GenericInputsJSON`
+/// happens to store them -- a single scalar, or an array.
+fn read_values(prover_inputs: &GenericInputsJSON, name: &str) -> Result<Vec<BigUint>, Box<dyn Error>> {
+    if let Ok(value) = prover_inputs.get(name) {
+        return Ok(vec![value]);
+    }
+    Ok(prover_inputs.get_array(name)?)
+}
+
+/// Turns a `DisclosurePolicy` into the per-position `Vec<PublicIOType>`
+/// `Groth16Repr::show_groth16`/`verify_groth16` consume, plus the
+/// cleartext/committed values the policy's named entries disclose --
+/// pulled from `prover_inputs` via `GenericInputsJSON::get`/`get_array` --
+/// so the show protocol can include them and generate commitment
+/// openings. Positions `io_locations` declares but `policy` doesn't
+/// mention default to `Hidden`.
+pub fn build_disclosure(
+    policy: &DisclosurePolicy,
+    io_locations: &IOLocations,
+    prover_inputs: &GenericInputsJSON,
+) -> Result<(Vec<PublicIOType>, DisclosedValues), Box<dyn Error>> {
+    let num_positions = io_locations.public_io_locations.values().copied().max().unwrap_or(0);
+    let mut io_types = vec![PublicIOType::Hidden; num_positions];
+    let mut disclosed = DisclosedValues::default();
+
+    for (name, io_type) in policy {
+        for position in positions_for(io_locations, name)? {
+            io_types[position - 1] = *io_type;
+        }
+        match io_type {
+            PublicIOType::Hidden => {}
+            PublicIOType::Revealed => {
+                disclosed.revealed.insert(name.clone(), read_values(prover_inputs, name)?);
+            }
+            PublicIOType::Committed => {
+                disclosed.committed.insert(name.clone(), read_values(prover_inputs, name)?);
+            }
+        }
+    }
+
+    Ok((io_types, disclosed))
+}
+
+/// Given `io_types` (the per-position types reconstructed from a received
+/// presentation) and the `policy` the verifier expects it to honor,
+/// reconstructs which named attributes were revealed versus committed.
+/// Returns an error naming the first policy entry whose actual disclosure
+/// in `io_types` doesn't match, so verification code can assert the
+/// policy was honored rather than silently trusting the proof.
+pub fn check_disclosure(
+    policy: &DisclosurePolicy,
+    io_locations: &IOLocations,
+    io_types: &[PublicIOType],
+) -> Result<(Vec<String>, Vec<String>), Box<dyn Error>> {
+    let mut revealed = vec![];
+    let mut committed = vec![];
+
+    for (name, expected_type) in policy {
+        let positions = positions_for(io_locations, name)?;
+        let actual_types: Vec<PublicIOType> = positions.iter().map(|position| io_types[*position - 1]).collect();
+        if actual_types.iter().any(|actual_type| actual_type != expected_type) {
+            return_error!(format!(
+                "Signal {} was not disclosed as its policy requires ({:?}); proof has {:?}",
+                name, expected_type, actual_types
+            ));
+        }
+        match expected_type {
+            PublicIOType::Revealed => revealed.push(name.clone()),
+            PublicIOType::Committed => committed.push(name.clone()),
+            PublicIOType::Hidden => {}
+        }
+    }
+
+    Ok((revealed, committed))
+}
+
 impl IOLocations {
     pub fn new(path: &str) -> Self {
         // main_clean.sym has rows of the form name,location
@@ -44,27 +157,108 @@ impl IOLocations {
         Self::new_from_str(&sym_file)
     }
 
+    /// Accepts either the hand-digested `name,location` CSV this module has
+    /// always produced, or circom's own native `.sym` format --
+    /// `symbolNumber,witnessIndex,componentNumber,fullSignalName` -- so an
+    /// unmodified circom compiler output can be loaded directly without
+    /// first being reduced to a `main_clean.sym`. The two formats can be
+    /// mixed line-by-line, though in practice a given `.sym` file is always
+    /// one or the other.
     pub fn new_from_str(io_data: &str) -> Self {
-        let mut public_io_locations = BTreeMap::default();        
+        let mut public_io_locations = BTreeMap::default();
+        let mut witness_indices = BTreeMap::default();
+        let mut by_witness_index = BTreeMap::default();
+        let mut component_numbers = BTreeMap::default();
+
         for line in io_data.lines() {
             let parts: Vec<&str> = line.split(",").collect();
-            if parts.len() == 2 {
-                let name = parts[0].to_string();
-                let location = parts[1].parse::<usize>().unwrap();
-                public_io_locations.insert(name, location);
-            } else {
-                panic!(
+            match parts.len() {
+                2 => {
+                    let name = parts[0].to_string();
+                    let location = parts[1].parse::<usize>().unwrap();
+                    public_io_locations.insert(name, location);
+                }
+                4 => {
+                    let symbol_number = parts[0].parse::<usize>().unwrap();
+                    let witness_index = parts[1].parse::<usize>().unwrap();
+                    let component_number = parts[2].parse::<usize>().unwrap();
+                    // circom emits names as `main.<signal>` (or
+                    // `main.<signal>[i]` for array entries); strip the
+                    // component prefix to match the naming
+                    // `get_io_location` callers already use.
+                    let name = parts[3].strip_prefix("main.").unwrap_or(parts[3]).to_string();
+
+                    public_io_locations.insert(name.clone(), symbol_number);
+                    witness_indices.insert(name.clone(), witness_index);
+                    by_witness_index.insert(witness_index, name.clone());
+                    component_numbers.insert(name, component_number);
+                }
+                _ => panic!(
                     "Line {} in io_locations.sym is not formatted correctly! Found {} parts.",
                     line,
                     parts.len()
-                );
+                ),
             }
         }
 
         Self {
             public_io_locations,
+            witness_indices,
+            by_witness_index,
+            component_numbers,
+        }
+    }
+
+    /// Reverse lookup of the witness-index column `new_from_str` reads from
+    /// circom's native `.sym` format. Returns `None` for a signal loaded
+    /// from the legacy 2-column CSV, or for a witness index no loaded
+    /// signal was assigned.
+    pub fn get_name_by_witness(&self, witness_index: usize) -> Option<&str> {
+        self.by_witness_index.get(&witness_index).map(String::as_str)
+    }
+
+    /// circom's component number for `key`, if it was loaded from the
+    /// native 4-column `.sym` format.
+    pub fn get_component_number(&self, key: &str) -> Option<usize> {
+        self.component_numbers.get(key).copied()
+    }
+
+    /// Splits a circom array element name like `modulus[3]` into its base
+    /// name and index; `None` for a scalar signal name.
+    fn array_element(name: &str) -> Option<(&str, usize)> {
+        let open = name.find('[')?;
+        if !name.ends_with(']') {
+            return None;
+        }
+        let index = name[open + 1..name.len() - 1].parse::<usize>().ok()?;
+        Some((&name[..open], index))
+    }
+
+    /// Collapses `base_name[0]..base_name[k]`-style array signals (as
+    /// circom emits them) into the single contiguous range of locations
+    /// they occupy, in index order. Returns `None` if `base_name` has no
+    /// array elements, or if their indices aren't the contiguous run
+    /// `0..=k`.
+    pub fn get_array_locations(&self, base_name: &str) -> Option<Vec<usize>> {
+        let mut members: Vec<(usize, usize)> = self
+            .public_io_locations
+            .iter()
+            .filter_map(|(name, &location)| {
+                let (base, index) = Self::array_element(name)?;
+                (base == base_name).then_some((index, location))
+            })
+            .collect();
+        if members.is_empty() {
+            return None;
+        }
+        members.sort_by_key(|(index, _)| *index);
+        for (i, (index, _)) in members.iter().enumerate() {
+            if *index != i {
+                return None;
+            }
         }
-    }    
+        Some(members.into_iter().map(|(_, location)| location).collect())
+    }
 
     pub fn get_io_location(&self, key: &str) -> Result<usize, std::io::Error> {
         match self.public_io_locations.get(key) {
@@ -78,16 +272,38 @@ impl IOLocations {
 
     pub fn get_public_key_indices(&self) -> Vec<usize> {
         let mut indices = vec![];
-        for key in self.public_io_locations.keys() {
-            if key.starts_with("modulus") || key.starts_with("pubkey") {
-                indices.push(*self.public_io_locations.get(key).unwrap() - 1);
+        for base_name in ["modulus", "pubkey"] {
+            if let Some(locations) = self.get_array_locations(base_name) {
+                indices.extend(locations.into_iter().map(|location| location - 1));
+            }
+        }
+        if indices.is_empty() {
+            // Hand-cleaned sym files (and ES256's two scalar limbs,
+            // `pubkey_0`/`pubkey_1`) don't use circom's `name[i]` array
+            // notation, so fall back to the original prefix match.
+            for key in self.public_io_locations.keys() {
+                if key.starts_with("modulus") || key.starts_with("pubkey") {
+                    indices.push(*self.public_io_locations.get(key).unwrap() - 1);
+                }
             }
         }
         indices.sort();
-        
+
         indices
     }
 
+    /// Serializes back to the `name,location` CSV format `new_from_str`
+    /// reads, so an `IOLocations` built by [`crate::circom_io`] can be
+    /// written out as the `io_locations.sym` artifact the rest of the
+    /// pipeline (and the `/show_params`/`/verifier_params` routes) expect.
+    pub fn to_csv(&self) -> String {
+        self.public_io_locations
+            .iter()
+            .map(|(name, location)| format!("{},{}", name, location))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
     pub fn get_all_names(&self) -> Vec<String> {
         let mut keys = vec![];
         for key in self.public_io_locations.keys() {
@@ -150,37 +366,249 @@ impl ProverInput for GenericInputsJSON {
 }
 
 impl GenericInputsJSON {
-    pub fn get(&self, key: &str) -> Result<BigUint, std::io::Error> {
-        match &self.prover_inputs[key] {
-            serde_json::Value::String(s) => {
-                Ok(bigint_from_str(s))
-            }
-            _ => {
-                Err(std::io::Error::new(
-                    ErrorKind::Other,
-                    "Key not found or is not a string",
-                ))
+    pub fn get(&self, key: &str) -> Result<BigUint, InputValidationError> {
+        match self.prover_inputs.get(key) {
+            None => Err(InputValidationError::MissingField(key.to_string())),
+            Some(Value::String(s)) => BigUint::parse_bytes(s.as_bytes(), 10)
+                .ok_or_else(|| InputValidationError::OutOfRange { name: key.to_string(), value: s.clone() }),
+            Some(other) => Err(InputValidationError::OutOfRange { name: key.to_string(), value: other.to_string() }),
+        }
+    }
+    pub fn get_array(&self, key: &str) -> Result<Vec<BigUint>, InputValidationError> {
+        match self.prover_inputs.get(key) {
+            None => Err(InputValidationError::MissingField(key.to_string())),
+            Some(Value::Array(a)) => a
+                .iter()
+                .map(|elt| match elt {
+                    Value::String(s) => BigUint::parse_bytes(s.as_bytes(), 10)
+                        .ok_or_else(|| InputValidationError::OutOfRange { name: key.to_string(), value: s.clone() }),
+                    other => Err(InputValidationError::OutOfRange { name: key.to_string(), value: other.to_string() }),
+                })
+                .collect(),
+            Some(other) => Err(InputValidationError::OutOfRange { name: key.to_string(), value: other.to_string() }),
+        }
+    }
+}
+
+/// Errors from [`validate_prover_inputs`] (and from [`GenericInputsJSON::get`]
+/// / [`GenericInputsJSON::get_array`]): a missing, unknown, or malformed
+/// entry in a `GenericInputsJSON` relative to an `IOLocations`'s declared
+/// public IO.
+#[derive(Debug, ThisError)]
+pub enum InputValidationError {
+    #[error("missing required input '{0}'")]
+    MissingField(String),
+    #[error("input '{0}' is not declared in io_locations")]
+    UnknownField(String),
+    #[error("input '{name}' has {actual} element(s), expected {expected}")]
+    LengthMismatch { name: String, expected: usize, actual: usize },
+    #[error("input '{name}' value '{value}' does not parse as a field element below the BN254 scalar modulus")]
+    OutOfRange { name: String, value: String },
+}
+
+/// Whether `IOLocations` declares `name` as a scalar public-IO signal, or
+/// as one element of a circom array signal (with the array's expected
+/// element count, taken from the highest array index declared).
+enum ExpectedInput {
+    Scalar,
+    Array(usize),
+}
+
+/// Groups `io_locations`'s declared public-IO names the way
+/// `GenericInputsJSON` actually stores them: circom's per-element
+/// `name[i]` array signals collapse into one expected entry (`name`, with
+/// its element count), everything else is an expected scalar entry. The
+/// constant `one` wire is never a prover input and is skipped.
+fn expected_inputs(io_locations: &IOLocations) -> BTreeMap<String, ExpectedInput> {
+    let mut expected: BTreeMap<String, ExpectedInput> = BTreeMap::new();
+    for name in io_locations.get_all_names() {
+        if name == "one" {
+            continue;
+        }
+        match IOLocations::array_element(&name) {
+            Some((base, index)) => match expected.entry(base.to_string()).or_insert(ExpectedInput::Array(0)) {
+                ExpectedInput::Array(len) => *len = (*len).max(index + 1),
+                ExpectedInput::Scalar => {}
+            },
+            None => {
+                expected.entry(name).or_insert(ExpectedInput::Scalar);
             }
         }
     }
-    pub fn get_array(&self, key: &str) -> Result<Vec<BigUint>, std::io::Error> {
-        match &self.prover_inputs[key] {
-            serde_json::Value::Array(a) => {
-                let mut vec = Vec::<BigUint>::new();
-                for elt in a.iter() {
-                    if let serde_json::Value::String(s) = elt {
-                        vec.push(bigint_from_str(s));
+    expected
+}
+
+/// Parses `value` as a base-10 field element strictly below the BN254
+/// scalar modulus, the same bound circuit proving requires of every input.
+fn validate_scalar_value(name: &str, value: &Value) -> Result<(), InputValidationError> {
+    let as_str = match value {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    };
+    let in_range = as_str.as_deref().and_then(|s| BigUint::parse_bytes(s.as_bytes(), 10)).and_then(|n| {
+        let bigint = <ark_bn254::Fr as ark_ff::PrimeField>::BigInt::try_from(n).ok()?;
+        ark_bn254::Fr::from_bigint(bigint)
+    });
+    match in_range {
+        Some(_) => Ok(()),
+        None => Err(InputValidationError::OutOfRange {
+            name: name.to_string(),
+            value: as_str.unwrap_or_else(|| value.to_string()),
+        }),
+    }
+}
+
+/// Cross-checks `prover_inputs` against every public-IO name
+/// `io_locations` declares: every name must have a corresponding entry,
+/// array-valued entries must have exactly the element count their index
+/// range implies, and every value must parse as a field element below the
+/// BN254 scalar modulus. Returns every violation found, rather than
+/// stopping at the first one, so a single run reports the full set of
+/// fixes needed before proving is attempted.
+pub fn validate_prover_inputs(
+    prover_inputs: &GenericInputsJSON,
+    io_locations: &IOLocations,
+) -> Result<(), Vec<InputValidationError>> {
+    let mut errors = Vec::new();
+    let expected = expected_inputs(io_locations);
+
+    for (name, kind) in &expected {
+        match (prover_inputs.prover_inputs.get(name), kind) {
+            (None, _) => errors.push(InputValidationError::MissingField(name.clone())),
+            (Some(value), ExpectedInput::Scalar) => {
+                if let Err(e) = validate_scalar_value(name, value) {
+                    errors.push(e);
+                }
+            }
+            (Some(value), ExpectedInput::Array(expected_len)) => match value.as_array() {
+                None => errors.push(InputValidationError::LengthMismatch { name: name.clone(), expected: *expected_len, actual: 0 }),
+                Some(arr) => {
+                    if arr.len() != *expected_len {
+                        errors.push(InputValidationError::LengthMismatch { name: name.clone(), expected: *expected_len, actual: arr.len() });
+                    }
+                    for element in arr {
+                        if let Err(e) = validate_scalar_value(name, element) {
+                            errors.push(e);
+                        }
                     }
                 }
-                Ok(vec)
+            },
+        }
+    }
+
+    for name in prover_inputs.prover_inputs.keys() {
+        if !expected.contains_key(name) {
+            errors.push(InputValidationError::UnknownField(name.clone()));
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+/// One file format `GenericInputs::from_layers` can read a [`Source::File`]
+/// as, detected from the file's extension.
+enum InputFormat {
+    Json,
+    Toml,
+    Yaml,
+    /// The binary MessagePack encoding, for large witness arrays that are
+    /// unwieldy to hand-edit as text in the first place.
+    MessagePack,
+}
+
+impl InputFormat {
+    fn from_path(path: &str) -> Result<Self, Box<dyn Error>> {
+        match std::path::Path::new(path).extension().and_then(|ext| ext.to_str()) {
+            Some("json") => Ok(InputFormat::Json),
+            Some("toml") => Ok(InputFormat::Toml),
+            Some("yaml") | Some("yml") => Ok(InputFormat::Yaml),
+            Some("msgpack") | Some("mpk") => Ok(InputFormat::MessagePack),
+            other => Err(format!("Unrecognized prover-input file extension in {}: {:?}", path, other).into()),
+        }
+    }
+
+    /// Parses `bytes` into the `Map<String, Value>` shape `push_inputs`
+    /// already consumes, regardless of on-disk format.
+    fn parse(&self, bytes: &[u8]) -> Result<Map<String, Value>, Box<dyn Error>> {
+        let value: Value = match self {
+            InputFormat::Json => serde_json::from_slice(bytes)?,
+            InputFormat::Toml => toml::from_str(std::str::from_utf8(bytes)?)?,
+            InputFormat::Yaml => serde_yaml::from_slice(bytes)?,
+            InputFormat::MessagePack => rmp_serde::from_slice(bytes)?,
+        };
+        value
+            .as_object()
+            .cloned()
+            .ok_or_else(|| "Prover-input source does not contain an object at its top level".into())
+    }
+}
+
+/// One layer of prover inputs, in priority order (later layers in a
+/// `from_layers` call replace keys set by earlier ones).
+pub enum Source {
+    /// A file on disk, in any format [`InputFormat`] recognizes. A missing
+    /// file loads as an empty layer rather than an error, so a layer list
+    /// can name an optional environment-specific overlay that may not
+    /// exist for every environment.
+    File(String),
+    /// Environment variables named `<prefix><NAME>` (e.g. with a `prefix` of
+    /// `CRESCENT_INPUT_`), where `<NAME>` is the upper-cased input key; each
+    /// becomes a string-valued entry.
+    /// Meant as the final, highest-priority layer, for overriding a single
+    /// input without touching the files it's ordinarily read from.
+    Env { prefix: String },
+}
+
+impl Source {
+    fn load(&self) -> Result<Map<String, Value>, Box<dyn Error>> {
+        match self {
+            Source::File(path) => {
+                if !std::path::Path::new(path).exists() {
+                    return Ok(Map::new());
+                }
+                InputFormat::from_path(path)?.parse(&std::fs::read(path)?)
             }
-            _ => {
-                Err(std::io::Error::new(
-                    ErrorKind::Other,
-                    "Key not found or is not an array",
-                ))
+            Source::Env { prefix } => {
+                let mut layer = Map::new();
+                for (name, value) in std::env::vars() {
+                    if let Some(key) = name.strip_prefix(prefix) {
+                        layer.insert(key.to_lowercase(), Value::String(value));
+                    }
+                }
+                Ok(layer)
             }
         }
     }
 }
 
+/// A pluggable, layered prover-input loader, in the spirit of a
+/// configuration system: merges JSON, TOML, YAML, and MessagePack file
+/// layers (and environment-variable overrides) into the same
+/// `Map<String, Value>` shape [`GenericInputsJSON`] wraps, so callers get one
+/// merged input set (or an error) regardless of on-disk format instead of
+/// [`ProverInput::new`]'s panic-on-any-error single JSON file. The merged set
+/// is not checked against an `IOLocations`; [`validate_prover_inputs`] does
+/// that for a [`GenericInputsJSON`].
+#[derive(Clone, Debug, Default)]
+pub struct GenericInputs {
+    pub prover_inputs: Map<String, Value>,
+}
+
+impl GenericInputs {
+    /// Loads and merges `sources` in order, so a base inputs file can be
+    /// overlaid by an environment-specific file and finally overridden by
+    /// `CRESCENT_INPUT_<NAME>` environment variables (later sources win).
+    pub fn from_layers(sources: &[Source]) -> Result<Self, Box<dyn Error>> {
+        let mut prover_inputs = Map::new();
+        for source in sources {
+            prover_inputs.extend(source.load()?);
+        }
+        Ok(Self { prover_inputs })
+    }
+}
+
@@ -0,0 +1,177 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT license.
+
+// A typed, bias-free Fiat-Shamir layer on top of `merlin::Transcript`.
+//
+// The range-proof and DLEQ protocols in this crate used to absorb messages
+// and squeeze challenges directly through `merlin::Transcript`, always under
+// the empty label `&[0u8]`, and turn the squeezed bytes into a field element
+// with `F::from_random_bytes(&bytes).unwrap()` on a 31-byte buffer. That has
+// two problems: truncating to 31 bytes before reducing leaves the top bits
+// of the field unreachable (a modular bias), and `from_random_bytes` returns
+// `None` whenever those bytes don't happen to parse as a canonical field
+// element, which `unwrap()` turns into a panic. The empty label also means
+// every absorbed message and every squeezed challenge across a protocol run
+// look identical to the transcript, so there's no domain separation between
+// e.g. a commitment to `f` and a commitment to `g`.
+//
+// `ProofTranscript` fixes both: callers pass a distinct label per message,
+// and `challenge_scalar` squeezes `ceil(log2(|F|))+128` bits and reduces them
+// into `F` via `from_le_bytes_mod_order`, which is a total function (never
+// panics) and, per the standard wide-reduction argument, statistically close
+// to uniform over `F`.
+
+// `DLogPoK` (see `dlog.rs`) needs the transcript itself to be pluggable: the
+// default `Transcript` (merlin, Strobe-based) impl below is fine for normal
+// proving/verifying, but it's opaque bytes to a downstream SNARK circuit, so
+// a proof that recursively verifies a `DLogPoK` can't cheaply re-derive its
+// challenge. `absorb_group`/`absorb_scalar`/`challenge` are fixed-label
+// convenience wrappers (over the existing labeled primitives) so a caller
+// like `DLogPoK::prove_with_transcript` can be generic over the backend;
+// `PoseidonTranscript` below is the algebraic-hash backend, whose challenges
+// are squeezed from a Poseidon sponge as field elements.
+
+use ark_ec::CurveGroup;
+use ark_ff::PrimeField;
+use ark_serialize::CanonicalSerialize;
+use merlin::Transcript;
+
+use ark_crypto_primitives::sponge::poseidon::{find_poseidon_ark_and_mds, PoseidonConfig, PoseidonSponge};
+use ark_crypto_primitives::sponge::CryptographicSponge;
+
+pub trait ProofTranscript {
+    /// Absorbs a commitment (or any canonically-serializable value) under `label`.
+    fn append_commitment(&mut self, label: &'static [u8], commitment: &impl CanonicalSerialize);
+
+    /// Absorbs a scalar under `label`.
+    fn append_scalar<F: PrimeField>(&mut self, label: &'static [u8], scalar: &F);
+
+    /// Absorbs raw bytes (e.g. a caller-supplied context string) under `label`.
+    fn append_bytes(&mut self, label: &'static [u8], bytes: &[u8]);
+
+    /// Squeezes a Fiat-Shamir challenge in `F`, labeled `label`.
+    fn challenge_scalar<F: PrimeField>(&mut self, label: &'static [u8]) -> F;
+
+    /// `absorb_group`/`absorb_scalar`/`absorb_bytes`/`challenge` are the
+    /// fixed-label shorthand `DLogPoK::prove_with_transcript` uses: a
+    /// prover and verifier generic over `T: ProofTranscript` don't need to
+    /// agree on per-call labels, just the sequence of absorptions, which
+    /// the shared `prove`/`verify` logic already fixes.
+    fn absorb_group<G: CurveGroup>(&mut self, point: &G) {
+        self.append_commitment(b"group element", point);
+    }
+
+    fn absorb_scalar<F: PrimeField>(&mut self, scalar: &F) {
+        ProofTranscript::append_scalar(self, b"scalar", scalar);
+    }
+
+    fn absorb_bytes(&mut self, bytes: &[u8]) {
+        self.append_bytes(b"bytes", bytes);
+    }
+
+    fn challenge<F: PrimeField>(&mut self) -> F {
+        self.challenge_scalar(b"challenge")
+    }
+}
+
+impl ProofTranscript for Transcript {
+    fn append_commitment(&mut self, label: &'static [u8], commitment: &impl CanonicalSerialize) {
+        let mut bytes = Vec::new();
+        commitment
+            .serialize_compressed(&mut bytes)
+            .expect("serialization of a transcript message should not fail");
+        self.append_message(label, &bytes);
+    }
+
+    fn append_scalar<F: PrimeField>(&mut self, label: &'static [u8], scalar: &F) {
+        let mut bytes = Vec::new();
+        scalar
+            .serialize_compressed(&mut bytes)
+            .expect("serialization of a transcript message should not fail");
+        self.append_message(label, &bytes);
+    }
+
+    fn append_bytes(&mut self, label: &'static [u8], bytes: &[u8]) {
+        self.append_message(label, bytes);
+    }
+
+    fn challenge_scalar<F: PrimeField>(&mut self, label: &'static [u8]) -> F {
+        let num_bytes = (F::MODULUS_BIT_SIZE as usize + 128).div_ceil(8);
+        let mut wide_bytes = vec![0u8; num_bytes];
+        self.challenge_bytes(label, &mut wide_bytes);
+        F::from_le_bytes_mod_order(&wide_bytes)
+    }
+}
+
+/// Standard-strength Poseidon parameters (rate 2, capacity 1, S-box x^5, 8
+/// full rounds + 57 partial rounds) for a given scalar field: round
+/// constants and the MDS matrix are derived deterministically from the
+/// field's modulus via the Grain LFSR construction from the original
+/// Poseidon paper (the same construction `ark-crypto-primitives`'s own
+/// example/test configs use), not hand-picked.
+pub fn default_poseidon_config<F: PrimeField>() -> PoseidonConfig<F> {
+    let full_rounds = 8;
+    let partial_rounds = 57;
+    let alpha = 5;
+    let rate = 2;
+    let capacity = 1;
+    let (ark, mds) = find_poseidon_ark_and_mds::<F>(
+        F::MODULUS_BIT_SIZE as u64,
+        rate,
+        full_rounds,
+        partial_rounds,
+        0,
+    );
+    PoseidonConfig::new(full_rounds as usize, partial_rounds as usize, alpha, mds, ark, rate, capacity)
+}
+
+/// A Poseidon-sponge-backed `ProofTranscript`: the algebraic-hash
+/// alternative to the default merlin backend above. Challenges are squeezed
+/// from the sponge as field elements; commitments, scalars and byte strings
+/// are absorbed as their compressed byte serialization, which the sponge
+/// packs into elements of `F`. The Poseidon hash is what makes a proof built
+/// on this transcript (e.g. a `DLogPoK`
+/// via `prove_with_transcript`/`verify_with_transcript`) cheap to re-verify
+/// inside a downstream SNARK circuit over the same field -- the building
+/// block for recursive composition of credential proofs.
+///
+/// Labels are accepted (to satisfy the `ProofTranscript` interface) but
+/// ignored: a sponge's absorptions are already strictly ordered, which is
+/// enough domain separation for a protocol whose absorption sequence is
+/// fixed, and skipping them keeps each absorption to the message's own
+/// bytes instead of a label-prefixed byte string.
+pub struct PoseidonTranscript<F: PrimeField> {
+    sponge: PoseidonSponge<F>,
+}
+
+impl<F: PrimeField> PoseidonTranscript<F> {
+    pub fn new(config: &PoseidonConfig<F>) -> Self {
+        Self { sponge: PoseidonSponge::new(config) }
+    }
+}
+
+impl<F: PrimeField> ProofTranscript for PoseidonTranscript<F> {
+    fn append_commitment(&mut self, _label: &'static [u8], commitment: &impl CanonicalSerialize) {
+        let mut bytes = Vec::new();
+        commitment
+            .serialize_compressed(&mut bytes)
+            .expect("serialization of a transcript message should not fail");
+        self.sponge.absorb(&bytes);
+    }
+
+    fn append_scalar<F2: PrimeField>(&mut self, _label: &'static [u8], scalar: &F2) {
+        let mut bytes = Vec::new();
+        scalar
+            .serialize_compressed(&mut bytes)
+            .expect("serialization of a transcript message should not fail");
+        self.sponge.absorb(&bytes);
+    }
+
+    fn append_bytes(&mut self, _label: &'static [u8], bytes: &[u8]) {
+        self.sponge.absorb(&bytes.to_vec());
+    }
+
+    fn challenge_scalar<F2: PrimeField>(&mut self, _label: &'static [u8]) -> F2 {
+        self.sponge.squeeze_field_elements::<F2>(1)[0]
+    }
+}
@@ -2,7 +2,7 @@
 // Licensed under the MIT license.
 
 use ark_ec::{AffineRepr, CurveGroup};
-use ark_ff::PrimeField;
+use ark_ff::{BigInteger, PrimeField};
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, SerializationError};
 use ark_std::rand::thread_rng;
 use merlin::Transcript;
@@ -60,11 +60,15 @@ where
 
 pub fn biguint_to_scalar<F: PrimeField>(a: &BigUint) -> F {
     let a_bigint = F::BigInt::try_from(a.clone()).unwrap();
-    
+
 
     F::from_bigint(a_bigint).unwrap()
 }
 
+pub fn scalar_to_biguint<F: PrimeField>(a: &F) -> BigUint {
+    BigUint::from_bytes_le(&a.into_bigint().to_bytes_le())
+}
+
 pub fn random_vec<F: PrimeField>(n: usize) -> Vec<F> {
     let mut rng = thread_rng();
     let mut v = Vec::with_capacity(n);
@@ -162,6 +166,18 @@ where
     Ok(state)
 }
 
+/// The `read_from_bytes` counterpart: encodes `obj` the same way
+/// `write_to_file`/`write_to_b64url` do, but returns the raw bytes instead
+/// of writing to a file or base64url-encoding them.
+pub fn write_to_bytes<T>(obj: &T) -> Vec<u8>
+where
+    T: CanonicalSerialize
+{
+    let mut buf = Vec::new();
+    obj.serialize_uncompressed(&mut buf).unwrap();
+    buf
+}
+
 #[cfg(test)]
 mod tests {
     use crate::rangeproof::RangeProofPK;
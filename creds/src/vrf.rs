@@ -0,0 +1,163 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT license.
+
+//! Verifiable, per-verifier-scoped pseudonyms.
+//!
+//! `proof_spec.presentation_message` binds a show proof to one challenge,
+//! but gives a verifier no stable identifier to tell whether two
+//! presentations came from the same credential without linking everything
+//! about them. This module adds an EC-VRF-style proof that lets a holder
+//! derive a *pseudonym*: a value that comes out the same every time the same
+//! secret is used with the same `scope` (e.g. a verifier's domain), but is
+//! unlinkable across different scopes, since each pseudonym is
+//! `Hash(sk · H(scope))` for a different `H(scope)` per scope.
+//!
+//! This is a standard VRF built from a Chaum-Pedersen / Schnorr-style proof
+//! of equal discrete logs: `pk = sk·G` and `gamma = sk·H` share the same
+//! exponent `sk`, and the proof `(c, s)` convinces a verifier of that without
+//! revealing `sk`.
+
+use crate::transcript::ProofTranscript;
+use crate::utils::hash_to_curve_vartime;
+use ark_ec::{AffineRepr, CurveGroup, Group, VariableBaseMSM};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::{rand::thread_rng, UniformRand};
+use merlin::Transcript;
+use sha2::{Digest, Sha256};
+
+/// A proof that `gamma = sk·H` for the same `sk` as `pk = sk·G`, where
+/// `H = hash_to_curve(scope)`. See [`prove_scoped_pseudonym`]/
+/// [`verify_scoped_pseudonym`].
+#[derive(Clone, Debug, CanonicalSerialize, CanonicalDeserialize)]
+pub struct ScopedPseudonymProof<G: Group> {
+    pub gamma: G,
+    pub c: G::ScalarField,
+    pub s: G::ScalarField,
+}
+
+/// Everything a verifier needs to check a scoped pseudonym: the public key
+/// the pseudonym was derived against, the pseudonym itself, and the proof
+/// that it was derived correctly.
+#[derive(Clone, Debug, CanonicalSerialize, CanonicalDeserialize)]
+pub struct ScopedPseudonymShowing<G: Group> {
+    pub pk: G,
+    pub pseudonym: Vec<u8>,
+    pub proof: ScopedPseudonymProof<G>,
+}
+
+fn scope_point<G: CurveGroup>(scope: &str) -> G {
+    hash_to_curve_vartime::<G>(scope).into_group()
+}
+
+/// The public, unlinkable-across-scopes pseudonym bytes: `Hash(gamma)`.
+fn pseudonym_bytes<G: CurveGroup>(gamma: &G) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    gamma
+        .serialize_compressed(&mut bytes)
+        .expect("serialization of a VRF pseudonym should not fail");
+    Sha256::digest(&bytes).to_vec()
+}
+
+/// Computes the scoped pseudonym `Hash(sk·H(scope))` for `sk`, plus a proof
+/// that it was derived from the same `sk` as the returned public key
+/// `pk = sk·G` -- see [`verify_scoped_pseudonym`]. The same `(sk, scope)`
+/// pair always yields the same pseudonym, but pseudonyms derived under
+/// different `scope`s are unlinkable to each other (short of breaking
+/// discrete log).
+pub fn prove_scoped_pseudonym<G>(sk: G::ScalarField, scope: &str) -> ScopedPseudonymShowing<G>
+where
+    G: CurveGroup + VariableBaseMSM,
+{
+    let mut rng = thread_rng();
+    let h: G = scope_point(scope);
+    let pk = G::generator() * sk;
+    let gamma = h * sk;
+
+    let k = G::ScalarField::rand(&mut rng);
+    let kg = G::generator() * k;
+    let kh = h * k;
+
+    let mut ts = Transcript::new(&[0u8]);
+    ts.absorb_group(&h);
+    ts.absorb_group(&gamma);
+    ts.absorb_group(&kg);
+    ts.absorb_group(&kh);
+    let c: G::ScalarField = ts.challenge();
+
+    let s = k + c * sk;
+
+    ScopedPseudonymShowing {
+        pk,
+        pseudonym: pseudonym_bytes(&gamma),
+        proof: ScopedPseudonymProof { gamma, c, s },
+    }
+}
+
+/// Verifies a [`ScopedPseudonymShowing`] against `scope`: recomputes
+/// `u = s·G − c·pk`, `v = s·H − c·gamma`, checks the Fiat-Shamir challenge
+/// over `(H, gamma, u, v)` matches `proof.c`, and that `pseudonym` really is
+/// `Hash(gamma)`.
+pub fn verify_scoped_pseudonym<G>(showing: &ScopedPseudonymShowing<G>, scope: &str) -> bool
+where
+    G: CurveGroup + VariableBaseMSM,
+{
+    let h: G = scope_point(scope);
+    let proof = &showing.proof;
+    let u = G::generator() * proof.s - showing.pk * proof.c;
+    let v = h * proof.s - proof.gamma * proof.c;
+
+    let mut ts = Transcript::new(&[0u8]);
+    ts.absorb_group(&h);
+    ts.absorb_group(&proof.gamma);
+    ts.absorb_group(&u);
+    ts.absorb_group(&v);
+    let c: G::ScalarField = ts.challenge();
+
+    c == proof.c && pseudonym_bytes(&proof.gamma) == showing.pseudonym
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_bn254::{Fr, G1Projective as G};
+
+    #[test]
+    fn test_scoped_pseudonym_round_trip() {
+        let sk = Fr::from(12345u64);
+        let showing = prove_scoped_pseudonym::<G>(sk, "verifier-a.example.com");
+        assert!(verify_scoped_pseudonym(&showing, "verifier-a.example.com"));
+    }
+
+    #[test]
+    fn test_scoped_pseudonym_deterministic_per_scope() {
+        let sk = Fr::from(12345u64);
+        let showing1 = prove_scoped_pseudonym::<G>(sk, "verifier-a.example.com");
+        let showing2 = prove_scoped_pseudonym::<G>(sk, "verifier-a.example.com");
+        // Same (sk, scope) always yields the same pseudonym, even though the
+        // proof itself is randomized.
+        assert_eq!(showing1.pseudonym, showing2.pseudonym);
+    }
+
+    #[test]
+    fn test_scoped_pseudonym_unlinkable_across_scopes() {
+        let sk = Fr::from(12345u64);
+        let showing_a = prove_scoped_pseudonym::<G>(sk, "verifier-a.example.com");
+        let showing_b = prove_scoped_pseudonym::<G>(sk, "verifier-b.example.com");
+        assert_ne!(showing_a.pseudonym, showing_b.pseudonym);
+    }
+
+    #[test]
+    fn test_scoped_pseudonym_rejects_wrong_scope() {
+        let sk = Fr::from(12345u64);
+        let showing = prove_scoped_pseudonym::<G>(sk, "verifier-a.example.com");
+        assert!(!verify_scoped_pseudonym(&showing, "verifier-b.example.com"));
+    }
+
+    #[test]
+    fn test_scoped_pseudonym_rejects_tampered_pseudonym() {
+        let sk = Fr::from(12345u64);
+        let mut showing = prove_scoped_pseudonym::<G>(sk, "verifier-a.example.com");
+        showing.pseudonym[0] ^= 0xff;
+        assert!(!verify_scoped_pseudonym(&showing, "verifier-a.example.com"));
+    }
+}
@@ -1,22 +1,45 @@
 // Copyright (c) Microsoft Corporation.
 // Licensed under the MIT license.
 
-use crate::create_show_proof;
-use crate::create_show_proof_mdl;
+use crate::create_show_proof_mdl_with_clock;
+use crate::create_show_proof_with_clock;
+use crate::daystamp::unix_seconds_for_year_start;
+use crate::daystamp::WasmClock;
+use crate::delegation::{check_disclosures_permitted, verify_delegation_chain, DelegationGrant};
 use crate::utils::write_to_b64url;
+use crate::vrf::prove_scoped_pseudonym;
 use crate::ClientState;
 use crate::IOLocations;
+use crate::Predicate;
+use crate::PredicateOp;
 use crate::ProofSpec;
 use crate::RangeProofPK;
+use crate::TimePredicate;
 use crate::DEFAULT_PROOF_SPEC;
 use ark_bn254::Bn254 as ECPairing;
+use ark_ec::pairing::Pairing;
 use ark_serialize::CanonicalDeserialize;
 use base64_url::decode;
+use serde::Serialize;
 use wasm_bindgen::prelude::wasm_bindgen;
 use sha2::{Digest, Sha256};
 use crate::device::TestDevice;
+use crate::DeviceSignature;
 use std::collections::HashMap;
 
+/// Output of [`create_show_proof_wasm`]: the show proof itself, plus an
+/// optional scoped pseudonym when the caller passed a `scope`.
+#[derive(Serialize)]
+struct ShowProofWasmOutput {
+    show_proof: String,
+    /// Base64url encoding of a [`crate::vrf::ScopedPseudonymShowing`], present iff
+    /// both `scope` and `device_priv_key` were given (whether or not the
+    /// proof spec is device bound) -- the same credential yields one deterministic
+    /// pseudonym per `scope`, but unlinkable pseudonyms across different
+    /// `scope`s.
+    pseudonym: Option<String>,
+}
+
 #[wasm_bindgen]
 extern "C" {
     #[wasm_bindgen(js_namespace = console)]
@@ -33,13 +56,90 @@ pub fn main() {
     console_error_panic_hook::set_once();
 }
 
-fn disc_uid_to_age(disc_uid: &str) -> Result<usize, &'static str> {
-    match disc_uid {
-        "crescent://over_18" => Ok(18),
-        "crescent://over_21" => Ok(21),
-        "crescent://over_65" => Ok(65),
-        _ => Err("disc_uid_to_age: invalid disclosure uid"),
+/// One disclosure directive parsed out of a `crescent://...` uid. A proof
+/// can combine any number of these -- see `apply_disc_uids`, which folds
+/// each into `proof_spec` so they all end up in the one aggregated proof
+/// instead of one presentation per disclosure.
+enum DiscUid {
+    /// `crescent://over_N` -- prove `birth_date` is at least `N` years in
+    /// the past. Sugar for a `range_over_year` entry.
+    OverAge(u64),
+    /// `crescent://after_YYYY` -- prove the credential's expiry claim
+    /// (`valid_until` for mDL, `exp` otherwise) is on or after Jan 1st of
+    /// `YYYY`.
+    AfterYear(usize),
+    /// `crescent://attr/op/value` -- a generic numeric predicate; `op` is
+    /// `gte` or `lte`.
+    FieldPredicate { attr: String, op: PredicateOp, value: i64 },
+}
+
+fn parse_disc_uid(uid: &str) -> Result<DiscUid, String> {
+    let rest = uid
+        .strip_prefix("crescent://")
+        .ok_or_else(|| format!("disclosure uid missing crescent:// prefix: {}", uid))?;
+
+    if let Some(n) = rest.strip_prefix("over_") {
+        return n
+            .parse::<u64>()
+            .map(DiscUid::OverAge)
+            .map_err(|_| format!("invalid age in disclosure uid: {}", uid));
+    }
+    if let Some(y) = rest.strip_prefix("after_") {
+        return y
+            .parse::<usize>()
+            .map(DiscUid::AfterYear)
+            .map_err(|_| format!("invalid year in disclosure uid: {}", uid));
+    }
+    let parts: Vec<&str> = rest.splitn(3, '/').collect();
+    if let [attr, op, value] = parts[..] {
+        let op = match op {
+            "gte" => PredicateOp::GreaterThanOrEqual,
+            "lte" => PredicateOp::LessThanOrEqual,
+            other => {
+                return Err(format!(
+                    "unsupported predicate operator in disclosure uid {}: {}",
+                    uid, other
+                ))
+            }
+        };
+        let value = value
+            .parse::<i64>()
+            .map_err(|_| format!("invalid predicate value in disclosure uid: {}", uid))?;
+        return Ok(DiscUid::FieldPredicate { attr: attr.to_string(), op, value });
+    }
+    Err(format!("invalid disclosure uid: {}", uid))
+}
+
+/// Parse every `disc_uids` entry and fold it into `proof_spec`'s
+/// `range_over_year`/`time_predicates`/`predicates`, so a single show proof
+/// ends up asserting all of them together.
+fn apply_disc_uids(proof_spec: &mut ProofSpec, disc_uids: &[String], is_mdl: bool) -> Result<(), String> {
+    for uid in disc_uids {
+        match parse_disc_uid(uid)? {
+            DiscUid::OverAge(age) => {
+                proof_spec
+                    .range_over_year
+                    .get_or_insert_with(std::collections::BTreeMap::new)
+                    .insert("birth_date".to_string(), age);
+            }
+            DiscUid::AfterYear(year) => {
+                let claim = if is_mdl { "valid_until" } else { "exp" };
+                let threshold = unix_seconds_for_year_start(year).map_err(|e| e.to_string())?;
+                proof_spec.time_predicates.get_or_insert_with(Vec::new).push(TimePredicate {
+                    claim: claim.to_string(),
+                    greater_than: Some(threshold),
+                    less_than: None,
+                });
+            }
+            DiscUid::FieldPredicate { attr, op, value } => {
+                proof_spec
+                    .predicates
+                    .get_or_insert_with(Vec::new)
+                    .push(Predicate { attr, op, value, value2: None });
+            }
+        }
     }
+    Ok(())
 }
 
 #[wasm_bindgen]
@@ -47,15 +147,17 @@ pub fn create_show_proof_wasm(
     client_state_b64url: String,
     range_pk_b64url: String,
     io_locations_str: String,
-    disc_uid: String,
+    disc_uids: Vec<String>,
     challenge: String,
     proof_spec: String,
     device_priv_key: Option<String>,
+    scope: Option<String>,
+    delegation_chain: Option<String>,
 ) -> Result<String, String> {
 
     let msg = format!(
-        "create_show_proof_wasm inputs: client_state_b64url: {}, range_pk_b64url: {}, io_locations_str: {}, disc_uid: {}, challenge: {}, proof_spec: {}, device_priv_key: {}",
-        client_state_b64url, range_pk_b64url, io_locations_str, disc_uid, challenge, proof_spec, device_priv_key.as_deref().unwrap_or("None")
+        "create_show_proof_wasm inputs: client_state_b64url: {}, range_pk_b64url: {}, io_locations_str: {}, disc_uids: {:?}, challenge: {}, proof_spec: {}, device_priv_key: {}, scope: {}, delegation_chain: {}",
+        client_state_b64url, range_pk_b64url, io_locations_str, disc_uids, challenge, proof_spec, device_priv_key.as_deref().unwrap_or("None"), scope.as_deref().unwrap_or("None"), delegation_chain.as_deref().unwrap_or("None")
     );
     log(&msg);
 
@@ -65,9 +167,6 @@ pub fn create_show_proof_wasm(
     if range_pk_b64url.is_empty() {
         return Err("Received empty range_pk_b64url".to_string());
     }
-    if disc_uid.is_empty() {
-        return Err("Received empty disc_uid".to_string());
-    }
     if io_locations_str.is_empty() {
         return Err("Received empty io_locations_str".to_string());
     }
@@ -102,13 +201,42 @@ pub fn create_show_proof_wasm(
         (Ok(mut client_state), Ok(range_pk), Ok(mut proof_spec)) => {
             log("Successfully deserialized client-state, range-pk, and proof-spec");
 
-            proof_spec.presentation_message = Some(Sha256::digest(challenge).to_vec());
+            // if the holder is showing on behalf of a delegation chain,
+            // verify it, check every requested disc_uid is covered by the
+            // chain's attenuated disclosures, and bind the final audience
+            // key into the presentation message
+            let delegation_audience_pubkey = match &delegation_chain {
+                Some(chain_b64url) => {
+                    let chain_bytes = base64_url::decode(chain_b64url)
+                        .map_err(|_| "Failed to decode base64url delegation_chain".to_string())?;
+                    let chain_json = String::from_utf8(chain_bytes)
+                        .map_err(|_| "Decoded delegation_chain is not valid UTF-8".to_string())?;
+                    let chain: Vec<DelegationGrant> = serde_json::from_str(&chain_json)
+                        .map_err(|e| format!("Failed to parse delegation_chain: {:?}", e))?;
+                    let now = js_now_seconds() as i64;
+                    let (allowed, audience_pubkey) = verify_delegation_chain(&chain, now)
+                        .map_err(|e| format!("Failed to verify delegation chain: {}", e))?;
+                    check_disclosures_permitted(&disc_uids, &allowed)
+                        .map_err(|e| format!("Disclosure not permitted by delegation chain: {}", e))?;
+                    Some(audience_pubkey)
+                }
+                None => None,
+            };
+
+            proof_spec.presentation_message = Some(match &delegation_audience_pubkey {
+                Some(audience_pubkey) => {
+                    let mut bytes = challenge.clone().into_bytes();
+                    bytes.extend_from_slice(audience_pubkey);
+                    Sha256::digest(&bytes).to_vec()
+                }
+                None => Sha256::digest(challenge.as_bytes()).to_vec(),
+            });
 
             // create the device signature (if cred is device-bound)
             let device_signature = if proof_spec.device_bound.unwrap_or(false) {
                 if let Some(key) = &device_priv_key {
                     let device = TestDevice::new_from_pem(key);
-                    Some(device.sign(proof_spec.presentation_message.as_ref().unwrap()))
+                    Some(DeviceSignature::RawEcdsa(device.sign(proof_spec.presentation_message.as_ref().unwrap())))
                 } else {
                     None
                 }
@@ -116,35 +244,49 @@ pub fn create_show_proof_wasm(
                 None
             };
 
-            let show_proof = if &client_state.credtype == "mdl" {
-                let age = disc_uid_to_age(&disc_uid)
-                    .map_err(|_| "Disclosure UID does not have associated age parameter".to_string())? as u64;
+            let is_mdl = &client_state.credtype == "mdl";
+            apply_disc_uids(&mut proof_spec, &disc_uids, is_mdl)?;
 
-                proof_spec.range_over_year = Some(std::collections::BTreeMap::from([
-                    ("birth_date".to_string(), age),
-                ]));
+            // derive a scoped pseudonym from the same device secret used for
+            // the device-binding signature, if both are available
+            let pseudonym_showing = match (&device_priv_key, &scope) {
+                (Some(key), Some(scope)) => {
+                    let device = TestDevice::new_from_pem(key);
+                    let sk = device.secret_scalar::<<ECPairing as Pairing>::ScalarField>();
+                    Some(prove_scoped_pseudonym::<<ECPairing as Pairing>::G1>(sk, scope))
+                }
+                _ => None,
+            };
 
-                create_show_proof_mdl(
+            let clock = WasmClock::new(js_now_seconds);
+            let show_proof = if is_mdl {
+                create_show_proof_mdl_with_clock(
                     &mut client_state,
                     &range_pk,
                     &proof_spec,
                     &io_locations,
                     device_signature,
+                    &clock,
                 )
                 .map_err(|e| format!("create_show_proof_mdl failed: {:?}", e))?
             } else {
-                create_show_proof(
+                create_show_proof_with_clock(
                     &mut client_state,
                     &range_pk,
                     &io_locations,
                     &proof_spec,
                     device_signature,
+                    &clock,
                 )
                 .map_err(|e| format!("create_show_proof failed: {:?}", e))?
             };
 
-            let show_proof_b64 = write_to_b64url(&show_proof);
-            Ok(show_proof_b64)
+            let output = ShowProofWasmOutput {
+                show_proof: write_to_b64url(&show_proof),
+                pseudonym: pseudonym_showing.as_ref().map(write_to_b64url),
+            };
+            serde_json::to_string(&output)
+                .map_err(|e| format!("Failed to serialize show-proof output: {:?}", e))
         }
         (Err(e), _, _) => {
             Err(format!("Failed to deserialize client state: {:?}", e))
@@ -0,0 +1,470 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT license.
+
+// Verifies a FIDO2/CTAP2 authenticator assertion, so `device_bound` proofs
+// can be backed by a real WebAuthn holder-of-device check instead of just
+// requiring `presentation_message` to be present. Reuses the minimal CBOR
+// reader in `cose` to parse the authenticator's EC2 COSE_Key (RFC 9053
+// section 7.1.1) out of the credential record, rather than pulling in a
+// dedicated CBOR crate.
+
+use std::error::Error;
+
+use ark_ec::CurveGroup;
+use ark_ff::PrimeField;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use num_bigint::BigUint;
+use p256::ecdsa::signature::Verifier;
+use p256::ecdsa::{Signature, VerifyingKey};
+use p256::EncodedPoint;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+
+use crate::cose::{major_minor, read_bstr, read_int, read_length, skip_item};
+use crate::dlog::PedersenOpening;
+use crate::return_error;
+use crate::utils::scalar_to_biguint;
+
+const COSE_KTY_EC2: i64 = 2;
+const COSE_CRV_P256: i64 = 1;
+
+/// Bit 0 (user presence) and bit 2 (user verification) of the
+/// `authenticatorData` flags byte (CTAP2 section 6.1).
+const FLAG_USER_PRESENT: u8 = 0x01;
+const FLAG_USER_VERIFIED: u8 = 0x04;
+
+/// The three pieces of a CTAP2 `get_assertion` response needed to verify it:
+/// the raw `authenticatorData`, the `clientDataJSON` it was bound to, and
+/// the signature the authenticator produced over both.
+#[derive(Clone, Debug, CanonicalSerialize, CanonicalDeserialize)]
+pub struct Assertion {
+    pub authenticator_data: Vec<u8>,
+    pub client_data_json: Vec<u8>,
+    pub signature: Vec<u8>,
+}
+
+/// The subset of `clientDataJSON` this module needs (WebAuthn level 2,
+/// section 5.8.1): the challenge the relying party asked the authenticator
+/// to sign over, base64url-encoded by the client.
+#[derive(Deserialize)]
+struct ClientData {
+    challenge: String,
+}
+
+/// An EC2 COSE_Key (RFC 9053 section 7.1.1) holding a P-256 public key,
+/// extracted from the map keyed by `kty` (1), `crv` (-1), `x` (-2), `y` (-3).
+pub struct CosePublicKey {
+    pub x: Vec<u8>,
+    pub y: Vec<u8>,
+}
+
+impl CosePublicKey {
+    /// Parses a CBOR-encoded EC2 COSE_Key, rejecting any key family/curve
+    /// other than P-256 -- the only curve Crescent's ECDSA device binding
+    /// (see `device.rs`) and the `p256` verifying key below understand.
+    pub fn from_cbor(data: &[u8]) -> Result<Self, Box<dyn Error>> {
+        let (major, minor) = major_minor(data, 0)?;
+        if major != 5 {
+            return_error!("COSE_Key is not a CBOR map");
+        }
+        let (len, mut pos) = read_length(data, 0, minor)?;
+
+        let (mut kty, mut crv, mut x, mut y) = (None, None, None, None);
+        for _ in 0..len {
+            let (key, value_pos) = read_int(data, pos)?;
+            match key {
+                1 => {
+                    let (v, next) = read_int(data, value_pos)?;
+                    kty = Some(v);
+                    pos = next;
+                }
+                -1 => {
+                    let (v, next) = read_int(data, value_pos)?;
+                    crv = Some(v);
+                    pos = next;
+                }
+                -2 => {
+                    let (bytes, next) = read_bstr(data, value_pos)?;
+                    x = Some(bytes.to_vec());
+                    pos = next;
+                }
+                -3 => {
+                    let (bytes, next) = read_bstr(data, value_pos)?;
+                    y = Some(bytes.to_vec());
+                    pos = next;
+                }
+                _ => pos = skip_item(data, value_pos)?,
+            }
+        }
+
+        if kty != Some(COSE_KTY_EC2) {
+            return_error!("COSE_Key is not an EC2 key");
+        }
+        if crv != Some(COSE_CRV_P256) {
+            return_error!("COSE_Key does not use the P-256 curve");
+        }
+        let x = x.ok_or("COSE_Key is missing the 'x' coordinate")?;
+        let y = y.ok_or("COSE_Key is missing the 'y' coordinate")?;
+
+        Ok(CosePublicKey { x, y })
+    }
+
+    fn to_verifying_key(&self) -> Result<VerifyingKey, Box<dyn Error>> {
+        let point = EncodedPoint::from_affine_coordinates(
+            self.x.as_slice().into(),
+            self.y.as_slice().into(),
+            false,
+        );
+        Ok(VerifyingKey::from_encoded_point(&point)?)
+    }
+}
+
+/// Checks `assertion`'s `clientDataJSON`/`authenticatorData` against
+/// `presentation_message`, `rp_id_hash` and the required flags -- everything
+/// `verify_assertion` checks except the signature itself -- and returns
+/// `authenticatorData || sha256(clientDataJSON)`, the data the authenticator
+/// actually signs.
+fn check_assertion_message(
+    assertion: &Assertion,
+    presentation_message: &[u8],
+    rp_id_hash: &[u8],
+    up_required: bool,
+    uv_required: bool,
+) -> Result<Vec<u8>, Box<dyn Error>> {
+    let client_data: ClientData = serde_json::from_slice(&assertion.client_data_json)
+        .map_err(|e| format!("clientDataJSON is not valid JSON: {}", e))?;
+    let challenge = base64_url::decode(&client_data.challenge)
+        .map_err(|e| format!("clientDataJSON 'challenge' is not valid base64url: {}", e))?;
+    if challenge != presentation_message {
+        return_error!("Authenticator assertion's challenge does not match the proof's presentation message");
+    }
+
+    let actual_rp_id_hash = assertion
+        .authenticator_data
+        .get(0..32)
+        .ok_or("authenticatorData is too short to contain an RP ID hash")?;
+    if actual_rp_id_hash != rp_id_hash {
+        return_error!("Authenticator assertion's RP ID hash does not match the expected relying party");
+    }
+
+    let flags = *assertion
+        .authenticator_data
+        .get(32)
+        .ok_or("authenticatorData is too short to contain a flags byte")?;
+    if up_required && flags & FLAG_USER_PRESENT == 0 {
+        return_error!("Authenticator assertion does not have the user-presence flag set");
+    }
+    if uv_required && flags & FLAG_USER_VERIFIED == 0 {
+        return_error!("Authenticator assertion does not have the user-verification flag set");
+    }
+
+    let client_data_hash = Sha256::digest(&assertion.client_data_json);
+    let mut signed_data = assertion.authenticator_data.clone();
+    signed_data.extend_from_slice(&client_data_hash);
+    Ok(signed_data)
+}
+
+fn verify_assertion_with_key(
+    assertion: &Assertion,
+    presentation_message: &[u8],
+    verifying_key: &VerifyingKey,
+    rp_id_hash: &[u8],
+    up_required: bool,
+    uv_required: bool,
+) -> Result<(), Box<dyn Error>> {
+    let signed_data = check_assertion_message(assertion, presentation_message, rp_id_hash, up_required, uv_required)?;
+
+    let signature = Signature::from_der(&assertion.signature)
+        .or_else(|_| Signature::from_slice(&assertion.signature))
+        .map_err(|e| format!("Authenticator assertion signature is malformed: {}", e))?;
+    verifying_key
+        .verify(&signed_data, &signature)
+        .map_err(|_| "Authenticator assertion signature failed to verify")?;
+
+    Ok(())
+}
+
+/// Verifies a CTAP2 assertion as a holder-of-device proof for
+/// `presentation_message`: the client's reported challenge must equal
+/// `presentation_message`, the authenticator data's RP ID hash must equal
+/// `rp_id_hash`, the user-presence/verification flags must satisfy
+/// `(up_required, uv_required)`, and the signature over
+/// `authenticatorData || sha256(clientDataJSON)` must verify against
+/// `device_public_key`.
+pub fn verify_assertion(
+    assertion: &Assertion,
+    presentation_message: &[u8],
+    device_public_key: &CosePublicKey,
+    rp_id_hash: &[u8],
+    up_required: bool,
+    uv_required: bool,
+) -> Result<(), Box<dyn Error>> {
+    let verifying_key = device_public_key.to_verifying_key()?;
+    verify_assertion_with_key(assertion, presentation_message, &verifying_key, rp_id_hash, up_required, uv_required)
+}
+
+/// A `device_bound` show proof's device-binding proof in
+/// [`DeviceBindingMode::WebAuthn`] mode: a real CTAP2 authenticator
+/// assertion, verified against the P-256 public key recovered from the
+/// show proof's opened `device_key_0`/`device_key_1` commitments.
+///
+/// Unlike [`DeviceBindingMode::RawEcdsa`] (see `device::DeviceProof`), this
+/// does not keep the device's public key hidden: `com0`/`com1` are opened
+/// (their committed message/randomness revealed) rather than proved correct
+/// in zero knowledge, trading away privacy of the device key for support of
+/// real hardware authenticators, which can't produce a signature the
+/// `device::DeviceProof` SNARK circuit understands. Only the committed
+/// inputs' x-coordinate limbs are opened; since the y-coordinate isn't
+/// separately committed to, `verify` recovers it from the curve equation by
+/// trying both parities and accepting whichever one makes the assertion's
+/// signature verify.
+///
+/// [`DeviceBindingMode::WebAuthn`]: crate::DeviceBindingMode::WebAuthn
+/// [`DeviceBindingMode::RawEcdsa`]: crate::DeviceBindingMode::RawEcdsa
+#[derive(Clone, Debug, CanonicalSerialize, CanonicalDeserialize)]
+pub struct WebAuthnDeviceBinding<G: CurveGroup> {
+    pub assertion: Assertion,
+    pub com0: PedersenOpening<G>,
+    pub com1: PedersenOpening<G>,
+}
+
+impl<G: CurveGroup> WebAuthnDeviceBinding<G>
+where
+    G::ScalarField: PrimeField,
+{
+    /// Verifies that `com0`/`com1` open to the same commitments the show
+    /// proof committed `device_key_0`/`device_key_1` to (under `bases0`/
+    /// `bases1`), recombines their opened messages into the device's P-256
+    /// public key x-coordinate (see `ecdsa_pop::ECDSAProof::split_public_key_x`),
+    /// and checks `assertion` against the resulting key.
+    #[allow(clippy::too_many_arguments)]
+    pub fn verify(
+        &self,
+        com0: &G::Affine,
+        com1: &G::Affine,
+        bases0: &[G::Affine],
+        bases1: &[G::Affine],
+        presentation_message: &[u8],
+        rp_id_hash: &[u8],
+        up_required: bool,
+        uv_required: bool,
+    ) -> bool {
+        if self.com0.bases != bases0 || self.com1.bases != bases1 {
+            return false;
+        }
+        if self.com0.c.into_affine() != *com0 || self.com1.c.into_affine() != *com1 {
+            return false;
+        }
+        if self.com0.c != bases0[0] * self.com0.m + bases0[1] * self.com0.r {
+            return false;
+        }
+        if self.com1.c != bases1[0] * self.com1.m + bases1[1] * self.com1.r {
+            return false;
+        }
+
+        let q0 = scalar_to_biguint(&self.com0.m);
+        let q1 = scalar_to_biguint(&self.com1.m);
+        let x = q0 + (q1 << 128);
+        let mut x_bytes = x.to_bytes_be();
+        if x_bytes.len() > 32 {
+            return false;
+        }
+        while x_bytes.len() < 32 {
+            x_bytes.insert(0, 0);
+        }
+
+        // Only the x-coordinate is committed to; recover the matching y by
+        // trying both parities and accepting whichever verifies the
+        // signature.
+        for prefix in [0x02u8, 0x03u8] {
+            let mut sec1 = Vec::with_capacity(33);
+            sec1.push(prefix);
+            sec1.extend_from_slice(&x_bytes);
+            let point = match EncodedPoint::from_bytes(&sec1) {
+                Ok(p) => p,
+                Err(_) => continue,
+            };
+            let verifying_key = match VerifyingKey::from_encoded_point(&point) {
+                Ok(vk) => vk,
+                Err(_) => continue,
+            };
+            if verify_assertion_with_key(&self.assertion, presentation_message, &verifying_key, rp_id_hash, up_required, uv_required).is_ok() {
+                return true;
+            }
+        }
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_bn254::Bn254;
+    use ark_ec::pairing::Pairing;
+    use p256::ecdsa::signature::Signer;
+    use p256::ecdsa::SigningKey;
+    use rand::thread_rng;
+
+    use crate::dlog::DLogPoK;
+    use crate::utils::biguint_to_scalar;
+
+    type G1 = <Bn254 as Pairing>::G1;
+    type G1Affine = <Bn254 as Pairing>::G1Affine;
+    type F = <Bn254 as Pairing>::ScalarField;
+
+    fn example_rp_id_hash() -> Vec<u8> {
+        Sha256::digest(b"example.com").to_vec()
+    }
+
+    fn sample_authenticator_data(flags: u8) -> Vec<u8> {
+        let mut data = vec![0u8; 37];
+        data[0..32].copy_from_slice(&example_rp_id_hash());
+        data[32] = flags;
+        data
+    }
+
+    fn sign_assertion(signing_key: &SigningKey, authenticator_data: &[u8], client_data_json: &[u8]) -> Vec<u8> {
+        let client_data_hash = Sha256::digest(client_data_json);
+        let mut signed_data = authenticator_data.to_vec();
+        signed_data.extend_from_slice(&client_data_hash);
+        let signature: Signature = signing_key.sign(&signed_data);
+        signature.to_der().as_bytes().to_vec()
+    }
+
+    fn cose_key_for(signing_key: &SigningKey) -> CosePublicKey {
+        let point = signing_key.verifying_key().to_encoded_point(false);
+        CosePublicKey {
+            x: point.x().unwrap().to_vec(),
+            y: point.y().unwrap().to_vec(),
+        }
+    }
+
+    #[test]
+    fn test_verify_assertion_accepts_matching_challenge_and_signature() {
+        let signing_key = SigningKey::random(&mut thread_rng());
+        let device_public_key = cose_key_for(&signing_key);
+        let challenge = b"some presentation message";
+        let client_data_json = format!(
+            r#"{{"type":"webauthn.get","challenge":"{}","origin":"https://example.com"}}"#,
+            base64_url::encode(challenge)
+        ).into_bytes();
+        let authenticator_data = sample_authenticator_data(FLAG_USER_PRESENT | FLAG_USER_VERIFIED);
+        let signature = sign_assertion(&signing_key, &authenticator_data, &client_data_json);
+
+        let assertion = Assertion { authenticator_data, client_data_json, signature };
+        let result = verify_assertion(&assertion, challenge, &device_public_key, &example_rp_id_hash(), true, true);
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_verify_assertion_rejects_rp_id_mismatch() {
+        let signing_key = SigningKey::random(&mut thread_rng());
+        let device_public_key = cose_key_for(&signing_key);
+        let challenge = b"some presentation message";
+        let client_data_json = format!(
+            r#"{{"type":"webauthn.get","challenge":"{}","origin":"https://example.com"}}"#,
+            base64_url::encode(challenge)
+        ).into_bytes();
+        let authenticator_data = sample_authenticator_data(FLAG_USER_PRESENT | FLAG_USER_VERIFIED);
+        let signature = sign_assertion(&signing_key, &authenticator_data, &client_data_json);
+
+        let assertion = Assertion { authenticator_data, client_data_json, signature };
+        let result = verify_assertion(&assertion, challenge, &device_public_key, &Sha256::digest(b"other-site.example").to_vec(), true, true);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_verify_assertion_rejects_challenge_mismatch() {
+        let signing_key = SigningKey::random(&mut thread_rng());
+        let device_public_key = cose_key_for(&signing_key);
+        let client_data_json = format!(
+            r#"{{"type":"webauthn.get","challenge":"{}","origin":"https://example.com"}}"#,
+            base64_url::encode(b"wrong challenge")
+        ).into_bytes();
+        let authenticator_data = sample_authenticator_data(FLAG_USER_PRESENT);
+        let signature = sign_assertion(&signing_key, &authenticator_data, &client_data_json);
+
+        let assertion = Assertion { authenticator_data, client_data_json, signature };
+        let result = verify_assertion(&assertion, b"some presentation message", &device_public_key, &example_rp_id_hash(), true, false);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_verify_assertion_rejects_missing_user_verification() {
+        let signing_key = SigningKey::random(&mut thread_rng());
+        let device_public_key = cose_key_for(&signing_key);
+        let challenge = b"some presentation message";
+        let client_data_json = format!(
+            r#"{{"type":"webauthn.get","challenge":"{}","origin":"https://example.com"}}"#,
+            base64_url::encode(challenge)
+        ).into_bytes();
+        // User present, but not verified.
+        let authenticator_data = sample_authenticator_data(FLAG_USER_PRESENT);
+        let signature = sign_assertion(&signing_key, &authenticator_data, &client_data_json);
+
+        let assertion = Assertion { authenticator_data, client_data_json, signature };
+        let result = verify_assertion(&assertion, challenge, &device_public_key, &example_rp_id_hash(), true, true);
+        assert!(result.is_err());
+    }
+
+    fn sample_webauthn_device_binding(signing_key: &SigningKey) -> (WebAuthnDeviceBinding<G1>, Vec<G1Affine>) {
+        let point = signing_key.verifying_key().to_encoded_point(false);
+        let qx = BigUint::from_bytes_be(point.x().unwrap());
+        let (q0, q1) = ecdsa_pop::ECDSAProof::split_public_key_x(&qx);
+        let q0 = biguint_to_scalar::<F>(&q0);
+        let q1 = biguint_to_scalar::<F>(&q1);
+
+        let bases = DLogPoK::<G1>::derive_pedersen_bases();
+        let com0 = DLogPoK::<G1>::pedersen_commit(&q0, &bases);
+        let com1 = DLogPoK::<G1>::pedersen_commit(&q1, &bases);
+
+        let challenge = b"some presentation message";
+        let client_data_json = format!(
+            r#"{{"type":"webauthn.get","challenge":"{}","origin":"https://example.com"}}"#,
+            base64_url::encode(challenge)
+        ).into_bytes();
+        let authenticator_data = sample_authenticator_data(FLAG_USER_PRESENT | FLAG_USER_VERIFIED);
+        let signature = sign_assertion(signing_key, &authenticator_data, &client_data_json);
+        let assertion = Assertion { authenticator_data, client_data_json, signature };
+
+        (WebAuthnDeviceBinding { assertion, com0, com1 }, bases)
+    }
+
+    #[test]
+    fn test_webauthn_device_binding_accepts_valid_assertion() {
+        let signing_key = SigningKey::random(&mut thread_rng());
+        let (binding, bases) = sample_webauthn_device_binding(&signing_key);
+
+        let valid = binding.verify(
+            &binding.com0.c.into_affine(),
+            &binding.com1.c.into_affine(),
+            &bases,
+            &bases,
+            b"some presentation message",
+            &example_rp_id_hash(),
+            true,
+            true,
+        );
+        assert!(valid);
+    }
+
+    #[test]
+    fn test_webauthn_device_binding_rejects_wrong_commitment() {
+        let signing_key = SigningKey::random(&mut thread_rng());
+        let (binding, bases) = sample_webauthn_device_binding(&signing_key);
+        let other_signing_key = SigningKey::random(&mut thread_rng());
+        let (other_binding, _) = sample_webauthn_device_binding(&other_signing_key);
+
+        let valid = binding.verify(
+            &other_binding.com0.c.into_affine(),
+            &binding.com1.c.into_affine(),
+            &bases,
+            &bases,
+            b"some presentation message",
+            &example_rp_id_hash(),
+            true,
+            true,
+        );
+        assert!(!valid);
+    }
+}
@@ -1,9 +1,10 @@
 //! This module implements various elliptic curve gadgets
 #![allow(non_snake_case)]
 use crate::utils::{
-  alloc_num_equals, alloc_one, alloc_zero, conditionally_select, conditionally_select2,
-  select_num_or_one, select_num_or_zero, select_num_or_zero2, select_one_or_diff2,
-  select_one_or_num2, select_zero_or_num2,
+  alloc_num_equals, alloc_one, alloc_zero, biguint_to_scalar, conditionally_select,
+  conditionally_select2, enforce_equal, hex_to_ff, scalar_to_bigint, select_num_or_one,
+  select_num_or_zero, select_num_or_zero2, select_one_or_diff2, select_one_or_num2,
+  select_zero_or_num2,
 };
 use bellpepper::gadgets::Assignment;
 use bellpepper_core::{
@@ -11,22 +12,212 @@ use bellpepper_core::{
   num::AllocatedNum,
   ConstraintSystem, SynthesisError,
 };
+use core::marker::PhantomData;
 use ff::{PrimeField, PrimeFieldBits};
+use num_bigint::BigUint;
+use num_traits::ToPrimitive;
+
+// P-256's `b` curve parameter from the Weierstrass equation y² = x³ − 3x + b.
+const P256_B_HEX: &str = "5ac635d8aa3a93e7b3ebbd55769886bc651d06b0cc53b0f63bce3c3e27d2604b";
+
+// Returns `b`'s value as a field element (1 for true, 0 for false), or
+// `None` if `b`'s value isn't known (e.g. during circuit shape synthesis).
+fn boolean_field_value<Scalar: PrimeField>(b: &Boolean) -> Option<Scalar> {
+  b.get_value().map(|v| if v { Scalar::ONE } else { Scalar::ZERO })
+}
+
+// Width-`window` wNAF recoding of `value` (a nonnegative integer in the
+// scalar field's canonical range): `num_windows` signed digits, index `i`
+// holding the digit for position `2^(window * i)`, each odd-or-zero and
+// bounded by `2^(window - 1) - 1` in absolute value. Runs outside the
+// circuit -- it only produces the witness the circuit below constrains.
+fn compute_wnaf_digits(value: Option<num_bigint::BigInt>, window: u32, num_windows: usize) -> Vec<Option<i64>> {
+  let Some(mut k) = value else {
+    return vec![None; num_windows];
+  };
+
+  let radix = num_bigint::BigInt::from(1u64) << window;
+  let half = num_bigint::BigInt::from(1u64) << (window - 1);
+  let two = num_bigint::BigInt::from(2u8);
+
+  let mut digits = Vec::with_capacity(num_windows);
+  for _ in 0..num_windows {
+    let is_odd = &k % &two != num_bigint::BigInt::from(0u8);
+    let digit = if is_odd {
+      let r = &k % &radix;
+      if r < half {
+        r
+      } else {
+        r - &radix
+      }
+    } else {
+      num_bigint::BigInt::from(0u8)
+    };
+    k -= &digit;
+    k /= &two;
+    digits.push(Some(digit.to_i64().expect("wNAF digit fits in i64")));
+  }
+  digits
+}
+
+// Allocates and constrains the product of two `Boolean`s.
+fn mul_booleans<Scalar, CS>(
+  mut cs: CS,
+  a: &Boolean,
+  b: &Boolean,
+) -> Result<AllocatedNum<Scalar>, SynthesisError>
+where
+  Scalar: PrimeField,
+  CS: ConstraintSystem<Scalar>,
+{
+  let value = boolean_field_value::<Scalar>(a).zip(boolean_field_value::<Scalar>(b)).map(|(a, b)| a * b);
+  let product =
+    AllocatedNum::alloc(cs.namespace(|| "product"), || value.ok_or(SynthesisError::AssignmentMissing))?;
+  cs.enforce(
+    || "check product",
+    |_| a.lc(CS::one(), Scalar::ONE),
+    |_| b.lc(CS::one(), Scalar::ONE),
+    |lc| lc + product.get_variable(),
+  );
+  Ok(product)
+}
+
+// Allocates and constrains the product of an `AllocatedNum` and a `Boolean`.
+fn mul_num_boolean<Scalar, CS>(
+  mut cs: CS,
+  a: &AllocatedNum<Scalar>,
+  b: &Boolean,
+) -> Result<AllocatedNum<Scalar>, SynthesisError>
+where
+  Scalar: PrimeField,
+  CS: ConstraintSystem<Scalar>,
+{
+  let value = a.get_value().zip(boolean_field_value::<Scalar>(b)).map(|(a, b)| a * b);
+  let product =
+    AllocatedNum::alloc(cs.namespace(|| "product"), || value.ok_or(SynthesisError::AssignmentMissing))?;
+  cs.enforce(
+    || "check product",
+    |lc| lc + a.get_variable(),
+    |_| b.lc(CS::one(), Scalar::ONE),
+    |lc| lc + product.get_variable(),
+  );
+  Ok(product)
+}
+
+// Selects one of 8 constants by 3-bit lookup, sapling-crypto's `lookup3_xy`
+// technique: since the 8 values are constants known at synthesis time, the
+// selected value is just a linear combination of the booleans and their
+// pairwise/triple products (`p01`, `p02`, `p12`, `p012`) with constant
+// coefficients -- no inversion, and no per-entry gate beyond the one
+// multiplication constraint each of `p01`/`p02`/`p12`/`p012` already costs.
+#[allow(clippy::too_many_arguments)]
+fn select_constant_by_bits3<Scalar, CS>(
+  mut cs: CS,
+  b0: &Boolean,
+  b1: &Boolean,
+  b2: &Boolean,
+  p01: &AllocatedNum<Scalar>,
+  p02: &AllocatedNum<Scalar>,
+  p12: &AllocatedNum<Scalar>,
+  p012: &AllocatedNum<Scalar>,
+  consts: &[Scalar; 8],
+) -> Result<AllocatedNum<Scalar>, SynthesisError>
+where
+  Scalar: PrimeField,
+  CS: ConstraintSystem<Scalar>,
+{
+  let [c0, c1, c2, c3, c4, c5, c6, c7] = *consts;
+  let coeff_b0 = c1 - c0;
+  let coeff_b1 = c2 - c0;
+  let coeff_b2 = c4 - c0;
+  let coeff_b0b1 = c3 - c2 - c1 + c0;
+  let coeff_b0b2 = c5 - c4 - c1 + c0;
+  let coeff_b1b2 = c6 - c4 - c2 + c0;
+  let coeff_b0b1b2 = c7 - c6 - c5 - c3 + c4 + c2 + c1 - c0;
+
+  let value = (|| {
+    Some(
+      c0 + boolean_field_value::<Scalar>(b0)? * coeff_b0
+        + boolean_field_value::<Scalar>(b1)? * coeff_b1
+        + boolean_field_value::<Scalar>(b2)? * coeff_b2
+        + p01.get_value()? * coeff_b0b1
+        + p02.get_value()? * coeff_b0b2
+        + p12.get_value()? * coeff_b1b2
+        + p012.get_value()? * coeff_b0b1b2,
+    )
+  })();
+
+  let out = AllocatedNum::alloc(cs.namespace(|| "select"), || value.ok_or(SynthesisError::AssignmentMissing))?;
+
+  cs.enforce(
+    || "check select",
+    |lc| {
+      lc + (c0, CS::one())
+        + b0.lc(CS::one(), coeff_b0)
+        + b1.lc(CS::one(), coeff_b1)
+        + b2.lc(CS::one(), coeff_b2)
+        + (coeff_b0b1, p01.get_variable())
+        + (coeff_b0b2, p02.get_variable())
+        + (coeff_b1b2, p12.get_variable())
+        + (coeff_b0b1b2, p012.get_variable())
+        - out.get_variable()
+    },
+    |lc| lc + CS::one(),
+    |lc| lc,
+  );
+
+  Ok(out)
+}
+
+/// Short-Weierstrass curve constants from `y² = x³ + Ax + B`, needed by the
+/// point-arithmetic gadgets below. `AllocatedPoint`/`AllocatedPointNonInfinity`
+/// are generic over this so the same circuit code serves any curve of this
+/// shape (P-256 today, others by adding an impl) instead of hardcoding
+/// P-256's `A = -3`. Since `A`/`B` are Rust-level constants rather than
+/// circuit witnesses, folding them into the linear combinations below costs
+/// nothing extra regardless of their value -- there is no separate "fast
+/// path" to special-case for `A == -3`; it just falls out of the existing
+/// constraint shape.
+pub trait CurveParams<Scalar: PrimeField> {
+  /// The curve's `A` coefficient.
+  fn a() -> Scalar;
+  /// The curve's `B` coefficient.
+  fn b() -> Scalar;
+}
+
+/// P-256 (secp256r1): `y² = x³ − 3x + b`.
+#[derive(Clone, Copy)]
+pub struct P256Params;
+
+impl<Scalar: PrimeField> CurveParams<Scalar> for P256Params {
+  fn a() -> Scalar {
+    -Scalar::from(3)
+  }
+
+  fn b() -> Scalar {
+    hex_to_ff::<Scalar>(P256_B_HEX)
+  }
+}
 
 /// `AllocatedPoint` provides an elliptic curve abstraction inside a circuit.
+/// Generic over the curve's parameters via `C`, defaulting to `P256Params`
+/// so existing call sites that write `AllocatedPoint<Scalar>` keep compiling
+/// unchanged.
 #[derive(Clone)]
-pub struct AllocatedPoint<Scalar>
+pub struct AllocatedPoint<Scalar, C = P256Params>
 where
   Scalar: PrimeField,
 {
   pub(crate) x: AllocatedNum<Scalar>,
   pub(crate) y: AllocatedNum<Scalar>,
   pub(crate) is_infinity: AllocatedNum<Scalar>,
+  _curve: PhantomData<C>,
 }
 
-impl<Scalar> AllocatedPoint<Scalar>
+impl<Scalar, C> AllocatedPoint<Scalar, C>
 where
   Scalar: PrimeField + PrimeFieldBits,
+  C: CurveParams<Scalar>,
 {
   /// Allocates a new point on the curve using coordinates provided by
   /// `coords`. If coords = None, it allocates the default infinity point
@@ -57,7 +248,52 @@ where
       |lc| lc,
     );
 
-    Ok(AllocatedPoint { x, y, is_infinity })
+    Ok(AllocatedPoint {
+      x,
+      y,
+      is_infinity,
+      _curve: PhantomData,
+    })
+  }
+
+  /// Enforces that `(x, y)` satisfies the curve equation `y² = x³ + Ax + B`
+  /// whenever `is_infinity = 0` (no constraint otherwise, since the identity
+  /// has no valid affine representative). This is what `alloc` is missing:
+  /// on its own it only constrains `is_infinity` to be a bit, so a malicious
+  /// witness can feed off-curve points and have the add/double laws silently
+  /// produce garbage.
+  pub fn check_on_curve<CS: ConstraintSystem<Scalar>>(&self, mut cs: CS) -> Result<(), SynthesisError> {
+    let a = C::a();
+    let b = C::b();
+
+    let x_sq = self.x.square(cs.namespace(|| "x^2"))?;
+    let x_cubed = x_sq.mul(cs.namespace(|| "x^3"), &self.x)?;
+    let y_sq = self.y.square(cs.namespace(|| "y^2"))?;
+
+    cs.enforce(
+      || "(1 - is_infinity) * (y^2 - x^3 - Ax - b) = 0",
+      |lc| lc + CS::one() - self.is_infinity.get_variable(),
+      |lc| {
+        lc + y_sq.get_variable() - x_cubed.get_variable() - (a, self.x.get_variable())
+          - (b, CS::one())
+      },
+      |lc| lc,
+    );
+
+    Ok(())
+  }
+
+  /// Like `alloc`, but additionally constrains the point to lie on the
+  /// curve via `check_on_curve`. Use this instead of `alloc` whenever the
+  /// coordinates come from an untrusted prover -- e.g. importing the public
+  /// key `Q` or signature point `R` in the SD example app.
+  pub fn alloc_checked<CS>(mut cs: CS, coords: Option<(Scalar, Scalar, bool)>) -> Result<Self, SynthesisError>
+  where
+    CS: ConstraintSystem<Scalar>,
+  {
+    let point = Self::alloc(cs.namespace(|| "alloc"), coords)?;
+    point.check_on_curve(cs.namespace(|| "check on curve"))?;
+    Ok(point)
   }
 
   pub fn inputize<CS: ConstraintSystem<Scalar>>(&self, mut cs: CS) -> Result<(), SynthesisError> {
@@ -79,6 +315,7 @@ where
       x: zero.clone(),
       y: zero,
       is_infinity: one,
+      _curve: PhantomData,
     })
   }
 
@@ -109,6 +346,7 @@ where
       x: self.x.clone(),
       y,
       is_infinity: self.is_infinity.clone(),
+      _curve: PhantomData,
     })
   }
 
@@ -116,7 +354,7 @@ where
   pub fn add<CS: ConstraintSystem<Scalar>>(
     &self,
     mut cs: CS,
-    other: &AllocatedPoint<Scalar>,
+    other: &AllocatedPoint<Scalar, C>,
   ) -> Result<Self, SynthesisError> {
     // Compute boolean equal indicating if self = other
 
@@ -166,7 +404,7 @@ where
   pub fn add_internal<CS: ConstraintSystem<Scalar>>(
     &self,
     mut cs: CS,
-    other: &AllocatedPoint<Scalar>,
+    other: &AllocatedPoint<Scalar, C>,
     equal_x: &AllocatedBit,
   ) -> Result<Self, SynthesisError> {
     //************************************************************************/
@@ -323,7 +561,12 @@ where
       &self.is_infinity,
     )?;
 
-    Ok(Self { x, y, is_infinity })
+    Ok(Self {
+      x,
+      y,
+      is_infinity,
+      _curve: PhantomData,
+    })
   }
 
 
@@ -345,15 +588,16 @@ where
     );
     let denom = select_one_or_num2(cs.namespace(|| "denom"), &denom_actual, &self.is_infinity)?;
 
-    // Compute `numerator = x^2 + a`,  ASSUMES A = -3 (True for P256r1)
+    // Compute `numerator = 3x^2 + a`
+    let a = C::a();
     let numerator = AllocatedNum::alloc(cs.namespace(|| "alloc numerator"), || {
-      Ok(Scalar::from(3) * self.x.get_value().get()? * self.x.get_value().get()? - Scalar::from(3))
+      Ok(Scalar::from(3) * self.x.get_value().get()? * self.x.get_value().get()? + a)
     })?;
     cs.enforce(
       || "Check numerator",
       |lc| lc + (Scalar::from(3), self.x.get_variable()),
       |lc| lc + self.x.get_variable(),
-      |lc| lc + numerator.get_variable() + CS::one() + CS::one() + CS::one(), 
+      |lc| lc + numerator.get_variable() - (a, CS::one()),
     );
 
     let lambda = AllocatedNum::alloc(cs.namespace(|| "alloc lambda"), || {
@@ -422,7 +666,12 @@ where
     // is_infinity
     let is_infinity = self.is_infinity.clone();
 
-    Ok(Self { x, y, is_infinity })
+    Ok(Self {
+      x,
+      y,
+      is_infinity,
+      _curve: PhantomData,
+    })
   }
 
   /// A gadget for scalar multiplication, optimized to use incomplete addition
@@ -505,6 +754,7 @@ where
       x,
       y,
       is_infinity: res.is_infinity,
+      _curve: PhantomData,
     };
     let mut p_complete = p.to_allocated_point(&self.is_infinity)?;
 
@@ -523,6 +773,361 @@ where
     Ok(acc)
   }
 
+  /// Fixed-base windowed scalar multiplication: eliminates all in-circuit
+  /// doublings by having the host precompute the fixed base's multiples.
+  /// `scalar_bits` (little-endian) are split into 3-bit windows, and
+  /// `window_table` holds `{k * 2^{3i} * G : k = 0..7}` for each window `i`,
+  /// packed consecutively (`window_table.len()` must be
+  /// `8 * ceil(scalar_bits.len() / 3)`). Use this for the ECDSA verification
+  /// equation's `u1 * G` term, since `G` is fixed and its multiples don't
+  /// depend on the witness.
+  pub fn fixed_base_scalar_mul<CS: ConstraintSystem<Scalar>>(
+    mut cs: CS,
+    scalar_bits: &[Boolean],
+    window_table: &[(Scalar, Scalar)],
+  ) -> Result<Self, SynthesisError> {
+    let num_windows = (scalar_bits.len() + 2) / 3;
+    assert_eq!(
+      window_table.len(),
+      num_windows * 8,
+      "window_table must hold 8 precomputed points per 3-bit window"
+    );
+
+    let zero = Boolean::constant(false);
+    let mut acc = Self::default(cs.namespace(|| "acc default"))?;
+    for i in 0..num_windows {
+      let mut cs = cs.namespace(|| format!("window {i}"));
+      let b0 = scalar_bits.get(3 * i).unwrap_or(&zero).clone();
+      let b1 = scalar_bits.get(3 * i + 1).unwrap_or(&zero).clone();
+      let b2 = scalar_bits.get(3 * i + 2).unwrap_or(&zero).clone();
+
+      let table = &window_table[8 * i..8 * i + 8];
+      let window_point = AllocatedPointNonInfinity::select_from_table(
+        cs.namespace(|| "select point"),
+        &b0,
+        &b1,
+        &b2,
+        table,
+      )?;
+
+      // k = 0 (all three bits zero) is table[0]'s identity placeholder;
+      // gate the selected point to infinity in that case instead of
+      // trusting table[0] to be a valid affine point.
+      let not_b0_and_not_b1 = Boolean::and(cs.namespace(|| "not b0 and not b1"), &b0.not(), &b1.not())?;
+      let is_identity = Boolean::and(cs.namespace(|| "is identity"), &not_b0_and_not_b1, &b2.not())?;
+      let zero_is_infinity = alloc_zero(cs.namespace(|| "zero is_infinity"))?;
+      let window_point = window_point.to_allocated_point(&zero_is_infinity)?;
+      let gated_point = AllocatedPoint::select_point_or_infinity(
+        cs.namespace(|| "gate identity"),
+        &window_point,
+        &is_identity.not(),
+      )?;
+
+      acc = acc.add(cs.namespace(|| "accumulate"), &gated_point)?;
+    }
+
+    Ok(acc)
+  }
+
+  /// Strauss-Shamir simultaneous double-and-add: computes `s1 * p1 + s2 * p2`
+  /// for ECDSA verification's `u1 * G + u2 * Q` in one interleaved pass
+  /// instead of two independent [`scalar_mul`](Self::scalar_mul) calls plus
+  /// a final [`add`](Self::add). Builds the combined table
+  /// `{O, p1, p2, p1 + p2}` once, then walks both scalars' bits together
+  /// from most to least significant: double the shared accumulator, then
+  /// add whichever table entry the current bit pair selects. That's one
+  /// doubling per bit position shared by both scalars, versus one doubling
+  /// per bit position *per* scalar in two separate `scalar_mul` calls --
+  /// roughly half as many.
+  ///
+  /// Uses the complete addition law throughout: unlike `scalar_mul`'s
+  /// single-base loop (which only ever adds two points it can assume are
+  /// never equal), the shared accumulator here can coincide with any of
+  /// the four table entries at any step -- e.g. when `p1 == p2`, or when
+  /// the running sum happens to land back on one of the precomputed
+  /// points -- so the incomplete law's "operands are never equal" premise
+  /// doesn't hold in general.
+  pub fn scalar_mul_multi<CS: ConstraintSystem<Scalar>>(
+    mut cs: CS,
+    terms: &[(Self, AllocatedNum<Scalar>)],
+  ) -> Result<Self, SynthesisError> {
+    assert_eq!(terms.len(), 2, "scalar_mul_multi currently supports exactly two terms");
+
+    let (p1, s1) = &terms[0];
+    let (p2, s2) = &terms[1];
+
+    let bits1 = s1.to_bits_le(cs.namespace(|| "s1 bits"))?;
+    let bits2 = s2.to_bits_le(cs.namespace(|| "s2 bits"))?;
+    assert_eq!(bits1.len(), bits2.len(), "scalars must decompose to the same bit length");
+
+    // Combined table, indexed by (bit1, bit2): {O, p1, p2, p1 + p2}.
+    let sum = p1.add(cs.namespace(|| "p1 + p2"), p2)?;
+    let zero = Self::default(cs.namespace(|| "table zero"))?;
+
+    let mut acc = zero.clone();
+    for i in (0..bits1.len()).rev() {
+      let mut cs = cs.namespace(|| format!("bit {i}"));
+
+      acc = acc.double(cs.namespace(|| "double"))?;
+
+      // Column selected by bit1: {O, p1} or {p2, p1 + p2}.
+      let column_b1_clear = Self::conditionally_select(
+        cs.namespace(|| "column for bit2 = 0"),
+        p1,
+        &zero,
+        &bits1[i].clone(),
+      )?;
+      let column_b1_set = Self::conditionally_select(
+        cs.namespace(|| "column for bit2 = 1"),
+        &sum,
+        p2,
+        &bits1[i].clone(),
+      )?;
+      // Row selected by bit2, landing on the one table entry the bit
+      // pair picks out.
+      let selected = Self::conditionally_select(
+        cs.namespace(|| "select table entry"),
+        &column_b1_set,
+        &column_b1_clear,
+        &bits2[i].clone(),
+      )?;
+
+      acc = acc.add(cs.namespace(|| "add"), &selected)?;
+    }
+
+    Ok(acc)
+  }
+
+  /// Width-4 signed-digit (wNAF-style) scalar multiplication: recodes `s`
+  /// into digits `d_i in {0, +-1, +-3, +-5, +-7}`, one per 4-bit window
+  /// (see [`compute_wnaf_digits`]), so most windows need no addition at
+  /// all instead of every bit needing one -- fewer constraints than
+  /// [`scalar_mul`](Self::scalar_mul)'s bit-at-a-time loop. The digits are
+  /// a host-supplied witness; the circuit only constrains that each is
+  /// well-formed (odd-or-zero magnitude selected from `{1, 3, 5, 7}`,
+  /// correctly signed) and that `sum d_i * 16^i = s`.
+  ///
+  /// The odd multiples `{P, 3P, 5P, 7P}` are built once via
+  /// `double_incomplete`/`add_incomplete` -- safe here, since they're all
+  /// nonzero multiples of the same point `P`, the same assumption
+  /// `scalar_mul` relies on for its main loop. The per-window
+  /// accumulation uses the complete law throughout instead: the
+  /// accumulator starts at the identity and can land back on any table
+  /// entry at any step, so the incomplete law's "operands are never
+  /// equal" premise can't be assumed there in general.
+  pub fn scalar_mul_wnaf<CS: ConstraintSystem<Scalar>>(
+    &self,
+    mut cs: CS,
+    s: &AllocatedNum<Scalar>,
+  ) -> Result<Self, SynthesisError> {
+    const WINDOW: u32 = 4;
+    const DIGIT_COUNT: usize = 1 << (WINDOW as usize - 2); // odd magnitudes {1, 3, 5, 7}
+    let num_windows = (Scalar::NUM_BITS as usize).div_ceil(WINDOW as usize);
+
+    let digit_values = compute_wnaf_digits(s.get_value().map(|v| scalar_to_bigint(&v)), WINDOW, num_windows);
+
+    // Precompute the odd multiples of `self`: {P, 3P, 5P, 7P}.
+    let base = AllocatedPointNonInfinity::from_allocated_point(self);
+    let double_base = base.double_incomplete(cs.namespace(|| "2P"))?;
+    let mut odd_multiples_ni = vec![base];
+    for k in 1..DIGIT_COUNT {
+      let next = odd_multiples_ni[k - 1]
+        .add_incomplete(cs.namespace(|| format!("{}P", 2 * k + 1)), &double_base)?;
+      odd_multiples_ni.push(next);
+    }
+    let odd_multiples = odd_multiples_ni
+      .into_iter()
+      .enumerate()
+      .map(|(k, pt)| {
+        let zero = alloc_zero(cs.namespace(|| format!("{}P is_infinity", 2 * k + 1)))?;
+        pt.to_allocated_point(&zero)
+      })
+      .collect::<Result<Vec<_>, _>>()?;
+
+    let mut acc = Self::default(cs.namespace(|| "acc default"))?;
+    let mut digit_terms: Vec<(Scalar, AllocatedNum<Scalar>)> = Vec::with_capacity(num_windows);
+
+    for position in (0..num_windows).rev() {
+      let mut cs = cs.namespace(|| format!("window {position}"));
+      let digit_value = digit_values[position];
+
+      for j in 0..WINDOW {
+        acc = acc.double(cs.namespace(|| format!("double {j}")))?;
+      }
+
+      // `k0, k1` select the magnitude `1 + 2*k0 + 4*k1 in {1, 3, 5, 7}`;
+      // `sign` negates it; `is_nonzero` gates the whole contribution to
+      // infinity when `d_i = 0`.
+      let magnitude_index = digit_value.map(|d| (d.unsigned_abs() as i64 - 1) / 2).unwrap_or(0);
+      let k0 = AllocatedBit::alloc(cs.namespace(|| "k0"), digit_value.map(|_| magnitude_index & 1 == 1))?;
+      let k1 = AllocatedBit::alloc(cs.namespace(|| "k1"), digit_value.map(|_| magnitude_index & 2 == 2))?;
+      let sign = AllocatedBit::alloc(cs.namespace(|| "sign"), digit_value.map(|d| d < 0))?;
+      let is_nonzero = AllocatedBit::alloc(cs.namespace(|| "is_nonzero"), digit_value.map(|d| d != 0))?;
+
+      let signed_magnitude = AllocatedNum::alloc(cs.namespace(|| "signed magnitude"), || {
+        let d = *digit_value.get()?;
+        let magnitude = Scalar::from(2 * magnitude_index.unsigned_abs() + 1);
+        Ok(if d < 0 { -magnitude } else { magnitude })
+      })?;
+      // `signed_magnitude = magnitude * (1 - 2*sign)`, rearranged to the
+      // `a * b = c` shape `cs.enforce` needs:
+      // `(2*magnitude) * sign = magnitude - signed_magnitude`.
+      cs.enforce(
+        || "signed magnitude = magnitude * (1 - 2*sign)",
+        |lc| lc + (Scalar::from(2), CS::one()) + (Scalar::from(4), k0.get_variable()) + (Scalar::from(8), k1.get_variable()),
+        |lc| lc + sign.get_variable(),
+        |lc| lc + (Scalar::ONE, CS::one()) + (Scalar::from(2), k0.get_variable()) + (Scalar::from(4), k1.get_variable()) - signed_magnitude.get_variable(),
+      );
+
+      let digit = AllocatedNum::alloc(cs.namespace(|| "digit"), || {
+        let d = *digit_value.get()?;
+        Ok(if d == 0 { Scalar::ZERO } else { *signed_magnitude.get_value().get()? })
+      })?;
+      cs.enforce(
+        || "digit = is_nonzero * signed_magnitude",
+        |lc| lc + is_nonzero.get_variable(),
+        |lc| lc + signed_magnitude.get_variable(),
+        |lc| lc + digit.get_variable(),
+      );
+
+      let coeff: Scalar = biguint_to_scalar(&(BigUint::from(1u8) << (WINDOW as usize * position)));
+      digit_terms.push((coeff, digit));
+
+      let col0 = AllocatedPoint::conditionally_select(
+        cs.namespace(|| "col k1=0"),
+        &odd_multiples[1],
+        &odd_multiples[0],
+        &Boolean::from(k0.clone()),
+      )?;
+      let col1 = AllocatedPoint::conditionally_select(
+        cs.namespace(|| "col k1=1"),
+        &odd_multiples[3],
+        &odd_multiples[2],
+        &Boolean::from(k0),
+      )?;
+      let selected_magnitude = AllocatedPoint::conditionally_select(
+        cs.namespace(|| "select magnitude"),
+        &col1,
+        &col0,
+        &Boolean::from(k1),
+      )?;
+      let negated = selected_magnitude.negate(cs.namespace(|| "negate"))?;
+      let signed_point = AllocatedPoint::conditionally_select(
+        cs.namespace(|| "apply sign"),
+        &negated,
+        &selected_magnitude,
+        &Boolean::from(sign),
+      )?;
+      let contribution = AllocatedPoint::select_point_or_infinity(
+        cs.namespace(|| "gate nonzero"),
+        &signed_point,
+        &Boolean::from(is_nonzero),
+      )?;
+
+      acc = acc.add(cs.namespace(|| "add"), &contribution)?;
+    }
+
+    cs.enforce(
+      || "digits reconstruct scalar",
+      |lc| lc + CS::one(),
+      |lc| {
+        let mut lc = lc;
+        for (coeff, digit) in digit_terms.iter() {
+          lc = lc + (*coeff, digit.get_variable());
+        }
+        lc - s.get_variable()
+      },
+      |lc| lc,
+    );
+
+    // Special-case `self.is_infinity` exactly like `scalar_mul`: the
+    // above is only meaningful when `self` is a real point.
+    let default = Self::default(cs.namespace(|| "default"))?;
+    let x = conditionally_select2(cs.namespace(|| "check self.is_infinity (x)"), &default.x, &acc.x, &self.is_infinity)?;
+    let y = conditionally_select2(cs.namespace(|| "check self.is_infinity (y)"), &default.y, &acc.y, &self.is_infinity)?;
+
+    Ok(Self {
+      x,
+      y,
+      is_infinity: self.is_infinity.clone(),
+      _curve: PhantomData,
+    })
+  }
+
+  /// Verifies an ECDSA-P256 signature `(r, s)` on message-hash scalar `z`
+  /// against public key `q`, wiring the gadgets above into the standard
+  /// verification equation: `R = u1*G + u2*Q` where `u1 = z*s⁻¹` and
+  /// `u2 = r*s⁻¹`, accepting iff `R` is not the point at infinity and
+  /// `R.x == r`.
+  ///
+  /// `s⁻¹` is an in-circuit witness constrained by `s * s⁻¹ = 1`, which
+  /// also rejects `s = 0` (unsatisfiable, since zero has no inverse). `u1*G`
+  /// is computed with [`fixed_base_scalar_mul`](Self::fixed_base_scalar_mul)
+  /// against the host-supplied `g_window_table` for `G`, since the
+  /// generator never changes across verifications; combining that with
+  /// `u2*Q` reuses [`scalar_mul_multi`](Self::scalar_mul_multi) (passing a
+  /// constant `1` alongside the already-computed `u1*G` folds the final
+  /// addition into the same gadget rather than a one-off `add`).
+  pub fn verify_ecdsa_p256<CS: ConstraintSystem<Scalar>>(
+    mut cs: CS,
+    q: &Self,
+    z: &AllocatedNum<Scalar>,
+    r: &AllocatedNum<Scalar>,
+    s: &AllocatedNum<Scalar>,
+    g_window_table: &[(Scalar, Scalar)],
+  ) -> Result<(), SynthesisError> {
+    let s_inv = AllocatedNum::alloc(cs.namespace(|| "s_inv"), || {
+      Ok((*s.get_value().get()?).invert().unwrap())
+    })?;
+    cs.enforce(
+      || "s * s_inv = 1",
+      |lc| lc + s.get_variable(),
+      |lc| lc + s_inv.get_variable(),
+      |lc| lc + CS::one(),
+    );
+
+    let u1 = AllocatedNum::alloc(cs.namespace(|| "u1 = z * s_inv"), || {
+      Ok(*z.get_value().get()? * s_inv.get_value().get()?)
+    })?;
+    cs.enforce(
+      || "u1 = z * s_inv",
+      |lc| lc + z.get_variable(),
+      |lc| lc + s_inv.get_variable(),
+      |lc| lc + u1.get_variable(),
+    );
+
+    let u2 = AllocatedNum::alloc(cs.namespace(|| "u2 = r * s_inv"), || {
+      Ok(*r.get_value().get()? * s_inv.get_value().get()?)
+    })?;
+    cs.enforce(
+      || "u2 = r * s_inv",
+      |lc| lc + r.get_variable(),
+      |lc| lc + s_inv.get_variable(),
+      |lc| lc + u2.get_variable(),
+    );
+
+    let u1_bits = u1.to_bits_le(cs.namespace(|| "u1 bits"))?;
+    let u1_g = Self::fixed_base_scalar_mul(cs.namespace(|| "u1*G"), &u1_bits, g_window_table)?;
+
+    let one = alloc_one(cs.namespace(|| "one"))?;
+    let result = Self::scalar_mul_multi(
+      cs.namespace(|| "u1*G + u2*Q"),
+      &[(u1_g, one), (q.clone(), u2)],
+    )?;
+
+    // Reject the point at infinity: it can never legitimately equal `r`.
+    cs.enforce(
+      || "result is not the point at infinity",
+      |lc| lc + result.is_infinity.get_variable(),
+      |lc| lc + CS::one(),
+      |lc| lc,
+    );
+
+    enforce_equal(cs.namespace(|| "R.x == r"), &result.x, r);
+
+    Ok(())
+  }
+
   /// If condition outputs a otherwise outputs b
   pub fn conditionally_select<CS: ConstraintSystem<Scalar>>(
     mut cs: CS,
@@ -541,7 +1146,12 @@ where
       condition,
     )?;
 
-    Ok(Self { x, y, is_infinity })
+    Ok(Self {
+      x,
+      y,
+      is_infinity,
+      _curve: PhantomData,
+    })
   }
 
   /// If condition outputs a otherwise infinity
@@ -560,15 +1170,20 @@ where
       condition,
     )?;
 
-    Ok(Self { x, y, is_infinity })
+    Ok(Self {
+      x,
+      y,
+      is_infinity,
+      _curve: PhantomData,
+    })
   }
 
   /// Compare two points and constrain them to be equal
   #[allow(dead_code)]
   pub fn enforce_equal<CS: ConstraintSystem<Scalar>>(
     mut cs: CS,
-    point1: &AllocatedPoint<Scalar>,
-    point2: &AllocatedPoint<Scalar>,
+    point1: &AllocatedPoint<Scalar, C>,
+    point2: &AllocatedPoint<Scalar, C>,
   ) -> Result<(), SynthesisError> {
 
     // Ensure x are the same
@@ -593,19 +1208,28 @@ where
 
 #[derive(Clone)]
 /// `AllocatedPoint` but one that is guaranteed to be not infinity
-pub struct AllocatedPointNonInfinity<Scalar>
+pub struct AllocatedPointNonInfinity<Scalar, C = P256Params>
 where
   Scalar: PrimeField,
 {
   x: AllocatedNum<Scalar>,
   y: AllocatedNum<Scalar>,
+  _curve: PhantomData<C>,
 }
 
-impl<Scalar: PrimeField + PrimeFieldBits> AllocatedPointNonInfinity<Scalar> {
+impl<Scalar, C> AllocatedPointNonInfinity<Scalar, C>
+where
+  Scalar: PrimeField + PrimeFieldBits,
+  C: CurveParams<Scalar>,
+{
   #[allow(unused)]
   /// Creates a new `AllocatedPointNonInfinity` from the specified coordinates
   pub const fn new(x: AllocatedNum<Scalar>, y: AllocatedNum<Scalar>) -> Self {
-    Self { x, y }
+    Self {
+      x,
+      y,
+      _curve: PhantomData,
+    }
   }
 
   #[allow(unused)]
@@ -622,15 +1246,20 @@ impl<Scalar: PrimeField + PrimeFieldBits> AllocatedPointNonInfinity<Scalar> {
       coords.map_or(Err(SynthesisError::AssignmentMissing), |c| Ok(c.1))
     })?;
 
-    Ok(Self { x, y })
+    Ok(Self {
+      x,
+      y,
+      _curve: PhantomData,
+    })
   }
 
   /// Turns an `AllocatedPoint` into an `AllocatedPointNonInfinity` (assumes it
   /// is not infinity)
-  pub fn from_allocated_point(p: &AllocatedPoint<Scalar>) -> Self {
+  pub fn from_allocated_point(p: &AllocatedPoint<Scalar, C>) -> Self {
     Self {
       x: p.x.clone(),
       y: p.y.clone(),
+      _curve: PhantomData,
     }
   }
 
@@ -638,11 +1267,12 @@ impl<Scalar: PrimeField + PrimeFieldBits> AllocatedPointNonInfinity<Scalar> {
   pub fn to_allocated_point(
     &self,
     is_infinity: &AllocatedNum<Scalar>,
-  ) -> Result<AllocatedPoint<Scalar>, SynthesisError> {
+  ) -> Result<AllocatedPoint<Scalar, C>, SynthesisError> {
     Ok(AllocatedPoint {
       x: self.x.clone(),
       y: self.y.clone(),
       is_infinity: is_infinity.clone(),
+      _curve: PhantomData,
     })
   }
 
@@ -712,7 +1342,11 @@ impl<Scalar: PrimeField + PrimeFieldBits> AllocatedPointNonInfinity<Scalar> {
       |lc| lc + y.get_variable() + self.y.get_variable(),
     );
 
-    Ok(Self { x, y })
+    Ok(Self {
+      x,
+      y,
+      _curve: PhantomData,
+    })
   }
 
   /// doubles the point; since this is called with a point not at infinity, it
@@ -721,13 +1355,13 @@ impl<Scalar: PrimeField + PrimeFieldBits> AllocatedPointNonInfinity<Scalar> {
   where
     CS: ConstraintSystem<Scalar>,
   {
-    // ASSUMES A = -3
     // lambda = (3 x^2 + a) / 2 * y
+    let a = C::a();
 
     let x_sq = self.x.square(cs.namespace(|| "x_sq"))?;
 
     let lambda = AllocatedNum::alloc(cs.namespace(|| "lambda"), || {
-      let n = Scalar::from(3) * x_sq.get_value().get()? - Scalar::from(3);
+      let n = Scalar::from(3) * x_sq.get_value().get()? + a;
       let d = Scalar::from(2) * *self.y.get_value().get()?;
       if d == Scalar::ZERO {
         Ok(Scalar::ONE)
@@ -739,7 +1373,7 @@ impl<Scalar: PrimeField + PrimeFieldBits> AllocatedPointNonInfinity<Scalar> {
       || "Check that lambda is computed correctly",
       |lc| lc + lambda.get_variable(),
       |lc| lc + (Scalar::from(2), self.y.get_variable()),
-      |lc| lc - CS::one() - CS::one() - CS::one() + (Scalar::from(3), x_sq.get_variable()),
+      |lc| lc + (a, CS::one()) + (Scalar::from(3), x_sq.get_variable()),
     );
 
     let x = AllocatedNum::alloc(cs.namespace(|| "x"), || {
@@ -771,7 +1405,11 @@ impl<Scalar: PrimeField + PrimeFieldBits> AllocatedPointNonInfinity<Scalar> {
       |lc| lc + y.get_variable() + self.y.get_variable(),
     );
 
-    Ok(Self { x, y })
+    Ok(Self {
+      x,
+      y,
+      _curve: PhantomData,
+    })
   }
 
   /// If condition outputs a otherwise outputs b
@@ -784,7 +1422,234 @@ impl<Scalar: PrimeField + PrimeFieldBits> AllocatedPointNonInfinity<Scalar> {
     let x = conditionally_select(cs.namespace(|| "select x"), &a.x, &b.x, condition)?;
     let y = conditionally_select(cs.namespace(|| "select y"), &a.y, &b.y, condition)?;
 
-    Ok(Self { x, y })
+    Ok(Self {
+      x,
+      y,
+      _curve: PhantomData,
+    })
+  }
+
+  /// Selects one of a fixed-base window's 8 precomputed points by 3-bit
+  /// lookup (see [`select_constant_by_bits3`]). `table[0]` is never read as
+  /// an affine point -- it's the window's identity placeholder, and callers
+  /// must gate it themselves (e.g. via `AllocatedPoint::select_point_or_infinity`).
+  fn select_from_table<CS: ConstraintSystem<Scalar>>(
+    mut cs: CS,
+    b0: &Boolean,
+    b1: &Boolean,
+    b2: &Boolean,
+    table: &[(Scalar, Scalar)],
+  ) -> Result<Self, SynthesisError> {
+    assert_eq!(table.len(), 8, "fixed-base window table must have exactly 8 entries");
+
+    let p01 = mul_booleans(cs.namespace(|| "b0*b1"), b0, b1)?;
+    let p02 = mul_booleans(cs.namespace(|| "b0*b2"), b0, b2)?;
+    let p12 = mul_booleans(cs.namespace(|| "b1*b2"), b1, b2)?;
+    let p012 = mul_num_boolean(cs.namespace(|| "b0*b1*b2"), &p01, b2)?;
+
+    let xs: [Scalar; 8] = core::array::from_fn(|k| table[k].0);
+    let ys: [Scalar; 8] = core::array::from_fn(|k| table[k].1);
+
+    let x = select_constant_by_bits3(cs.namespace(|| "select x"), b0, b1, b2, &p01, &p02, &p12, &p012, &xs)?;
+    let y = select_constant_by_bits3(cs.namespace(|| "select y"), b0, b1, b2, &p01, &p02, &p12, &p012, &ys)?;
+
+    Ok(Self {
+      x,
+      y,
+      _curve: PhantomData,
+    })
+  }
+
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use bellpepper_core::test_cs::TestConstraintSystem;
+  use halo2curves::secp256r1::Fp as Scalar;
+
+  // A second curve shape with `a = 0` (secp256k1's shape), distinct from
+  // P-256's `a = -3`, to confirm `double_incomplete` actually threads `C::a()`
+  // through rather than hardcoding P-256's value.
+  #[derive(Clone, Copy)]
+  struct ParamsAZero;
+
+  impl CurveParams<Scalar> for ParamsAZero {
+    fn a() -> Scalar {
+      Scalar::ZERO
+    }
+
+    fn b() -> Scalar {
+      Scalar::from(7)
+    }
+  }
+
+  // Builds a `fixed_base_scalar_mul` window table for the P-256 generator:
+  // `table[8*i + k] = k * 2^(3i) * G`, affine, for `k` in `0..8`.
+  fn build_g_window_table(num_windows: usize) -> Vec<(Scalar, Scalar)> {
+    use halo2curves::{group::Curve, secp256r1::Fq as P256Order, secp256r1::Secp256r1Affine, CurveAffine};
+
+    let mut table = Vec::with_capacity(num_windows * 8);
+    let mut window_base = Secp256r1Affine::generator();
+    for _ in 0..num_windows {
+      for k in 0..8u64 {
+        let p = (window_base * P256Order::from(k)).to_affine();
+        table.push((p.x, p.y));
+      }
+      window_base = (window_base * P256Order::from(8)).to_affine();
+    }
+    table
+  }
+
+  // Doubles an arbitrary (not necessarily on-curve) witness point under a
+  // given curve's `a` via the standard formula, to compare against the
+  // circuit's output -- `double_incomplete`'s constraints are purely
+  // algebraic, so they hold regardless of curve membership.
+  fn double_reference(x: Scalar, y: Scalar, a: Scalar) -> (Scalar, Scalar) {
+    let lambda = (Scalar::from(3) * x * x + a) * (Scalar::from(2) * y).invert().unwrap();
+    let x3 = lambda * lambda - x - x;
+    let y3 = lambda * (x - x3) - y;
+    (x3, y3)
   }
 
+  #[test]
+  fn test_double_incomplete_generic_over_a() {
+    let x = Scalar::from(5);
+    let y = Scalar::from(11);
+
+    let (expected_x, expected_y) = double_reference(x, y, P256Params::a());
+    let mut cs = TestConstraintSystem::<Scalar>::new();
+    let point =
+      AllocatedPointNonInfinity::<Scalar, P256Params>::alloc(cs.namespace(|| "point"), Some((x, y)))
+        .unwrap();
+    let doubled = point.double_incomplete(cs.namespace(|| "double")).unwrap();
+    assert!(cs.is_satisfied());
+    assert_eq!(doubled.x.get_value().unwrap(), expected_x);
+    assert_eq!(doubled.y.get_value().unwrap(), expected_y);
+
+    let (expected_x, expected_y) = double_reference(x, y, ParamsAZero::a());
+    let mut cs = TestConstraintSystem::<Scalar>::new();
+    let point =
+      AllocatedPointNonInfinity::<Scalar, ParamsAZero>::alloc(cs.namespace(|| "point"), Some((x, y)))
+        .unwrap();
+    let doubled = point.double_incomplete(cs.namespace(|| "double")).unwrap();
+    assert!(cs.is_satisfied());
+    assert_eq!(doubled.x.get_value().unwrap(), expected_x);
+    assert_eq!(doubled.y.get_value().unwrap(), expected_y);
+  }
+
+  #[test]
+  fn test_add_incomplete_matches_curve_arithmetic() {
+    use halo2curves::{group::Curve, secp256r1::Fq as P256Order, secp256r1::Secp256r1Affine, CurveAffine};
+
+    let g = Secp256r1Affine::generator();
+    let g2 = (g * P256Order::from(2)).to_affine();
+    let g3 = (g * P256Order::from(3)).to_affine();
+
+    let mut cs = TestConstraintSystem::<Scalar>::new();
+    let p1 = AllocatedPointNonInfinity::<Scalar, P256Params>::alloc(cs.namespace(|| "g"), Some((g.x, g.y)))
+      .unwrap();
+    let p2 = AllocatedPointNonInfinity::<Scalar, P256Params>::alloc(cs.namespace(|| "g2"), Some((g2.x, g2.y)))
+      .unwrap();
+    let sum = p1.add_incomplete(cs.namespace(|| "add"), &p2).unwrap();
+
+    assert!(cs.is_satisfied());
+    assert_eq!(sum.x.get_value().unwrap(), g3.x);
+    assert_eq!(sum.y.get_value().unwrap(), g3.y);
+  }
+
+  #[test]
+  fn test_scalar_mul_matches_curve_arithmetic() {
+    use ark_std::rand::{thread_rng, Rng};
+    use halo2curves::{group::Curve, secp256r1::Fq as P256Order, secp256r1::Secp256r1Affine, CurveAffine};
+
+    let mut rng = thread_rng();
+    let k: u64 = rng.gen();
+
+    let g = Secp256r1Affine::generator();
+    let expected = (g * P256Order::from(k)).to_affine();
+
+    let mut cs = TestConstraintSystem::<Scalar>::new();
+    let point =
+      AllocatedPoint::<Scalar, P256Params>::alloc(cs.namespace(|| "g"), Some((g.x, g.y, false)))
+        .unwrap();
+    let k_alloc = AllocatedNum::alloc(cs.namespace(|| "k"), || Ok(Scalar::from(k))).unwrap();
+    let result = point.scalar_mul(cs.namespace(|| "k*G"), &k_alloc).unwrap();
+
+    assert!(cs.is_satisfied());
+    assert_eq!(result.x.get_value().unwrap(), expected.x);
+    assert_eq!(result.y.get_value().unwrap(), expected.y);
+  }
+
+  #[test]
+  fn test_fixed_base_scalar_mul_matches_variable_base() {
+    use ark_std::rand::{thread_rng, Rng};
+    use halo2curves::{secp256r1::Secp256r1Affine, CurveAffine};
+
+    let mut rng = thread_rng();
+    let k: u64 = rng.gen();
+
+    let mut cs = TestConstraintSystem::<Scalar>::new();
+    let k_alloc = AllocatedNum::alloc(cs.namespace(|| "k"), || Ok(Scalar::from(k))).unwrap();
+    let bits = k_alloc.to_bits_le(cs.namespace(|| "k bits")).unwrap();
+    let num_windows = bits.len().div_ceil(3);
+    let table = build_g_window_table(num_windows);
+
+    let fixed_base_result = AllocatedPoint::<Scalar, P256Params>::fixed_base_scalar_mul(
+      cs.namespace(|| "fixed-base k*G"),
+      &bits,
+      &table,
+    )
+    .unwrap();
+
+    let g = Secp256r1Affine::generator();
+    let g_alloc =
+      AllocatedPoint::<Scalar, P256Params>::alloc(cs.namespace(|| "g"), Some((g.x, g.y, false)))
+        .unwrap();
+    let variable_base_result = g_alloc
+      .scalar_mul(cs.namespace(|| "variable-base k*G"), &k_alloc)
+      .unwrap();
+
+    assert!(cs.is_satisfied());
+    assert_eq!(
+      fixed_base_result.x.get_value().unwrap(),
+      variable_base_result.x.get_value().unwrap()
+    );
+    assert_eq!(
+      fixed_base_result.y.get_value().unwrap(),
+      variable_base_result.y.get_value().unwrap()
+    );
+  }
+
+  #[test]
+  fn test_check_on_curve_accepts_valid_point() {
+    use halo2curves::secp256r1::Secp256r1Affine;
+
+    let g = Secp256r1Affine::generator();
+    let mut cs = TestConstraintSystem::<Scalar>::new();
+    let point =
+      AllocatedPoint::<Scalar, P256Params>::alloc(cs.namespace(|| "g"), Some((g.x, g.y, false)))
+        .unwrap();
+    point.check_on_curve(cs.namespace(|| "check on curve")).unwrap();
+    assert!(cs.is_satisfied());
+  }
+
+  // A malicious prover feeding in coordinates that don't satisfy
+  // `y^2 = x^3 - 3x + b` should be rejected, not silently accepted -- this
+  // is the soundness gap `check_on_curve`/`alloc_checked` close.
+  #[test]
+  fn test_check_on_curve_rejects_off_curve_point() {
+    use halo2curves::secp256r1::Secp256r1Affine;
+
+    let g = Secp256r1Affine::generator();
+    let mut cs = TestConstraintSystem::<Scalar>::new();
+    // Shift `y` by one so `(x, y)` no longer satisfies the curve equation.
+    let point = AllocatedPoint::<Scalar, P256Params>::alloc(
+      cs.namespace(|| "off-curve point"),
+      Some((g.x, g.y + Scalar::ONE, false)),
+    )
+    .unwrap();
+    point.check_on_curve(cs.namespace(|| "check on curve")).unwrap();
+    assert!(!cs.is_satisfied());
+  }
 }
@@ -14,6 +14,9 @@ mod ecc;
 mod utils;
 mod poseidon;
 mod emulated;
+mod uint32;
+mod sha256;
+mod multipack;
 
 use std::io::Write;
 use bellpepper_core::{num::AllocatedNum, test_cs::TestConstraintSystem, Circuit, ConstraintSystem, SynthesisError, Comparable};
@@ -0,0 +1,85 @@
+#![allow(unused)]
+
+//! Packs many `Boolean` wires into as few field-element public inputs as
+//! possible, instead of exposing one public input per bit. Selective
+//! disclosure circuits expose bit-level commitments (disclosed-claim
+//! bitmaps, digest bits) as public inputs; packing them here cuts
+//! verification cost from one group element per bit down to one per
+//! `F::CAPACITY` bits.
+
+use bellpepper_core::{boolean::Boolean, num::AllocatedNum, ConstraintSystem, LinearCombination, SynthesisError};
+use ff::{PrimeField, PrimeFieldBits};
+
+/// Packs `bits` into as few public inputs as possible: chunks them into
+/// groups of `F::CAPACITY`, builds each chunk's value with the same
+/// coefficient-doubling loop `le_bits_to_num` uses, allocates it as a
+/// public input, and enforces the input equals that bit-weighted linear
+/// combination.
+pub fn pack_into_inputs<F, CS>(mut cs: CS, bits: &[Boolean]) -> Result<Vec<AllocatedNum<F>>, SynthesisError>
+where
+  F: PrimeField,
+  CS: ConstraintSystem<F>,
+{
+  let mut inputs = Vec::new();
+
+  for (i, chunk) in bits.chunks(F::CAPACITY as usize).enumerate() {
+    let mut cs = cs.namespace(|| format!("chunk {i}"));
+
+    let mut lc = LinearCombination::zero();
+    let mut coeff = F::ONE;
+    let mut value = Some(F::ZERO);
+
+    for bit in chunk {
+      lc = lc + &bit.lc(CS::one(), coeff);
+      value = match (value, bit.get_value()) {
+        (Some(v), Some(b)) => Some(if b { v + coeff } else { v }),
+        _ => None,
+      };
+      coeff = coeff.double();
+    }
+
+    let input = AllocatedNum::alloc_input(cs.namespace(|| "packed input"), || {
+      value.ok_or(SynthesisError::AssignmentMissing)
+    })?;
+
+    let lc = lc - input.get_variable();
+    cs.enforce(|| "packed input matches bit-weighted sum", |lc| lc, |lc| lc, |_| lc);
+
+    inputs.push(input);
+  }
+
+  Ok(inputs)
+}
+
+/// Out-of-circuit mirror of `pack_into_inputs`: packs raw little-endian
+/// bits into the same `F::CAPACITY`-sized field elements, so a verifier can
+/// reproduce the packed public inputs from a witnessed byte string without
+/// running the circuit.
+pub fn compute_multipacking<F: PrimeField + PrimeFieldBits>(bits: &[bool]) -> Vec<F> {
+  bits
+    .chunks(F::CAPACITY as usize)
+    .map(|chunk| {
+      let mut coeff = F::ONE;
+      let mut value = F::ZERO;
+      for &bit in chunk {
+        if bit {
+          value += coeff;
+        }
+        coeff = coeff.double();
+      }
+      value
+    })
+    .collect()
+}
+
+/// Out-of-circuit mirror of `pack_into_inputs` starting from raw bytes
+/// (little-endian bit order within each byte, the order `UInt32` keeps its
+/// bits in; `sha256` instead takes MSB-first bits): unpacks `bytes` into bits and packs them the
+/// same way `compute_multipacking` does.
+pub fn bytes_to_field_elements<F: PrimeField + PrimeFieldBits>(bytes: &[u8]) -> Vec<F> {
+  let bits: Vec<bool> = bytes
+    .iter()
+    .flat_map(|byte| (0..8).map(move |i| (byte >> i) & 1 == 1))
+    .collect();
+  compute_multipacking(&bits)
+}
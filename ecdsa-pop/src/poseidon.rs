@@ -8,7 +8,7 @@ use bellpepper_core::{
 };
 //use core::marker::PhantomData;
 use ff::{PrimeField, PrimeFieldBits};
-use generic_array::typenum::U2;
+use generic_array::typenum::{Unsigned, U2};
 use neptune::{
   circuit2::Elt,
   poseidon::PoseidonConstants,
@@ -17,41 +17,91 @@ use neptune::{
     circuit::SpongeCircuit,
     vanilla::{Mode::Simplex, Sponge, SpongeTrait},
   },
-  Strength,
+  Arity, Strength,
 };
 use serde::{Deserialize, Serialize};
 
 use crate::utils::le_bits_to_num;
 
+/// Builds the `IOPattern` for one absorb-then-squeeze round: a single
+/// `Absorb(num_absorbs)` op covering every caller's one
+/// `SpongeAPI::absorb(..., num_absorbs, &state, ...)` call, followed by
+/// a `Squeeze(num_squeezes)` op squeezing that many elements out of the
+/// same permutation sequence. `rate` (the sponge's arity) is taken so
+/// callers have one place to get it from, but it no longer affects how
+/// the pattern is chunked -- every `absorb` call here really does cover
+/// the whole of `num_absorbs` in one op, so the declared pattern has to
+/// match that.
+fn rated_io_pattern(num_absorbs: usize, _rate: usize, num_squeezes: usize) -> IOPattern {
+  IOPattern(vec![
+    SpongeOp::Absorb(num_absorbs as u32),
+    SpongeOp::Squeeze(num_squeezes as u32),
+  ])
+}
+
 /// All Poseidon constants
 #[derive(Clone, PartialEq, Serialize, Deserialize)]
-pub struct PoseidonConstantsCircuit<Scalar: PrimeField>(PoseidonConstants<Scalar, U2>);
+pub struct PoseidonConstantsCircuit<Scalar: PrimeField, A: Arity<Scalar> = U2>(
+  PoseidonConstants<Scalar, A>,
+);
 
-impl<Scalar: PrimeField> Default for PoseidonConstantsCircuit<Scalar> {
+impl<Scalar: PrimeField, A: Arity<Scalar>> Default for PoseidonConstantsCircuit<Scalar, A> {
   /// Generate Poseidon constants
   fn default() -> Self {
-    Self(Sponge::<Scalar, U2>::api_constants(Strength::Standard))
+    Self(Sponge::<Scalar, A>::api_constants(Strength::Standard))
+  }
+}
+
+impl<Scalar: PrimeField, A: Arity<Scalar> + Unsigned> PoseidonConstantsCircuit<Scalar, A> {
+  /// Builds constants at an explicit security `Strength` instead of the
+  /// `Default` impl's hardcoded `Strength::Standard`, e.g. to request
+  /// `Strength::Strengthened` for extra security margin.
+  pub fn with_strength(strength: Strength) -> Self {
+    Self(Sponge::<Scalar, A>::api_constants(strength))
+  }
+
+  /// Builds constants for an explicit full-round/partial-round split,
+  /// so the app can match a specific on-chain verifier's Poseidon
+  /// parameterization instead of being tied to neptune's defaults.
+  /// neptune derives `(r_f, r_p)` internally from a `Strength` rather
+  /// than accepting them as raw input, so this checks `r_f`/`r_p`
+  /// against what each known `Strength` preset computes and reuses that
+  /// preset's constants; it panics if no preset matches rather than
+  /// silently using a different round count than the caller asked for.
+  pub fn with_rounds(r_f: usize, r_p: usize) -> Self {
+    let t = A::to_usize() + 1;
+    for &strength in &[Strength::Standard, Strength::Strengthened] {
+      let (preset_r_f, preset_r_p) = neptune::round_numbers::round_numbers_strength(t, strength);
+      if preset_r_f == r_f && preset_r_p == r_p {
+        return Self::with_strength(strength);
+      }
+    }
+    panic!(
+      "no known Poseidon strength preset has {r_f} full rounds and {r_p} partial rounds for arity {}",
+      A::to_usize()
+    );
   }
 }
 
 /// A Poseidon-based sponge to use outside circuits
 #[derive(Serialize, Deserialize)]
-pub struct Poseidon<Scalar>
+pub struct Poseidon<Scalar, A: Arity<Scalar> = U2>
 where
   Scalar: PrimeField,
 {
   // Internal State
   state: Vec<Scalar>,
-  constants: PoseidonConstantsCircuit<Scalar>,
+  constants: PoseidonConstantsCircuit<Scalar, A>,
   num_absorbs: usize,
   squeezed: bool,
 }
 
-impl<Scalar>  Poseidon<Scalar> 
+impl<Scalar, A> Poseidon<Scalar, A>
 where
   Scalar: PrimeField + PrimeFieldBits + Serialize + for<'de> Deserialize<'de>,
+  A: Arity<Scalar> + Unsigned,
 {
-  pub fn new(constants: PoseidonConstantsCircuit<Scalar>, num_absorbs: usize) -> Self {
+  pub fn new(constants: PoseidonConstantsCircuit<Scalar, A>, num_absorbs: usize) -> Self {
     Self {
       state: Vec::new(),
       constants,
@@ -69,25 +119,10 @@ where
   #[allow(dead_code)]
   /// Compute a digest by hashing the current state
   pub fn squeeze(&mut self, num_bits: usize) -> Scalar {
-    // check if we have squeezed already
-    assert!(!self.squeezed, "Cannot squeeze again after squeezing");
-    self.squeezed = true;
-
-    let mut sponge = Sponge::new_with_constants(&self.constants.0, Simplex);
-    let acc = &mut ();
-    let parameter = IOPattern(vec![
-      SpongeOp::Absorb(self.num_absorbs as u32),
-      SpongeOp::Squeeze(1u32),
-    ]);
-
-    sponge.start(parameter, None, acc);
-    assert_eq!(self.num_absorbs, self.state.len());
-    SpongeAPI::absorb(&mut sponge, self.num_absorbs as u32, &self.state, acc);
-    let hash = SpongeAPI::squeeze(&mut sponge, 1, acc);
-    sponge.finish(acc).unwrap();
+    let hash = self.squeeze_many(1)[0];
 
     // Only return `num_bits`
-    let bits = hash[0].to_le_bits();
+    let bits = hash.to_le_bits();
     let mut res = Scalar::ZERO;
     let mut coeff = Scalar::ONE;
     for bit in bits[0..num_bits].into_iter() {
@@ -101,44 +136,51 @@ where
 
   /// Compute a digest that is one field element long
   pub fn squeeze_field_element(&mut self) -> Scalar {
+    self.squeeze_many(1)[0]
+  }
+
+  /// Squeeze `n` field elements out of the current state in a single
+  /// permutation sequence, for deriving a vector of correlated-but-
+  /// distinct challenges (e.g. RLC coefficients or a batch of verifier
+  /// randomness) from one absorbed state instead of rebuilding the
+  /// sponge per output.
+  pub fn squeeze_many(&mut self, n: usize) -> Vec<Scalar> {
     // check if we have squeezed already
     assert!(!self.squeezed, "Cannot squeeze again after squeezing");
     self.squeezed = true;
 
     let mut sponge = Sponge::new_with_constants(&self.constants.0, Simplex);
     let acc = &mut ();
-    let parameter = IOPattern(vec![
-      SpongeOp::Absorb(self.num_absorbs as u32),
-      SpongeOp::Squeeze(1u32),
-    ]);
+    let parameter = rated_io_pattern(self.num_absorbs, A::to_usize(), n);
 
     sponge.start(parameter, None, acc);
     assert_eq!(self.num_absorbs, self.state.len());
     SpongeAPI::absorb(&mut sponge, self.num_absorbs as u32, &self.state, acc);
-    let hash = SpongeAPI::squeeze(&mut sponge, 1, acc);
+    let hash = SpongeAPI::squeeze(&mut sponge, n as u32, acc);
     sponge.finish(acc).unwrap();
 
-    hash[0]
+    hash
   }
 
 }
 
 /// A Poseidon-based sponge gadget to use inside the verifier circuit.
 #[derive(Serialize, Deserialize)]
-pub struct PoseidonCircuit<Scalar: PrimeField> {
+pub struct PoseidonCircuit<Scalar: PrimeField, A: Arity<Scalar> = U2> {
   // Internal state
   state: Vec<AllocatedNum<Scalar>>,
-  constants: PoseidonConstantsCircuit<Scalar>,
+  constants: PoseidonConstantsCircuit<Scalar, A>,
   num_absorbs: usize,
   squeezed: bool,
 }
 
-impl<Scalar> PoseidonCircuit<Scalar>
+impl<Scalar, A> PoseidonCircuit<Scalar, A>
 where
   Scalar: PrimeField + PrimeFieldBits + Serialize + for<'de> Deserialize<'de>,
+  A: Arity<Scalar> + Unsigned,
 {
   /// Initialize the internal state and set the poseidon constants
-  pub fn new(constants: PoseidonConstantsCircuit<Scalar>, num_absorbs: usize) -> Self {
+  pub fn new(constants: PoseidonConstantsCircuit<Scalar, A>, num_absorbs: usize) -> Self {
     Self {
       state: Vec::new(),
       constants,
@@ -192,16 +234,24 @@ where
     /// Compute a digest by hashing the current state
     pub fn squeeze_field_element<CS: ConstraintSystem<Scalar>>(
       &mut self,
-      mut cs: CS,   
+      cs: CS,
     ) -> Result<AllocatedNum<Scalar>, SynthesisError> {
+      Ok(self.squeeze_many(cs, 1)?.remove(0))
+    }
+
+    /// In-circuit counterpart to `Poseidon::squeeze_many`: squeezes `n`
+    /// allocated field elements out of the current state in a single
+    /// permutation sequence, instead of re-running the gadget per output.
+    pub fn squeeze_many<CS: ConstraintSystem<Scalar>>(
+      &mut self,
+      mut cs: CS,
+      n: usize,
+    ) -> Result<Vec<AllocatedNum<Scalar>>, SynthesisError> {
 
       // check if we have squeezed already
       assert!(!self.squeezed, "Cannot squeeze again after squeezing");
       self.squeezed = true;
-      let parameter = IOPattern(vec![
-        SpongeOp::Absorb(self.num_absorbs as u32),
-        SpongeOp::Squeeze(1u32),
-      ]);
+      let parameter = rated_io_pattern(self.num_absorbs, A::to_usize(), n);
       let mut ns = cs.namespace(|| "ns");
 
       let hash = {
@@ -219,16 +269,254 @@ where
           acc,
         );
 
-        let output = neptune::sponge::api::SpongeAPI::squeeze(&mut sponge, 1, acc);
+        let output = neptune::sponge::api::SpongeAPI::squeeze(&mut sponge, n as u32, acc);
         sponge.finish(acc).unwrap();
         output
       };
 
-      let hash = Elt::ensure_allocated(&hash[0], &mut ns.namespace(|| "ensure allocated"), true)?;
+      hash
+        .iter()
+        .enumerate()
+        .map(|(i, elt)| {
+          Elt::ensure_allocated(elt, &mut ns.namespace(|| format!("ensure allocated {i}")), true)
+        })
+        .collect()
+    }
 
-      Ok(hash)
-    }    
+}
 
+/// A streaming Fiat-Shamir transcript on top of the same sponge
+/// `Poseidon` uses, but able to interleave absorbs and squeezes
+/// indefinitely instead of panicking after a single `squeeze`/
+/// `squeeze_field_element` call.
+///
+/// Each `squeeze_challenge` runs one full absorb-then-squeeze round
+/// (the same Simplex-mode sponge machinery `Poseidon` already uses)
+/// over whatever has been buffered since the last squeeze, then folds
+/// the squeezed output back in as the first element of the next
+/// round's buffer. That makes every later challenge bind to everything
+/// absorbed or squeezed before it, the way a true duplex sponge would,
+/// without this crate depending on neptune's lower-level duplex-mode
+/// API.
+pub struct PoseidonTranscript<Scalar: PrimeField, A: Arity<Scalar> = U2> {
+  constants: PoseidonConstantsCircuit<Scalar, A>,
+  // Elements buffered since the last squeeze (or since `new`, before
+  // the first one): starts as just the domain separator, and after
+  // every `squeeze_challenge` starts over as just that squeeze's output.
+  buffer: Vec<Scalar>,
+}
+
+impl<Scalar, A> PoseidonTranscript<Scalar, A>
+where
+  Scalar: PrimeField + PrimeFieldBits + Serialize + for<'de> Deserialize<'de>,
+  A: Arity<Scalar> + Unsigned,
+{
+  /// Starts a new transcript, seeded with a domain separator derived
+  /// from `label` (typically the protocol's name), so two protocols
+  /// absorbing the same values in the same order still produce
+  /// different challenges.
+  pub fn new(constants: PoseidonConstantsCircuit<Scalar, A>, label: &'static [u8]) -> Self {
+    let domain_separator = Self::label_to_scalar(label);
+    Self {
+      constants,
+      buffer: vec![domain_separator],
+    }
+  }
+
+  /// Packs a short label (protocol name, field name, ...) into a single
+  /// field element, via the same `bytes_to_field_elements` multipacking
+  /// `multipack` uses elsewhere in this crate. Labels are assumed to fit
+  /// in one `F::CAPACITY`-sized chunk; longer byte strings should go
+  /// through `absorb_bytes` instead, which keeps every chunk.
+  fn label_to_scalar(label: &'static [u8]) -> Scalar {
+    crate::multipack::bytes_to_field_elements(label)[0]
+  }
+
+  /// Absorbs `e` under `label`: the label is folded into a
+  /// domain-separator scalar and absorbed alongside `e`, so the same
+  /// value absorbed under two different labels leaves the transcript in
+  /// different states.
+  pub fn absorb_label(&mut self, label: &'static [u8], e: Scalar) {
+    self.buffer.push(Self::label_to_scalar(label));
+    self.buffer.push(e);
+  }
+
+  /// Absorbs a byte blob under `label`: the blob is packed into field
+  /// elements with `bytes_to_field_elements` (one per `F::CAPACITY`
+  /// bits, so this handles blobs of any length, unlike `label_to_scalar`).
+  pub fn absorb_bytes(&mut self, label: &'static [u8], bytes: &[u8]) {
+    self.buffer.push(Self::label_to_scalar(label));
+    self.buffer.extend(crate::multipack::bytes_to_field_elements(bytes));
+  }
+
+  /// Squeezes the next challenge: permutes everything buffered since
+  /// the last squeeze through one Simplex-mode sponge round, then seeds
+  /// the next round's buffer with this round's output so later
+  /// challenges stay bound to everything absorbed or squeezed so far.
+  pub fn squeeze_challenge(&mut self) -> Scalar {
+    let num_absorbs = self.buffer.len();
+    let mut sponge = Sponge::new_with_constants(&self.constants.0, Simplex);
+    let acc = &mut ();
+    let parameter = rated_io_pattern(num_absorbs, A::to_usize(), 1);
+
+    sponge.start(parameter, None, acc);
+    SpongeAPI::absorb(&mut sponge, num_absorbs as u32, &self.buffer, acc);
+    let hash = SpongeAPI::squeeze(&mut sponge, 1, acc);
+    sponge.finish(acc).unwrap();
+
+    let challenge = hash[0];
+    self.buffer = vec![challenge];
+    challenge
+  }
+}
+
+/// Pads `inputs` with zero scalars up to the next multiple of `rate`,
+/// the same zero-padding discipline `ConstantLength`-style Poseidon
+/// domains use for the final partial chunk.
+fn pad_to_rate<Scalar: PrimeField>(inputs: &[Scalar], rate: usize) -> Vec<Scalar> {
+  let mut padded = inputs.to_vec();
+  let remainder = padded.len() % rate;
+  if remainder != 0 {
+    padded.extend(std::iter::repeat(Scalar::ZERO).take(rate - remainder));
+  }
+  padded
+}
+
+/// Hashes `inputs` of any length down to one field element, using a
+/// `ConstantLength`-style domain: `inputs.len()` is absorbed first as a
+/// domain-separation tag (so hashing `[a]` can never collide with
+/// hashing `[a, 0]`), then `inputs` is absorbed in `rate`-sized chunks
+/// with the final partial chunk zero-padded, then one element is
+/// squeezed. Unlike `Poseidon`, which fixes `num_absorbs` at
+/// construction, this accepts inputs of any length.
+pub fn hash_constant_length<Scalar, A>(
+  constants: &PoseidonConstantsCircuit<Scalar, A>,
+  inputs: &[Scalar],
+) -> Scalar
+where
+  Scalar: PrimeField + PrimeFieldBits + Serialize + for<'de> Deserialize<'de>,
+  A: Arity<Scalar> + Unsigned,
+{
+  let rate = A::to_usize();
+  let domain_tag = Scalar::from(inputs.len() as u64);
+
+  let mut state = Vec::with_capacity(inputs.len() + rate + 1);
+  state.push(domain_tag);
+  state.extend(pad_to_rate(inputs, rate));
+
+  let mut sponge = Sponge::new_with_constants(&constants.0, Simplex);
+  let acc = &mut ();
+  let parameter = rated_io_pattern(state.len(), rate, 1);
+
+  sponge.start(parameter, None, acc);
+  SpongeAPI::absorb(&mut sponge, state.len() as u32, &state, acc);
+  let hash = SpongeAPI::squeeze(&mut sponge, 1, acc);
+  sponge.finish(acc).unwrap();
+
+  hash[0]
+}
+
+/// In-circuit counterpart to `hash_constant_length`.
+pub fn hash_constant_length_circuit<Scalar, A, CS>(
+  mut cs: CS,
+  constants: &PoseidonConstantsCircuit<Scalar, A>,
+  inputs: &[AllocatedNum<Scalar>],
+) -> Result<AllocatedNum<Scalar>, SynthesisError>
+where
+  Scalar: PrimeField + PrimeFieldBits + Serialize + for<'de> Deserialize<'de>,
+  A: Arity<Scalar> + Unsigned,
+  CS: ConstraintSystem<Scalar>,
+{
+  let rate = A::to_usize();
+  let remainder = inputs.len() % rate;
+  let pad_len = if remainder == 0 { 0 } else { rate - remainder };
+
+  let domain_tag = AllocatedNum::alloc(cs.namespace(|| "domain tag"), || {
+    Ok(Scalar::from(inputs.len() as u64))
+  })?;
+  cs.enforce(
+    || "domain tag is the input length",
+    |lc| lc + (Scalar::from(inputs.len() as u64), CS::one()),
+    |lc| lc + CS::one(),
+    |lc| lc + domain_tag.get_variable(),
+  );
+
+  let mut state = Vec::with_capacity(inputs.len() + pad_len + 1);
+  state.push(domain_tag);
+  state.extend(inputs.iter().cloned());
+  for i in 0..pad_len {
+    let zero = AllocatedNum::alloc(cs.namespace(|| format!("pad {i}")), || Ok(Scalar::ZERO))?;
+    cs.enforce(
+      || format!("pad {i} is zero"),
+      |lc| lc,
+      |lc| lc,
+      |lc| lc + zero.get_variable(),
+    );
+    state.push(zero);
+  }
+
+  let parameter = rated_io_pattern(state.len(), rate, 1);
+  let mut ns = cs.namespace(|| "ns");
+
+  let hash = {
+    let mut sponge = SpongeCircuit::new_with_constants(&constants.0, Simplex);
+    let acc = &mut ns;
+
+    sponge.start(parameter, None, acc);
+    neptune::sponge::api::SpongeAPI::absorb(
+      &mut sponge,
+      state.len() as u32,
+      &state
+        .iter()
+        .cloned()
+        .map(Elt::Allocated)
+        .collect::<Vec<Elt<Scalar>>>(),
+      acc,
+    );
+    let output = neptune::sponge::api::SpongeAPI::squeeze(&mut sponge, 1, acc);
+    sponge.finish(acc).unwrap();
+    output
+  };
+
+  Elt::ensure_allocated(&hash[0], &mut ns.namespace(|| "ensure allocated"), true)
+}
+
+/// Hashes `inputs` with the out-of-circuit sponge, the way
+/// `test_poseidon_compatibility` below cross-checks against the
+/// in-circuit `poseidon_circuit`: builds a fresh `Poseidon` fixed at
+/// exactly `inputs.len()` absorbs and returns its `squeeze_field_element`.
+pub fn poseidon_native<Scalar, A>(
+  constants: PoseidonConstantsCircuit<Scalar, A>,
+  inputs: &[Scalar],
+) -> Scalar
+where
+  Scalar: PrimeField + PrimeFieldBits + Serialize + for<'de> Deserialize<'de>,
+  A: Arity<Scalar> + Unsigned,
+{
+  let mut sponge = Poseidon::new(constants, inputs.len());
+  for &input in inputs {
+    sponge.absorb(input);
+  }
+  sponge.squeeze_field_element()
+}
+
+/// In-circuit counterpart to `poseidon_native`, for cross-checking that
+/// the two sponges agree on every input.
+pub fn poseidon_circuit<Scalar, A, CS>(
+  mut cs: CS,
+  constants: PoseidonConstantsCircuit<Scalar, A>,
+  inputs: &[AllocatedNum<Scalar>],
+) -> Result<AllocatedNum<Scalar>, SynthesisError>
+where
+  Scalar: PrimeField + PrimeFieldBits + Serialize + for<'de> Deserialize<'de>,
+  A: Arity<Scalar> + Unsigned,
+  CS: ConstraintSystem<Scalar>,
+{
+  let mut sponge = PoseidonCircuit::new(constants, inputs.len());
+  for input in inputs {
+    sponge.absorb(input);
+  }
+  sponge.squeeze_field_element(cs.namespace(|| "squeeze"))
 }
 
 #[cfg(test)]
@@ -329,8 +617,62 @@ use bellpepper_core::LinearCombination;
     let mut poseidon: Poseidon<Scalar> = Poseidon::new(consts.clone(), 2);
     poseidon.absorb(preimage2[0]);
     poseidon.absorb(preimage2[1]);
-    let _hash2 = poseidon.squeeze(248);    
+    let _hash2 = poseidon.squeeze(248);
     end_timer!(poseidon_timer);
   }
 
+  #[test]
+  fn test_poseidon_compatibility() {
+    // Cross-check the out-of-circuit and in-circuit sponges against each
+    // other across a range of input lengths and truncation widths, not
+    // just the single `num_absorbs = 2` case `test_poseidon_sponge`
+    // hardcodes, so a regression in bit-truncation, IO-pattern, or
+    // constant generation in either sponge shows up here.
+    for num_absorbs in 1..=6 {
+      let inputs: Vec<Scalar> = (0..num_absorbs).map(|i| Scalar::from(i as u64)).collect();
+      let constants = PoseidonConstantsCircuit::<Scalar>::default();
+
+      let native_hash = poseidon_native(constants.clone(), &inputs);
+
+      let mut cs = TestConstraintSystem::<Scalar>::new();
+      let input_gadgets: Vec<AllocatedNum<Scalar>> = inputs
+        .iter()
+        .enumerate()
+        .map(|(i, &input)| {
+          AllocatedNum::alloc(cs.namespace(|| format!("input {num_absorbs} {i}")), || {
+            Ok(input)
+          })
+          .unwrap()
+        })
+        .collect();
+      let circuit_hash = poseidon_circuit(
+        cs.namespace(|| format!("poseidon {num_absorbs}")),
+        constants.clone(),
+        &input_gadgets,
+      )
+      .unwrap();
+      assert!(cs.is_satisfied());
+      assert_eq!(native_hash, circuit_hash.get_value().unwrap());
+
+      // Cross-check the `squeeze_to_bits`/`le_bits_to_num` truncation
+      // path at several `num_bits` too.
+      for &num_bits in &[1usize, 8, 64, 128, 248] {
+        let mut ro: Poseidon<Scalar> = Poseidon::new(constants.clone(), num_absorbs);
+        let mut ro_gadget: PoseidonCircuit<Scalar> = PoseidonCircuit::new(constants.clone(), num_absorbs);
+        let mut cs = TestConstraintSystem::<Scalar>::new();
+        for (i, &input) in inputs.iter().enumerate() {
+          ro.absorb(input);
+          let input_gadget =
+            AllocatedNum::alloc(cs.namespace(|| format!("bits input {i}")), || Ok(input)).unwrap();
+          ro_gadget.absorb(&input_gadget);
+        }
+        let truncated_native = ro.squeeze(num_bits);
+        let truncated_bits = ro_gadget.squeeze_to_bits(&mut cs, num_bits).unwrap();
+        let truncated_circuit = le_bits_to_num(&mut cs, &truncated_bits).unwrap();
+        assert!(cs.is_satisfied());
+        assert_eq!(truncated_native, truncated_circuit.get_value().unwrap());
+      }
+    }
+  }
+
 }
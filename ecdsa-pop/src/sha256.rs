@@ -0,0 +1,229 @@
+#![allow(unused)]
+
+//! An in-circuit SHA-256 (FIPS 180-4), built on `crate::uint32::UInt32`.
+//!
+//! The RS256 selective-disclosure circuit needs this for the PKCS#1 v1.5
+//! digest and for hashing disclosed claims, so the digest has to be
+//! produced inside the circuit rather than taken as a precomputed input.
+
+use bellpepper_core::{boolean::Boolean, ConstraintSystem, SynthesisError};
+use ff::{PrimeField, PrimeFieldBits};
+
+use crate::uint32::UInt32;
+
+const IV: [u32; 8] = [
+  0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+];
+
+const ROUND_CONSTANTS: [u32; 64] = [
+  0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+  0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+  0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+  0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+  0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+  0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+  0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+  0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
+
+/// `(x AND y) XOR (NOT x AND z)`, bit by bit.
+fn ch<Scalar, CS>(mut cs: CS, x: &UInt32, y: &UInt32, z: &UInt32) -> Result<UInt32, SynthesisError>
+where
+  Scalar: PrimeField,
+  CS: ConstraintSystem<Scalar>,
+{
+  let x_bits = x.into_bits();
+  let y_bits = y.into_bits();
+  let z_bits = z.into_bits();
+
+  let bits = (0..32)
+    .map(|i| {
+      let mut cs = cs.namespace(|| format!("bit {i}"));
+      let xy = Boolean::and(cs.namespace(|| "x and y"), &x_bits[i], &y_bits[i])?;
+      let not_x_z = Boolean::and(cs.namespace(|| "not x and z"), &x_bits[i].not(), &z_bits[i])?;
+      Boolean::xor(cs.namespace(|| "ch"), &xy, &not_x_z)
+    })
+    .collect::<Result<Vec<_>, SynthesisError>>()?;
+
+  Ok(UInt32::from_bits(&bits))
+}
+
+/// The bitwise majority of `x`, `y`, `z`.
+fn maj<Scalar, CS>(mut cs: CS, x: &UInt32, y: &UInt32, z: &UInt32) -> Result<UInt32, SynthesisError>
+where
+  Scalar: PrimeField,
+  CS: ConstraintSystem<Scalar>,
+{
+  let x_bits = x.into_bits();
+  let y_bits = y.into_bits();
+  let z_bits = z.into_bits();
+
+  let bits = (0..32)
+    .map(|i| {
+      let mut cs = cs.namespace(|| format!("bit {i}"));
+      let xy = Boolean::and(cs.namespace(|| "x and y"), &x_bits[i], &y_bits[i])?;
+      let xz = Boolean::and(cs.namespace(|| "x and z"), &x_bits[i], &z_bits[i])?;
+      let yz = Boolean::and(cs.namespace(|| "y and z"), &y_bits[i], &z_bits[i])?;
+      let t = Boolean::xor(cs.namespace(|| "xy xor xz"), &xy, &xz)?;
+      Boolean::xor(cs.namespace(|| "maj"), &t, &yz)
+    })
+    .collect::<Result<Vec<_>, SynthesisError>>()?;
+
+  Ok(UInt32::from_bits(&bits))
+}
+
+/// The "big sigma 0" compression-round function: `ROTR2 xor ROTR13 xor ROTR22`.
+fn bsig0<Scalar, CS>(mut cs: CS, x: &UInt32) -> Result<UInt32, SynthesisError>
+where
+  Scalar: PrimeField + PrimeFieldBits,
+  CS: ConstraintSystem<Scalar>,
+{
+  let a = x.rotr(2).xor(cs.namespace(|| "rotr2 xor rotr13"), &x.rotr(13))?;
+  a.xor(cs.namespace(|| "xor rotr22"), &x.rotr(22))
+}
+
+/// The "big sigma 1" compression-round function: `ROTR6 xor ROTR11 xor ROTR25`.
+fn bsig1<Scalar, CS>(mut cs: CS, x: &UInt32) -> Result<UInt32, SynthesisError>
+where
+  Scalar: PrimeField + PrimeFieldBits,
+  CS: ConstraintSystem<Scalar>,
+{
+  let a = x.rotr(6).xor(cs.namespace(|| "rotr6 xor rotr11"), &x.rotr(11))?;
+  a.xor(cs.namespace(|| "xor rotr25"), &x.rotr(25))
+}
+
+/// The "small sigma 0" schedule function: `ROTR7 xor ROTR18 xor SHR3`.
+fn ssig0<Scalar, CS>(mut cs: CS, x: &UInt32) -> Result<UInt32, SynthesisError>
+where
+  Scalar: PrimeField + PrimeFieldBits,
+  CS: ConstraintSystem<Scalar>,
+{
+  let a = x.rotr(7).xor(cs.namespace(|| "rotr7 xor rotr18"), &x.rotr(18))?;
+  a.xor(cs.namespace(|| "xor shr3"), &x.shr(3))
+}
+
+/// The "small sigma 1" schedule function: `ROTR17 xor ROTR19 xor SHR10`.
+fn ssig1<Scalar, CS>(mut cs: CS, x: &UInt32) -> Result<UInt32, SynthesisError>
+where
+  Scalar: PrimeField + PrimeFieldBits,
+  CS: ConstraintSystem<Scalar>,
+{
+  let a = x.rotr(17).xor(cs.namespace(|| "rotr17 xor rotr19"), &x.rotr(19))?;
+  a.xor(cs.namespace(|| "xor shr10"), &x.shr(10))
+}
+
+/// Processes one 512-bit (16-word) block against the running hash state,
+/// per FIPS 180-4 section 6.2.2.
+fn compression_round<Scalar, CS>(
+  mut cs: CS,
+  state: [UInt32; 8],
+  block: &[UInt32],
+) -> Result<[UInt32; 8], SynthesisError>
+where
+  Scalar: PrimeField + PrimeFieldBits,
+  CS: ConstraintSystem<Scalar>,
+{
+  assert_eq!(block.len(), 16);
+
+  let mut w = Vec::with_capacity(64);
+  w.extend_from_slice(block);
+  for i in 16..64 {
+    let mut cs = cs.namespace(|| format!("message schedule {i}"));
+    let s0 = ssig0(cs.namespace(|| "ssig0"), &w[i - 15])?;
+    let s1 = ssig1(cs.namespace(|| "ssig1"), &w[i - 2])?;
+    let wi = UInt32::addmany(cs.namespace(|| "w[i]"), &[w[i - 16].clone(), s0, w[i - 7].clone(), s1])?;
+    w.push(wi);
+  }
+
+  let initial = state.clone();
+  let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = state;
+
+  for i in 0..64 {
+    let mut cs = cs.namespace(|| format!("round {i}"));
+
+    let s1 = bsig1(cs.namespace(|| "bsig1(e)"), &e)?;
+    let ch_efg = ch(cs.namespace(|| "ch(e,f,g)"), &e, &f, &g)?;
+    let k = UInt32::constant(ROUND_CONSTANTS[i]);
+    let t1 = UInt32::addmany(cs.namespace(|| "t1"), &[h, s1, ch_efg, k, w[i].clone()])?;
+
+    let s0 = bsig0(cs.namespace(|| "bsig0(a)"), &a)?;
+    let maj_abc = maj(cs.namespace(|| "maj(a,b,c)"), &a, &b, &c)?;
+    let t2 = UInt32::addmany(cs.namespace(|| "t2"), &[s0, maj_abc])?;
+
+    h = g;
+    g = f;
+    f = e;
+    e = UInt32::addmany(cs.namespace(|| "e = d + t1"), &[d, t1.clone()])?;
+    d = c;
+    c = b;
+    b = a;
+    a = UInt32::addmany(cs.namespace(|| "a = t1 + t2"), &[t1, t2])?;
+  }
+
+  let mut out = [a, b, c, d, e, f, g, h];
+  for (i, (word, original)) in out.iter_mut().zip(initial.iter()).enumerate() {
+    *word = UInt32::addmany(cs.namespace(|| format!("final add {i}")), &[word.clone(), original.clone()])?;
+  }
+
+  Ok(out)
+}
+
+/// Pads `input` (big-endian bit order, as produced by e.g. bit-decomposing
+/// a byte string MSB-first) to a whole number of 512-bit blocks per the
+/// standard SHA-256 message schedule: a single `1` bit, zero bits, then the
+/// original bit length as a big-endian 64-bit integer.
+fn pad_message(input: &[Boolean]) -> Vec<Boolean> {
+  let mut padded = input.to_vec();
+  let bit_len = input.len() as u64;
+
+  padded.push(Boolean::constant(true));
+  while (padded.len() + 64) % 512 != 0 {
+    padded.push(Boolean::constant(false));
+  }
+  for i in (0..64).rev() {
+    padded.push(Boolean::constant((bit_len >> i) & 1 == 1));
+  }
+
+  padded
+}
+
+/// Computes SHA-256 over `input`, a big-endian bit string (MSB first within
+/// each byte, as produced by bit-decomposing a byte string). Returns the
+/// 256-bit digest in the same big-endian bit order.
+pub fn sha256<Scalar, CS>(mut cs: CS, input: &[Boolean]) -> Result<Vec<Boolean>, SynthesisError>
+where
+  Scalar: PrimeField + PrimeFieldBits,
+  CS: ConstraintSystem<Scalar>,
+{
+  let padded = pad_message(input);
+  assert_eq!(padded.len() % 512, 0);
+
+  let mut state: [UInt32; 8] = IV.map(UInt32::constant);
+
+  for (block_idx, block_bits) in padded.chunks(512).enumerate() {
+    let mut cs = cs.namespace(|| format!("block {block_idx}"));
+    // Each 32-bit message word is big-endian in the spec (MSB bit first),
+    // but `UInt32` stores bits little-endian internally, so reverse each
+    // word's 32 bits on the way in.
+    let words = block_bits
+      .chunks(32)
+      .map(|word_bits| {
+        let le: Vec<Boolean> = word_bits.iter().rev().cloned().collect();
+        UInt32::from_bits(&le)
+      })
+      .collect::<Vec<_>>();
+
+    state = compression_round(cs.namespace(|| "compress"), state, &words)?;
+  }
+
+  // Reassemble the digest, converting each word's internal little-endian
+  // bits back to the spec's big-endian bit order.
+  let mut digest = Vec::with_capacity(256);
+  for word in &state {
+    let mut bits = word.into_bits();
+    bits.reverse();
+    digest.extend(bits);
+  }
+
+  Ok(digest)
+}
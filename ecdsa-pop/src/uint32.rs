@@ -0,0 +1,187 @@
+#![allow(unused)]
+
+//! A `UInt32` word gadget backed by 32 little-endian `Boolean` wires, and
+//! the modular `addmany` needed to build the SHA-256 compression function
+//! (see `crate::sha256`) -- bellpepper_core has no native word type.
+
+use bellpepper_core::{
+  boolean::{AllocatedBit, Boolean},
+  num::AllocatedNum,
+  ConstraintSystem, LinearCombination, SynthesisError,
+};
+use ff::{PrimeField, PrimeFieldBits};
+
+use crate::utils::num_to_bits_le;
+
+/// A 32-bit word represented as 32 little-endian `Boolean` wires, plus its
+/// concrete `u32` value when known to the prover.
+#[derive(Clone)]
+pub struct UInt32 {
+  bits: Vec<Boolean>,
+  value: Option<u32>,
+}
+
+impl UInt32 {
+  /// Builds a `UInt32` wholly out of constants, not tied to any witness.
+  pub fn constant(value: u32) -> Self {
+    let mut bits = Vec::with_capacity(32);
+    let mut v = value;
+    for _ in 0..32 {
+      bits.push(Boolean::constant(v & 1 == 1));
+      v >>= 1;
+    }
+    UInt32 { bits, value: Some(value) }
+  }
+
+  /// Allocates a `UInt32`, witnessing each of its 32 bits individually.
+  pub fn alloc<Scalar, CS>(mut cs: CS, value: Option<u32>) -> Result<Self, SynthesisError>
+  where
+    Scalar: PrimeField,
+    CS: ConstraintSystem<Scalar>,
+  {
+    let values = match value {
+      Some(mut v) => {
+        let mut tmp = Vec::with_capacity(32);
+        for _ in 0..32 {
+          tmp.push(Some(v & 1 == 1));
+          v >>= 1;
+        }
+        tmp
+      }
+      None => vec![None; 32],
+    };
+
+    let bits = values
+      .into_iter()
+      .enumerate()
+      .map(|(i, v)| {
+        Ok(Boolean::from(AllocatedBit::alloc(cs.namespace(|| format!("bit {i}")), v)?))
+      })
+      .collect::<Result<Vec<_>, SynthesisError>>()?;
+
+    Ok(UInt32 { bits, value })
+  }
+
+  /// Builds a `UInt32` directly out of 32 little-endian `Boolean`s, e.g. the
+  /// output of another gadget, recomputing its concrete value if known.
+  pub fn from_bits(bits: &[Boolean]) -> Self {
+    assert_eq!(bits.len(), 32);
+    let bits = bits.to_vec();
+
+    let mut value = Some(0u32);
+    for b in bits.iter().rev() {
+      value = value.and_then(|v| b.get_value().map(|bit| (v << 1) | (bit as u32)));
+    }
+
+    UInt32 { bits, value }
+  }
+
+  /// The 32 little-endian bits backing this word.
+  pub fn into_bits(&self) -> Vec<Boolean> {
+    self.bits.clone()
+  }
+
+  /// The word's concrete value, if known to the prover.
+  pub fn get_value(&self) -> Option<u32> {
+    self.value
+  }
+
+  /// Bitwise XOR against another `UInt32`.
+  pub fn xor<Scalar, CS>(&self, mut cs: CS, other: &Self) -> Result<Self, SynthesisError>
+  where
+    Scalar: PrimeField,
+    CS: ConstraintSystem<Scalar>,
+  {
+    let new_value = match (self.value, other.value) {
+      (Some(a), Some(b)) => Some(a ^ b),
+      _ => None,
+    };
+
+    let bits = self
+      .bits
+      .iter()
+      .zip(other.bits.iter())
+      .enumerate()
+      .map(|(i, (a, b))| Boolean::xor(cs.namespace(|| format!("xor {i}")), a, b))
+      .collect::<Result<Vec<_>, SynthesisError>>()?;
+
+    Ok(UInt32 { bits, value: new_value })
+  }
+
+  /// Rotates the bits right by `by` positions (SHA-256's `ROTR`).
+  pub fn rotr(&self, by: usize) -> Self {
+    let by = by % 32;
+    let bits = self.bits.iter().cycle().skip(by).take(32).cloned().collect();
+    UInt32 { bits, value: self.value.map(|v| v.rotate_right(by as u32)) }
+  }
+
+  /// Shifts the bits right by `by` positions, shifting in zero bits from
+  /// the top (SHA-256's `SHR`).
+  pub fn shr(&self, by: usize) -> Self {
+    let by = by % 32;
+    let bits = self
+      .bits
+      .iter()
+      .skip(by)
+      .cloned()
+      .chain(std::iter::repeat(Boolean::constant(false)))
+      .take(32)
+      .collect();
+    UInt32 { bits, value: self.value.map(|v| v >> by) }
+  }
+
+  /// Computes the modular sum of several `UInt32`s (SHA-256's `ADD`, which
+  /// combines up to five words in the compression function). Sums the
+  /// operands as a linear combination of field elements, witnesses the
+  /// result, strictly decomposes it back into bits wide enough to hold the
+  /// full (uncarried) sum, and keeps only the low 32 bits -- dropping the
+  /// carry gives the wraparound result.
+  pub fn addmany<Scalar, CS>(mut cs: CS, operands: &[Self]) -> Result<Self, SynthesisError>
+  where
+    Scalar: PrimeField + PrimeFieldBits,
+    CS: ConstraintSystem<Scalar>,
+  {
+    assert!(!operands.is_empty());
+
+    let mut max_value = (operands.len() as u64) * u64::from(u32::MAX);
+    let mut max_bits = 0usize;
+    while max_value != 0 {
+      max_bits += 1;
+      max_value >>= 1;
+    }
+    assert!(max_bits <= Scalar::CAPACITY as usize);
+
+    let mut lc = LinearCombination::zero();
+    let mut value = Some(0u64);
+
+    for op in operands {
+      let mut coeff = Scalar::ONE;
+      for bit in &op.bits {
+        lc = lc + &bit.lc(CS::one(), coeff);
+        coeff = coeff.double();
+      }
+
+      value = match (value, op.value) {
+        (Some(v), Some(o)) => Some(v + u64::from(o)),
+        _ => None,
+      };
+    }
+
+    let result = AllocatedNum::alloc(cs.namespace(|| "addmany sum"), || {
+      value.map(Scalar::from).ok_or(SynthesisError::AssignmentMissing)
+    })?;
+
+    cs.enforce(
+      || "addmany sum matches operand sum",
+      |_| lc,
+      |lc| lc + CS::one(),
+      |lc| lc + result.get_variable(),
+    );
+
+    let all_bits = num_to_bits_le(cs.namespace(|| "decompose sum"), &result, max_bits)?;
+    let bits = all_bits.into_iter().take(32).map(Boolean::from).collect::<Vec<_>>();
+    let new_value = value.map(|v| (v & u64::from(u32::MAX)) as u32);
+
+    Ok(UInt32 { bits, value: new_value })
+  }
+}
@@ -5,7 +5,7 @@ use bellpepper::gadgets::Assignment;
 use bellpepper_core::{
   boolean::{AllocatedBit, Boolean},
   num::AllocatedNum,
-  ConstraintSystem, LinearCombination, SynthesisError,
+  ConstraintSystem, LinearCombination, SynthesisError, Variable,
 };
 use ff::{PrimeField, PrimeFieldBits};
 use num_bigint::{BigInt, BigUint, ToBigInt};
@@ -45,6 +45,248 @@ where
   Ok(num)
 }
 
+/// Decomposes `num` into `n` little-endian bits, enforcing that recombining
+/// them (via the same linear combination `le_bits_to_num` builds) yields
+/// `num` back. Doesn't by itself rule out the other, larger representative
+/// of `num`'s residue class when `n == F::NUM_BITS` -- see
+/// `num_to_bits_le_strict` for that.
+pub fn num_to_bits_le<F, CS>(
+  mut cs: CS,
+  num: &AllocatedNum<F>,
+  n: usize,
+) -> Result<Vec<AllocatedBit>, SynthesisError>
+where
+  F: PrimeField,
+  CS: ConstraintSystem<F>,
+{
+  assert!(n <= F::NUM_BITS as usize);
+
+  let values = match num.get_value() {
+    Some(value) => {
+      let bits = value.to_le_bits();
+      (0..n).map(|i| Some(bits[i])).collect::<Vec<_>>()
+    }
+    None => vec![None; n],
+  };
+
+  let bits = values
+    .into_iter()
+    .enumerate()
+    .map(|(i, b)| AllocatedBit::alloc(cs.namespace(|| format!("bit {i}")), b))
+    .collect::<Result<Vec<_>, SynthesisError>>()?;
+
+  let mut lc = LinearCombination::zero();
+  let mut coeff = F::ONE;
+  for bit in &bits {
+    lc = lc + (coeff, bit.get_variable());
+    coeff = coeff.double();
+  }
+  lc = lc - num.get_variable();
+  cs.enforce(|| "bit decomposition recombines to num", |lc| lc, |lc| lc, |_| lc);
+
+  Ok(bits)
+}
+
+/// ANDs two bits together.
+fn and_bits<F, CS>(mut cs: CS, a: &AllocatedBit, b: &AllocatedBit) -> Result<AllocatedBit, SynthesisError>
+where
+  F: PrimeField,
+  CS: ConstraintSystem<F>,
+{
+  let result_value = match (a.get_value(), b.get_value()) {
+    (Some(a), Some(b)) => Some(a && b),
+    _ => None,
+  };
+
+  let result = AllocatedBit::alloc(cs.namespace(|| "and result"), result_value)?;
+
+  cs.enforce(
+    || "a * b = result",
+    |lc| lc + a.get_variable(),
+    |lc| lc + b.get_variable(),
+    |lc| lc + result.get_variable(),
+  );
+
+  Ok(result)
+}
+
+/// Computes the multilinear-expansion coefficients needed to select
+/// `coords[4*b2 + 2*b1 + b0]` out of 8 constants: the part linear in `b0`,
+/// `b1` and `b0b1` alone (`l`), and the part that additionally needs
+/// multiplying by `b2` (`m`), so that `result = l + b2 * m`. Shared by
+/// `lookup3` and `lookup3_xy` since the expansion only depends on the
+/// constants, not on which field element they land in.
+fn lookup3_linear_combinations<F: PrimeField>(
+  coords: &[F; 8],
+  one: Variable,
+  b0: Variable,
+  b1: Variable,
+  b0b1: Variable,
+) -> (LinearCombination<F>, LinearCombination<F>) {
+  let c = coords;
+  let l = LinearCombination::zero()
+    + (c[0], one)
+    + (c[1] - c[0], b0)
+    + (c[2] - c[0], b1)
+    + (c[3] - c[2] - c[1] + c[0], b0b1);
+
+  let m = LinearCombination::zero()
+    + (c[4] - c[0], one)
+    + (c[5] - c[4] - c[1] + c[0], b0)
+    + (c[6] - c[4] - c[2] + c[0], b1)
+    + (c[7] - c[6] - c[5] - c[3] + c[4] + c[2] + c[1] - c[0], b0b1);
+
+  (l, m)
+}
+
+/// Selects `coords[4*b2 + 2*b1 + b0]` out of 8 precomputed constants using a
+/// windowed lookup: rather than chaining `conditionally_select` (one
+/// multiplication constraint per bit of index, ~7 for 8 entries), this
+/// allocates only the product `b0*b1`, expresses the selection as the
+/// multilinear expansion over `coords`, and binds it to `result` with a
+/// single final multiplication constraint (`m * b2 = result - l`) -- the building
+/// block for fixed-base scalar multiplication and windowed MSM lookups
+/// (e.g. negating/conditional-add windows over `IPABases`).
+pub fn lookup3<F: PrimeField, CS: ConstraintSystem<F>>(
+  mut cs: CS,
+  bits: &[AllocatedBit; 3],
+  coords: &[F; 8],
+) -> Result<AllocatedNum<F>, SynthesisError> {
+  let (b0, b1, b2) = (&bits[0], &bits[1], &bits[2]);
+
+  let b0b1 = and_bits(cs.namespace(|| "b0 and b1"), b0, b1)?;
+
+  let result_value = match (b0.get_value(), b1.get_value(), b2.get_value()) {
+    (Some(b0v), Some(b1v), Some(b2v)) => {
+      let idx = (b2v as usize) * 4 + (b1v as usize) * 2 + (b0v as usize);
+      Some(coords[idx])
+    }
+    _ => None,
+  };
+
+  let result = AllocatedNum::alloc(cs.namespace(|| "lookup result"), || {
+    result_value.ok_or(SynthesisError::AssignmentMissing)
+  })?;
+
+  let (l, m) = lookup3_linear_combinations(
+    coords,
+    CS::one(),
+    b0.get_variable(),
+    b1.get_variable(),
+    b0b1.get_variable(),
+  );
+
+  cs.enforce(
+    || "bind lookup result",
+    |_| m,
+    |lc| lc + b2.get_variable(),
+    |lc| lc + result.get_variable() - &l,
+  );
+
+  Ok(result)
+}
+
+/// Same windowed lookup as `lookup3`, but selects a pair of coordinates
+/// (e.g. an affine point's `x` and `y`) at once, reusing the same
+/// allocated product `b0*b1` for both.
+pub fn lookup3_xy<F: PrimeField, CS: ConstraintSystem<F>>(
+  mut cs: CS,
+  bits: &[AllocatedBit; 3],
+  x_coords: &[F; 8],
+  y_coords: &[F; 8],
+) -> Result<(AllocatedNum<F>, AllocatedNum<F>), SynthesisError> {
+  let (b0, b1, b2) = (&bits[0], &bits[1], &bits[2]);
+
+  let b0b1 = and_bits(cs.namespace(|| "b0 and b1"), b0, b1)?;
+
+  let idx = match (b0.get_value(), b1.get_value(), b2.get_value()) {
+    (Some(b0v), Some(b1v), Some(b2v)) => Some((b2v as usize) * 4 + (b1v as usize) * 2 + (b0v as usize)),
+    _ => None,
+  };
+
+  let x = AllocatedNum::alloc(cs.namespace(|| "lookup x"), || {
+    idx.map(|i| x_coords[i]).ok_or(SynthesisError::AssignmentMissing)
+  })?;
+  let y = AllocatedNum::alloc(cs.namespace(|| "lookup y"), || {
+    idx.map(|i| y_coords[i]).ok_or(SynthesisError::AssignmentMissing)
+  })?;
+
+  let (lx, mx) = lookup3_linear_combinations(
+    x_coords,
+    CS::one(),
+    b0.get_variable(),
+    b1.get_variable(),
+    b0b1.get_variable(),
+  );
+  cs.enforce(
+    || "bind lookup x",
+    |_| mx,
+    |lc| lc + b2.get_variable(),
+    |lc| lc + x.get_variable() - &lx,
+  );
+
+  let (ly, my) = lookup3_linear_combinations(
+    y_coords,
+    CS::one(),
+    b0.get_variable(),
+    b1.get_variable(),
+    b0b1.get_variable(),
+  );
+  cs.enforce(
+    || "bind lookup y",
+    |_| my,
+    |lc| lc + b2.get_variable(),
+    |lc| lc + y.get_variable() - &ly,
+  );
+
+  Ok((x, y))
+}
+
+/// Like `num_to_bits_le`, but additionally proves `num`'s `F::NUM_BITS`-bit
+/// decomposition is strictly less than the field modulus, so it's the
+/// unique canonical representative rather than one that wraps around --
+/// needed wherever bits are later hashed or compared byte-by-byte (e.g. the
+/// `sha256`/`uint32` gadgets), since a non-canonical decomposition would let
+/// a prover equivocate between two bit patterns for the same field element.
+pub fn num_to_bits_le_strict<F, CS>(
+  mut cs: CS,
+  num: &AllocatedNum<F>,
+) -> Result<Vec<AllocatedBit>, SynthesisError>
+where
+  F: PrimeField + PrimeFieldBits,
+  CS: ConstraintSystem<F>,
+{
+  let n = F::NUM_BITS as usize;
+  let bits = num_to_bits_le(cs.namespace(|| "decompose"), num, n)?;
+  // Bits of `p - 1`, the largest canonical value.
+  let modulus_bits = (F::ZERO - F::ONE).to_le_bits();
+
+  // Walk from the most-significant bit down, tracking whether every bit
+  // seen so far matches `p - 1` exactly (`run_prefix`). At a bound bit of
+  // 0, the candidate bit must also be 0 if the run is still live (else the
+  // candidate would exceed `p - 1` there). At a bound bit of 1, the run
+  // continues only if the candidate bit also matched.
+  let mut run_prefix: Option<AllocatedBit> = None;
+  for i in (0..n).rev() {
+    let bit = &bits[i];
+    if modulus_bits[i] {
+      run_prefix = match run_prefix {
+        Some(prefix) => Some(and_bits(cs.namespace(|| format!("run_prefix {i}")), &prefix, bit)?),
+        None => Some(bit.clone()),
+      };
+    } else if let Some(prefix) = &run_prefix {
+      cs.enforce(
+        || format!("bit {i} must be zero if modulus prefix matched so far"),
+        |lc| lc + bit.get_variable(),
+        |lc| lc + prefix.get_variable(),
+        |lc| lc,
+      );
+    }
+  }
+
+  Ok(bits)
+}
+
 /// Allocate a variable that is set to zero
 pub fn alloc_zero<F: PrimeField, CS: ConstraintSystem<F>>(
   mut cs: CS,
@@ -434,6 +676,144 @@ pub fn mul_add<F: PrimeField, CS: ConstraintSystem<F>>(
   Ok(r)
 }
 
+/// Adapter around a `ConstraintSystem` that packs many bounded-width
+/// equality checks into as few field-element equalities as possible,
+/// instead of emitting one R1CS constraint per comparison. `alloc_num_equals`
+/// and friends above each emit their own constraint per call, which is
+/// wasteful when a circuit checks dozens of independent small equalities --
+/// e.g. byte-level JSON/JWT field matching in the selective-disclosure path.
+///
+/// As long as each equality's operands are known to fit in `num_bits` bits,
+/// `enforce_equal` shifts them into their own non-overlapping slice of the
+/// packed field element (`lhs`/`rhs` accumulators) rather than asserting
+/// them individually; because the slices never overlap, a single
+/// `lhs == rhs` equality over the whole packed element proves every slice's
+/// equality at once. The packed equality is flushed (as one `cs.enforce`
+/// call) once adding another `num_bits`-wide term would overflow the field's
+/// capacity, and whatever remains is flushed by `Drop` so callers can't
+/// forget to finalize it.
+pub struct MultiEq<F: PrimeField, CS: ConstraintSystem<F>> {
+  cs: CS,
+  ops: usize,
+  bits_used: usize,
+  lhs: LinearCombination<F>,
+  rhs: LinearCombination<F>,
+}
+
+impl<F: PrimeField, CS: ConstraintSystem<F>> MultiEq<F, CS> {
+  pub fn new(cs: CS) -> Self {
+    MultiEq { cs, ops: 0, bits_used: 0, lhs: LinearCombination::zero(), rhs: LinearCombination::zero() }
+  }
+
+  fn accumulate(&mut self) {
+    let ops = self.ops;
+    let lhs = std::mem::replace(&mut self.lhs, LinearCombination::zero());
+    let rhs = std::mem::replace(&mut self.rhs, LinearCombination::zero());
+    self.cs.enforce(|| format!("multieq {}", ops), |_| lhs, |lc| lc + CS::one(), |_| rhs);
+    self.bits_used = 0;
+    self.ops += 1;
+  }
+
+  /// Asserts `lhs == rhs`, where both are known to fit in `num_bits` bits,
+  /// packing the check alongside any other equalities accumulated so far
+  /// that still fit within the field's capacity.
+  pub fn enforce_equal(&mut self, num_bits: usize, lhs: &LinearCombination<F>, rhs: &LinearCombination<F>) {
+    assert!(num_bits <= F::CAPACITY as usize, "num_bits exceeds field capacity");
+
+    if self.bits_used + num_bits > F::CAPACITY as usize {
+      self.accumulate();
+    }
+
+    let coeff = F::from(2u64).pow_vartime([self.bits_used as u64]);
+    self.lhs = self.lhs.clone() + (coeff, lhs);
+    self.rhs = self.rhs.clone() + (coeff, rhs);
+    self.bits_used += num_bits;
+  }
+}
+
+impl<F: PrimeField, CS: ConstraintSystem<F>> Drop for MultiEq<F, CS> {
+  fn drop(&mut self) {
+    if self.bits_used > 0 {
+      self.accumulate();
+    }
+  }
+}
+
+impl<F: PrimeField, CS: ConstraintSystem<F>> ConstraintSystem<F> for MultiEq<F, CS> {
+  type Root = Self;
+
+  fn one() -> Variable {
+    CS::one()
+  }
+
+  fn alloc<A, AR, FN>(&mut self, annotation: A, f: FN) -> Result<Variable, SynthesisError>
+  where
+    FN: FnOnce() -> Result<F, SynthesisError>,
+    A: FnOnce() -> AR,
+    AR: Into<String>,
+  {
+    self.cs.alloc(annotation, f)
+  }
+
+  fn alloc_input<A, AR, FN>(&mut self, annotation: A, f: FN) -> Result<Variable, SynthesisError>
+  where
+    FN: FnOnce() -> Result<F, SynthesisError>,
+    A: FnOnce() -> AR,
+    AR: Into<String>,
+  {
+    self.cs.alloc_input(annotation, f)
+  }
+
+  fn enforce<A, AR, LA, LB, LC>(&mut self, annotation: A, a: LA, b: LB, c: LC)
+  where
+    A: FnOnce() -> AR,
+    AR: Into<String>,
+    LA: FnOnce(LinearCombination<F>) -> LinearCombination<F>,
+    LB: FnOnce(LinearCombination<F>) -> LinearCombination<F>,
+    LC: FnOnce(LinearCombination<F>) -> LinearCombination<F>,
+  {
+    self.cs.enforce(annotation, a, b, c)
+  }
+
+  fn push_namespace<NR, N>(&mut self, name_fn: N)
+  where
+    NR: Into<String>,
+    N: FnOnce() -> NR,
+  {
+    self.cs.get_root().push_namespace(name_fn)
+  }
+
+  fn pop_namespace(&mut self) {
+    self.cs.get_root().pop_namespace()
+  }
+
+  fn get_root(&mut self) -> &mut Self::Root {
+    self
+  }
+
+  fn is_extensible() -> bool {
+    false
+  }
+}
+
+/// Asserts `a == F::from(b)`, for use when many constant comparisons are
+/// checked in a loop (e.g. byte-level JSON/JWT field matching): unlike
+/// `alloc_num_equals_constant`, this doesn't allocate a boolean result bit
+/// (the `t*(a-b) = 1-r` / `r*(a-b) = 0` trick that produces one is
+/// inherently quadratic and can't be packed), it only proves the equality
+/// itself -- via `multieq`, so it's packed alongside any other equalities
+/// accumulated in the same `MultiEq` instead of costing its own constraint.
+pub fn enforce_num_equals_constant_multieq<F: PrimeField, CS: ConstraintSystem<F>>(
+  multieq: &mut MultiEq<F, CS>,
+  num_bits: usize,
+  a: &AllocatedNum<F>,
+  b: u64,
+) {
+  let a_lc = LinearCombination::zero() + a.get_variable();
+  let b_lc = LinearCombination::zero() + (F::from(b), CS::one());
+  multieq.enforce_equal(num_bits, &a_lc, &b_lc);
+}
+
 pub fn scalar_to_biguint<Scalar: PrimeField>(x : &Scalar) -> BigUint {
   BigUint::from_bytes_le(x.to_repr().as_ref())
 }
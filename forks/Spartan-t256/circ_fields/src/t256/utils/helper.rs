@@ -2,12 +2,42 @@
 use crate::t256::Config;
 use ark_ec::{models::CurveConfig};
 use ark_serialize::CanonicalSerialize;
+use std::fmt;
 // type SF = <Config as CurveConfig>::ScalarField; // scalar field of T256
 
+/// Error returned when a scalar fails to serialize to its fixed-size byte form.
+#[derive(Debug)]
+pub enum SpartanError {
+    /// The underlying `ark-serialize` call failed.
+    Serialization(ark_serialize::SerializationError),
+}
+
+impl fmt::Display for SpartanError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SpartanError::Serialization(e) => write!(f, "failed to serialize scalar: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for SpartanError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SpartanError::Serialization(e) => Some(e),
+        }
+    }
+}
+
+impl From<ark_serialize::SerializationError> for SpartanError {
+    fn from(e: ark_serialize::SerializationError) -> Self {
+        SpartanError::Serialization(e)
+    }
+}
+
 /// Trait for Spartan
 pub trait SpartanTrait {
-    /// Convert to bytes
-    fn to_bytes(&self) -> [u8; 32];
+    /// Convert to bytes, without panicking on a serialization failure.
+    fn try_to_bytes(&self) -> Result<[u8; 32], SpartanError>;
     // /// Create a zero scalar
     // fn zero() -> Self;
 }
@@ -24,10 +54,10 @@ impl SpartanTrait for <Config as CurveConfig>::ScalarField {
     // }
 
     /// Convert Scalar to bytes
-    fn to_bytes(&self) -> [u8; 32] {
+    fn try_to_bytes(&self) -> Result<[u8; 32], SpartanError> {
         let mut array_bytes = [0u8; 32];
-        self.serialize_compressed(&mut &mut array_bytes[..]).unwrap();
-        array_bytes
+        self.serialize_compressed(&mut &mut array_bytes[..])?;
+        Ok(array_bytes)
     }
     // /// Create a zero scalar
     // fn zero() -> Self {
@@ -114,14 +114,96 @@ mod tests {
     for (i, b) in  bits_le.iter().enumerate() {
        alloc_bits.push(
         AllocatedBit::alloc(
-        &mut cs.namespace(|| format!("alloc x[{}] = {}",i,b)), 
+        &mut cs.namespace(|| format!("alloc x[{}] = {}",i,b)),
         Some(b.clone()))?
       );
     }
     let alloc_num = le_bits_to_num(&mut cs.namespace(||"let_bits_to_num(x)"), &alloc_bits)?;
 
     Ok(alloc_num.get_value())
-  }  
+  }
+
+  /// Allocate a boolean `a`, additionally gated so it is forced to `0`
+  /// whenever the control bit `must_be_false` is set. This is
+  /// `AllocatedBit::alloc` (which already enforces the ordinary boolean
+  /// constraint `(1-a)*a = 0`) plus one extra constraint,
+  /// `(1 - must_be_false - a) * a = 0`: substituting `must_be_false = 1`
+  /// collapses it to `-a*a = 0`, forcing `a = 0`; substituting
+  /// `must_be_false = 0` collapses it to the ordinary boolean constraint
+  /// again, so it's redundant (but harmless) in that case.
+  fn alloc_conditionally<F: PrimeField, CS: ConstraintSystem<F>>(
+    mut cs: CS,
+    value: Option<bool>,
+    must_be_false: &AllocatedBit,
+  ) -> Result<AllocatedBit, SynthesisError> {
+    let a = AllocatedBit::alloc(cs.namespace(|| "conditional bit"), value)?;
+
+    cs.enforce(
+      || "(1 - must_be_false - a) * a = 0",
+      |lc| lc + CS::one() - must_be_false.get_variable() - a.get_variable(),
+      |lc| lc + a.get_variable(),
+      |lc| lc,
+    );
+
+    Ok(a)
+  }
+
+  /// `not_a = 1 - a`, as an `AllocatedBit` with its own boolean constraint.
+  fn alloc_not<F: PrimeField, CS: ConstraintSystem<F>>(
+    mut cs: CS,
+    a: &AllocatedBit,
+  ) -> Result<AllocatedBit, SynthesisError> {
+    let not_a = AllocatedBit::alloc(cs.namespace(|| "not"), a.get_value().map(|v| !v))?;
+
+    cs.enforce(
+      || "not_a = 1 - a",
+      |lc| lc + CS::one(),
+      |lc| lc + CS::one() - a.get_variable() - not_a.get_variable(),
+      |lc| lc,
+    );
+
+    Ok(not_a)
+  }
+
+  /// Like `le_bits_to_num`, but every bit is routed through
+  /// `alloc_conditionally` gated on `!reveal` first: the attribute's real
+  /// bits are only let through when `reveal` is set, and are forced to zero
+  /// otherwise. This is the selective-disclosure building block -- a
+  /// credential field can be range/decomposition-checked in a circuit that's
+  /// shared across disclosure choices, while only actually constraining the
+  /// field to its real value when the holder chooses to reveal it.
+  fn conditional_le_bits_to_num<F, CS>(
+    mut cs: CS,
+    bits_le: &[bool],
+    reveal: &AllocatedBit,
+  ) -> Result<AllocatedNum<F>, SynthesisError>
+  where
+    F: PrimeField + PrimeFieldBits,
+    CS: ConstraintSystem<F>,
+  {
+    let not_reveal = alloc_not(cs.namespace(|| "not reveal"), reveal)?;
+
+    let mut alloc_bits: Vec<AllocatedBit> = vec![];
+    for (i, b) in bits_le.iter().enumerate() {
+      alloc_bits.push(alloc_conditionally(
+        cs.namespace(|| format!("conditional alloc x[{}] = {}", i, b)),
+        reveal.get_value().map(|r| r && *b),
+        &not_reveal,
+      )?);
+    }
+    le_bits_to_num(cs.namespace(|| "le_bits_to_num(x)"), &alloc_bits)
+  }
+
+  fn synthesize_conditional_bits_to_num<F: PrimeField + PrimeFieldBits, CS: ConstraintSystem<F>>(
+    cs: &mut CS,
+    bits_le: &Vec<bool>,
+    reveal: bool,
+  ) -> Result<Option<F>, SynthesisError> {
+    let reveal_bit = AllocatedBit::alloc(cs.namespace(|| "reveal"), Some(reveal))?;
+    let alloc_num = conditional_le_bits_to_num(cs.namespace(|| "conditional_le_bits_to_num(x)"), bits_le, &reveal_bit)?;
+
+    Ok(alloc_num.get_value())
+  }
 
   #[test]
   fn test_bits_to_num() {
@@ -147,6 +229,230 @@ mod tests {
 
   }
 
+  #[test]
+  fn test_conditional_bits_to_num_revealed() {
+    let x = random::<u64>();
+    let x_bits_le : Vec<bool> = (0..64).map(|i| ((x>>i) & 1) != 0 ).collect_vec();
+
+    // First create the shape
+    let mut cs = ShapeCS::<F>::new();
+    let _ = synthesize_conditional_bits_to_num(&mut cs, &x_bits_le, true);
+    let shape = cs.r1cs_shape();
+
+    // Now get the assignment
+    let mut cs: SatisfyingAssignment<F> = SatisfyingAssignment::new();
+    let num = synthesize_conditional_bits_to_num(&mut cs, &x_bits_le, true);
+
+    // reveal = true: the gadget should produce the real number, same as le_bits_to_num
+    assert_eq!(num.unwrap().unwrap(), F::from(x));
+
+    let (inst, witness, inputs) = cs.r1cs_instance_and_witness(&shape);
+
+    let is_sat = inst.is_sat(&witness, &inputs);
+    assert!(is_sat.is_ok());
+    assert_eq!(is_sat.unwrap(), true);
+  }
+
+  #[test]
+  fn test_conditional_bits_to_num_hidden() {
+    let x = random::<u64>();
+    let x_bits_le : Vec<bool> = (0..64).map(|i| ((x>>i) & 1) != 0 ).collect_vec();
+
+    // First create the shape
+    let mut cs = ShapeCS::<F>::new();
+    let _ = synthesize_conditional_bits_to_num(&mut cs, &x_bits_le, false);
+    let shape = cs.r1cs_shape();
+
+    // Now get the assignment
+    let mut cs: SatisfyingAssignment<F> = SatisfyingAssignment::new();
+    let num = synthesize_conditional_bits_to_num(&mut cs, &x_bits_le, false);
+
+    // reveal = false: every bit is forced to 0, regardless of x's real bits
+    assert_eq!(num.unwrap().unwrap(), F::from(0u64));
+
+    let (inst, witness, inputs) = cs.r1cs_instance_and_witness(&shape);
+
+    let is_sat = inst.is_sat(&witness, &inputs);
+    assert!(is_sat.is_ok());
+    assert_eq!(is_sat.unwrap(), true);
+  }
+
+  /// Decomposes `num` into exactly `n` little-endian bits (`n <= F::NUM_BITS`),
+  /// enforcing that those bits are booleans which recompose to `num` -- i.e.
+  /// that `num`'s value fits in `n` bits. Unlike `num_to_bits_le_strict`,
+  /// this does not rule out the handful of representations above `p - 1` that
+  /// happen to also fit in `n` bits when `n == F::NUM_BITS`, so it should
+  /// only be used when `n` is known to be small enough that every `n`-bit
+  /// value is already `< p` (e.g. a fixed-width limb), or when the caller
+  /// doesn't care about canonicity.
+  fn num_to_bits_le<F, CS>(
+    mut cs: CS,
+    num: &AllocatedNum<F>,
+    n: usize,
+  ) -> Result<Vec<AllocatedBit>, SynthesisError>
+  where
+    F: PrimeField + PrimeFieldBits,
+    CS: ConstraintSystem<F>,
+  {
+    assert!(n <= F::NUM_BITS as usize, "n must not exceed F::NUM_BITS");
+
+    let bit_values: Vec<Option<bool>> = match num.get_value() {
+      Some(value) => value.to_le_bits().iter().by_vals().take(n).map(Some).collect(),
+      None => vec![None; n],
+    };
+
+    let mut bits = Vec::with_capacity(n);
+    let mut lc = LinearCombination::zero();
+    let mut coeff = F::ONE;
+    for (i, value) in bit_values.into_iter().enumerate() {
+      let bit = AllocatedBit::alloc(cs.namespace(|| format!("bit {}", i)), value)?;
+      lc = lc + (coeff, bit.get_variable());
+      coeff = coeff.double();
+      bits.push(bit);
+    }
+    lc = lc - num.get_variable();
+    cs.enforce(|| "bits recompose to num", |lc| lc, |lc| lc, |_| lc);
+
+    Ok(bits)
+  }
+
+  /// `a AND b`, as a freshly allocated `AllocatedBit`.
+  fn and_bits<F: PrimeField, CS: ConstraintSystem<F>>(
+    mut cs: CS,
+    a: &AllocatedBit,
+    b: &AllocatedBit,
+  ) -> Result<AllocatedBit, SynthesisError> {
+    let and_value = match (a.get_value(), b.get_value()) {
+      (Some(a), Some(b)) => Some(a && b),
+      _ => None,
+    };
+    let result = AllocatedBit::alloc(cs.namespace(|| "and"), and_value)?;
+    cs.enforce(
+      || "a * b = result",
+      |lc| lc + a.get_variable(),
+      |lc| lc + b.get_variable(),
+      |lc| lc + result.get_variable(),
+    );
+    Ok(result)
+  }
+
+  /// Like `num_to_bits_le` with `n = F::NUM_BITS`, but additionally enforces
+  /// that the decomposed bits are the unique canonical representative in
+  /// `[0, p)` -- i.e. rules out the wrap-around representations that a plain
+  /// bit re-summation would also accept. This matters whenever the bits feed
+  /// downstream range logic that assumes a non-wrapping representation (RSA
+  /// limbs, hash outputs, etc).
+  ///
+  /// Walks the bits from most to least significant alongside the bits of
+  /// `p - 1` (the largest canonical value), maintaining `run_prefix`: "every bit
+  /// above this position equals the bound's corresponding bit" (`None`
+  /// stands for the constant `true` before the first bit is seen). At a
+  /// bound-bit-0 position, `bit` must be `0` while `run_prefix` still
+  /// holds -- `bit * run_prefix = 0` -- since matching `p - 1` digit for
+  /// digit up to here and then exceeding it at a `0` would make the value
+  /// `>= p`. At a bound-bit-1 position, `run_prefix` is ANDed with `bit`,
+  /// since the prefix only continues to match if this bit is also `1`.
+  fn num_to_bits_le_strict<F, CS>(
+    mut cs: CS,
+    num: &AllocatedNum<F>,
+  ) -> Result<Vec<AllocatedBit>, SynthesisError>
+  where
+    F: PrimeField + PrimeFieldBits,
+    CS: ConstraintSystem<F>,
+  {
+    let num_bits = F::NUM_BITS as usize;
+    let bits = num_to_bits_le(cs.namespace(|| "decompose into NUM_BITS bits"), num, num_bits)?;
+
+    let modulus_bits: Vec<bool> = (F::ZERO - F::ONE).to_le_bits().iter().by_vals().take(num_bits).collect();
+
+    let mut run_prefix: Option<AllocatedBit> = None;
+    for i in (0..num_bits).rev() {
+      if modulus_bits[i] {
+        run_prefix = Some(match run_prefix {
+          Some(prefix) => and_bits(cs.namespace(|| format!("run_prefix through bit[{}]", i)), &prefix, &bits[i])?,
+          None => bits[i].clone(),
+        });
+      } else {
+        match &run_prefix {
+          Some(prefix) => {
+            cs.enforce(
+              || format!("bit[{}] * run_prefix = 0", i),
+              |lc| lc + bits[i].get_variable(),
+              |lc| lc + prefix.get_variable(),
+              |lc| lc,
+            );
+          }
+          None => {
+            cs.enforce(
+              || format!("bit[{}] = 0", i),
+              |lc| lc + bits[i].get_variable(),
+              |lc| lc + CS::one(),
+              |lc| lc,
+            );
+          }
+        }
+      }
+    }
+
+    Ok(bits)
+  }
+
+  fn synthesize_num_to_bits_le_strict<F: PrimeField + PrimeFieldBits, CS: ConstraintSystem<F>>(
+    cs: &mut CS,
+    x: F,
+  ) -> Result<Vec<Option<bool>>, SynthesisError> {
+    let num = AllocatedNum::alloc(cs.namespace(|| "x"), || Ok(x))?;
+    let bits = num_to_bits_le_strict(cs.namespace(|| "num_to_bits_le_strict(x)"), &num)?;
+    Ok(bits.iter().map(|b| b.get_value()).collect())
+  }
+
+  #[test]
+  fn test_num_to_bits_le_strict() {
+    let x = F::from(random::<u64>());
+    let x_bits_le: Vec<bool> = x.to_le_bits().iter().by_vals().take(F::NUM_BITS as usize).collect();
+
+    // First create the shape
+    let mut cs = ShapeCS::<F>::new();
+    let _ = synthesize_num_to_bits_le_strict(&mut cs, x);
+    let shape = cs.r1cs_shape();
+
+    // Now get the assignment
+    let mut cs: SatisfyingAssignment<F> = SatisfyingAssignment::new();
+    let bits = synthesize_num_to_bits_le_strict(&mut cs, x).unwrap();
+
+    let bits: Vec<bool> = bits.into_iter().map(|b| b.unwrap()).collect();
+    assert_eq!(bits, x_bits_le);
+
+    let (inst, witness, inputs) = cs.r1cs_instance_and_witness(&shape);
+
+    let is_sat = inst.is_sat(&witness, &inputs);
+    assert!(is_sat.is_ok());
+    assert_eq!(is_sat.unwrap(), true);
+  }
+
+  #[test]
+  fn test_num_to_bits_le_strict_accepts_largest_canonical_value() {
+    // p - 1 is the largest value the `< p` walk lets through: its bits match
+    // the bound on every position. Check it is still accepted and decomposed
+    // into its canonical bits.
+    let x = F::ZERO - F::ONE; // p - 1, the largest canonical representative
+    let x_bits_le: Vec<bool> = x.to_le_bits().iter().by_vals().take(F::NUM_BITS as usize).collect();
+
+    let mut cs = ShapeCS::<F>::new();
+    let _ = synthesize_num_to_bits_le_strict(&mut cs, x);
+    let shape = cs.r1cs_shape();
+
+    let mut cs: SatisfyingAssignment<F> = SatisfyingAssignment::new();
+    let bits = synthesize_num_to_bits_le_strict(&mut cs, x).unwrap();
+    let bits: Vec<bool> = bits.into_iter().map(|b| b.unwrap()).collect();
+    assert_eq!(bits, x_bits_le);
+
+    let (inst, witness, inputs) = cs.r1cs_instance_and_witness(&shape);
+    let is_sat = inst.is_sat(&witness, &inputs);
+    assert!(is_sat.is_ok());
+    assert_eq!(is_sat.unwrap(), true);
+  }
+
   #[test]
   fn test_bellpepper_circuit_with_nizk() {
     let x = random::<u64>();
@@ -3,8 +3,10 @@ use super::scalar::Scalar;
 use core::borrow::Borrow;
 use core::ops::{Mul, MulAssign, Add, Sub};
 use halo2curves::serde::Repr;
-use halo2curves::t256::{T256Affine as Affine, T256 as Projective};
-use halo2curves::group::{Curve, GroupEncoding};
+use halo2curves::t256::{Fp, T256Affine as Affine, T256 as Projective};
+use halo2curves::group::{cofactor::CofactorGroup, Curve, GroupEncoding};
+use halo2curves::ff::PrimeField;
+use halo2curves::{Coordinates, CurveAffine};
 use serde_bytes::ByteArray;
 use lazy_static::lazy_static;
 use serde::{Deserialize, Serialize, Serializer, Deserializer};
@@ -15,6 +17,12 @@ use crate::scalar::ScalarBytesFromScalar;
 pub struct GroupElement(pub Projective); 
 pub type GroupElementOri = Projective;
 pub type CompressedGroup = ByteArray<33>;
+/// Uncompressed `0x04 || x || y` point encoding (SEC1-style): twice the wire
+/// size of `CompressedGroup`, but `from_uncompressed` recovers the point
+/// without `decompress`'s modular square root for `y` -- worthwhile when
+/// many points are read back-to-back, e.g. a batch of DLEQ or range-proof
+/// group elements.
+pub type UncompressedGroup = ByteArray<65>;
 
 lazy_static! {
   /// Compressed form of the generator
@@ -45,6 +53,21 @@ impl GroupElement {
   pub fn compress(&self) -> CompressedGroup {
     CompressedGroup::new(self.0.to_bytes().into())
   }
+
+  /// Encodes the point as `0x04 || x || y`, skipping the `y` square root
+  /// `compress`/`decompress` pay for. The identity is encoded as `0x04`
+  /// followed by 64 zero bytes, matching `from_uncompressed`'s handling of it.
+  pub fn to_uncompressed(&self) -> UncompressedGroup {
+    let affine = self.0.to_affine();
+    let mut bytes = [0u8; 65];
+    bytes[0] = 0x04;
+    if let Some(coords) = Option::from(affine.coordinates()) {
+      let coords: Coordinates<Affine> = coords;
+      bytes[1..33].copy_from_slice(coords.x().to_repr().as_ref());
+      bytes[33..65].copy_from_slice(coords.y().to_repr().as_ref());
+    }
+    UncompressedGroup::new(bytes)
+  }
 }
 
 impl Serialize for GroupElement {
@@ -86,6 +109,50 @@ impl CompressedGroupExt for CompressedGroup {
   }
 }
 
+/// Parses the `0x04 || x || y` encoding `GroupElement::to_uncompressed` produces.
+/// Unlike `CompressedGroupExt::decompress`, this never computes a modular
+/// square root -- `from_xy` just checks `(x, y)` satisfies the curve equation,
+/// and `is_torsion_free` confirms the result is in the prime-order subgroup.
+pub trait UncompressedEncoding {
+  type Group;
+  fn unpack(&self) -> Result<Self::Group, ProofVerifyError>;
+  fn from_uncompressed(&self) -> Option<Self::Group>;
+}
+
+impl UncompressedEncoding for UncompressedGroup {
+  type Group = GroupElement;
+
+  fn unpack(&self) -> Result<Self::Group, ProofVerifyError> {
+    self.from_uncompressed().ok_or(ProofVerifyError::DecompressionError([4; 32]))
+  }
+
+  fn from_uncompressed(&self) -> Option<Self::Group> {
+    let bytes = &self[..];
+    if bytes[0] != 0x04 {
+      return None;
+    }
+
+    let mut x_repr = <Fp as PrimeField>::Repr::default();
+    let mut y_repr = <Fp as PrimeField>::Repr::default();
+    x_repr.as_mut().copy_from_slice(&bytes[1..33]);
+    y_repr.as_mut().copy_from_slice(&bytes[33..65]);
+    let x = Option::from(Fp::from_repr(x_repr))?;
+    let y = Option::from(Fp::from_repr(y_repr))?;
+
+    let affine: Affine = Option::from(Affine::from_xy(x, y))?;
+    if !bool::from(affine.is_on_curve()) {
+      return None;
+    }
+
+    let point = Projective::from(affine);
+    if !bool::from(point.is_torsion_free()) {
+      return None;
+    }
+
+    Some(GroupElement(point))
+  }
+}
+
 impl<'b> MulAssign<&'b Scalar> for GroupElement {
   fn mul_assign(&mut self, scalar: &'b Scalar) {
     let point = (self as &GroupElement).into();
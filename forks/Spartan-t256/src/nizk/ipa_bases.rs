@@ -1,5 +1,6 @@
 
 use crate::group::GroupElement as C;
+use crate::group::VartimeMultiscalarMul;
 use crate::scalar::Scalar as F;
 
 
@@ -85,8 +86,17 @@ impl IPABases<C, F>
         }
         
         (result_scalars, result_bases)
-    }    
+    }
 
-   
+    /// Folds the deferred base terms for `values` into a single group
+    /// element, instead of leaving the caller to fold `get`'s flat term
+    /// list naively (which is quadratic-cost for large inner-product
+    /// arguments). Delegates to `vartime_multiscalar_mul`, the same
+    /// variable-time, windowed (Pippenger-style) MSM backend every other
+    /// multi-scalar multiplication in this crate already goes through.
+    pub fn eval(&self, values: &[F]) -> C {
+        let (scalars, bases) = self.get(values);
+        C::vartime_multiscalar_mul(&scalars, &bases)
+    }
 
 }
\ No newline at end of file
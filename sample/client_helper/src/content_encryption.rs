@@ -0,0 +1,134 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT license.
+
+// A minimal implementation of the `aes128gcm` scheme from RFC 8188
+// ("Encrypted Content-Encoding for HTTP"): a content-encryption key and
+// nonce are derived from an input keying material (IKM) and a random salt
+// via HKDF-SHA256, then the plaintext is split into fixed-size records,
+// each AES-128-GCM encrypted with the salt-derived nonce XORed with the
+// record's sequence number, and each carrying a trailing delimiter byte
+// (2 for the last record, 1 otherwise) before encryption. Records are
+// prefixed by the standard header block: salt, record size, keyid length,
+// and keyid.
+
+use aes_gcm::aead::{Aead, KeyInit};
+use aes_gcm::{Aes128Gcm, Nonce};
+use hkdf::Hkdf;
+use rand::{thread_rng, RngCore};
+use sha2::Sha256;
+
+const SALT_LEN: usize = 16;
+const KEY_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+const TAG_LEN: usize = 16;
+const DEFAULT_RECORD_SIZE: u32 = 4096;
+
+const CEK_INFO: &[u8] = b"Content-Encoding: aes128gcm\0";
+const NONCE_INFO: &[u8] = b"Content-Encoding: nonce\0";
+
+#[derive(Debug)]
+pub struct ContentEncryptionError(pub String);
+
+fn derive_key_and_base_nonce(ikm: &[u8], salt: &[u8]) -> ([u8; KEY_LEN], [u8; NONCE_LEN]) {
+    let hk = Hkdf::<Sha256>::new(Some(salt), ikm);
+    let mut cek = [0u8; KEY_LEN];
+    hk.expand(CEK_INFO, &mut cek).expect("HKDF expand for content-encryption key");
+    let mut nonce = [0u8; NONCE_LEN];
+    hk.expand(NONCE_INFO, &mut nonce).expect("HKDF expand for nonce");
+    (cek, nonce)
+}
+
+fn record_nonce(base_nonce: &[u8; NONCE_LEN], seq: u64) -> [u8; NONCE_LEN] {
+    let mut nonce = *base_nonce;
+    let seq_bytes = seq.to_be_bytes();
+    for i in 0..8 {
+        nonce[NONCE_LEN - 8 + i] ^= seq_bytes[i];
+    }
+    nonce
+}
+
+/// Encrypts `plaintext` under `ikm`, tagging the header with `keyid` so a
+/// recipient knows which keying material to use (the keyid does not itself
+/// appear in the key derivation, it is only metadata).
+pub fn encrypt(ikm: &[u8], keyid: &str, plaintext: &[u8]) -> Vec<u8> {
+    let mut salt = [0u8; SALT_LEN];
+    thread_rng().fill_bytes(&mut salt);
+    let rs = DEFAULT_RECORD_SIZE;
+    let (cek, base_nonce) = derive_key_and_base_nonce(ikm, &salt);
+    let cipher = Aes128Gcm::new_from_slice(&cek).expect("AES-128-GCM key is the right length");
+
+    let mut out = Vec::with_capacity(SALT_LEN + 4 + 1 + keyid.len() + plaintext.len() + TAG_LEN);
+    out.extend_from_slice(&salt);
+    out.extend_from_slice(&rs.to_be_bytes());
+    out.push(keyid.len() as u8);
+    out.extend_from_slice(keyid.as_bytes());
+
+    let record_plaintext_len = rs as usize - TAG_LEN - 1;
+    let mut offset = 0;
+    let mut seq: u64 = 0;
+    loop {
+        let remaining = plaintext.len() - offset;
+        let chunk_len = remaining.min(record_plaintext_len);
+        let is_last = remaining <= record_plaintext_len;
+
+        let mut record = plaintext[offset..offset + chunk_len].to_vec();
+        record.push(if is_last { 2 } else { 1 });
+
+        let nonce = record_nonce(&base_nonce, seq);
+        let ciphertext = cipher
+            .encrypt(Nonce::from_slice(&nonce), record.as_ref())
+            .expect("AES-128-GCM encryption failure");
+        out.extend_from_slice(&ciphertext);
+
+        offset += chunk_len;
+        seq += 1;
+        if is_last {
+            break;
+        }
+    }
+    out
+}
+
+/// Reverses [`encrypt`]. `ikm` must be the same keying material the blob was
+/// encrypted with; the keyid embedded in the header is not checked here,
+/// callers that manage multiple keys are expected to read it themselves
+/// before picking the matching `ikm`.
+pub fn decrypt(ikm: &[u8], blob: &[u8]) -> Result<Vec<u8>, ContentEncryptionError> {
+    if blob.len() < SALT_LEN + 4 + 1 {
+        return Err(ContentEncryptionError("ciphertext shorter than the aes128gcm header".to_string()));
+    }
+    let salt = &blob[0..SALT_LEN];
+    let rs = u32::from_be_bytes(blob[SALT_LEN..SALT_LEN + 4].try_into().unwrap()) as usize;
+    let idlen = blob[SALT_LEN + 4] as usize;
+    let header_len = SALT_LEN + 4 + 1 + idlen;
+    if blob.len() < header_len {
+        return Err(ContentEncryptionError("ciphertext truncated within the keyid field".to_string()));
+    }
+
+    let (cek, base_nonce) = derive_key_and_base_nonce(ikm, salt);
+    let cipher = Aes128Gcm::new_from_slice(&cek).expect("AES-128-GCM key is the right length");
+
+    let mut plaintext = Vec::new();
+    let mut offset = header_len;
+    let mut seq: u64 = 0;
+    while offset < blob.len() {
+        let record_len = rs.min(blob.len() - offset);
+        let record = &blob[offset..offset + record_len];
+        let nonce = record_nonce(&base_nonce, seq);
+        let mut decrypted = cipher
+            .decrypt(Nonce::from_slice(&nonce), record)
+            .map_err(|_| ContentEncryptionError("record failed to decrypt or authenticate".to_string()))?;
+
+        let is_last = offset + record_len >= blob.len();
+        let delimiter = decrypted.pop().ok_or_else(|| ContentEncryptionError("empty record".to_string()))?;
+        match (delimiter, is_last) {
+            (2, true) | (1, false) => {}
+            _ => return Err(ContentEncryptionError("unexpected record delimiter".to_string())),
+        }
+
+        plaintext.extend_from_slice(&decrypted);
+        offset += record_len;
+        seq += 1;
+    }
+    Ok(plaintext)
+}
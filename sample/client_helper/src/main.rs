@@ -7,13 +7,19 @@ use crescent::groth16rand::ClientState;
 use crescent::prep_inputs::{parse_config, prepare_prover_inputs};
 use crescent::rangeproof::RangeProofPK;
 use crescent::structs::{GenericInputsJSON, IOLocations};
-use crescent::{create_client_state, create_show_proof, create_show_proof_mdl, CachePaths, CrescentPairing, ProofSpec};
+use crescent::{check_revocation, create_client_state, create_show_proof, create_show_proof_mdl, CachePaths, CrescentPairing, ProofSpec, ShowProof};
 use crescent::utils::{read_from_b64url, read_from_file, write_to_b64url};
 use crescent::ProverParams;
 use crescent::device::TestDevice;
+use ark_ff::PrimeField;
 
 use crescent_sample_setup_service::common::*;
 
+mod task_store;
+use task_store::{SqliteTaskStore, TaskState, TaskStore};
+
+mod content_encryption;
+
 use rocket::serde::{Serialize, Deserialize};
 use rocket::serde::json::Json;
 use rocket::{get, post};
@@ -25,6 +31,8 @@ use tokio::sync::Mutex;
 use serde_json::{json, Value};
 use jsonwebkey::JsonWebKey;
 use sha2::{Digest, Sha256};
+use hkdf::Hkdf;
+use rand::RngCore;
 
 use std::collections::HashMap;
 use std::fs::{self};
@@ -39,6 +47,9 @@ use std::cmp::min;
 //       For caching the client helper could re-use the CachePaths struct and approach.
 const CRESCENT_DATA_BASE_PATH : &str = "./data/creds";
 const CRESCENT_SHARED_DATA_SUFFIX : &str = "shared";
+const TASK_STORE_DB_PATH : &str = "./data/creds/tasks.sqlite";
+const MASTER_SECRET_PATH : &str = "./data/creds/master.key";
+const MASTER_SECRET_LEN : usize = 32;
 
 // struct for the JWT info
 #[derive(Serialize, Deserialize, Clone)]
@@ -48,15 +59,169 @@ struct CredInfo {
     issuer_url: String  // The URL of the issuer
 }
 
-// holds the ShowData for ready credentials
-struct SharedState(Arc<Mutex<HashMap<String, Option<ShowData>>>>);
+// Holds the per-credential task state. `cache` is an in-memory write-through
+// cache of `store`, so a hot instance doesn't have to hit SQLite on every
+// request; `store` is the durable source of truth that survives restarts
+// and lets multiple helper instances share prepared credentials.
+struct SharedState {
+    cache: Arc<Mutex<HashMap<String, TaskState>>>,
+    store: Arc<dyn TaskStore>,
+    // Root key material for encrypting `ShowData` at rest (see
+    // `encrypt_show_data`/`decrypt_show_data`). Generated once and persisted
+    // to `MASTER_SECRET_PATH`; never written to the task store or disk cache
+    // alongside the ciphertext it protects.
+    master_secret: Arc<Vec<u8>>,
+}
+
+impl SharedState {
+    // Looks up `cred_uid` in the in-memory cache first, falling back to the
+    // durable store (and repopulating the cache) on a miss -- e.g. right
+    // after this instance started and hasn't seen the credential yet.
+    async fn get_task(&self, cred_uid: &str) -> Option<TaskState> {
+        if let Some(task_state) = self.cache.lock().await.get(cred_uid).cloned() {
+            return Some(task_state);
+        }
+        match self.store.get(cred_uid) {
+            Ok(Some(task_state)) => {
+                self.cache.lock().await.insert(cred_uid.to_string(), task_state.clone());
+                Some(task_state)
+            }
+            Ok(None) => None,
+            Err(e) => {
+                eprintln!("Failed to read task store entry for cred_uid {}: {}", cred_uid, e);
+                None
+            }
+        }
+    }
+
+    async fn insert_preparing(&self, cred_uid: &str) {
+        self.cache.lock().await.insert(cred_uid.to_string(), TaskState::Preparing);
+        if let Err(e) = self.store.insert_preparing(cred_uid) {
+            eprintln!("Failed to persist preparing state for cred_uid {}: {}", cred_uid, e);
+        }
+    }
+
+    async fn insert_ready(&self, cred_uid: &str, ciphertext: Vec<u8>) {
+        if let Err(e) = self.store.insert_ready(cred_uid, &ciphertext) {
+            eprintln!("Failed to persist ready state for cred_uid {}: {}", cred_uid, e);
+        }
+        self.cache.lock().await.insert(cred_uid.to_string(), TaskState::Ready(ciphertext));
+    }
+
+    async fn remove(&self, cred_uid: &str) {
+        self.cache.lock().await.remove(cred_uid);
+        if let Err(e) = self.store.remove(cred_uid) {
+            eprintln!("Failed to remove task store entry for cred_uid {}: {}", cred_uid, e);
+        }
+    }
+}
+
+// Loads the server's root key material from `path`, generating and
+// persisting a fresh random one if it doesn't exist yet. Every
+// per-credential content-encryption key is derived from this value, so
+// losing it makes every cached `ShowData` unrecoverable -- same trust level
+// as the task store database itself.
+fn load_or_create_master_secret(path: &str) -> Vec<u8> {
+    if let Ok(secret) = fs::read(path) {
+        return secret;
+    }
+    let mut secret = vec![0u8; MASTER_SECRET_LEN];
+    rand::thread_rng().fill_bytes(&mut secret);
+    fs::write(path, &secret).expect("Failed to persist master secret");
+    secret
+}
+
+// Derives the per-credential content-encryption keying material for
+// `cred_uid` from the server's master secret. `cred_uid` also becomes the
+// ciphertext's RFC 8188 `keyid`, since it's exactly what the caller already
+// has on hand to ask the server to derive the matching key again.
+fn derive_credential_ikm(master_secret: &[u8], cred_uid: &str) -> [u8; 32] {
+    let hk = Hkdf::<Sha256>::new(Some(cred_uid.as_bytes()), master_secret);
+    let mut ikm = [0u8; 32];
+    hk.expand(b"crescent client_helper show data", &mut ikm)
+        .expect("HKDF expand for credential IKM");
+    ikm
+}
+
+fn encrypt_show_data(master_secret: &[u8], cred_uid: &str, show_data: &ShowData) -> Vec<u8> {
+    let ikm = derive_credential_ikm(master_secret, cred_uid);
+    let plaintext = serde_json::to_vec(show_data).expect("Failed to serialize ShowData");
+    content_encryption::encrypt(&ikm, cred_uid, &plaintext)
+}
+
+fn decrypt_show_data(master_secret: &[u8], cred_uid: &str, ciphertext: &[u8]) -> Result<ShowData, String> {
+    let ikm = derive_credential_ikm(master_secret, cred_uid);
+    let plaintext = content_encryption::decrypt(&ikm, ciphertext).map_err(|e| e.0)?;
+    serde_json::from_slice(&plaintext).map_err(|e| format!("Failed to parse decrypted ShowData: {}", e))
+}
+
+// Wraps a show proof in a minimal W3C-style Verifiable Presentation envelope
+// (https://www.w3.org/TR/vc-data-model-2.0/#presentations), for callers that
+// want one self-describing document instead of tracking the proof spec,
+// issuer and credential type out of band alongside the bare proof. The
+// disclosed claims are rendered from `show_proof.revealed_inputs` (plain
+// field elements, following the same `.into_bigint().to_string()` rendering
+// used elsewhere when a `ShowProof`'s revealed inputs need to become JSON)
+// and `show_proof.revealed_preimages` (a JSON object of hashed claims), and
+// the proof itself is embedded as an opaque base64url-encoded proof value.
+fn build_verifiable_presentation(
+    show_data: &ShowData,
+    client_state: &ClientState<CrescentPairing>,
+    proof_spec: &ProofSpec,
+    show_proof: &ShowProof<CrescentPairing>,
+    show_proof_b64: &str,
+) -> String {
+    let mut credential_subject = serde_json::Map::new();
+    for (name, value) in proof_spec.revealed.iter().zip(show_proof.revealed_inputs.iter()) {
+        credential_subject.insert(name.clone(), json!(value.into_bigint().to_string()));
+    }
+    if let Some(preimages_str) = &show_proof.revealed_preimages {
+        if let Ok(Value::Object(preimages)) = serde_json::from_str::<Value>(preimages_str) {
+            for (name, value) in preimages {
+                credential_subject.insert(name, value);
+            }
+        }
+    }
+
+    let presentation_message_b64 = proof_spec.presentation_message.as_ref().map(base64_url::encode);
+
+    let vp = json!({
+        "@context": ["https://www.w3.org/ns/credentials/v2"],
+        "type": ["VerifiablePresentation"],
+        "issuer": show_data.issuer_url,
+        "credentialType": client_state.credtype,
+        "proofSpec": proof_spec,
+        "credentialSubject": credential_subject,
+        "proof": {
+            "type": "CrescentGroth16Signature2024",
+            "presentationMessage": presentation_message_b64,
+            "proofValue": show_proof_b64,
+        }
+    });
+    vp.to_string()
+}
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 struct ShowData {
     client_state_b64: String,
     range_pk_b64: String,
     io_locations_str: String,
-    device_priv_key_path: String
+    device_priv_key_path: String,
+    // The issuer this credential came from, if any (mDL credentials have no
+    // issuer URL in this sample). Only needed to name an `issuer` in the
+    // `format=vp` Verifiable Presentation envelope `/show` can emit.
+    #[serde(default)]
+    issuer_url: Option<String>,
+}
+
+// What `/getshowdata` actually returns: the RFC 8188 `aes128gcm` ciphertext
+// of a `ShowData`, plus the key to open it. The ciphertext alone is what
+// sits in the task store and on disk; the key is derived fresh for this
+// response so it's never persisted alongside it.
+#[derive(Serialize, Deserialize, Clone)]
+struct EncryptedShowData {
+    ciphertext_b64: String,
+    key_b64: String,
 }
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -71,6 +236,23 @@ struct VerifyResult {
     email_domain: String
 }
 
+// Identifies the signature suite a JWK is for, from its `kty`/`crv` fields
+// (the `alg` field is optional in JWK and issuers don't always set it).
+// Only "RS256" has a matching Crescent circuit in this deployment; the other
+// cases are recognized so we can name the algorithm in the error we return,
+// rather than failing with an opaque PEM-conversion error.
+fn detect_key_algorithm(jwk_value: &Value) -> Result<&'static str, String> {
+    let kty = jwk_value.get("kty").and_then(|v| v.as_str()).unwrap_or("");
+    let crv = jwk_value.get("crv").and_then(|v| v.as_str());
+
+    match (kty, crv) {
+        ("RSA", _) => Ok("RS256"),
+        ("EC", Some("P-256")) => Ok("ES256"),
+        ("OKP", Some("Ed25519")) => Ok("EdDSA"),
+        _ => Err(format!("Unrecognized JWK key type/curve (kty={}, crv={:?})", kty, crv)),
+    }
+}
+
 async fn fetch_and_save_jwk(issuer_url: &str, cred_folder: &str) -> Result<(), String> {
     // Prepare the JWK URL
     let jwk_url = format!("{}/.well-known/jwks.json", issuer_url);
@@ -91,6 +273,14 @@ async fn fetch_and_save_jwk(issuer_url: &str, cred_folder: &str) -> Result<(), S
         .and_then(|keys| keys.first())
         .ok_or_else(|| "No keys found in JWK set".to_string())?;
 
+    // Only RS256 has a matching proving circuit right now; fail cleanly here
+    // instead of letting an ES256/EdDSA key reach `prepare_prover_inputs` and
+    // fail confusingly (or panic) deeper in the RSA-specific proving path.
+    let key_alg = detect_key_algorithm(jwk_value)?;
+    if key_alg != "RS256" {
+        return Err(format!("Unsupported signature algorithm '{}': this deployment only has a circuit for RSA/RS256 credentials", key_alg));
+    }
+
     // Deserialize the JSON `Value` into a `JsonWebKey`
     let jwk: JsonWebKey = serde_json::from_value(jwk_value.clone())
         .map_err(|e| format!("Failed to parse JWK: {}", e))?;
@@ -119,8 +309,8 @@ async fn prepare(cred_info: Json<CredInfo>, state: &State<SharedState>) -> Json<
     let l = min(50, cred_info.cred.len());
     println!("Credential: {}... ({} bytes)", &cred_info.cred[..l], cred_info.cred.len());
 
-    // verify if the schema_uid is one of our supported SCHEMA_UIDS
-    if !SCHEMA_UIDS.contains(&cred_info.schema_uid.as_str()) {
+    // verify if the schema_uid is one of our supported schema_uids
+    if !schema_uids().contains(&cred_info.schema_uid.as_str()) {
         println!("Unsupported schema UID: {}", cred_info.schema_uid);
         return Json(("error".to_string(), None)); // FIXME: not the right way to handle errors
     }
@@ -143,13 +333,12 @@ async fn prepare(cred_info: Json<CredInfo>, state: &State<SharedState>) -> Json<
     println!("Copied base folder to credential-specific folder: {}", cred_folder);
 
     // Insert task with empty data (indicating "preparing")
-    {
-        let mut tasks = state.inner().0.lock().await;
-        tasks.insert(cred_uid.clone(), None);
-    }
+    state.inner().insert_preparing(&cred_uid).await;
 
-    // Clone the state for async task
-    let state = state.inner().0.clone();
+    // Clone the state for async task (SharedState is Arc-backed, so this is cheap)
+    let cache = state.inner().cache.clone();
+    let store = state.inner().store.clone();
+    let master_secret = state.inner().master_secret.clone();
     let cred_uid_clone = cred_uid.clone();
     let issuer_url = cred_info.issuer_url.clone();
 
@@ -221,21 +410,44 @@ async fn prepare(cred_info: Json<CredInfo>, state: &State<SharedState>) -> Json<
             
             // save the path to the device private key if the credential is device-bound
             let device_priv_key_path = paths.device_prv_pem.clone();
-            let show_data = ShowData { client_state_b64, range_pk_b64, io_locations_str, device_priv_key_path };
+            let show_data = ShowData {
+                client_state_b64,
+                range_pk_b64,
+                io_locations_str,
+                device_priv_key_path,
+                issuer_url: if issuer_url.is_empty() { None } else { Some(issuer_url.clone()) },
+            };
             println!("Task complete, storing ShowData (size: {:?} bytes, took {:?})",
                 show_data.client_state_b64.len() + show_data.io_locations_str.len() + show_data.range_pk_b64.len(), start_time.elapsed().unwrap());
 
-            // Store the ShowData into the shared state (indicating "ready")
-            let mut tasks = state.lock().await;
-            tasks.insert(cred_uid_clone.clone(), Some(show_data));
-            
+            // Encrypt ShowData (RFC 8188 aes128gcm) before it touches disk or
+            // the task store, so a compromised database or filesystem cache
+            // never exposes client state or the device-key path in the clear.
+            let ciphertext = encrypt_show_data(&master_secret, &cred_uid_clone, &show_data);
+
+            // Also drop a copy next to the rest of the credential's cached
+            // files, so a fresh task store can bootstrap this row without
+            // redoing the (slow) proving setup above.
+            let show_data_path = Path::new(&cred_folder).join(task_store::SHOW_DATA_FILE_NAME);
+            if let Err(e) = fs::write(&show_data_path, &ciphertext) {
+                eprintln!("Failed to write {:?}: {}", show_data_path, e);
+            }
+
+            // Store the ciphertext into the shared state (indicating "ready")
+            if let Err(e) = store.insert_ready(&cred_uid_clone, &ciphertext) {
+                eprintln!("Failed to persist ready state for cred_uid {}: {}", cred_uid_clone, e);
+            }
+            cache.lock().await.insert(cred_uid_clone.clone(), TaskState::Ready(ciphertext));
+
             Ok(())
         }.await;
 
         // Handle any error by removing the `cred_uid` entry from the state
         if task_result.is_err() {
-            let mut tasks = state.lock().await;
-            tasks.remove(&cred_uid_clone);
+            cache.lock().await.remove(&cred_uid_clone);
+            if let Err(e) = store.remove(&cred_uid_clone) {
+                eprintln!("Failed to remove task store entry for cred_uid {}: {}", cred_uid_clone, e);
+            }
             eprintln!("Error occurred, removing cred_uid from state: {:?}", task_result.err());
         }
     });
@@ -246,10 +458,9 @@ async fn prepare(cred_info: Json<CredInfo>, state: &State<SharedState>) -> Json<
 #[get("/status?<cred_uid>")]
 async fn status(cred_uid: String, state: &State<SharedState>) -> String {
     println!("*** /status called with credential UID: {}", cred_uid);
-    let tasks = state.inner().0.lock().await;
-    let status = match tasks.get(&cred_uid) {
-        Some(Some(_)) => "ready".to_string(),    // If ShowData exists, return "ready"
-        Some(None) => "preparing".to_string(),   // If still preparing, return "preparing"
+    let status = match state.inner().get_task(&cred_uid).await {
+        Some(TaskState::Ready(_)) => "ready".to_string(),    // If ShowData exists, return "ready"
+        Some(TaskState::Preparing) => "preparing".to_string(), // If still preparing, return "preparing"
         None => "unknown".to_string(),           // If no entry exists, return "unknown"
     };
     println!("Status for cred_uid {}: {}", cred_uid, status);
@@ -257,24 +468,33 @@ async fn status(cred_uid: String, state: &State<SharedState>) -> String {
 }
 
 #[get("/getshowdata?<cred_uid>")]
-async fn get_show_data(cred_uid: String, state: &State<SharedState>) -> Result<Json<ShowData>, String> {
+async fn get_show_data(cred_uid: String, state: &State<SharedState>) -> Result<Json<EncryptedShowData>, String> {
     println!("*** /getshowdata called with credential UID: {}", cred_uid);
-    let tasks = state.inner().0.lock().await;
 
-    match tasks.get(&cred_uid) {
-        Some(Some(show_data)) => Ok(Json(show_data.clone())), // Return the ShowData if found
-        Some(None) => Err("ShowData is still being prepared.".to_string()), // Still preparing
+    match state.inner().get_task(&cred_uid).await {
+        Some(TaskState::Ready(ciphertext)) => {
+            // The ciphertext at rest never carries its own key; the key is
+            // only ever derived on demand and handed back here, over this
+            // (authenticated, TLS-protected) response.
+            let ikm = derive_credential_ikm(&state.inner().master_secret, &cred_uid);
+            Ok(Json(EncryptedShowData {
+                ciphertext_b64: base64_url::encode(&ciphertext),
+                key_b64: base64_url::encode(&ikm),
+            }))
+        }
+        Some(TaskState::Preparing) => Err("ShowData is still being prepared.".to_string()), // Still preparing
         None => Err("No ShowData found for the given cred_uid.".to_string()), // Invalid cred_uid
     }
 }
 
-#[get("/show?<cred_uid>&<disc_uid>&<challenge>&<proof_spec>")]
-async fn show<'a>(cred_uid: String, disc_uid: String, challenge: String, proof_spec: String, state: &State<SharedState>) -> Result<String, String> {
-    println!("*** /show called with credential UID {}, disc_uid {}, challenge {}, and proof_spec {}", cred_uid, disc_uid, challenge, proof_spec);
-    let tasks = state.inner().0.lock().await;
+#[get("/show?<cred_uid>&<disc_uid>&<challenge>&<proof_spec>&<format>")]
+async fn show<'a>(cred_uid: String, disc_uid: String, challenge: String, proof_spec: String, format: Option<String>, state: &State<SharedState>) -> Result<String, String> {
+    println!("*** /show called with credential UID {}, disc_uid {}, challenge {}, proof_spec {}, and format {:?}", cred_uid, disc_uid, challenge, proof_spec, format);
 
-    match tasks.get(&cred_uid) {
-        Some(Some(show_data)) => {
+    match state.inner().get_task(&cred_uid).await {
+        Some(TaskState::Ready(ciphertext)) => {
+            let show_data = decrypt_show_data(&state.inner().master_secret, &cred_uid, &ciphertext)
+                .map_err(|e| format!("Failed to decrypt show data: {}", e))?;
 
             // Deserialize the ClientState and range proof public key from ShowData
             let mut client_state = read_from_b64url::<ClientState<CrescentPairing>>(&show_data.client_state_b64)
@@ -283,6 +503,19 @@ async fn show<'a>(cred_uid: String, disc_uid: String, challenge: String, proof_s
             let range_pk = read_from_b64url::<RangeProofPK<CrescentPairing>>(&show_data.range_pk_b64)
                 .map_err(|_| "Failed to parse range proof public key".to_string())?;
 
+            // Reject revoked credentials before doing any proving work. The cascade is
+            // cached alongside the rest of the credential's data, keyed off the same
+            // folder as the device key files.
+            let cred_folder = Path::new(&show_data.device_priv_key_path)
+                .parent()
+                .ok_or("Invalid device key path")?;
+            let paths = CachePaths::new_from_str(cred_folder.to_str().ok_or("Invalid credential folder path")?);
+            if check_revocation(&paths, &cred_uid).map_err(|e| format!("Failed to check revocation status: {:?}", e))? {
+                let msg = format!("Credential {} has been revoked", cred_uid);
+                println!("{}", msg);
+                return Err(msg);
+            }
+
             // Check that the cred stored at cred_uid supports the disclosure type disc_uid
             if !is_disc_uid_supported(&disc_uid, &client_state.credtype) {
                 let msg = format!("Disclosure UID {} is not supported with credential of type {}", disc_uid, client_state.credtype);
@@ -322,11 +555,15 @@ async fn show<'a>(cred_uid: String, disc_uid: String, challenge: String, proof_s
             };
             
             // Return the show proof as a base64-url encoded string
-            let show_proof_b64 = write_to_b64url(&show_proof);     
+            let show_proof_b64 = write_to_b64url(&show_proof);
 
-            Ok(show_proof_b64)
+            if format.as_deref() == Some("vp") {
+                Ok(build_verifiable_presentation(&show_data, &client_state, &proof_spec, &show_proof, &show_proof_b64))
+            } else {
+                Ok(show_proof_b64)
+            }
         }
-        Some(None) => Err("ShowData is still being prepared.".to_string()), // Data is still being prepared
+        Some(TaskState::Preparing) => Err("ShowData is still being prepared.".to_string()), // Data is still being prepared
         None => Err("No ShowData found for the given cred_uid.".to_string()), // No data for this cred_uid
     }
 }
@@ -342,8 +579,8 @@ async fn delete(cred_uid: String, state: &State<SharedState>) -> String {
     // (we could lookup the schema_uid from the show_data associated from the cred_uid,
     // but that would only be available for prepared credentials)
 
-    // Iterate over each schema_uid in SCHEMA_UIDS
-    for schema_uid in SCHEMA_UIDS.iter() {
+    // Iterate over each supported schema_uid
+    for schema_uid in schema_uids() {
         // Define the path to the credential-specific folder
         let cred_folder = format!("{}/{}/{}", CRESCENT_DATA_BASE_PATH, schema_uid, cred_uid);
         println!("Attempting to delete folder: {}", cred_folder);
@@ -363,9 +600,8 @@ async fn delete(cred_uid: String, state: &State<SharedState>) -> String {
     }
 
     // Remove the entry from shared state
-    let mut tasks = state.inner().0.lock().await;
-    tasks.remove(&cred_uid);
-    
+    state.inner().remove(&cred_uid).await;
+
     // Check if deletion was successful
     if delete_successful {
         "Deleted".to_string()
@@ -376,7 +612,14 @@ async fn delete(cred_uid: String, state: &State<SharedState>) -> String {
 
 #[launch]
 fn rocket() -> _ {
-    let shared_state = SharedState(Arc::new(Mutex::new(HashMap::new())));
+    fs::create_dir_all(CRESCENT_DATA_BASE_PATH).expect("Failed to create credential data folder");
+    let master_secret = load_or_create_master_secret(MASTER_SECRET_PATH);
+    let store = SqliteTaskStore::open(TASK_STORE_DB_PATH).expect("Failed to open task store database");
+    let shared_state = SharedState {
+        cache: Arc::new(Mutex::new(HashMap::new())),
+        store: Arc::new(store),
+        master_secret: Arc::new(master_secret),
+    };
 
     rocket::build()
     .manage(shared_state)
@@ -0,0 +1,146 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT license.
+
+// Durable backing store for the client helper's per-credential task state.
+// `SharedState` used to be a bare in-memory `HashMap`, so every prepared
+// credential -- including the expensive `ClientState` that is slow to
+// compute -- was lost on restart, and two helper instances couldn't share
+// work. `TaskStore` is the persistence interface; `SqliteTaskStore` is the
+// only implementation, one row per `cred_uid` holding the status plus an
+// opaque ciphertext blob. The store never sees a decrypted `ShowData` --
+// encryption and decryption are `content_encryption`'s job, driven from
+// `main.rs` -- so a compromised copy of this database on its own reveals
+// nothing about a credential's client state or device-key location.
+
+use std::fs;
+use rusqlite::{params, Connection};
+use std::sync::Mutex;
+
+use crate::{CRESCENT_DATA_BASE_PATH, CRESCENT_SHARED_DATA_SUFFIX};
+use crescent_sample_setup_service::common::schema_uids;
+
+// `prepare` writes this alongside the rest of a prepared credential's files
+// so a fresh database (or a folder copied in from elsewhere) can still be
+// bootstrapped without redoing the expensive proving setup. It holds the
+// same `aes128gcm` ciphertext blob as the `tasks` table, not plaintext.
+pub const SHOW_DATA_FILE_NAME: &str = "show_data.enc";
+
+#[derive(Clone, Debug)]
+pub enum TaskState {
+    Preparing,
+    Ready(Vec<u8>),
+}
+
+pub trait TaskStore: Send + Sync {
+    fn insert_preparing(&self, cred_uid: &str) -> Result<(), String>;
+    fn insert_ready(&self, cred_uid: &str, ciphertext: &[u8]) -> Result<(), String>;
+    fn get(&self, cred_uid: &str) -> Result<Option<TaskState>, String>;
+    fn remove(&self, cred_uid: &str) -> Result<(), String>;
+}
+
+pub struct SqliteTaskStore {
+    conn: Mutex<Connection>,
+}
+
+impl SqliteTaskStore {
+    pub fn open(db_path: &str) -> Result<Self, String> {
+        let conn = Connection::open(db_path)
+            .map_err(|e| format!("Failed to open task store database {}: {}", db_path, e))?;
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS tasks (
+                cred_uid TEXT PRIMARY KEY,
+                status TEXT NOT NULL,
+                show_data_ciphertext BLOB
+            )",
+            [],
+        )
+        .map_err(|e| format!("Failed to create tasks table: {}", e))?;
+
+        let store = SqliteTaskStore { conn: Mutex::new(conn) };
+        store.bootstrap_from_disk();
+        Ok(store)
+    }
+
+    // Scans every schema's credential folders for a `show_data.enc` left by
+    // a previous `prepare` run and inserts any that aren't already tracked.
+    // This is best-effort: a folder with no (or unreadable) `show_data.enc`
+    // is simply left out, since we have no way to recompute its `ShowData`
+    // without redoing the slow proving setup.
+    fn bootstrap_from_disk(&self) {
+        for schema_uid in schema_uids() {
+            let base_folder = format!("{}/{}", CRESCENT_DATA_BASE_PATH, schema_uid);
+            let entries = match fs::read_dir(&base_folder) {
+                Ok(entries) => entries,
+                Err(_) => continue, // schema not provisioned on this instance
+            };
+            for entry in entries.flatten() {
+                let cred_uid = entry.file_name().to_string_lossy().into_owned();
+                if cred_uid == CRESCENT_SHARED_DATA_SUFFIX {
+                    continue;
+                }
+                match self.get(&cred_uid) {
+                    Ok(Some(_)) => continue, // already tracked
+                    Ok(None) => {}
+                    Err(e) => {
+                        eprintln!("Bootstrap: failed to look up cred_uid {}: {}", cred_uid, e);
+                        continue;
+                    }
+                }
+                let show_data_path = entry.path().join(SHOW_DATA_FILE_NAME);
+                let Ok(ciphertext) = fs::read(&show_data_path) else { continue };
+                println!("Bootstrapping task store entry for cred_uid {} from {:?}", cred_uid, show_data_path);
+                if let Err(e) = self.insert_ready(&cred_uid, &ciphertext) {
+                    eprintln!("Bootstrap: failed to insert cred_uid {}: {}", cred_uid, e);
+                }
+            }
+        }
+    }
+}
+
+impl TaskStore for SqliteTaskStore {
+    fn insert_preparing(&self, cred_uid: &str) -> Result<(), String> {
+        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+        conn.execute(
+            "INSERT OR REPLACE INTO tasks (cred_uid, status, show_data_ciphertext) VALUES (?1, 'preparing', NULL)",
+            params![cred_uid],
+        )
+        .map_err(|e| format!("Failed to insert preparing task: {}", e))?;
+        Ok(())
+    }
+
+    fn insert_ready(&self, cred_uid: &str, ciphertext: &[u8]) -> Result<(), String> {
+        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+        conn.execute(
+            "INSERT OR REPLACE INTO tasks (cred_uid, status, show_data_ciphertext) VALUES (?1, 'ready', ?2)",
+            params![cred_uid, ciphertext],
+        )
+        .map_err(|e| format!("Failed to insert ready task: {}", e))?;
+        Ok(())
+    }
+
+    fn get(&self, cred_uid: &str) -> Result<Option<TaskState>, String> {
+        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+        let mut stmt = conn
+            .prepare("SELECT status, show_data_ciphertext FROM tasks WHERE cred_uid = ?1")
+            .map_err(|e| e.to_string())?;
+        let mut rows = stmt.query(params![cred_uid]).map_err(|e| e.to_string())?;
+        let Some(row) = rows.next().map_err(|e| e.to_string())? else {
+            return Ok(None);
+        };
+
+        let status: String = row.get(0).map_err(|e| e.to_string())?;
+        if status == "ready" {
+            let ciphertext: Vec<u8> = row.get(1).map_err(|e| e.to_string())?;
+            Ok(Some(TaskState::Ready(ciphertext)))
+        } else {
+            Ok(Some(TaskState::Preparing))
+        }
+    }
+
+    fn remove(&self, cred_uid: &str) -> Result<(), String> {
+        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+        conn.execute("DELETE FROM tasks WHERE cred_uid = ?1", params![cred_uid])
+            .map_err(|e| format!("Failed to delete task: {}", e))?;
+        Ok(())
+    }
+}
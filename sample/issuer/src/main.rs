@@ -10,22 +10,28 @@
 
 use rocket::fs::{FileServer, NamedFile};
 use rocket::http::{Cookie, CookieJar};
+use rocket::request::{FromRequest, Outcome};
 use rocket::response::Redirect;
-use rocket::serde::{Serialize};
+use rocket::serde::{Deserialize, Serialize};
+use rocket::serde::json::Json;
 use rocket::response::content::RawHtml;
-use rocket::State;
+use rocket::{Request, State};
 use rocket_dyn_templates::{context, Template};
-use std::path::PathBuf;
-use chrono::{Duration, Utc};
-use jsonwebtoken::{encode, EncodingKey, Header};
+use chrono::{DateTime, Duration, Utc};
+use jsonwebtoken::{decode, decode_header, encode, Algorithm, DecodingKey, EncodingKey, Header, Validation};
+use jsonwebkey::JsonWebKey;
 use std::fs;
-use p256::ecdsa::VerifyingKey;
-use p256::pkcs8::DecodePublicKey;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+use p256::ecdsa::{SigningKey as EcSigningKey, VerifyingKey};
+use p256::pkcs8::{DecodePrivateKey, DecodePublicKey};
+use uuid::Uuid;
 
 // issuer config values
-const PRIVATE_KEY_PATH : &str = "keys/issuer.prv"; // private key path
+const PRIVATE_KEYS_DIR: &str = "keys/signing"; // one `{kid}.prv` PEM file per key published in the JWKS
 const DEVICE_PUB_KEY_PATH: &str = "keys/device.pub"; // device public key path
 const JWKS_PATH: &str = ".well-known/jwks.json"; // JWKS path
+const UPSTREAM_SESSION_TTL_MINUTES: i64 = 30; // how long an upstream-IdP login stays usable for /issue
 
 // struct for the personal claims related to the user
 #[derive(Serialize, Clone)]
@@ -77,16 +83,95 @@ struct Claims {
     xms_tpl: String
 }
 
+// A locale-aware claim value: a map from BCP-47 language tag to the
+// localized string, plus a default tag to fall back to when the caller
+// didn't ask for (or we don't have) any of the requested locales. The JWT
+// itself never carries this map -- `issue_token` resolves it down to a
+// single plain string before serializing, so the token stays schema-compatible.
+#[derive(Clone)]
+struct LocalizedValue {
+    values: BTreeMap<String, String>,
+    default_locale: String,
+}
+
+impl LocalizedValue {
+    // Resolves against an ordered list of requested locale tags (most
+    // preferred first). Falls back to the tag's primary subtag (`fr` for a
+    // requested `fr-CA`), then to `default_locale`.
+    fn resolve(&self, requested: &[String]) -> String {
+        for tag in requested {
+            if let Some(value) = self.values.get(tag) {
+                return value.clone();
+            }
+        }
+        for tag in requested {
+            if let Some(primary) = tag.split('-').next() {
+                if let Some(value) = self.values.get(primary) {
+                    return value.clone();
+                }
+            }
+        }
+        self.values.get(&self.default_locale).cloned().unwrap_or_default()
+    }
+}
+
+// The localized variants of `UserClaims`'s human-readable name fields, kept
+// alongside it rather than folded in since those fields stay plain strings
+// in the issued JWT.
+#[derive(Clone)]
+struct LocalizedNames {
+    name: LocalizedValue,
+    given_name: LocalizedValue,
+    family_name: LocalizedValue,
+}
+
+impl LocalizedNames {
+    fn resolve(&self, requested: &[String]) -> (String, String, String) {
+        (
+            self.name.resolve(requested),
+            self.given_name.resolve(requested),
+            self.family_name.resolve(requested),
+        )
+    }
+}
+
 // struct to hold a user's data
 struct User {
     username: String,
     password: String,
     user_claims: UserClaims,
+    localized_names: LocalizedNames,
 }
 
-// struct to hold the loaded issuer private key
-struct PrivateKey {
-    key: EncodingKey,
+// A single loaded signing key. Which variant a `kid` gets depends on the
+// issuer's configured algorithm: RSA keys carry only the `EncodingKey`
+// `jsonwebtoken` needs to sign with, but EC keys also keep the public point
+// around, since `serve_jwks` derives the EC JWK's x/y coordinates from it
+// instead of relying on a hand-maintained JWKS entry on disk.
+enum SigningKeyMaterial {
+    Rsa(EncodingKey),
+    Ec { encoding_key: EncodingKey, verifying_key: VerifyingKey },
+}
+
+// struct to hold the issuer's signing keyring: one key per `kid` published
+// in the JWKS, plus which one is currently active. Keeping every key that's
+// still in the JWKS (not just the active one) lets tokens signed before a
+// rotation go on verifying after it, since their `kid` still resolves to a
+// public key -- they just won't be the kid `issue_token` picks for new
+// tokens.
+struct SigningKeys {
+    keys: BTreeMap<String, SigningKeyMaterial>,
+    active_kid: String,
+    algorithm: jsonwebtoken::Algorithm,
+}
+
+impl SigningKeys {
+    fn active_encoding_key(&self) -> &EncodingKey {
+        match self.keys.get(&self.active_kid).expect("active_kid must have a loaded signing key") {
+            SigningKeyMaterial::Rsa(encoding_key) => encoding_key,
+            SigningKeyMaterial::Ec { encoding_key, .. } => encoding_key,
+        }
+    }
 }
 
 // add a new struct for the login form data
@@ -96,12 +181,41 @@ struct LoginForm {
     password: String,
 }
 
+// Settings for the optional "upstream IdP" login mode: instead of checking
+// the demo password list, `/login` redirects to a real OIDC provider and
+// `/callback` completes the authorization-code exchange against it.
+struct UpstreamIdpConfig {
+    discovery_url: String,
+    client_id: String,
+    client_secret: String,
+    redirect_uri: String,
+}
+
 // issuer config from Rocket.toml
 struct IssuerConfig {
     issuer_name: String,
     issuer_domain: String,
-    issuer_kid: String,
     _device_key_binding: bool,
+    upstream_idp: Option<UpstreamIdpConfig>,
+}
+
+// A signed-in upstream-IdP user's claims, plus when this login stops being
+// usable -- unlike the demo-password path (whose `username` cookie names a
+// fixed, publicly-known test account), this is a real identity's claims, so
+// access to them has to expire rather than last for the life of the cookie.
+#[derive(Clone)]
+struct UpstreamSessionEntry {
+    claims: UserClaims,
+    expires_at: DateTime<Utc>,
+}
+
+// Claims for users who signed in through the upstream IdP, keyed by a
+// server-generated session id -- not `sub` -- so the cookie naming a session
+// is unguessable and distinct from the identity it grants access to. Kept
+// separate from the statically-provisioned `Vec<User>` since these are
+// discovered at login time from the provider's ID token, not known up front.
+struct UpstreamSessions {
+    sessions: Mutex<HashMap<String, UpstreamSessionEntry>>,
 }
 
 // redirect from `/` to `/login`
@@ -110,11 +224,114 @@ fn index_redirect() -> Redirect {
     Redirect::to("/login")
 }
 
-// route to serve the login page
+// Minimal percent-encoding for query parameter values (letters, digits and
+// `-_.~` pass through unescaped, everything else is escaped); avoids
+// pulling in a URL crate for the handful of values that go into the
+// authorization request.
+fn percent_encode(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => out.push(byte as char),
+            _ => out.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    out
+}
+
+// Parses an `Accept-Language` header value (e.g. "fr-CA,fr;q=0.9,en;q=0.8")
+// into language tags ordered by descending `q` weight (default weight 1.0).
+fn parse_accept_language(header: &str) -> Vec<String> {
+    let mut tags: Vec<(String, f32)> = header
+        .split(',')
+        .filter_map(|part| {
+            let mut pieces = part.trim().split(';');
+            let tag = pieces.next()?.trim().to_string();
+            if tag.is_empty() {
+                return None;
+            }
+            let q = pieces
+                .find_map(|p| p.trim().strip_prefix("q="))
+                .and_then(|q| q.parse::<f32>().ok())
+                .unwrap_or(1.0);
+            Some((tag, q))
+        })
+        .collect();
+    tags.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+    tags.into_iter().map(|(tag, _)| tag).collect()
+}
+
+// Ordered list of requested locale tags, most-preferred first: a
+// `ui_locales` query param (the OIDC convention for requesting a locale)
+// takes precedence over the `Accept-Language` header; an empty list falls
+// back to each `LocalizedValue`'s own default.
+struct RequestedLocales(Vec<String>);
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for RequestedLocales {
+    type Error = std::convert::Infallible;
+
+    async fn from_request(req: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        if let Some(Ok(ui_locales)) = req.query_value::<String>("ui_locales") {
+            let tags = ui_locales.split_whitespace().map(|s| s.to_string()).collect();
+            return Outcome::Success(RequestedLocales(tags));
+        }
+
+        let tags = req
+            .headers()
+            .get_one("Accept-Language")
+            .map(parse_accept_language)
+            .unwrap_or_default();
+        Outcome::Success(RequestedLocales(tags))
+    }
+}
+
+// Discovers the upstream IdP's `authorization_endpoint` and redirects the
+// browser there with a generated `state`/`nonce`, stashed in cookies so
+// `/callback` can check what comes back against them.
+fn start_upstream_login(upstream: &UpstreamIdpConfig, jar: &CookieJar<'_>) -> Result<Redirect, String> {
+    let discovery_body = ureq::get(&upstream.discovery_url)
+        .call()
+        .map_err(|e| format!("Discovery request failed: {}", e))?
+        .into_string()
+        .map_err(|e| format!("Failed to read discovery response: {}", e))?;
+    let discovery: serde_json::Value = serde_json::from_str(&discovery_body)
+        .map_err(|e| format!("Failed to parse discovery document: {}", e))?;
+    let authorization_endpoint = discovery["authorization_endpoint"]
+        .as_str()
+        .ok_or("Discovery document missing authorization_endpoint")?;
+
+    let state = Uuid::new_v4().to_string();
+    let nonce = Uuid::new_v4().to_string();
+    jar.add(Cookie::new("oidc_state", state.clone()));
+    jar.add(Cookie::new("oidc_nonce", nonce.clone()));
+
+    let url = format!(
+        "{}?response_type=code&client_id={}&redirect_uri={}&scope=openid%20profile%20email&state={}&nonce={}",
+        authorization_endpoint,
+        percent_encode(&upstream.client_id),
+        percent_encode(&upstream.redirect_uri),
+        state,
+        nonce,
+    );
+    Ok(Redirect::to(url))
+}
+
+// route to serve the login page. When an upstream IdP is configured this
+// redirects there instead of rendering the demo-password form, per the
+// stated design goal of Crescent provers interacting with standard
+// Identity Providers.
 #[get("/login")]
-fn login_page(issuer_config: &State<IssuerConfig>) -> Template {
+fn login_page(issuer_config: &State<IssuerConfig>, jar: &CookieJar<'_>) -> Result<Redirect, Template> {
     let issuer_name_str = issuer_config.issuer_name.as_str();
-    Template::render("login", context! { issuer_name: issuer_name_str })
+    let Some(upstream) = &issuer_config.upstream_idp else {
+        return Err(Template::render("login", context! { issuer_name: issuer_name_str }));
+    };
+
+    start_upstream_login(upstream, jar).map_err(|e| {
+        eprintln!("Failed to start upstream login: {}", e);
+        Template::render("login", context! { issuer_name: issuer_name_str, error: "Upstream identity provider is unavailable." })
+    })
 }
 
 // route to handle login form submission
@@ -147,104 +364,436 @@ fn login(
 
 // route to serve the welcome page after successful login
 #[get("/welcome")]
-fn welcome_page(jar: &CookieJar<'_>, issuer_config: &State<IssuerConfig>) -> Result<Template, Redirect> {
+fn welcome_page(
+    jar: &CookieJar<'_>,
+    issuer_config: &State<IssuerConfig>,
+    upstream_sessions: &State<UpstreamSessions>,
+) -> Result<Template, Redirect> {
     let issuer_name_str = issuer_config.issuer_name.as_str();
     if let Some(cookie) = jar.get("username") {
         let username = cookie.value().to_string();
-        Ok(Template::render(
+        return Ok(Template::render(
             "welcome",
             context! {
                 user_name: &username,
                 issuer_name: issuer_name_str
             },
-        ))
-    } else {
-        // if there's no username cookie, redirect to the login page
-        Err(Redirect::to(uri!(login_page)))
+        ));
     }
+    if let Some(entry) = upstream_session_entry(jar, upstream_sessions) {
+        return Ok(Template::render(
+            "welcome",
+            context! {
+                user_name: &entry.claims.name,
+                issuer_name: issuer_name_str
+            },
+        ));
+    }
+    // no valid session cookie at all -- back to the login page
+    Err(Redirect::to(uri!(login_page)))
+}
+
+// Looks up the upstream session named by the `upstream_session` cookie, if
+// any, evicting it (and any other expired entries) once its TTL has passed
+// rather than trusting the cookie's mere presence indefinitely.
+fn upstream_session_entry(jar: &CookieJar<'_>, upstream_sessions: &State<UpstreamSessions>) -> Option<UpstreamSessionEntry> {
+    let session_id = jar.get("upstream_session")?.value().to_string();
+    let now = Utc::now();
+    let mut sessions = upstream_sessions.sessions.lock().unwrap();
+    sessions.retain(|_, entry| entry.expires_at > now);
+    sessions.get(&session_id).cloned()
+}
+
+// Exchanges an authorization code at the upstream IdP's `token_endpoint`,
+// returning the raw (still-unvalidated) ID token JWT string.
+fn exchange_code_for_id_token(upstream: &UpstreamIdpConfig, discovery: &serde_json::Value, code: &str) -> Result<String, String> {
+    let token_endpoint = discovery["token_endpoint"].as_str().ok_or("Discovery document missing token_endpoint")?;
+
+    let response_body = ureq::post(token_endpoint)
+        .send_form(&[
+            ("grant_type", "authorization_code"),
+            ("code", code),
+            ("redirect_uri", &upstream.redirect_uri),
+            ("client_id", &upstream.client_id),
+            ("client_secret", &upstream.client_secret),
+        ])
+        .map_err(|e| format!("Token request failed: {}", e))?
+        .into_string()
+        .map_err(|e| format!("Failed to read token response: {}", e))?;
+
+    let token_response: serde_json::Value = serde_json::from_str(&response_body)
+        .map_err(|e| format!("Failed to parse token response: {}", e))?;
+    token_response["id_token"]
+        .as_str()
+        .map(|s| s.to_string())
+        .ok_or_else(|| "Token response missing id_token".to_string())
+}
+
+// Fetches the upstream IdP's JWKS, validates the ID token's signature
+// against the key named by its `kid`, and checks `iss`/`aud`/`nonce`/`exp`.
+// Only RSA-signed ID tokens are supported here, the same limitation
+// `client_helper`'s `fetch_and_save_jwk` has for RS256-only credentials.
+fn validate_upstream_id_token(
+    upstream: &UpstreamIdpConfig,
+    discovery: &serde_json::Value,
+    id_token: &str,
+    expected_nonce: &str,
+) -> Result<serde_json::Value, String> {
+    let jwks_uri = discovery["jwks_uri"].as_str().ok_or("Discovery document missing jwks_uri")?;
+    let jwks_body = ureq::get(jwks_uri)
+        .call()
+        .map_err(|e| format!("JWKS request failed: {}", e))?
+        .into_string()
+        .map_err(|e| format!("Failed to read JWKS response: {}", e))?;
+    let jwks: serde_json::Value = serde_json::from_str(&jwks_body)
+        .map_err(|e| format!("Failed to parse JWKS: {}", e))?;
+
+    let header = jsonwebtoken::decode_header(id_token).map_err(|e| format!("Failed to parse ID token header: {}", e))?;
+    let kid = header.kid.ok_or("ID token header missing kid")?;
+    let jwk_value = jwks["keys"]
+        .as_array()
+        .and_then(|keys| keys.iter().find(|k| k["kid"].as_str() == Some(kid.as_str())))
+        .ok_or_else(|| format!("No JWKS entry for kid {}", kid))?;
+    if jwk_value["kty"].as_str() != Some("RSA") {
+        return Err(format!("Unsupported upstream signing key type: {:?}", jwk_value["kty"]));
+    }
+    let jwk: JsonWebKey = serde_json::from_value(jwk_value.clone()).map_err(|e| format!("Failed to parse JWK: {}", e))?;
+    let decoding_key = DecodingKey::from_rsa_pem(jwk.key.to_pem().as_bytes()).map_err(|e| format!("Failed to load upstream public key: {}", e))?;
+
+    let issuer = discovery["issuer"].as_str().ok_or("Discovery document missing issuer")?;
+    let mut validation = Validation::new(Algorithm::RS256);
+    validation.set_issuer(&[issuer]);
+    validation.set_audience(&[upstream.client_id.clone()]);
+
+    let claims = decode::<serde_json::Value>(id_token, &decoding_key, &validation)
+        .map_err(|e| format!("ID token validation failed: {}", e))?
+        .claims;
+
+    if claims["nonce"].as_str() != Some(expected_nonce) {
+        return Err("ID token nonce does not match the one set at login".to_string());
+    }
+
+    Ok(claims)
+}
+
+// Maps an upstream ID token's standard OIDC claims into our `UserClaims`
+// shape; fields the provider doesn't carry (the Entra-specific ones this
+// demo otherwise hard-codes per user) get a placeholder value rather than
+// being guessed at.
+fn map_upstream_claims(upstream_claims: &serde_json::Value, issuer_config: &IssuerConfig) -> UserClaims {
+    let user_domain = issuer_config.issuer_domain.as_str();
+    let sub = upstream_claims["sub"].as_str().unwrap_or("unknown").to_string();
+    let email = upstream_claims["email"].as_str().map(|s| s.to_string()).unwrap_or_else(|| format!("{}@{}", sub, user_domain));
+    let given_name = upstream_claims["given_name"].as_str().unwrap_or("Unknown").to_string();
+    let family_name = upstream_claims["family_name"].as_str().unwrap_or("User").to_string();
+    let name = upstream_claims["name"].as_str().map(|s| s.to_string()).unwrap_or_else(|| format!("{} {}", given_name, family_name));
+
+    UserClaims {
+        email: email.clone(),
+        family_name,
+        given_name,
+        login_hint: format!("O.{}", sub),
+        name,
+        oid: sub.clone(),
+        onprem_sid: "".to_string(),
+        preferred_username: email.clone(),
+        rh: "".to_string(),
+        sid: sub.clone(),
+        sub,
+        upn: email.clone(),
+        uti: "".to_string(),
+        tenant_ctry: "US".to_string(),
+        tenant_region_scope: "NA".to_string(),
+        verified_primary_email: vec![email.clone()],
+        verified_secondary_email: vec![],
+        device_key_0: None,
+        device_key_1: None,
+    }
+}
+
+// route completing the upstream IdP's authorization-code flow: exchanges
+// the code, validates the returned ID token, and stashes the mapped claims
+// so `issue_token` can find them by the same `username` cookie the
+// demo-password path uses.
+#[get("/callback?<code>&<state>")]
+fn callback(
+    code: String,
+    state: String,
+    jar: &CookieJar<'_>,
+    issuer_config: &State<IssuerConfig>,
+    upstream_sessions: &State<UpstreamSessions>,
+) -> Result<Redirect, &'static str> {
+    let upstream = issuer_config.upstream_idp.as_ref().ok_or("Upstream identity provider is not configured")?;
+
+    let expected_state = jar.get("oidc_state").map(|c| c.value().to_string()).ok_or("Missing oidc_state cookie")?;
+    if state != expected_state {
+        return Err("State does not match the one set at login");
+    }
+    let expected_nonce = jar.get("oidc_nonce").map(|c| c.value().to_string()).ok_or("Missing oidc_nonce cookie")?;
+
+    let discovery_body = ureq::get(&upstream.discovery_url)
+        .call()
+        .map_err(|_| "Discovery request failed")?
+        .into_string()
+        .map_err(|_| "Failed to read discovery response")?;
+    let discovery: serde_json::Value = serde_json::from_str(&discovery_body).map_err(|_| "Failed to parse discovery document")?;
+
+    let id_token = exchange_code_for_id_token(upstream, &discovery, &code).map_err(|e| {
+        eprintln!("Failed to exchange authorization code: {}", e);
+        "Failed to exchange authorization code"
+    })?;
+    let upstream_claims = validate_upstream_id_token(upstream, &discovery, &id_token, &expected_nonce).map_err(|e| {
+        eprintln!("Failed to validate upstream ID token: {}", e);
+        "Failed to validate upstream ID token"
+    })?;
+
+    let user_claims = map_upstream_claims(&upstream_claims, issuer_config);
+    let session_id = Uuid::new_v4().to_string();
+    let expires_at = Utc::now() + Duration::minutes(UPSTREAM_SESSION_TTL_MINUTES);
+    upstream_sessions.sessions.lock().unwrap().insert(session_id.clone(), UpstreamSessionEntry { claims: user_claims, expires_at });
+
+    jar.add(Cookie::new("upstream_session", session_id));
+    Ok(Redirect::to(uri!(welcome_page)))
 }
 
 // route to issue JWTs
 #[post("/issue")]
 fn issue_token(
     jar: &CookieJar<'_>,
-    private_key: &State<PrivateKey>,
+    signing_keys: &State<SigningKeys>,
     users: &State<Vec<User>>,
-    issuer_config: &State<IssuerConfig>
+    upstream_sessions: &State<UpstreamSessions>,
+    issuer_config: &State<IssuerConfig>,
+    requested_locales: RequestedLocales,
 ) -> Result<RawHtml<String>, &'static str> {
-    if let Some(cookie) = jar.get("username") {
+    let issuer_name_str = issuer_config.issuer_name.as_str();
+    let issuer_domain_str = issuer_config.issuer_domain.as_str();
+
+    // an upstream-IdP login's claims live in `upstream_sessions`, keyed by
+    // the unguessable `upstream_session` cookie; check there first. Upstream
+    // claims carry no locale map (the provider already picked one), so only
+    // the demo-password path resolves `localized_names` against the
+    // request's locale.
+    let (username, resolved_user_claims) = if let Some(entry) = upstream_session_entry(jar, upstream_sessions) {
+        (entry.claims.name.clone(), Some(entry.claims))
+    } else if let Some(cookie) = jar.get("username") {
         let username = cookie.value().to_string();
-        let issuer_name_str = issuer_config.issuer_name.as_str();
-        let issuer_domain_str = issuer_config.issuer_domain.as_str();
-        let issuer_kid_str = issuer_config.issuer_kid.as_str();
-
-        // find the user based on the username
-        if let Some(user) = users.iter().find(|user| user.username == username) {
-            // generate the JWT token
-            let current_time = Utc::now();
-            let claims = Claims {
-                user_claims: user.user_claims.clone(),
-                acct: 0,
-                aud: "relyingparty.example.com".to_string(),
-                auth_time: current_time.timestamp() as usize,
-                exp: (current_time + Duration::days(30)).timestamp() as usize,
-                iat: current_time.timestamp() as usize,
-                ipaddr: "203.0.113.0".to_string(),
-                iss: format!("https://{}", issuer_domain_str),
-                jti: "fGYCO1mK2dBWTAfCjGAoTQ".to_string(),
-                nbf: current_time.timestamp() as usize,
-                tid: "12345678-1234-abcd-1234-abcdef124567".to_string(),
-                ver: "2.0".to_string(),
-                xms_pdl: "NAM".to_string(),
-                xms_tpl: "en".to_string(),
-            };
-
-            let mut header = Header::new(jsonwebtoken::Algorithm::RS256);
-            header.kid = Some(issuer_kid_str.to_string());
-
-            let token = encode(&header, &claims, &private_key.key)
-                .map_err(|_| "Failed to generate token")?;
-
-            // return the JWT embedded in an HTML page
-            let response_html = format!(
-                r#"
-                <html>
-                <head>
-                    <link rel="stylesheet" href="css/style.css">
-                    <meta name="CRESCENT_JWT" content="{}">
-                </head>
-                <body>
-                    <header class="header">
-                        <h1>{}</h1>
-                    </header>
-                    <div class="welcome-container">
-                        <h1>Here is your JWT, {}</h1>
-                        <textarea id="jwt" rows="10" cols="100">{}</textarea>
-                        <p>Copy and use this JWT, or let your browser extension access it.</p>
-                    </div>
-                </body>
-                </html>
-                "#,
-                token,
-                issuer_name_str,
-                username,
-                token
-            );
-
-            Ok(RawHtml(response_html))
-        } else {
-            Err("User not found.")
-        }
+        let demo_user = users.iter().find(|user| user.username == username).map(|user| {
+            let mut user_claims = user.user_claims.clone();
+            let (name, given_name, family_name) = user.localized_names.resolve(&requested_locales.0);
+            user_claims.name = name;
+            user_claims.given_name = given_name;
+            user_claims.family_name = family_name;
+            user_claims
+        });
+        (username, demo_user)
+    } else {
+        return Err("User not authenticated.");
+    };
+
+    // find the user based on the username
+    if let Some(user_claims) = resolved_user_claims {
+        // generate the JWT token
+        let current_time = Utc::now();
+        let claims = Claims {
+            user_claims,
+            acct: 0,
+            aud: "relyingparty.example.com".to_string(),
+            auth_time: current_time.timestamp() as usize,
+            exp: (current_time + Duration::days(30)).timestamp() as usize,
+            iat: current_time.timestamp() as usize,
+            ipaddr: "203.0.113.0".to_string(),
+            iss: format!("https://{}", issuer_domain_str),
+            jti: "fGYCO1mK2dBWTAfCjGAoTQ".to_string(),
+            nbf: current_time.timestamp() as usize,
+            tid: "12345678-1234-abcd-1234-abcdef124567".to_string(),
+            ver: "2.0".to_string(),
+            xms_pdl: "NAM".to_string(),
+            xms_tpl: "en".to_string(),
+        };
+
+        let mut header = Header::new(signing_keys.algorithm);
+        header.kid = Some(signing_keys.active_kid.clone());
+
+        let token = encode(&header, &claims, signing_keys.active_encoding_key())
+            .map_err(|_| "Failed to generate token")?;
+
+        // return the JWT embedded in an HTML page
+        let response_html = format!(
+            r#"
+            <html>
+            <head>
+                <link rel="stylesheet" href="css/style.css">
+                <meta name="CRESCENT_JWT" content="{}">
+            </head>
+            <body>
+                <header class="header">
+                    <h1>{}</h1>
+                </header>
+                <div class="welcome-container">
+                    <h1>Here is your JWT, {}</h1>
+                    <textarea id="jwt" rows="10" cols="100">{}</textarea>
+                    <p>Copy and use this JWT, or let your browser extension access it.</p>
+                </div>
+            </body>
+            </html>
+            "#,
+            token,
+            issuer_name_str,
+            username,
+            token
+        );
+
+        Ok(RawHtml(response_html))
     } else {
-        Err("User not authenticated.")
+        Err("User not found.")
     }
 }
 
+// Builds the EC JWK for a P-256 public key, mirroring how
+// `parse_device_public_key` pulls the uncompressed x/y coordinates out of
+// the affine point.
+fn ec_jwk(kid: &str, verifying_key: &VerifyingKey) -> serde_json::Value {
+    let encoded_point = verifying_key.to_encoded_point(false); // uncompressed
+    let x = encoded_point.x().expect("Missing x-coordinate");
+    let y = encoded_point.y().expect("Missing y-coordinate");
+    serde_json::json!({
+        "kty": "EC",
+        "crv": "P-256",
+        "kid": kid,
+        "use": "sig",
+        "alg": "ES256",
+        "x": base64_url::encode(x),
+        "y": base64_url::encode(y),
+    })
+}
+
+// Builds the JWKS entries this issuer publishes: one per loaded signing key.
+// RSA public-key parameters (n, e) aren't reconstructed from the private key
+// here; an RSA kid is published exactly as hand-maintained in the static
+// JWKS file. EC keys are derived fresh from the loaded public point, since
+// there's no EC JWK on disk to fall back to. Shared by `serve_jwks` and
+// `introspect`, which both need to resolve a `kid` to its public key.
+fn published_jwks(signing_keys: &SigningKeys) -> Option<Vec<serde_json::Value>> {
+    let static_jwks_data = fs::read(JWKS_PATH).ok()?;
+    let static_jwks: serde_json::Value = serde_json::from_slice(&static_jwks_data).ok()?;
+    let static_keys = static_jwks["keys"].as_array().cloned().unwrap_or_default();
+
+    let mut keys = Vec::new();
+    for (kid, signing_key) in signing_keys.keys.iter() {
+        match signing_key {
+            SigningKeyMaterial::Rsa(_) => {
+                if let Some(entry) = static_keys.iter().find(|k| k["kid"].as_str() == Some(kid.as_str())) {
+                    keys.push(entry.clone());
+                }
+            }
+            SigningKeyMaterial::Ec { verifying_key, .. } => {
+                keys.push(ec_jwk(kid, verifying_key));
+            }
+        }
+    }
+    Some(keys)
+}
+
 // route to serve the JWKS file
 #[get("/.well-known/jwks.json")]
-async fn serve_jwks() -> Option<NamedFile> {
-    // serve the JWKS file from the specified path
-    NamedFile::open(PathBuf::from(JWKS_PATH)).await.ok()
+async fn serve_jwks(signing_keys: &State<SigningKeys>) -> Option<Json<serde_json::Value>> {
+    let keys = published_jwks(signing_keys)?;
+    Some(Json(serde_json::json!({ "keys": keys })))
+}
+
+// request body for `/introspect`: the JWT to check, as issued by `/issue`
+#[derive(Deserialize)]
+struct IntrospectRequest {
+    token: String,
+}
+
+// Resolves the token header's `kid` against our own published JWKS, builds
+// the matching `DecodingKey` (RSA or EC, mirroring `validate_upstream_id_token`'s
+// kty-branch but supporting both key types now that chunk2-3 made EC signing
+// real), and validates the signature plus the `aud`/`iss`/`exp`/`nbf` claims
+// `issue_token` sets.
+fn verify_issued_token(token: &str, signing_keys: &SigningKeys, issuer_config: &IssuerConfig) -> Result<serde_json::Value, String> {
+    let header = decode_header(token).map_err(|e| format!("Failed to parse token header: {}", e))?;
+    let kid = header.kid.ok_or("Token header missing kid")?;
+
+    let keys = published_jwks(signing_keys).ok_or("Failed to load published JWKS")?;
+    let jwk_value = keys
+        .iter()
+        .find(|k| k["kid"].as_str() == Some(kid.as_str()))
+        .ok_or_else(|| format!("No published key for kid {}", kid))?;
+
+    let decoding_key = match jwk_value["kty"].as_str() {
+        Some("RSA") => {
+            let jwk: JsonWebKey = serde_json::from_value(jwk_value.clone()).map_err(|e| format!("Failed to parse JWK: {}", e))?;
+            DecodingKey::from_rsa_pem(jwk.key.to_pem().as_bytes()).map_err(|e| format!("Failed to load RSA public key: {}", e))?
+        }
+        Some("EC") => {
+            let x = jwk_value["x"].as_str().ok_or("JWK missing x coordinate")?;
+            let y = jwk_value["y"].as_str().ok_or("JWK missing y coordinate")?;
+            DecodingKey::from_ec_components(x, y).map_err(|e| format!("Failed to load EC public key: {}", e))?
+        }
+        other => return Err(format!("Unsupported key type: {:?}", other)),
+    };
+
+    // Pin the algorithm we actually sign with rather than trusting the
+    // token's own header -- deriving it from the token being verified is
+    // the classic "alg confusion" anti-pattern (see `validate_upstream_id_token`
+    // above, which correctly hardcodes RS256 for the same reason).
+    let mut validation = Validation::new(signing_keys.algorithm);
+    validation.set_issuer(&[format!("https://{}", issuer_config.issuer_domain)]);
+    validation.set_audience(&["relyingparty.example.com"]);
+
+    decode::<serde_json::Value>(token, &decoding_key, &validation)
+        .map(|data| data.claims)
+        .map_err(|e| format!("Token validation failed: {}", e))
+}
+
+// route to introspect a JWT issued by `/issue`: re-validates it against our
+// own JWKS the same way a relying party would, so a prover or RP can check a
+// token without duplicating the signature/claims logic themselves
+#[post("/introspect", format = "json", data = "<request>")]
+fn introspect(request: Json<IntrospectRequest>, signing_keys: &State<SigningKeys>, issuer_config: &State<IssuerConfig>) -> Json<serde_json::Value> {
+    match verify_issued_token(&request.token, signing_keys, issuer_config) {
+        Ok(claims) => Json(serde_json::json!({ "active": true, "claims": claims })),
+        Err(e) => Json(serde_json::json!({ "active": false, "error": e })),
+    }
+}
+
+// Field names of `UserClaims` and the token-specific fields of `Claims`
+// (`#[serde(flatten)]` merges the two into one JWT object), kept in the same
+// order as the structs so the two stay easy to compare by eye.
+fn claims_supported() -> Vec<&'static str> {
+    vec![
+        // UserClaims
+        "email", "family_name", "given_name", "login_hint", "name", "oid", "onprem_sid",
+        "preferred_username", "rh", "sid", "sub", "upn", "uti", "tenant_ctry",
+        "tenant_region_scope", "verified_primary_email", "verified_secondary_email",
+        "device_key_0", "device_key_1",
+        // Claims (token-specific)
+        "acct", "aud", "auth_time", "exp", "iat", "ipaddr", "iss", "jti", "nbf", "tid",
+        "ver", "xms_pdl", "xms_tpl",
+    ]
+}
+
+// route to serve OIDC discovery metadata, so a verifier or Crescent prover
+// can learn the issuer's signing algorithm and endpoints instead of having
+// them hard-coded
+#[get("/.well-known/openid-configuration")]
+fn openid_configuration(issuer_config: &State<IssuerConfig>, signing_keys: &State<SigningKeys>) -> Json<serde_json::Value> {
+    let issuer = format!("https://{}", issuer_config.issuer_domain);
+    let alg_name = match signing_keys.algorithm {
+        jsonwebtoken::Algorithm::ES256 => "ES256",
+        _ => "RS256",
+    };
+    Json(serde_json::json!({
+        "issuer": issuer,
+        "jwks_uri": format!("{}/.well-known/jwks.json", issuer),
+        "authorization_endpoint": format!("{}/login", issuer),
+        "token_endpoint": format!("{}/issue", issuer),
+        "id_token_signing_alg_values_supported": [alg_name],
+        "claims_supported": claims_supported(),
+    }))
 }
 
 fn parse_device_public_key(device_pub_key: &VerifyingKey) -> (u128, u128) {
@@ -298,6 +847,29 @@ fn create_demo_users(issuer_config: &IssuerConfig, device_pub_key: Option<Verify
                 device_key_0: device_key_0,
                 device_key_1: device_key_1,
             },
+            localized_names: LocalizedNames {
+                name: LocalizedValue {
+                    values: BTreeMap::from([
+                        ("en".to_string(), "Alice Example".to_string()),
+                        ("fr".to_string(), "Alice Exemple".to_string()),
+                    ]),
+                    default_locale: "en".to_string(),
+                },
+                given_name: LocalizedValue {
+                    values: BTreeMap::from([
+                        ("en".to_string(), "Alice".to_string()),
+                        ("fr".to_string(), "Alice".to_string()),
+                    ]),
+                    default_locale: "en".to_string(),
+                },
+                family_name: LocalizedValue {
+                    values: BTreeMap::from([
+                        ("en".to_string(), "Example".to_string()),
+                        ("fr".to_string(), "Exemple".to_string()),
+                    ]),
+                    default_locale: "en".to_string(),
+                },
+            },
         },
         User {
             username: "bob".to_string(),
@@ -323,6 +895,29 @@ fn create_demo_users(issuer_config: &IssuerConfig, device_pub_key: Option<Verify
                 device_key_0: device_key_0,
                 device_key_1: device_key_1,
             },
+            localized_names: LocalizedNames {
+                name: LocalizedValue {
+                    values: BTreeMap::from([
+                        ("en".to_string(), "Bob Example".to_string()),
+                        ("es".to_string(), "Roberto Ejemplo".to_string()),
+                    ]),
+                    default_locale: "en".to_string(),
+                },
+                given_name: LocalizedValue {
+                    values: BTreeMap::from([
+                        ("en".to_string(), "Bob".to_string()),
+                        ("es".to_string(), "Roberto".to_string()),
+                    ]),
+                    default_locale: "en".to_string(),
+                },
+                family_name: LocalizedValue {
+                    values: BTreeMap::from([
+                        ("en".to_string(), "Example".to_string()),
+                        ("es".to_string(), "Ejemplo".to_string()),
+                    ]),
+                    default_locale: "en".to_string(),
+                },
+            },
         },
     ]
 }
@@ -334,38 +929,83 @@ async fn favicon() -> Option<NamedFile> {
 
 #[launch]
 fn rocket() -> _ {
-    // load the issuer private key at server startup
-    let private_key_data = fs::read(PRIVATE_KEY_PATH)
-        .expect("Failed to read private key");
-    let encoding_key = EncodingKey::from_rsa_pem(&private_key_data)
-        .expect("Failed to create encoding key");
+    // Load issuer configuration
+    let figment = rocket::Config::figment();
+    let issuer_name: String = figment.extract_inner("issuer_name").unwrap_or_else(|_| "Example Issuer".to_string());
+    let issuer_domain: String = figment.extract_inner("issuer_domain").unwrap_or_else(|_| "example.com".to_string());
+    let device_key_binding: bool = figment.extract_inner("device_key_binding").unwrap_or(false);
+    let upstream_idp_enabled: bool = figment.extract_inner("upstream_idp_enabled").unwrap_or(false);
+    let issuer_algorithm_str: String = figment.extract_inner("issuer_algorithm").unwrap_or_else(|_| "RS256".to_string());
+    let issuer_algorithm = match issuer_algorithm_str.as_str() {
+        "ES256" => jsonwebtoken::Algorithm::ES256,
+        _ => jsonwebtoken::Algorithm::RS256,
+    };
 
-    // read the kid from the JWK set in JWKS_PATH
+    // read every kid in the published JWKS, and load the matching private
+    // key PEM for each from PRIVATE_KEYS_DIR -- this is what lets a rotation
+    // (publish a new kid in the JWKS, drop a PEM file next to it) pick up
+    // without restarting with a different private-key path
     let jwks_data = fs::read(JWKS_PATH)
         .expect("Failed to read JWKS file");
     let jwks: serde_json::Value = serde_json::from_slice(&jwks_data)
         .expect("Failed to parse JWKS file");
-    let issuer_kid = jwks["keys"][0]["kid"].as_str().expect("issuer_kid should exist").to_string();
-    println!("Loaded JWKS with kid: {:?}", issuer_kid);
+    let jwk_entries = jwks["keys"].as_array().expect("JWKS file must have a keys array");
 
-    // create the private key struct
-    let private_key = PrivateKey {
-        key: encoding_key,
-    };
+    let mut keys = BTreeMap::new();
+    for jwk in jwk_entries {
+        let kid = jwk["kid"].as_str().expect("each JWKS entry needs a kid").to_string();
+        let key_path = format!("{}/{}.prv", PRIVATE_KEYS_DIR, kid);
+        let private_key_data = fs::read(&key_path)
+            .unwrap_or_else(|_| panic!("Failed to read private key for kid {} at {}", kid, key_path));
+        let signing_key = match issuer_algorithm {
+            jsonwebtoken::Algorithm::ES256 => {
+                let encoding_key = EncodingKey::from_ec_pem(&private_key_data)
+                    .unwrap_or_else(|_| panic!("Failed to create EC encoding key for kid {}", kid));
+                let pem_str = std::str::from_utf8(&private_key_data)
+                    .unwrap_or_else(|_| panic!("EC private key for kid {} is not valid UTF-8 PEM", kid));
+                let verifying_key = *EcSigningKey::from_pkcs8_pem(pem_str)
+                    .unwrap_or_else(|_| panic!("Failed to parse EC private key for kid {}", kid))
+                    .verifying_key();
+                SigningKeyMaterial::Ec { encoding_key, verifying_key }
+            }
+            _ => {
+                let encoding_key = EncodingKey::from_rsa_pem(&private_key_data)
+                    .unwrap_or_else(|_| panic!("Failed to create RSA encoding key for kid {}", kid));
+                SigningKeyMaterial::Rsa(encoding_key)
+            }
+        };
+        println!("Loaded signing key for kid: {}", kid);
+        keys.insert(kid, signing_key);
+    }
+
+     // Which loaded kid to sign new tokens with; rotating is flipping this
+     // one value once the new key's kid is in the JWKS and PRIVATE_KEYS_DIR
+     let active_kid: String = figment.extract_inner("issuer_signing_kid").unwrap_or_else(|_| {
+         jwk_entries[0]["kid"].as_str().expect("issuer_signing_kid should exist").to_string()
+     });
+     let signing_keys = SigningKeys { keys, active_kid, algorithm: issuer_algorithm };
+
+     // Only built (and required) when `upstream_idp_enabled` is set, so the
+     // default demo-password path doesn't need these configured at all
+     let upstream_idp = if upstream_idp_enabled {
+         Some(UpstreamIdpConfig {
+             discovery_url: figment.extract_inner("upstream_discovery_url").expect("upstream_discovery_url must be set when upstream_idp_enabled is true"),
+             client_id: figment.extract_inner("upstream_client_id").expect("upstream_client_id must be set when upstream_idp_enabled is true"),
+             client_secret: figment.extract_inner("upstream_client_secret").expect("upstream_client_secret must be set when upstream_idp_enabled is true"),
+             redirect_uri: figment.extract_inner("upstream_redirect_uri").unwrap_or_else(|_| format!("https://{}/callback", issuer_domain)),
+         })
+     } else {
+         None
+     };
 
-     // Load issuer configuration
-     let figment = rocket::Config::figment();
-     let issuer_name: String = figment.extract_inner("issuer_name").unwrap_or_else(|_| "Example Issuer".to_string());
-     let issuer_domain: String = figment.extract_inner("issuer_domain").unwrap_or_else(|_| "example.com".to_string());
-     let device_key_binding: bool = figment.extract_inner("device_key_binding").unwrap_or(false);
-     
      let issuer_config = IssuerConfig {
          issuer_name,
          issuer_domain,
-         issuer_kid,
          _device_key_binding: device_key_binding,
+         upstream_idp,
      };
- 
+     let upstream_sessions = UpstreamSessions { sessions: Mutex::new(HashMap::new()) };
+
      let mut device_pub_key = None;
      if device_key_binding {
         // read the device public key
@@ -387,7 +1027,8 @@ fn rocket() -> _ {
     rocket::build()
         .manage(issuer_config)
         .manage(users)
-        .manage(private_key)
+        .manage(signing_keys)
+        .manage(upstream_sessions)
         .attach(Template::fairing())
         .mount("/", FileServer::from("static"))
         .mount(
@@ -396,9 +1037,12 @@ fn rocket() -> _ {
                 index_redirect,
                 login_page,
                 login,
+                callback,
                 welcome_page,
                 issue_token,
                 serve_jwks,
+                introspect,
+                openid_configuration,
                 favicon
             ],
         )
@@ -4,6 +4,9 @@
 use std::path::Path;
 use std::fs;
 use std::io;
+use std::sync::OnceLock;
+
+use rocket::serde::{Deserialize, Serialize};
 
 #[cfg(unix)]
 use std::os::unix::fs::symlink as symlink_any;
@@ -11,53 +14,126 @@ use std::os::unix::fs::symlink as symlink_any;
 #[cfg(windows)]
 use junction;
 
-// TODO: Encode this information in a json config file containing, e.g,. 
-//   schema_uid: jwt_corporate_1
-//   cred_type : jwt
-//   disclosure_ids : [email_domain]Put all the disclosure UIDs and Schema UIDs in a json config file
+// Registry of supported credential schemas, read once from `schemas.json`
+// (resolved relative to this crate, not the caller's working directory, so
+// `setup_service`/`client_helper`/`verifier` all see the same file however
+// they're launched). Replaces the old hard-coded `SCHEMA_UIDS` constant and
+// the `match`-based lookup functions below, so a deployment can register a
+// new JWT/mDL schema or disclosure predicate by editing `schemas.json`
+// instead of recompiling.
+const SCHEMAS_JSON_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/schemas.json");
+
+/// Metadata about one disclosure a [`CredentialSchema`] supports: its
+/// opaque `crescent://...` UID, the kind of claim it makes (`"reveal"` for
+/// a plain attribute disclosure, `"age_over"` for a numeric-predicate proof
+/// like mDL's age checks), and whatever parameters that kind needs -- e.g.
+/// `age_over`'s integer `age` threshold.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DisclosureSpec {
+    pub uid: String,
+    pub kind: String,
+    #[serde(default)]
+    pub params: serde_json::Map<String, serde_json::Value>,
+}
+
+/// One registered credential schema, deserialized straight from
+/// `schemas.json`: its opaque `schema_uid`, the credential type
+/// (`"jwt"`/`"mdl"`) it's issued as, and the disclosures it supports.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CredentialSchema {
+    pub schema_uid: String,
+    pub cred_type: String,
+    pub disclosures: Vec<DisclosureSpec>,
+}
+
+/// The set of credential schemas this deployment supports. Loaded once
+/// from `schemas.json` by [`schema_registry`] and kept for the life of the
+/// process; see the free functions below for the per-lookup API the rest
+/// of this crate (and `client_helper`/`verifier`) use.
+pub struct SchemaRegistry {
+    schemas: Vec<CredentialSchema>,
+}
+
+impl SchemaRegistry {
+    fn load() -> Self {
+        let raw = fs::read_to_string(SCHEMAS_JSON_PATH)
+            .unwrap_or_else(|e| panic!("Failed to read schema registry from {}: {}", SCHEMAS_JSON_PATH, e));
+        let schemas: Vec<CredentialSchema> = serde_json::from_str(&raw)
+            .unwrap_or_else(|e| panic!("Failed to parse schema registry {}: {}", SCHEMAS_JSON_PATH, e));
+        SchemaRegistry { schemas }
+    }
+
+    fn schema(&self, schema_uid: &str) -> Option<&CredentialSchema> {
+        self.schemas.iter().find(|s| s.schema_uid == schema_uid)
+    }
+
+    fn disclosure(&self, disc_uid: &str) -> Option<&DisclosureSpec> {
+        self.schemas.iter().flat_map(|s| &s.disclosures).find(|d| d.uid == disc_uid)
+    }
+
+    pub fn schema_uids(&self) -> Vec<&str> {
+        self.schemas.iter().map(|s| s.schema_uid.as_str()).collect()
+    }
+
+    pub fn is_disc_supported_by_schema(&self, disc_uid: &str, schema_uid: &str) -> bool {
+        self.schema(schema_uid)
+            .map(|s| s.disclosures.iter().any(|d| d.uid == disc_uid))
+            .unwrap_or(false)
+    }
+
+    pub fn is_disc_uid_supported(&self, disc_uid: &str, cred_type: &str) -> bool {
+        self.schemas.iter()
+            .filter(|s| s.cred_type == cred_type)
+            .any(|s| s.disclosures.iter().any(|d| d.uid == disc_uid))
+    }
+
+    pub fn cred_type_from_schema(&self, schema_uid: &str) -> Result<&str, &'static str> {
+        self.schema(schema_uid)
+            .map(|s| s.cred_type.as_str())
+            .ok_or("cred_type_from_schema: Unknown schema UID")
+    }
+
+    /// The integer threshold an `age_over`-kind disclosure (e.g.
+    /// `crescent://over_18`) proves the holder is above.
+    pub fn age_threshold(&self, disc_uid: &str) -> Result<usize, &'static str> {
+        self.disclosure(disc_uid)
+            .and_then(|d| d.params.get("age"))
+            .and_then(|v| v.as_u64())
+            .map(|age| age as usize)
+            .ok_or("disc_uid_to_age: invalid disclosure uid")
+    }
+}
+
+static REGISTRY: OnceLock<SchemaRegistry> = OnceLock::new();
 
-// define the supported cred schema UIDs. These are an opaque strings that identifies the setup parameters
-pub const SCHEMA_UIDS: [&str; 2] = ["jwt_corporate_1", "mdl_1"];
+/// The process-wide schema registry, loaded from `schemas.json` on first
+/// access.
+pub fn schema_registry() -> &'static SchemaRegistry {
+    REGISTRY.get_or_init(SchemaRegistry::load)
+}
+
+// Thin wrappers kept so existing call sites read the same as before, now
+// backed by the registry instead of a hard-coded `match`.
+
+pub fn schema_uids() -> Vec<&'static str> {
+    schema_registry().schema_uids()
+}
 
-// TODO: this is not quite right; we need to also use the Schema ID. It assumes that all JWTs support the email_domain predicate
 // This is needed during show, in the client_helper, to check if we can actually create the proof with the cred we have.
 pub fn is_disc_uid_supported(disc_uid : &str, cred_type: &str) -> bool {
-    match cred_type {
-        "jwt" => {
-            matches!(disc_uid, "crescent://email_domain")
-        }
-        "mdl" => {
-            matches!(disc_uid, "crescent://over_18" | "crescent://over_21" | "crescent://over_65")
-        }
-        _ => false  // unknown cred type
-    }
+    schema_registry().is_disc_uid_supported(disc_uid, cred_type)
 }
 
 pub fn is_disc_supported_by_schema(disc : &str, schema : &str) -> bool {
-
-    matches!( (schema, disc),
-        ("jwt_corporate_1", "crescent://email_domain") | 
-        ("mdl_1", "crescent://over_18") |
-        ("mdl_1", "crescent://over_21") |
-        ("mdl_1", "crescent://over_65")
-    )
+    schema_registry().is_disc_supported_by_schema(disc, schema)
 }
 
 pub fn disc_uid_to_age(disc_uid : &str) -> Result<usize, &'static str> {
-    match disc_uid {
-        "crescent://over_18" => Ok(18),
-        "crescent://over_21" => Ok(21),
-        "crescent://over_65" => Ok(65),
-        _ => Err("disc_uid_to_age: invalid disclosure uid"),
-    }
+    schema_registry().age_threshold(disc_uid)
 }
 
 pub fn cred_type_from_schema(schema_uid : &str) -> Result<&'static str, &'static str> {
-    match schema_uid {
-        "jwt_corporate_1" => Ok("jwt"), 
-        "mdl_1" => Ok("mdl"),
-        _ => Err("cred_type_from_schema: Unknown schema UID"),
-    }
+    schema_registry().cred_type_from_schema(schema_uid)
 }
 
 
@@ -4,15 +4,22 @@
 #[macro_use] extern crate rocket;
 
 use rocket::serde::{Serialize, Deserialize};
+use rocket::serde::json::Json;
 use rocket::fs::NamedFile;
+use rocket::State;
 use crescent::{CachePaths, CrescentPairing, ShowParams};
 use crescent::VerifierParams;
 use crescent::utils::write_to_b64url;
+use std::collections::BTreeMap;
 use std::path::PathBuf;
 use std::path::Path;
+use std::fs;
 
-// path where the parameters are stored. For now, we hardcode these; later, we'll generate them for each token scheme UID (TODO)
-const CRESCENT_DATA_BASE_PATH : &str = "../../creds/test-vectors/rs256";
+// Directory containing one subdirectory per registered credential scheme,
+// each holding that scheme's own `CachePaths` layout (e.g. `rs256/`, and in
+// the future `mdl/` or other custom schemas). Each subdirectory's name is
+// its `schema_uid`.
+const CRESCENT_DATA_BASE_PATH : &str = "../../creds/test-vectors";
 
 // struct for the JWT info
 #[derive(Serialize, Deserialize, Clone)]
@@ -22,11 +29,11 @@ struct TokenInfo {
 }
 
 ///// Routes for hosting parameters
- // Small parameters are sent as b64_url encoded strings. 
+ // Small parameters are sent as b64_url encoded strings.
  // The large params required for one-time proof generation are hosted in a file
 
 // Ensure that both setup steps in README.md
-// 1) /setup/scripts/run_setup.sh and 
+// 1) /setup/scripts/run_setup.sh and
 // 2) /creds/crescent zksetup
 // have been run and CRESCENT_DATA_BASE_PATH points to the place where the generated
 // parameters are stored.
@@ -46,50 +53,123 @@ fn check_for_stored_params(paths :&CachePaths) -> bool {
         if !Path::new(&f).exists() {
             println!("Error: required file not found ({})", f);
             return false;
-        }        
+        }
     }
 
     true
 }
 
+/// Maps every registered `schema_uid` to its own `CachePaths`, built once at
+/// launch by scanning `CRESCENT_DATA_BASE_PATH` for one subdirectory per
+/// scheme. A scheme whose files aren't all present is skipped (with a
+/// warning) rather than aborting the whole server, so one missing/partial
+/// scheme doesn't take every other scheme down with it.
+struct SchemeRegistry {
+    schemes: BTreeMap<String, CachePaths>,
+}
+
+impl SchemeRegistry {
+    fn discover(base_dir: &str) -> Self {
+        let mut schemes = BTreeMap::new();
+
+        let entries = match fs::read_dir(base_dir) {
+            Ok(entries) => entries,
+            Err(e) => {
+                println!("Error: could not read schema registry directory {}: {}", base_dir, e);
+                return SchemeRegistry { schemes };
+            }
+        };
+
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if !path.is_dir() {
+                continue;
+            }
+            let schema_uid = path.file_name().unwrap().to_string_lossy().to_string();
+            let paths = CachePaths::new(path);
+            if check_for_stored_params(&paths) {
+                println!("Registered schema '{}'", schema_uid);
+                schemes.insert(schema_uid, paths);
+            } else {
+                println!("Warning: skipping schema '{}', required files not found", schema_uid);
+            }
+        }
+
+        SchemeRegistry { schemes }
+    }
+
+    fn get(&self, schema_uid: &str) -> Option<&CachePaths> {
+        self.schemes.get(schema_uid)
+    }
+}
+
+// Lists the `schema_uid`s available on this deployment.
+#[get("/")]
+fn index(registry: &State<SchemeRegistry>) -> Json<Vec<String>> {
+    Json(registry.schemes.keys().cloned().collect())
+}
+
 // Get the parameters required to generate the one-time proofs (the Groth16 proofs)
 // Since the params are so big, we just expose the binary file for download
-#[get("/prove_params/<file..>")]
-async fn files(file: PathBuf) -> Option<NamedFile> {
-    let paths = CachePaths::new_from_str(CRESCENT_DATA_BASE_PATH);
+#[get("/prove_params/<schema_uid>/<file..>")]
+async fn files(registry: &State<SchemeRegistry>, schema_uid: &str, file: PathBuf) -> Option<NamedFile> {
+    let paths = registry.get(schema_uid)?;
     let path = Path::new(&paths._base).join(file);
     println!("Got request for file : {:?}", path);
     NamedFile::open(path).await.ok()
 }
 
-// Get the parameters required to generate presentation/show proofs // TODO: add schema_uid to the path (as documented)
-#[get("/show_params")]
-fn show_params() -> String {
-    let paths = CachePaths::new_from_str(CRESCENT_DATA_BASE_PATH);
-    let show_params = ShowParams::<CrescentPairing>::new(&paths).expect("Failed to create ShowParams instance");
-    
-    
-    write_to_b64url(&show_params)
+// Get the parameters required to generate presentation/show proofs, as the
+// default opaque b64url-wrapped blob.
+#[get("/show_params/<schema_uid>", rank = 2)]
+fn show_params(registry: &State<SchemeRegistry>, schema_uid: &str) -> Option<String> {
+    let paths = registry.get(schema_uid)?;
+    let show_params = ShowParams::<CrescentPairing>::new(paths).expect("Failed to create ShowParams instance");
+
+    Some(write_to_b64url(&show_params))
+}
+
+// Same parameters, as a self-describing JSON document -- picked over
+// `show_params` by Rocket's format-based routing when the client sends
+// `Accept: application/json`, for verifiers that aren't Rust/arkworks and
+// can't decode the b64url blob.
+#[get("/show_params/<schema_uid>", format = "json", rank = 1)]
+fn show_params_json(registry: &State<SchemeRegistry>, schema_uid: &str) -> Option<Json<ShowParams<'static, CrescentPairing>>> {
+    let paths = registry.get(schema_uid)?;
+    let show_params = ShowParams::<CrescentPairing>::new(paths).expect("Failed to create ShowParams instance");
+
+    Some(Json(show_params))
 }
 
-// Get the parameters required to verify presentation proofs // TODO: add schema_uid to the path (as documented)
-#[get("/verifier_params")]
-fn verifier_params() -> String {
-    let paths = CachePaths::new_from_str(CRESCENT_DATA_BASE_PATH);
-    let verifier_params = VerifierParams::<CrescentPairing>::new(&paths).expect("Failed to create VerifierParams instance");
-    
-    
-    write_to_b64url(&verifier_params)
+// Get the parameters required to verify presentation proofs, as the
+// default opaque b64url-wrapped blob.
+#[get("/verifier_params/<schema_uid>", rank = 2)]
+fn verifier_params(registry: &State<SchemeRegistry>, schema_uid: &str) -> Option<String> {
+    let paths = registry.get(schema_uid)?;
+    let verifier_params = VerifierParams::<CrescentPairing>::new(paths).expect("Failed to create VerifierParams instance");
+
+    Some(write_to_b64url(&verifier_params))
+}
+
+// Same parameters, as a self-describing JSON document -- see `show_params_json`.
+#[get("/verifier_params/<schema_uid>", format = "json", rank = 1)]
+fn verifier_params_json(registry: &State<SchemeRegistry>, schema_uid: &str) -> Option<Json<VerifierParams<CrescentPairing>>> {
+    let paths = registry.get(schema_uid)?;
+    let verifier_params = VerifierParams::<CrescentPairing>::new(paths).expect("Failed to create VerifierParams instance");
+
+    Some(Json(verifier_params))
 }
 
 #[launch]
 fn rocket() -> _ {
-    let paths = CachePaths::new_from_str(CRESCENT_DATA_BASE_PATH);
-    if ! check_for_stored_params(&paths) {
-        println!("Error: parameters not present, not starting setup service");
+    let registry = SchemeRegistry::discover(CRESCENT_DATA_BASE_PATH);
+    if registry.schemes.is_empty() {
+        println!("Error: no schemas registered, not starting setup service");
         std::process::exit(-1);
     }
-    rocket::build().mount("/", routes![show_params, verifier_params, files])
+    rocket::build()
+        .manage(registry)
+        .mount("/", routes![index, show_params, show_params_json, verifier_params, verifier_params_json, files])
 }
 
 
@@ -98,12 +178,14 @@ mod test {
     use super::*;
     use crate::test::rocket::local::blocking::Client;
     use crescent::{utils::{read_from_b64url, read_from_bytes}, CrescentPairing, ProverParams, VerifierParams};
-    use rocket::http::Status;
+    use rocket::http::{Accept, Status};
+
+    const TEST_SCHEMA: &str = "rs256";
 
     #[test]
     fn test_verifier_params() {
         let client = Client::untracked(rocket()).expect("valid rocket instance");
-        let response = client.get("/verifier_params").dispatch();
+        let response = client.get(format!("/verifier_params/{}", TEST_SCHEMA)).dispatch();
         assert_eq!(response.status(), Status::Ok);
         let s = response.into_string().unwrap();
         let vp = read_from_b64url::<VerifierParams<CrescentPairing>>(&s);
@@ -114,7 +196,7 @@ mod test {
     #[test]
     fn test_show_params() {
         let client = Client::untracked(rocket()).expect("valid rocket instance");
-        let response = client.get("/show_params").dispatch();
+        let response = client.get(format!("/show_params/{}", TEST_SCHEMA)).dispatch();
         assert_eq!(response.status(), Status::Ok);
         let s = response.into_string().unwrap();
         let sp = read_from_b64url::<ShowParams<CrescentPairing>>(&s);
@@ -122,10 +204,46 @@ mod test {
         assert!(sp.is_ok());
     }
 
+    #[test]
+    fn test_verifier_params_json() {
+        let client = Client::untracked(rocket()).expect("valid rocket instance");
+        let response = client.get(format!("/verifier_params/{}", TEST_SCHEMA)).header(Accept::JSON).dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        let s = response.into_string().unwrap();
+        let vp: VerifierParams<CrescentPairing> = serde_json::from_str(&s).unwrap();
+        assert!(!vp.config_str.is_empty());
+    }
+
+    #[test]
+    fn test_show_params_json() {
+        let client = Client::untracked(rocket()).expect("valid rocket instance");
+        let response = client.get(format!("/show_params/{}", TEST_SCHEMA)).header(Accept::JSON).dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        let s = response.into_string().unwrap();
+        let sp: Result<ShowParams<CrescentPairing>, _> = serde_json::from_str(&s);
+        assert!(sp.is_ok());
+    }
+
+    #[test]
+    fn test_unknown_schema_not_found() {
+        let client = Client::untracked(rocket()).expect("valid rocket instance");
+        let response = client.get("/show_params/does-not-exist").dispatch();
+        assert_eq!(response.status(), Status::NotFound);
+    }
+
+    #[test]
+    fn test_index_lists_schemas() {
+        let client = Client::untracked(rocket()).expect("valid rocket instance");
+        let response = client.get("/").dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        let uids: Vec<String> = response.into_json().unwrap();
+        assert!(uids.contains(&TEST_SCHEMA.to_string()));
+    }
+
     #[test]
     fn test_prover_params() {
         let client = Client::untracked(rocket()).expect("valid rocket instance");
-        let response = client.get("/prove_params/cache/prover_params.bin").dispatch();
+        let response = client.get(format!("/prove_params/{}/cache/prover_params.bin", TEST_SCHEMA)).dispatch();
         assert_eq!(response.status(), Status::Ok);
         println!("Downloading large file...");
         let s = response.into_bytes().unwrap();
@@ -133,6 +251,6 @@ mod test {
         assert!(pp.is_ok());
         let pp = pp.unwrap();
         println!("Got config file {}", pp.config_str);
-        // Can also test with `wget localhost:8002/prover_params.bin`
-    }       
+        // Can also test with `wget localhost:8002/prove_params/rs256/cache/prover_params.bin`
+    }
 }
@@ -12,14 +12,20 @@ use rocket::response::status::Custom;
 use rocket::State;
 use rocket::fs::{FileServer, NamedFile};
 use rocket::http::Status;
-use std::collections::{HashMap, HashSet};
-use serde_json::{json, Value};
+use std::collections::HashMap;
+use serde_json::{json, Map, Value};
 use jsonwebkey::JsonWebKey;
+use jwt_simple::prelude::*;
+use flate2::read::GzDecoder;
 use std::path::Path;
 use std::fs;
+use std::io::Read;
 use std::sync::Mutex;
 use uuid::Uuid;
+use hmac::{Hmac, Mac};
+use rand::RngCore;
 use crescent::{utils::read_from_b64url, CachePaths, CrescentPairing, ShowProof, VerifierParams, verify_show};
+use crescent::challenge::{NonceLedger, VerifierChallenge};
 use crescent_sample_setup_service::common::*;
 use sha2::{Digest, Sha256};
 
@@ -27,11 +33,78 @@ use sha2::{Digest, Sha256};
 const CRESCENT_DATA_BASE_PATH : &str = "./data/issuers";
 const CRESCENT_SHARED_DATA_SUFFIX : &str = "shared";
 
-#[derive(Clone)]
-struct ValidationResult {
+type HmacSha256 = Hmac<Sha256>;
+
+// The claims carried by a session token: the session's own UUID (kept
+// around for logging, not for any server-side lookup), when it was
+// minted and when it expires, which site ("site1"/"site2") the session's
+// composite policy is evaluated against (set by `/presentation-request`),
+// the presentation nonce issued to this session (if any), and -- once
+// `/verify` has accepted a bundle -- the merged disclosed claims that
+// used to live in the `validation_results` map. The whole struct is what
+// gets HMAC-signed and handed back to the browser, so the verifier
+// itself holds no per-session state at all.
+#[derive(Clone, Serialize, Deserialize)]
+struct SessionClaims {
+    session_id: String,
+    issued_at: u64,
+    expiry: u64,
+    site: Option<String>,
+    nonce: Option<String>,
     disclosed_info: Option<String>,
 }
 
+fn now_secs() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+/// Mints a signed, self-contained session token: base64url(claims JSON) +
+/// `.` + base64url(HMAC-SHA256 of the claims JSON), using the verifier's
+/// `session_secret`. Replaces the old `active_session_ids`/
+/// `validation_results` server-side state -- everything a later request
+/// needs is carried in the token itself.
+fn mint_session_token(verifier_config: &VerifierConfig, session_id: &str, site: Option<String>, nonce: Option<String>, disclosed_info: Option<String>) -> String {
+    let claims = SessionClaims {
+        session_id: session_id.to_string(),
+        issued_at: now_secs(),
+        expiry: now_secs() + verifier_config.session_ttl_secs,
+        site,
+        nonce,
+        disclosed_info,
+    };
+    let payload = serde_json::to_vec(&claims).expect("SessionClaims always serializes");
+
+    let mut mac = HmacSha256::new_from_slice(verifier_config.session_secret.as_bytes())
+        .expect("HMAC accepts a key of any size");
+    mac.update(&payload);
+    let signature = mac.finalize().into_bytes();
+
+    format!("{}.{}", base64_url::encode(&payload), base64_url::encode(&signature))
+}
+
+/// Validates a session token's signature and expiry, returning its claims
+/// if both check out. Used in place of the old `HashSet`/`HashMap`
+/// lookups -- a tampered or expired token is rejected without the
+/// verifier having to remember anything about the session.
+fn verify_session_token(verifier_config: &VerifierConfig, token: &str) -> Option<SessionClaims> {
+    let (payload_b64, signature_b64) = token.split_once('.')?;
+    let payload = base64_url::decode(payload_b64).ok()?;
+    let signature = base64_url::decode(signature_b64).ok()?;
+
+    let mut mac = HmacSha256::new_from_slice(verifier_config.session_secret.as_bytes()).ok()?;
+    mac.update(&payload);
+    mac.verify_slice(&signature).ok()?;
+
+    let claims: SessionClaims = serde_json::from_slice(&payload).ok()?;
+    if claims.expiry < now_secs() {
+        return None;
+    }
+    Some(claims)
+}
+
 // verifer config from Rocket.toml
 struct VerifierConfig {
     // server port
@@ -51,12 +124,37 @@ struct VerifierConfig {
     site2_disclosure_uid: String,
     site2_proof_spec: String,
 
-    // holds active session IDs (in a real system, these would be removed
-    // after a timeout period)
-    active_session_ids: Mutex<HashSet<String>>,
+    // composite policy: every (schema_uid, disclosure_uid) pair here must
+    // be covered by a `PresentationBundle`'s proofs before `/verify`
+    // accepts the session (e.g. requiring an mDL age proof *and* a JWT
+    // work-email proof together). Defaults to the site's own single
+    // credential, so a plain Rocket.toml keeps the old one-credential
+    // behavior.
+    site1_required_credentials: Vec<(String, String)>,
+    site2_required_credentials: Vec<(String, String)>,
+
+    // allowlist of trusted issuer identifiers per schema_uid. A first-seen
+    // issuer_url must appear here, and must pass the well-known domain
+    // linkage check, before the verifier will provision a folder for it
+    // and trust its key -- defaults to empty, i.e. no issuer is trusted
+    // until explicitly configured.
+    trusted_issuers: HashMap<String, Vec<String>>,
 
-    // holds validation state
-    validation_results: Mutex<HashMap<String, ValidationResult>>,
+    // key used to HMAC-sign session tokens, and how long a token is valid
+    // for -- replaces the old in-memory `active_session_ids`/
+    // `validation_results` state, which never shrank and didn't scale
+    // past a single server instance
+    session_secret: String,
+    session_ttl_secs: u64,
+
+    // short-TTL cache of fetched Bitstring Status List credentials, keyed
+    // by their URL, so a busy verifier doesn't refetch one on every proof
+    status_list_cache: Mutex<HashMap<String, (Vec<u8>, u64)>>,
+
+    // tracks which issued challenges (nonce + audience + not_after) have
+    // already been accepted in a show proof, so a captured proof can't be
+    // replayed against this verifier again before it expires
+    nonce_ledger: Mutex<NonceLedger>,
 }
 
 // struct for the JWT info
@@ -67,6 +165,30 @@ struct ProofInfo {
     issuer_url: String,
     disclosure_uid: String,
     session_id: String,
+    // The `kid` of the key the credential was signed with, used to select
+    // the right entry out of a JWKS or DID document that carries more
+    // than one. Older clients that don't send one still resolve fine --
+    // the key resolvers fall back to the first entry.
+    #[serde(default)]
+    kid: Option<String>,
+}
+
+// `/show` can return the proof either as a bare base64url string or, when
+// the client asked for `format=vp`, wrapped in a Verifiable Presentation
+// JSON envelope with the base64url proof at `proof.proofValue`. Accept
+// either here so callers don't need to know which flavor a given
+// `client_helper` call used: a `proof` that isn't a JSON object is assumed
+// to already be the bare base64url string this route has always accepted.
+fn extract_show_proof_b64(proof: &str) -> String {
+    match serde_json::from_str::<Value>(proof) {
+        Ok(Value::Object(obj)) => obj
+            .get("proof")
+            .and_then(|p| p.get("proofValue"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string())
+            .unwrap_or_else(|| proof.to_string()),
+        _ => proof.to_string(),
+    }
 }
 
 // helper function to provide the base context for the login page
@@ -84,8 +206,7 @@ fn base_context(verifier_config: &State<VerifierConfig>) -> HashMap<String, Stri
     let site1_proof_spec_b64url = base64_url::encode(site1_proof_spec_str.as_bytes());
     let site2_proof_spec_b64url = base64_url::encode(site2_proof_spec_str.as_bytes());
 
-    let session_id = Uuid::new_v4().to_string();
-    verifier_config.active_session_ids.lock().unwrap().insert(session_id.clone());
+    let session_id = mint_session_token(verifier_config, &Uuid::new_v4().to_string(), None, None, None);
 
     let mut context = HashMap::new();
     context.insert("site1_verifier_name".to_string(), site1_verifier_name_str);
@@ -97,10 +218,47 @@ fn base_context(verifier_config: &State<VerifierConfig>) -> HashMap<String, Stri
     context.insert("site2_disclosure_uid".to_string(), site2_disclosure_uid_str);
     context.insert("site2_proof_spec_b64url".to_string(), site2_proof_spec_b64url);
     context.insert("session_id".to_string(), session_id);
-    
+
     context
 }
 
+// route for the OID4VP-style presentation request handshake: binds a
+// fresh, single-use nonce to the caller's session and returns a signed
+// description of the proof the holder must present. `/verify` then
+// requires the proof's presentation message to be the SHA-256 of this
+// nonce, so a proof minted for one verifier/session can't be replayed
+// against another.
+#[get("/presentation-request?<session_id>&<site>")]
+fn presentation_request(session_id: String, site: String, verifier_config: &State<VerifierConfig>) -> Result<Json<Value>, Custom<Value>> {
+    let claims = match verify_session_token(verifier_config, &session_id) {
+        Some(claims) => claims,
+        None => return Err(Custom(Status::BadRequest, json!({ "error": "Invalid or expired session ID" }))),
+    };
+
+    let (schema_uid, disclosure_uid, proof_spec, verifier_domain) = match site.as_str() {
+        "site1" => (schema_uids()[0], verifier_config.site1_disclosure_uid.clone(), verifier_config.site1_proof_spec.clone(), verifier_config.site1_verifier_domain.clone()),
+        "site2" => (schema_uids()[1], verifier_config.site2_disclosure_uid.clone(), verifier_config.site2_proof_spec.clone(), verifier_config.site2_verifier_domain.clone()),
+        _ => return Err(Custom(Status::BadRequest, json!({ "error": "Unknown site" }))),
+    };
+
+    let mut nonce_bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut nonce_bytes);
+    let nonce = base64_url::encode(&nonce_bytes);
+
+    // Re-mint the session token with the nonce attached; the holder sends
+    // this back as `session_id` in its `/verify` POST.
+    let request_session_id = mint_session_token(verifier_config, &claims.session_id, Some(site.clone()), Some(nonce.clone()), None);
+
+    Ok(Json(json!({
+        "schema_uid": schema_uid,
+        "disclosure_uid": disclosure_uid,
+        "proof_spec_b64url": base64_url::encode(proof_spec.as_bytes()),
+        "verifier_domain": verifier_domain,
+        "nonce": nonce,
+        "session_id": request_session_id,
+    })))
+}
+
 // route to serve the index page
 #[get("/")]
 fn index_page(verifier_config: &State<VerifierConfig>) -> Template {
@@ -156,22 +314,16 @@ fn resource_page(session_id: String, verifier_config: &State<VerifierConfig>) ->
             preferred_language: "en",
         })
     } else {
-        let validation_result = verifier_config
-            .validation_results
-            .lock()
-            .unwrap()
-            .get(&session_id)
-            .cloned();
-
-        if let Some(result) = validation_result {
-            Template::render("resource", context! {
-                site1_verifier_name: verifier_config.site1_verifier_name.as_str(),               
-                email_domain: get_email_domain(&result.disclosed_info),
-                country: get_disclosed_claim("tenant_ctry_value", &result.disclosed_info),
-                preferred_language: "en", // eventually, we can: get_disclosed_claim("xms_tpl_value", &result.disclosed_info),
-            })
-        } else {
-            Template::render("error", context! { error: "Invalid session ID" })
+        match verify_session_token(verifier_config, &session_id) {
+            Some(claims) => {
+                Template::render("resource", context! {
+                    site1_verifier_name: verifier_config.site1_verifier_name.as_str(),
+                    email_domain: get_email_domain(&claims.disclosed_info),
+                    country: get_disclosed_claim("tenant_ctry_value", &claims.disclosed_info),
+                    preferred_language: "en", // eventually, we can: get_disclosed_claim("xms_tpl_value", &claims.disclosed_info),
+                })
+            }
+            None => Template::render("error", context! { error: "Invalid session ID" }),
         }
     }
 }
@@ -199,14 +351,9 @@ fn signup2_page(session_id: String, verifier_config: &State<VerifierConfig>) ->
             email_domain: "TEST",
         })
     } else {
-        let validation_result = verifier_config
-            .validation_results
-            .lock()
-            .unwrap()
-            .get(&session_id)
-            .cloned();
-
-        if validation_result.is_some() {
+        let claims = verify_session_token(verifier_config, &session_id);
+
+        if claims.is_some() {
             // Determine site2_age based on site2_disclosure_uid
             let site2_age = match verifier_config.site2_disclosure_uid.as_str() {
                 "crescent://over_18" => 18,
@@ -241,12 +388,110 @@ fn get_disclosed_claim(claim: &str, disclsosed_info : &Option<String>) -> String
     }
 }
 
-async fn fetch_and_save_jwk(issuer_url: &str, issuer_folder: &str) -> Result<(), String> {
-    // Prepare the JWK URL
+/// Like `get_disclosed_claim`, but returns `None` instead of an "ERROR: ..."
+/// placeholder when the claim isn't present -- used for optional claims
+/// (like the status-list reference) whose absence just means the feature
+/// they drive doesn't apply to this credential.
+fn get_disclosed_str(claim: &str, disclosed_info: &Option<String>) -> Option<String> {
+    let info = disclosed_info.as_ref()?;
+    let j: Value = serde_json::from_str(info).ok()?;
+    j.get(claim)?.as_str().map(|s| s.to_string())
+}
+
+// How long a fetched Bitstring Status List credential is cached for,
+// keyed by its URL, before `/verify` will refetch it.
+const STATUS_LIST_CACHE_TTL_SECS: u64 = 300;
+
+/// Fetches the Bitstring Status List credential at `status_list_url` (a
+/// signed JWT using the same issuer key as the credential it covers),
+/// verifies its signature against `issuer_pem_path`, and returns the
+/// gunzipped bitstring from its `credentialSubject.encodedList`. Results
+/// are cached by URL for `STATUS_LIST_CACHE_TTL_SECS` so a busy verifier
+/// doesn't refetch the list on every `/verify` call.
+fn fetch_status_list_bits(verifier_config: &VerifierConfig, status_list_url: &str, issuer_pem_path: &str) -> Result<Vec<u8>, String> {
+    if let Some((bits, cached_at)) = verifier_config.status_list_cache.lock().unwrap().get(status_list_url) {
+        if now_secs() < cached_at + STATUS_LIST_CACHE_TTL_SECS {
+            return Ok(bits.clone());
+        }
+    }
+
+    println!("Fetching status list credential from: {}", status_list_url);
+    let response = ureq::get(status_list_url)
+        .call()
+        .map_err(|e| format!("Failed to fetch status list credential: {}", e))?;
+    let token_str = response.into_string()
+        .map_err(|e| format!("Failed to read status list response: {}", e))?;
+
+    let issuer_pem = fs::read_to_string(issuer_pem_path)
+        .map_err(|e| format!("Failed to read issuer public key: {}", e))?;
+    let issuer_pub = RS256PublicKey::from_pem(&issuer_pem)
+        .map_err(|e| format!("Failed to parse issuer public key: {}", e))?;
+    issuer_pub.verify_token::<NoCustomClaims>(token_str.trim(), None)
+        .map_err(|e| format!("Status list credential failed to verify: {}", e))?;
+
+    let claims_b64 = token_str.trim().split('.').nth(1)
+        .ok_or_else(|| "Status list credential is not a well-formed JWT".to_string())?;
+    let claims: Value = serde_json::from_slice(&base64_url::decode(claims_b64)
+        .map_err(|e| format!("Status list claims are not valid base64url: {}", e))?)
+        .map_err(|e| format!("Status list claims are not valid JSON: {}", e))?;
+
+    let encoded_list = claims.get("credentialSubject")
+        .and_then(|cs| cs.get("encodedList"))
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| "Status list credential is missing credentialSubject.encodedList".to_string())?;
+
+    let compressed = base64_url::decode(encoded_list)
+        .map_err(|e| format!("encodedList is not valid base64url: {}", e))?;
+    let mut bits = Vec::new();
+    GzDecoder::new(&compressed[..]).read_to_end(&mut bits)
+        .map_err(|e| format!("Failed to gunzip status list: {}", e))?;
+
+    verifier_config.status_list_cache.lock().unwrap().insert(status_list_url.to_string(), (bits.clone(), now_secs()));
+    Ok(bits)
+}
+
+/// Checks the bit for `index` in a Bitstring Status List's bitstring:
+/// byte `index / 8`, bit `index % 8` within that byte, MSB-first.
+fn status_list_bit_set(bits: &[u8], index: u64) -> bool {
+    let byte_idx = (index / 8) as usize;
+    let bit_idx = (index % 8) as u32;
+    bits.get(byte_idx)
+        .map(|byte| (byte >> (7 - bit_idx)) & 1 == 1)
+        .unwrap_or(false)
+}
+
+// Resolves `issuer_url` to the issuer's signing key -- either a classic
+// `{issuer_url}/.well-known/jwks.json` endpoint, or a `did:web`, `did:jwk`,
+// or `did:key` DID -- and writes it as `issuer.pub`, so the sample app can
+// interoperate with DID-based issuers as well as JWKS ones.
+async fn fetch_and_save_jwk(issuer_url: &str, issuer_folder: &str, kid: Option<&str>) -> Result<(), String> {
+    let jwk_value = if issuer_url.starts_with("did:") {
+        resolve_did_jwk(issuer_url, kid)?
+    } else {
+        fetch_jwks_entry(issuer_url, kid)?
+    };
+
+    // Deserialize the JSON `Value` into a `JsonWebKey`
+    let jwk: JsonWebKey = serde_json::from_value(jwk_value)
+        .map_err(|e| format!("Failed to parse JWK: {}", e))?;
+
+    // Convert the JWK to PEM format
+    let pem_key = jwk.key.to_pem();
+
+    // Save the PEM-encoded key to issuer.pub in the issuer_folder
+    let pub_key_path = Path::new(issuer_folder).join("issuer.pub");
+    fs::write(&pub_key_path, pem_key).map_err(|err| format!("Failed to save public key: {:?}", err))?;
+
+    println!("Saved issuer's public key to {:?}", pub_key_path);
+    Ok(())
+}
+
+/// Fetches `{issuer_url}/.well-known/jwks.json` and selects the entry
+/// matching `kid` (the first entry, if `kid` is `None` or none match).
+fn fetch_jwks_entry(issuer_url: &str, kid: Option<&str>) -> Result<Value, String> {
     let jwk_url = format!("{}/.well-known/jwks.json", issuer_url);
     println!("Fetching JWK set from: {}", jwk_url);
 
-    // Fetch the JWK
     let response = ureq::get(&jwk_url)
         .call()
         .map_err(|e| format!("Request failed: {}", e))?;
@@ -255,25 +500,213 @@ async fn fetch_and_save_jwk(issuer_url: &str, issuer_folder: &str) -> Result<(),
     let jwk_set: Value = serde_json::from_str(&body)
         .map_err(|e| format!("Failed to parse JSON: {}", e))?;
 
-     // Extract the first key from the JWK set and parse it into `JsonWebKey`
-     let jwk_value = jwk_set.get("keys")
+    let keys = jwk_set.get("keys")
         .and_then(|keys| keys.as_array())
-        .and_then(|keys| keys.first())
         .ok_or_else(|| "No keys found in JWK set".to_string())?;
 
-    // Deserialize the JSON `Value` into a `JsonWebKey`
-    let jwk: JsonWebKey = serde_json::from_value(jwk_value.clone())
-        .map_err(|e| format!("Failed to parse JWK: {}", e))?;
+    select_by_kid(keys, kid)
+}
 
-    // Convert the JWK to PEM format
-    let pem_key = jwk.key.to_pem();
+/// Picks the entry of `keys` whose `kid` matches, falling back to the
+/// first entry when `kid` is `None` or none match -- the same fallback
+/// the original single-key JWKS handling always used.
+fn select_by_kid(keys: &[Value], kid: Option<&str>) -> Result<Value, String> {
+    if let Some(kid) = kid {
+        if let Some(entry) = keys.iter().find(|key| key.get("kid").and_then(|v| v.as_str()) == Some(kid)) {
+            return Ok(entry.clone());
+        }
+    }
+    keys.first().cloned().ok_or_else(|| "No keys found in JWK set".to_string())
+}
 
-    // Save the PEM-encoded key to issuer.pub in the issuer_folder
-    let pub_key_path = Path::new(issuer_folder).join("issuer.pub");
-    fs::write(&pub_key_path, pem_key).map_err(|err| format!("Failed to save public key: {:?}", err))?;
+/// Resolves a `did:web`, `did:jwk`, or `did:key` issuer identifier to the
+/// JWK that should verify its credentials.
+fn resolve_did_jwk(did: &str, kid: Option<&str>) -> Result<Value, String> {
+    let mut parts = did.splitn(3, ':');
+    parts.next(); // "did"
+    let method = parts.next().ok_or_else(|| format!("Malformed DID: {}", did))?;
+    let identifier = parts.next().ok_or_else(|| format!("Malformed DID: {}", did))?;
 
-    println!("Saved issuer's public key to {:?}", pub_key_path);
-    Ok(())
+    match method {
+        "web" => resolve_did_web(identifier, kid),
+        "jwk" => resolve_did_jwk_method(identifier),
+        "key" => resolve_did_key(identifier),
+        other => Err(format!("Unsupported DID method: {}", other)),
+    }
+}
+
+/// Fetches a `did:web` DID document and pulls the RSA verification
+/// method's `publicKeyJwk`, selecting by `kid` (matching either the full
+/// verification method `id` or just its fragment) when given one.
+fn resolve_did_web(identifier: &str, kid: Option<&str>) -> Result<Value, String> {
+    let doc_url = did_web_document_url(identifier);
+    println!("Fetching DID document from: {}", doc_url);
+
+    let response = ureq::get(&doc_url)
+        .call()
+        .map_err(|e| format!("Request failed: {}", e))?;
+    let body = response.into_string()
+        .map_err(|e| format!("Failed to parse response body: {}", e))?;
+    let did_document: Value = serde_json::from_str(&body)
+        .map_err(|e| format!("Failed to parse DID document: {}", e))?;
+
+    let methods = did_document.get("verificationMethod")
+        .and_then(|v| v.as_array())
+        .ok_or_else(|| "DID document has no verificationMethod entries".to_string())?;
+
+    let selected = match kid {
+        Some(kid) => methods.iter()
+            .find(|method| method.get("id").and_then(|v| v.as_str()).is_some_and(|id| id == kid || id.ends_with(&format!("#{}", kid))))
+            .or_else(|| methods.first()),
+        None => methods.first(),
+    }.ok_or_else(|| "DID document's verificationMethod array is empty".to_string())?;
+
+    selected.get("publicKeyJwk").cloned().ok_or_else(|| "verificationMethod is missing publicKeyJwk".to_string())
+}
+
+/// Maps a `did:web` method-specific identifier to its DID document URL,
+/// per the did:web spec: `example.com:path` -> `https://example.com/path/did.json`,
+/// `example.com` (no path) -> `https://example.com/.well-known/did.json`.
+/// A `%3A`-encoded colon denotes a non-default port rather than a path
+/// separator.
+fn did_web_document_url(identifier: &str) -> String {
+    let mut segments = identifier.split(':').map(|segment| segment.replace("%3A", ":").replace("%3a", ":"));
+    let domain = segments.next().unwrap_or_default();
+    let path: Vec<String> = segments.collect();
+    if path.is_empty() {
+        format!("https://{}/.well-known/did.json", domain)
+    } else {
+        format!("https://{}/{}/did.json", domain, path.join("/"))
+    }
+}
+
+/// `did:jwk:<base64url(JWK JSON)>`: the method-specific identifier is the
+/// JWK itself, optionally followed by a `#0` fragment selecting its (sole)
+/// verification method.
+fn resolve_did_jwk_method(identifier: &str) -> Result<Value, String> {
+    let encoded = identifier.split('#').next().unwrap_or(identifier);
+    let decoded = base64_url::decode(encoded).map_err(|e| format!("did:jwk identifier is not valid base64url: {}", e))?;
+    serde_json::from_slice(&decoded).map_err(|e| format!("did:jwk identifier is not a valid JWK: {}", e))
+}
+
+/// `did:key:<multibase(multicodec(public key bytes))>`: decodes the
+/// base58btc (`z`-prefixed) multibase encoding, strips the leading
+/// multicodec varint, and builds the JWK the key type implies.
+fn resolve_did_key(identifier: &str) -> Result<Value, String> {
+    let encoded = identifier.split('#').next().unwrap_or(identifier);
+    let multibase_prefix = encoded.chars().next().ok_or_else(|| "Empty did:key identifier".to_string())?;
+    if multibase_prefix != 'z' {
+        return Err(format!("Unsupported did:key multibase prefix: {}", multibase_prefix));
+    }
+    let bytes = bs58::decode(&encoded[1..]).into_vec().map_err(|e| format!("did:key identifier is not valid base58btc: {}", e))?;
+    let (codec, key_bytes) = read_multicodec_prefix(&bytes)?;
+
+    match codec {
+        0xed => Ok(json!({ "kty": "OKP", "crv": "Ed25519", "x": base64_url::encode(key_bytes) })),
+        0x1200 => p256_point_to_jwk(key_bytes),
+        other => Err(format!("Unsupported did:key multicodec: 0x{:x}", other)),
+    }
+}
+
+/// Reads a did:key's leading unsigned-varint multicodec code, returning it
+/// alongside the remaining key bytes.
+fn read_multicodec_prefix(bytes: &[u8]) -> Result<(u64, &[u8]), String> {
+    let mut value: u64 = 0;
+    for (i, byte) in bytes.iter().enumerate() {
+        value |= ((byte & 0x7f) as u64) << (7 * i);
+        if byte & 0x80 == 0 {
+            return Ok((value, &bytes[i + 1..]));
+        }
+    }
+    Err("did:key identifier is truncated (multicodec varint never terminates)".to_string())
+}
+
+/// Builds an EC `P-256` JWK from an uncompressed (`0x04 || x || y`) point.
+fn p256_point_to_jwk(point: &[u8]) -> Result<Value, String> {
+    if point.len() != 65 || point[0] != 0x04 {
+        return Err("did:key P-256 public key is not an uncompressed point".to_string());
+    }
+    Ok(json!({
+        "kty": "EC",
+        "crv": "P-256",
+        "x": base64_url::encode(&point[1..33]),
+        "y": base64_url::encode(&point[33..65]),
+    }))
+}
+
+/// Extracts the domain an issuer identifier is bound to, for the
+/// well-known domain-linkage check below. `did:web` identifiers encode
+/// the domain as their first `:`-delimited segment (same rule
+/// `did_web_document_url` uses); plain `https://` issuer URLs use their
+/// host. `did:jwk`/`did:key` have no domain to bind to a website, so
+/// they can't pass the linkage check at all.
+fn issuer_domain(issuer_url: &str) -> Result<String, String> {
+    if let Some(identifier) = issuer_url.strip_prefix("did:web:") {
+        let domain = identifier.split(':').next().unwrap_or_default().replace("%3A", ":").replace("%3a", ":");
+        if domain.is_empty() {
+            return Err(format!("Malformed did:web identifier: {}", issuer_url));
+        }
+        Ok(domain)
+    } else if let Some(rest) = issuer_url.strip_prefix("https://").or_else(|| issuer_url.strip_prefix("http://")) {
+        let domain = rest.split('/').next().unwrap_or_default();
+        if domain.is_empty() {
+            return Err(format!("Malformed issuer URL: {}", issuer_url));
+        }
+        Ok(domain.to_string())
+    } else {
+        Err(format!("Issuer {} has no well-known domain to link (unsupported DID method for domain linkage)", issuer_url))
+    }
+}
+
+/// Confirms `issuer_url` is linked to `domain` per the Well Known DID
+/// Configuration spec: fetches `https://{domain}/.well-known/did-configuration.json`
+/// and looks for a `linked_dids` entry -- a JWT-VC signed by the issuer's
+/// own key -- whose `credentialSubject.id` is `issuer_url` and whose
+/// `credentialSubject.origin` matches `domain`. This is what lets the
+/// verifier trust a key fetched from `issuer_url` without trusting
+/// whatever domain the client claims that key came from.
+async fn verify_domain_linkage(issuer_url: &str, domain: &str, issuer_pem_path: &str) -> Result<(), String> {
+    let config_url = format!("https://{}/.well-known/did-configuration.json", domain);
+    println!("Fetching DID configuration from: {}", config_url);
+
+    let response = ureq::get(&config_url).call().map_err(|e| format!("Failed to fetch DID configuration: {}", e))?;
+    let body = response.into_string().map_err(|e| format!("Failed to read DID configuration response: {}", e))?;
+    let did_configuration: Value = serde_json::from_str(&body).map_err(|e| format!("DID configuration is not valid JSON: {}", e))?;
+
+    let linked_dids = did_configuration.get("linked_dids")
+        .and_then(|v| v.as_array())
+        .ok_or_else(|| "DID configuration is missing linked_dids".to_string())?;
+
+    let issuer_pem = fs::read_to_string(issuer_pem_path).map_err(|e| format!("Failed to read issuer public key: {}", e))?;
+    let issuer_pub = RS256PublicKey::from_pem(&issuer_pem).map_err(|e| format!("Failed to parse issuer public key: {}", e))?;
+
+    for entry in linked_dids {
+        let token_str = match entry.as_str() {
+            Some(s) => s,
+            None => continue,
+        };
+        if issuer_pub.verify_token::<NoCustomClaims>(token_str, None).is_err() {
+            continue;
+        }
+        let claims_b64 = match token_str.split('.').nth(1) {
+            Some(c) => c,
+            None => continue,
+        };
+        let claims: Value = match base64_url::decode(claims_b64).ok().and_then(|b| serde_json::from_slice(&b).ok()) {
+            Some(c) => c,
+            None => continue,
+        };
+        let vc = claims.get("vc").unwrap_or(&claims);
+        let subject_id = vc.get("credentialSubject").and_then(|cs| cs.get("id")).and_then(|v| v.as_str());
+        let linked_origin = vc.get("credentialSubject").and_then(|cs| cs.get("origin")).and_then(|v| v.as_str());
+        let domain_url = format!("https://{}", domain);
+
+        if subject_id == Some(issuer_url) && linked_origin.map(|o| o.trim_end_matches('/')) == Some(domain_url.trim_end_matches('/')) {
+            return Ok(());
+        }
+    }
+
+    Err(format!("No valid domain-linkage credential found for issuer {} at domain {}", issuer_url, domain))
 }
 
 macro_rules! error_template {
@@ -285,41 +718,24 @@ macro_rules! error_template {
     }};
 }
 
-// route to verify a ZK proof given a ProofInfo, return a status  
-#[post("/verify", format = "json", data = "<proof_info>")]
-async fn verify(proof_info: Json<ProofInfo>, verifier_config: &State<VerifierConfig>) -> Result<Custom<Redirect>, Template> {
-    println!("*** /verify called");
-    println!("Session ID: {}", proof_info.session_id);
-    println!("Schema UID: {}", proof_info.schema_uid);
-    println!("Issuer URL: {}", proof_info.issuer_url);
-    println!("Disclosure UID: {}", proof_info.disclosure_uid);
-    println!("Proof: {}", proof_info.proof);
-
-    // check if session_id is present in active_session_ids
-    if !verifier_config.active_session_ids.lock().unwrap().contains(&proof_info.session_id) {
-        let msg = format!("Unknown session ID ({})", proof_info.session_id);
-        error_template!(msg, verifier_config);
-    }
-
-    // verify if the schema_uid is one of our supported SCHEMA_UIDS
-    if !SCHEMA_UIDS.contains(&proof_info.schema_uid.as_str()) {
-        let msg = format!("Unsupported schema UID ({})", proof_info.schema_uid);
-        error_template!(msg, verifier_config);
+/// Verifies a single `ProofInfo` against its matching `ProofSpec` --
+/// schema/disclosure compatibility, issuer key resolution, the ZK proof
+/// itself, and (for JWT credentials) status-list revocation -- returning
+/// its validity and disclosed claims. Used for each proof in a
+/// `PresentationBundle`, so a composite policy can require several
+/// credentials in the same session.
+async fn verify_single_proof(proof_info: &ProofInfo, nonce: &str, not_after: u64, verifier_config: &VerifierConfig) -> Result<(bool, Option<String>), String> {
+    // verify if the schema_uid is one of our supported schema_uids
+    if !schema_uids().contains(&proof_info.schema_uid.as_str()) {
+        return Err(format!("Unsupported schema UID ({})", proof_info.schema_uid));
     }
 
     // Check that the schema and disclosure are compatible
     if !is_disc_supported_by_schema(&proof_info.disclosure_uid, &proof_info.schema_uid) {
-        let msg = format!("Disclosure UID {} is not supported by schema {}", proof_info.disclosure_uid, proof_info.schema_uid);
-        error_template!(msg, verifier_config);
+        return Err(format!("Disclosure UID {} is not supported by schema {}", proof_info.disclosure_uid, proof_info.schema_uid));
     }
 
-    let cred_type = match cred_type_from_schema(&proof_info.schema_uid) {
-        Ok(cred_type) => cred_type,
-        Err(_) => error_template!("Credential type not found", verifier_config),
-    };
-
-    // Parse the challenge session ID as a byte array for the presentation message
-    let challenge = proof_info.session_id.clone();
+    let cred_type = cred_type_from_schema(&proof_info.schema_uid).map_err(|_| "Credential type not found".to_string())?;
 
     // Define base folder path and credential-specific folder path
     let base_folder = format!("{}/{}", CRESCENT_DATA_BASE_PATH, proof_info.schema_uid);
@@ -331,67 +747,212 @@ async fn verify(proof_info: Json<ProofInfo>, verifier_config: &State<VerifierCon
     if fs::metadata(&issuer_folder).is_err() {
         println!("Issuer folder does not exist. Creating it: {}", issuer_folder);
 
+        // Reject issuers we haven't explicitly allowlisted for this schema
+        // before doing anything else with them -- no folder, no key fetch.
+        let allowed = verifier_config.trusted_issuers.get(&proof_info.schema_uid)
+            .map(|issuers| issuers.iter().any(|i| i == &proof_info.issuer_url))
+            .unwrap_or(false);
+        if !allowed {
+            return Err(format!("Issuer {} is not on the trust allowlist for schema {}", proof_info.issuer_url, proof_info.schema_uid));
+        }
+
         // Create credential-specific folder
         fs::create_dir_all(&issuer_folder).expect("Failed to create credential folder");
 
         // Copy the base folder content to the new credential-specific folder
-        match copy_with_symlinks(shared_folder.as_ref(), issuer_folder.as_ref()) {
-            Ok(_) => println!("Copied base folder to credential-specific folder: {}", issuer_folder),
-            Err(_) => error_template!("Failed to copy base folder to credential-specific folder", verifier_config),
-        };
+        copy_with_symlinks(shared_folder.as_ref(), issuer_folder.as_ref())
+            .map_err(|_| "Failed to copy base folder to credential-specific folder".to_string())?;
+        println!("Copied base folder to credential-specific folder: {}", issuer_folder);
 
         if cred_type == "jwt" {
-            // Fetch the issuer's public key and save it to issuer.pub 
-            fetch_and_save_jwk(&proof_info.issuer_url, &issuer_folder).await.expect("Failed to fetch and save issuer's public key (JWT case)");
-        }    
+            // Fetch the issuer's public key and save it to issuer.pub
+            fetch_and_save_jwk(&proof_info.issuer_url, &issuer_folder, proof_info.kid.as_deref()).await.expect("Failed to fetch and save issuer's public key (JWT case)");
+
+            // The key alone isn't enough -- confirm the issuer's domain
+            // actually vouches for it via a well-known DID configuration
+            // before we keep this folder around.
+            let issuer_pem_path = format!("{}/issuer.pub", issuer_folder);
+            let domain = issuer_domain(&proof_info.issuer_url)?;
+            if let Err(e) = verify_domain_linkage(&proof_info.issuer_url, &domain, &issuer_pem_path).await {
+                fs::remove_dir_all(&issuer_folder).ok();
+                return Err(format!("Issuer domain linkage check failed: {}", e));
+            }
+        }
     }
 
     let paths = CachePaths::new_from_str(&issuer_folder);
     let vp = VerifierParams::<CrescentPairing>::new(&paths).unwrap();
 
-    let show_proof = match read_from_b64url::<ShowProof<CrescentPairing>>(&proof_info.proof) {
-        Ok(show_proof) => show_proof, 
-        Err(_) => error_template!("Invalid proof; deserialization error", verifier_config),
-    };
+    let show_proof_b64 = extract_show_proof_b64(&proof_info.proof);
+    let show_proof = read_from_b64url::<ShowProof<CrescentPairing>>(&show_proof_b64)
+        .map_err(|_| "Invalid proof; deserialization error".to_string())?;
 
-    let is_valid;
-    let disclosed_info;
-    let config_proof_spec = match cred_type {
-        "jwt" => verifier_config.site1_proof_spec.clone(),
-        "mdl" => verifier_config.site2_proof_spec.clone(),
-        _ => error_template!("Unsupported credential type", verifier_config),
+    let (config_proof_spec, verifier_domain) = match cred_type {
+        "jwt" => (verifier_config.site1_proof_spec.clone(), verifier_config.site1_verifier_domain.clone()),
+        "mdl" => (verifier_config.site2_proof_spec.clone(), verifier_config.site2_verifier_domain.clone()),
+        _ => return Err("Unsupported credential type".to_string()),
     };
     let mut ps : ProofSpec = serde_json::from_str(&config_proof_spec).unwrap();
-    // hash the challenge to use as the presentation message (we need to hash it because device (for device-bound creds) only support signing digests)   
-    ps.presentation_message = Some(Sha256::digest(challenge).to_vec());       
+    // hash the nonce to use as the presentation message (we need to hash it because device (for device-bound creds) only support signing digests)
+    ps.presentation_message = Some(Sha256::digest(nonce).to_vec());
+    // Bind this verifier's own identity, the session's nonce, and the
+    // session token's own expiry into the proof as a `VerifierChallenge`,
+    // so a proof shown here can't be replayed against another verifier, in
+    // a later session, or past the session's validity -- independent of
+    // the prover's own clock.
+    let nonce_bytes: [u8; 32] = base64_url::decode(nonce)
+        .map_err(|_| "Session nonce is not valid base64url".to_string())?
+        .try_into()
+        .map_err(|_| "Session nonce is not 32 bytes".to_string())?;
+    let challenge = VerifierChallenge { nonce: nonce_bytes, audience: verifier_domain, not_after };
+
+    // Reject a second presentation of the same challenge outright, before
+    // spending any effort verifying the proof itself -- this is what keeps
+    // a captured-but-unexpired proof from being replayed against us.
+    if !verifier_config.nonce_ledger.lock().unwrap().check_and_record(&challenge, now_secs()) {
+        return Err("Presentation challenge has already been used or has expired".to_string());
+    }
+
+    ps.audience = Some(challenge.audience.clone());
+    ps.nonce = Some(challenge.nonce.to_vec());
+    ps.not_after = Some(challenge.not_after);
     if cred_type == "mdl" {
         let age = disc_uid_to_age(&proof_info.disclosure_uid).unwrap() as u64; // disclosure UID validated, so unwrap should be safe
         ps.range_over_year = Some(std::collections::BTreeMap::from([("birth_date".to_string(), age)]));
     }
-    let (valid, info) = verify_show(&vp, &show_proof, &ps);
-    is_valid = valid;
-    disclosed_info = Some(info);
+    let outcome = verify_show(&vp, &show_proof, &ps, Some(&challenge));
+    let is_valid = outcome.verified;
+    let disclosed_info = is_valid.then(|| serde_json::Value::Object(outcome.revealed).to_string());
 
-    println!("Proof is valid: {}", is_valid);
+    println!("Proof for schema {} is valid: {}", proof_info.schema_uid, is_valid);
+    if let Some(failure) = outcome.failure {
+        println!("Verification failure: {}", failure);
+    }
     println!("Disclosed info: {:?}", disclosed_info);
 
-    if is_valid {
-        // Store the validation result in the hashmap
-        let validation_result = ValidationResult {
-            disclosed_info: disclosed_info.clone(),
-        };
-        verifier_config.validation_results.lock().unwrap().insert(proof_info.session_id.clone(), validation_result);
+    if is_valid && cred_type == "jwt" {
+        if let (Some(status_list_url), Some(status_list_index)) = (
+            get_disclosed_str("status_list_credential_value", &disclosed_info),
+            get_disclosed_str("status_list_index_value", &disclosed_info).and_then(|s| s.parse::<u64>().ok()),
+        ) {
+            let bits = fetch_status_list_bits(verifier_config, &status_list_url, &paths.issuer_pem)
+                .map_err(|e| format!("Failed to check credential revocation status: {}", e))?;
+            if status_list_bit_set(&bits, status_list_index) {
+                return Err("Credential has been revoked".to_string());
+            }
+        }
+    }
+
+    Ok((is_valid, disclosed_info))
+}
+
+/// Merges each proof's disclosed-claims JSON object into one combined
+/// object, so a composite presentation's resource/signup pages can read
+/// claims from every credential in the bundle the same way they read a
+/// single credential's claims today.
+fn merge_disclosed_info(disclosed: &[Option<String>]) -> Option<String> {
+    let mut merged = Map::new();
+    for info in disclosed.iter().flatten() {
+        if let Ok(Value::Object(obj)) = serde_json::from_str::<Value>(info) {
+            merged.extend(obj);
+        }
+    }
+    Some(Value::Object(merged).to_string())
+}
+
+// A batch of proofs presented together to satisfy a composite policy --
+// e.g. an mDL age proof *and* a JWT work-email proof in one session.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+struct PresentationBundle {
+    proofs: Vec<ProofInfo>,
+}
+
+// route to verify a bundle of ZK proofs against a site's (possibly
+// composite) credential policy, return a status
+#[post("/verify", format = "json", data = "<bundle>")]
+async fn verify(bundle: Json<PresentationBundle>, verifier_config: &State<VerifierConfig>) -> Result<Custom<Redirect>, Template> {
+    println!("*** /verify called");
+    let proofs = &bundle.proofs;
+
+    if proofs.is_empty() {
+        error_template!("Presentation bundle is empty", verifier_config);
+    }
+
+    // all proofs in a composite presentation must be bound to the same
+    // session nonce, so they can't be mixed-and-matched from different
+    // sessions
+    let shared_session_id = &proofs[0].session_id;
+    if proofs.iter().any(|p| &p.session_id != shared_session_id) {
+        error_template!("All proofs in a presentation bundle must share the same session ID", verifier_config);
+    }
+
+    // check that session_id is a validly signed, unexpired session token
+    let session_claims = match verify_session_token(verifier_config, shared_session_id) {
+        Some(claims) => claims,
+        None => {
+            let msg = format!("Unknown or expired session ID ({})", shared_session_id);
+            error_template!(msg, verifier_config);
+        }
+    };
+
+    // The presentation message must be the nonce this session was issued
+    // by /presentation-request -- not the raw session id -- so a proof
+    // minted for one verifier/session can't be replayed at another.
+    let nonce = match &session_claims.nonce {
+        Some(nonce) => nonce.clone(),
+        None => error_template!("Session is missing a presentation nonce; call /presentation-request first", verifier_config),
+    };
+
+    let site = session_claims.site.clone().unwrap_or_else(|| "site1".to_string());
+    let required_credentials = match site.as_str() {
+        "site1" => &verifier_config.site1_required_credentials,
+        "site2" => &verifier_config.site2_required_credentials,
+        _ => error_template!("Unknown site for session", verifier_config),
+    };
+
+    // the bundle must cover every (schema_uid, disclosure_uid) pair the
+    // site's composite policy requires
+    for (schema_uid, disclosure_uid) in required_credentials {
+        if !proofs.iter().any(|p| &p.schema_uid == schema_uid && &p.disclosure_uid == disclosure_uid) {
+            let msg = format!("Presentation bundle is missing required credential {} / {}", schema_uid, disclosure_uid);
+            error_template!(msg, verifier_config);
+        }
+    }
+
+    let mut all_valid = true;
+    let mut disclosed: Vec<Option<String>> = Vec::with_capacity(proofs.len());
+    for proof_info in proofs {
+        println!("Schema UID: {}", proof_info.schema_uid);
+        println!("Issuer URL: {}", proof_info.issuer_url);
+        println!("Disclosure UID: {}", proof_info.disclosure_uid);
+        println!("Proof: {}", proof_info.proof);
+
+        match verify_single_proof(proof_info, &nonce, session_claims.expiry, verifier_config).await {
+            Ok((is_valid, info)) => {
+                all_valid &= is_valid;
+                disclosed.push(info);
+            }
+            Err(msg) => error_template!(msg, verifier_config),
+        }
+    }
+
+    if all_valid {
+        // Fold the merged disclosed claims into a fresh signed token,
+        // carried forward in the redirect instead of a server-side map
+        // entry.
+        let merged_disclosed_info = merge_disclosed_info(&disclosed);
+        let result_token = mint_session_token(verifier_config, &session_claims.session_id, Some(site.clone()), None, merged_disclosed_info);
 
         // Redirect to the resource page or signup2 page with the session_id as a query parameter
-        let redirect_url = match cred_type {
-            "jwt" => uri!(resource_page(session_id = proof_info.session_id.clone())).to_string(),
-            "mdl" => uri!(signup2_page(session_id = proof_info.session_id.clone())).to_string(),
-            _ => error_template!("Unsupported credential type", verifier_config),
+        let redirect_url = match site.as_str() {
+            "site1" => uri!(resource_page(session_id = result_token)).to_string(),
+            "site2" => uri!(signup2_page(session_id = result_token)).to_string(),
+            _ => error_template!("Unknown site for session", verifier_config),
         };
 
         Ok(Custom(Status::SeeOther, Redirect::to(redirect_url)))
     } else {
-        // return an error template if the proof is invalid
+        // return an error template if any proof in the bundle is invalid
         error_template!("Proof is invalid.", verifier_config);
     }
 }
@@ -423,7 +984,22 @@ fn rocket() -> _ {
     let site2_verify_url: String = format!("http://{}:{}/verify", site2_verifier_domain, port);
     let site2_disclosure_uid: String = figment.extract_inner("site2_disclosure_uid").unwrap_or_else(|_| "{}".to_string());
     let site2_proof_spec: String = figment.extract_inner("site2_proof_spec").unwrap_or_else(|_| "{}".to_string());
-    
+
+    let site1_required_credentials: Vec<(String, String)> = figment.extract_inner("site1_required_credentials")
+        .unwrap_or_else(|_| vec![(schema_uids()[0].to_string(), site1_disclosure_uid.clone())]);
+    let site2_required_credentials: Vec<(String, String)> = figment.extract_inner("site2_required_credentials")
+        .unwrap_or_else(|_| vec![(schema_uids()[1].to_string(), site2_disclosure_uid.clone())]);
+
+    // no issuer is trusted unless this deployment's Rocket.toml says so --
+    // fail closed rather than auto-trusting whatever issuer_url a client sends
+    let trusted_issuers: HashMap<String, Vec<String>> = figment.extract_inner("trusted_issuers").unwrap_or_default();
+
+    let session_ttl_secs: u64 = figment.extract_inner("session_ttl_secs").unwrap_or(300);
+    let session_secret: String = figment.extract_inner("session_secret").unwrap_or_else(|_| {
+        println!("*** WARNING: no session_secret set in Rocket.toml, using an insecure default. Do not use this in production.");
+        "insecure-dev-session-secret-change-me".to_string()
+    });
+
     let verifier_config = VerifierConfig {
         port,
         site1_verifier_name,
@@ -436,13 +1012,18 @@ fn rocket() -> _ {
         site2_verify_url,
         site2_disclosure_uid,
         site2_proof_spec,
-        active_session_ids: Mutex::new(HashSet::new()),
-        validation_results: Mutex::new(HashMap::new()),
+        site1_required_credentials,
+        site2_required_credentials,
+        trusted_issuers,
+        session_secret,
+        session_ttl_secs,
+        status_list_cache: Mutex::new(HashMap::new()),
+        nonce_ledger: Mutex::new(NonceLedger::new()),
     };
-    
+
     rocket::build()
         .manage(verifier_config)
         .mount("/", FileServer::from("static"))
-        .mount("/", routes![index_page, login_page, resource_page, signup1_page, signup2_page, verify, site1_favicon, site2_favicon])
+        .mount("/", routes![index_page, login_page, resource_page, signup1_page, signup2_page, verify, presentation_request, site1_favicon, site2_favicon])
     .attach(Template::fairing())
 }